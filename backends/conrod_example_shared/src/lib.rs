@@ -51,6 +51,8 @@ pub fn theme() -> conrod_core::Theme {
         border_width: 0.0,
         label_color: conrod_core::color::WHITE,
         font_id: None,
+        font_query: None,
+        font_fallbacks: Vec::new(),
         font_size_large: 26,
         font_size_medium: 18,
         font_size_small: 12,
@@ -10,9 +10,11 @@ use gfx::{
 };
 
 use conrod_core::{
-    color, image, render,
+    color, image,
+    position::Transform,
+    render,
     text::{self, rt, GlyphCache},
-    Rect, Scalar,
+    widget, Rect, Scalar,
 };
 
 /// A `Command` describing a step in the drawing process.
@@ -302,6 +304,17 @@ impl<'a, R: Resources> Renderer<'a, R> {
         let vx = |x: Scalar| (x * dpi_factor / half_win_w) as f32;
         let vy = |y: Scalar| (y * dpi_factor / half_win_h) as f32;
 
+        // Apply a transform to a position in GL vertex coords.
+        let transform_vertex = |transform: Transform, p: [f32; 2]| {
+            if transform.is_identity() {
+                return p;
+            }
+            let x = p[0] as Scalar * half_win_w / dpi_factor;
+            let y = p[1] as Scalar * half_win_h / dpi_factor;
+            let [x, y] = transform.transform_point([x, y]);
+            [vx(x), vy(y)]
+        };
+
         let mut current_scizzor = gfx::Rect {
             x: 0,
             w: screen_w as u16,
@@ -323,15 +336,22 @@ impl<'a, R: Resources> Renderer<'a, R> {
             }
         };
 
+        // A buffer for tessellating `Shadow` primitives.
+        let mut shadow_triangles = Vec::new();
+
         // Draw each primitive in order of depth.
         while let Some(primitive) = primitives.next_primitive() {
             let render::Primitive {
                 kind,
                 scizzor,
                 rect,
+                transform,
                 ..
             } = primitive;
 
+            // Apply the primitive's transform to a position in GL vertex coords.
+            let tv = |p: [f32; 2]| transform_vertex(transform, p);
+
             // Check for a `Scizzor` command.
             let new_scizzor = rect_to_gfx_rect(scizzor);
             if new_scizzor != current_scizzor {
@@ -365,7 +385,7 @@ impl<'a, R: Resources> Renderer<'a, R> {
                     let v = |x, y| {
                         // Convert from conrod Scalar range to GL range -1.0 to 1.0.
                         Vertex {
-                            pos: [vx(x), vy(y)],
+                            pos: tv([vx(x), vy(y)]),
                             uv: [0.0, 0.0],
                             color: color,
                             mode: MODE_GEOMETRY,
@@ -395,7 +415,7 @@ impl<'a, R: Resources> Renderer<'a, R> {
                     let color = gamma_srgb_to_linear(color.into());
 
                     let v = |p: [Scalar; 2]| Vertex {
-                        pos: [vx(p[0]), vy(p[1])],
+                        pos: tv([vx(p[0]), vy(p[1])]),
                         uv: [0.0, 0.0],
                         color: color,
                         mode: MODE_GEOMETRY,
@@ -416,7 +436,7 @@ impl<'a, R: Resources> Renderer<'a, R> {
                     switch_to_plain_state!();
 
                     let v = |(p, c): ([Scalar; 2], color::Rgba)| Vertex {
-                        pos: [vx(p[0]), vy(p[1])],
+                        pos: tv([vx(p[0]), vy(p[1])]),
                         uv: [0.0, 0.0],
                         color: gamma_srgb_to_linear(c.into()),
                         mode: MODE_GEOMETRY,
@@ -429,6 +449,38 @@ impl<'a, R: Resources> Renderer<'a, R> {
                     }
                 }
 
+                render::PrimitiveKind::Shadow {
+                    color,
+                    offset,
+                    blur_radius,
+                    spread,
+                } => {
+                    switch_to_plain_state!();
+
+                    shadow_triangles.clear();
+                    widget::shadow::triangles(
+                        &mut shadow_triangles,
+                        rect,
+                        offset,
+                        blur_radius,
+                        spread,
+                        color.to_rgb(),
+                    );
+
+                    let v = |(p, c): ([Scalar; 2], color::Rgba)| Vertex {
+                        pos: tv([vx(p[0]), vy(p[1])]),
+                        uv: [0.0, 0.0],
+                        color: gamma_srgb_to_linear(c.into()),
+                        mode: MODE_GEOMETRY,
+                    };
+
+                    for triangle in shadow_triangles.iter() {
+                        vertices.push(v(triangle[0]));
+                        vertices.push(v(triangle[1]));
+                        vertices.push(v(triangle[2]));
+                    }
+                }
+
                 render::PrimitiveKind::Text {
                     color,
                     text,
@@ -436,6 +488,31 @@ impl<'a, R: Resources> Renderer<'a, R> {
                 } => {
                     switch_to_plain_state!();
 
+                    // Pushes the two triangles of a decoration quad.
+                    let push_quad =
+                        |vertices: &mut Vec<Vertex>, rect: Rect, color: color::Color| {
+                            let (l, r, b, t) = rect.l_r_b_t();
+                            let v = |x, y| Vertex {
+                                pos: tv([vx(x), vy(y)]),
+                                uv: [0.0, 0.0],
+                                color: gamma_srgb_to_linear(color.to_fsa()),
+                                mode: MODE_GEOMETRY,
+                            };
+                            vertices.extend_from_slice(&[
+                                v(l, t),
+                                v(r, b),
+                                v(l, b),
+                                v(l, t),
+                                v(r, b),
+                                v(r, t),
+                            ]);
+                        };
+
+                    let (behind, in_front) = text.decoration_quads(color);
+                    for (rect, color) in behind {
+                        push_quad(vertices, rect, color);
+                    }
+
                     positioned_glyphs.clear();
                     positioned_glyphs.extend(text.positioned_glyphs(dpi_factor as f32));
 
@@ -479,7 +556,7 @@ impl<'a, R: Resources> Renderer<'a, R> {
                         {
                             let gl_rect = to_gl_rect(screen_rect);
                             let v = |p, t| Vertex {
-                                pos: p,
+                                pos: tv(p),
                                 uv: t,
                                 color: color,
                                 mode: MODE_TEXT,
@@ -511,6 +588,10 @@ impl<'a, R: Resources> Renderer<'a, R> {
                             );
                         }
                     }
+
+                    for (rect, color) in in_front {
+                        push_quad(vertices, rect, color);
+                    }
                 }
 
                 render::PrimitiveKind::Image {
@@ -518,6 +599,13 @@ impl<'a, R: Resources> Renderer<'a, R> {
                     color,
                     source_rect,
                 } => {
+                    // Images packed into the atlas are drawn from their page.
+                    let (image_id, source_rect) = image_map.atlas().resolve(image_id, source_rect);
+                    let (image_w, image_h) = match image_map.get(&image_id) {
+                        None => continue,
+                        Some(&(_, dimensions)) => dimensions,
+                    };
+
                     // Switch to the `Image` state for this image if we're not in it already.
                     let new_image_id = image_id;
                     match current_state {
@@ -545,7 +633,6 @@ impl<'a, R: Resources> Renderer<'a, R> {
 
                     let color = color.unwrap_or(color::WHITE).to_fsa();
 
-                    let (image_w, image_h) = image_map.get(&image_id).unwrap().1;
                     let (image_w, image_h) = (image_w as Scalar, image_h as Scalar);
 
                     // Get the sides of the source rectangle as uv coordinates.
@@ -572,7 +659,7 @@ impl<'a, R: Resources> Renderer<'a, R> {
                         let x = (x * dpi_factor as Scalar / half_win_w) as f32;
                         let y = (y * dpi_factor as Scalar / half_win_h) as f32;
                         Vertex {
-                            pos: [x, y],
+                            pos: tv([x, y]),
                             uv: t,
                             color: color,
                             mode: MODE_IMAGE,
@@ -596,6 +683,9 @@ impl<'a, R: Resources> Renderer<'a, R> {
 
                 // We have no special case widgets to handle.
                 render::PrimitiveKind::Other(_) => (),
+
+                render::PrimitiveKind::BeginLayer { .. }
+                | render::PrimitiveKind::EndLayer { .. } => (),
             }
         }
 
@@ -650,7 +740,10 @@ impl<'a, R: Resources> Renderer<'a, R> {
                     // Draw an image whose texture data lies within the `image_map` at the
                     // given `id`.
                     Draw::Image(image_id, verts) => {
-                        let image = &image_map.get(&image_id).unwrap().0;
+                        let image = match image_map.get(&image_id) {
+                            None => continue,
+                            Some(&(ref image, _)) => image,
+                        };
                         data.color.0 = image.clone();
                         let (vbuf, slice) = factory.create_vertex_buffer_with_slice(&verts, ());
                         data.vbuf = vbuf;
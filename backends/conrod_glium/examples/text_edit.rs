@@ -72,6 +72,16 @@ fn main() {
                     *should_update_ui = true;
                 }
 
+                // `winit` only reports the text committed by an input method editor (IME), so the
+                // `TextEdit` cannot display the text being composed. Where the platform's IME is
+                // available, feed each change to the composition to the `Ui` so that it is shown
+                // inline at the caret:
+                //
+                //     use conrod_core::event::{Ime, Input};
+                //     ui.handle_event(Input::Ime(Ime::Preedit { string, cursor }));
+                //
+                // and once it finishes, `Input::Ime(Ime::Commit(string))` in place of `Text`.
+
                 match event {
                     glium::glutin::event::Event::WindowEvent { event, .. } => match event {
                         // Break from the loop upon `Escape`.
@@ -100,6 +110,9 @@ fn main() {
                     .window()
                     .set_cursor_icon(support::convert_mouse_cursor(ui.mouse_cursor()));
 
+                // Show the candidate window of any input method editor next to the text caret.
+                support::set_ime_position(display.gl_window().window(), ui.caret_rect());
+
                 *needs_redraw = ui.has_changed();
             }
             support::Request::Redraw => {
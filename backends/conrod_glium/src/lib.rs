@@ -4,7 +4,9 @@ extern crate conrod_core;
 #[macro_use]
 extern crate glium;
 
-use conrod_core::{color, image, render, text, Rect, Scalar};
+use conrod_core::position::Transform;
+use conrod_core::{color, image, mesh, render, text, widget, Rect, Scalar};
+use glium::CapabilitiesSource;
 
 /// A `Command` describing a step in the drawing process.
 #[derive(Clone, Debug)]
@@ -46,7 +48,7 @@ pub struct Renderer {
     glyph_cache: GlyphCache,
     commands: Vec<PreparedCommand>,
     vertices: Vec<Vertex>,
-    positioned_glyphs: Vec<text::PositionedGlyph>,
+    glyph_queue: mesh::GlyphQueue,
 }
 
 /// An iterator yielding `Command`s, produced by the `Renderer::commands` method.
@@ -425,7 +427,7 @@ impl GlyphCache {
     }
 }
 
-pub trait Display {
+pub trait Display: glium::backend::Facade {
     fn opengl_version(&self) -> &glium::Version;
     fn framebuffer_dimensions(&self) -> (u32, u32);
     fn hidpi_factor(&self) -> f64;
@@ -482,7 +484,7 @@ impl Renderer {
             glyph_cache: gc,
             commands: Vec::new(),
             vertices: Vec::new(),
-            positioned_glyphs: Vec::new(),
+            glyph_queue: mesh::GlyphQueue::new(),
         })
     }
 
@@ -510,7 +512,7 @@ impl Renderer {
             ref mut commands,
             ref mut vertices,
             ref mut glyph_cache,
-            ref mut positioned_glyphs,
+            ref mut glyph_queue,
             ..
         } = *self;
 
@@ -563,6 +565,17 @@ impl Renderer {
         let vx = |x: Scalar| (x * dpi_factor / half_win_w) as f32;
         let vy = |y: Scalar| (y * dpi_factor / half_win_h) as f32;
 
+        // Apply a transform to a position in GL vertex coords.
+        let transform_vertex = |transform: Transform, p: [f32; 2]| {
+            if transform.is_identity() {
+                return p;
+            }
+            let x = p[0] as Scalar * half_win_w / dpi_factor;
+            let y = p[1] as Scalar * half_win_h / dpi_factor;
+            let [x, y] = transform.transform_point([x, y]);
+            [vx(x), vy(y)]
+        };
+
         let mut current_scizzor = glium::Rect {
             left: 0,
             width: screen_w,
@@ -584,15 +597,22 @@ impl Renderer {
             }
         };
 
+        // A buffer for tessellating `Shadow` primitives.
+        let mut shadow_triangles = Vec::new();
+
         // Draw each primitive in order of depth.
         while let Some(primitive) = primitives.next_primitive() {
             let render::Primitive {
                 kind,
                 scizzor,
                 rect,
+                transform,
                 ..
             } = primitive;
 
+            // Apply the primitive's transform to a position in GL vertex coords.
+            let tv = |p: [f32; 2]| transform_vertex(transform, p);
+
             // Check for a `Scizzor` command.
             let new_scizzor = rect_to_glium_rect(scizzor);
             if new_scizzor != current_scizzor {
@@ -626,7 +646,7 @@ impl Renderer {
                     let v = |x, y| {
                         // Convert from conrod Scalar range to GL range -1.0 to 1.0.
                         Vertex {
-                            position: [vx(x), vy(y)],
+                            position: tv([vx(x), vy(y)]),
                             tex_coords: [0.0, 0.0],
                             color: color,
                             mode: MODE_GEOMETRY,
@@ -656,7 +676,7 @@ impl Renderer {
                     let color = gamma_srgb_to_linear(color.into());
 
                     let v = |p: [Scalar; 2]| Vertex {
-                        position: [vx(p[0]), vy(p[1])],
+                        position: tv([vx(p[0]), vy(p[1])]),
                         tex_coords: [0.0, 0.0],
                         color: color,
                         mode: MODE_GEOMETRY,
@@ -677,7 +697,7 @@ impl Renderer {
                     switch_to_plain_state!();
 
                     let v = |(p, c): ([Scalar; 2], color::Rgba)| Vertex {
-                        position: [vx(p[0]), vy(p[1])],
+                        position: tv([vx(p[0]), vy(p[1])]),
                         tex_coords: [0.0, 0.0],
                         color: gamma_srgb_to_linear(c.into()),
                         mode: MODE_GEOMETRY,
@@ -690,120 +710,96 @@ impl Renderer {
                     }
                 }
 
-                render::PrimitiveKind::Text {
+                render::PrimitiveKind::Shadow {
                     color,
-                    text,
-                    font_id,
+                    offset,
+                    blur_radius,
+                    spread,
                 } => {
                     switch_to_plain_state!();
 
-                    positioned_glyphs.clear();
-                    positioned_glyphs.extend(text.positioned_glyphs(dpi_factor as f32));
+                    shadow_triangles.clear();
+                    widget::shadow::triangles(
+                        &mut shadow_triangles,
+                        rect,
+                        offset,
+                        blur_radius,
+                        spread,
+                        color.to_rgb(),
+                    );
 
-                    let GlyphCache {
-                        ref mut cache,
-                        ref mut texture,
-                    } = *glyph_cache;
+                    let v = |(p, c): ([Scalar; 2], color::Rgba)| Vertex {
+                        position: tv([vx(p[0]), vy(p[1])]),
+                        tex_coords: [0.0, 0.0],
+                        color: gamma_srgb_to_linear(c.into()),
+                        mode: MODE_GEOMETRY,
+                    };
 
-                    // Queue the glyphs to be cached.
-                    for glyph in positioned_glyphs.iter() {
-                        cache.queue_glyph(font_id.index(), glyph.clone());
+                    for triangle in shadow_triangles.iter() {
+                        vertices.push(v(triangle[0]));
+                        vertices.push(v(triangle[1]));
+                        vertices.push(v(triangle[2]));
                     }
+                }
 
-                    // Cache the glyphs on the GPU.
-                    cache
-                        .cache_queued(|rect, data| {
-                            let w = rect.width();
-                            let h = rect.height();
-                            let glium_rect = glium::Rect {
-                                left: rect.min.x,
-                                bottom: rect.min.y,
-                                width: w,
-                                height: h,
-                            };
+                render::PrimitiveKind::Text {
+                    color,
+                    text,
+                    font_id,
+                } => {
+                    switch_to_plain_state!();
 
-                            let data = match client_format {
-                                // `rusttype` gives data in the `U8` format so we can use it directly.
-                                glium::texture::ClientFormat::U8 => {
-                                    std::borrow::Cow::Borrowed(data)
-                                }
-                                // Otherwise we have to convert to the supported format.
-                                glium::texture::ClientFormat::U8U8U8 => {
-                                    text_data_u8u8u8.clear();
-                                    for &b in data.iter() {
-                                        text_data_u8u8u8.push(b);
-                                        text_data_u8u8u8.push(b);
-                                        text_data_u8u8u8.push(b);
-                                    }
-                                    std::borrow::Cow::Borrowed(&text_data_u8u8u8[..])
-                                }
-                                // The text cache is only ever created with U8 or U8U8U8 formats.
-                                _ => unreachable!(),
+                    // Pushes the two triangles of a decoration quad.
+                    let push_quad =
+                        |vertices: &mut Vec<Vertex>, rect: Rect, color: color::Color| {
+                            let (l, r, b, t) = rect.l_r_b_t();
+                            let v = |x, y| Vertex {
+                                position: tv([vx(x), vy(y)]),
+                                tex_coords: [0.0, 0.0],
+                                color: gamma_srgb_to_linear(color.to_fsa()),
+                                mode: MODE_GEOMETRY,
                             };
+                            vertices.extend_from_slice(&[
+                                v(l, t),
+                                v(r, b),
+                                v(l, b),
+                                v(l, t),
+                                v(r, b),
+                                v(r, t),
+                            ]);
+                        };
 
-                            let image = glium::texture::RawImage2d {
-                                data: data,
-                                width: w,
-                                height: h,
-                                format: client_format,
-                            };
-                            texture.main_level().write(glium_rect, image);
-                        })
-                        .unwrap();
+                    let (behind, in_front) = text.decoration_quads(color);
+                    for (rect, color) in behind {
+                        push_quad(vertices, rect, color);
+                    }
 
+                    // The glyphs are cached once all text within the frame has been queued, at
+                    // which point the vertices of each glyph are positioned.
                     let color = gamma_srgb_to_linear(color.to_fsa());
-
-                    let cache_id = font_id.index();
-
-                    let origin = text::rt::point(0.0, 0.0);
-                    let to_gl_rect = |screen_rect: text::rt::Rect<i32>| text::rt::Rect {
-                        min: origin
-                            + (text::rt::vector(
-                                screen_rect.min.x as f32 / screen_w as f32 - 0.5,
-                                1.0 - screen_rect.min.y as f32 / screen_h as f32 - 0.5,
-                            )) * 2.0,
-                        max: origin
-                            + (text::rt::vector(
-                                screen_rect.max.x as f32 / screen_w as f32 - 0.5,
-                                1.0 - screen_rect.max.y as f32 / screen_h as f32 - 0.5,
-                            )) * 2.0,
-                    };
-
-                    for g in positioned_glyphs.drain(..) {
-                        if let Ok(Some((uv_rect, screen_rect))) = cache.rect_for(cache_id, &g) {
-                            let gl_rect = to_gl_rect(screen_rect);
-                            let v = |p, t| Vertex {
-                                position: p,
-                                tex_coords: t,
-                                color: color,
-                                mode: MODE_TEXT,
-                            };
-                            let mut push_v = |p, t| vertices.push(v(p, t));
-                            push_v(
-                                [gl_rect.min.x, gl_rect.max.y],
-                                [uv_rect.min.x, uv_rect.max.y],
-                            );
-                            push_v(
-                                [gl_rect.min.x, gl_rect.min.y],
-                                [uv_rect.min.x, uv_rect.min.y],
-                            );
-                            push_v(
-                                [gl_rect.max.x, gl_rect.min.y],
-                                [uv_rect.max.x, uv_rect.min.y],
-                            );
-                            push_v(
-                                [gl_rect.max.x, gl_rect.min.y],
-                                [uv_rect.max.x, uv_rect.min.y],
-                            );
-                            push_v(
-                                [gl_rect.max.x, gl_rect.max.y],
-                                [uv_rect.max.x, uv_rect.max.y],
-                            );
-                            push_v(
-                                [gl_rect.min.x, gl_rect.max.y],
-                                [uv_rect.min.x, uv_rect.max.y],
-                            );
+                    let font_id = font_id.index();
+                    for glyph in text.positioned_glyphs(dpi_factor as f32) {
+                        if glyph.pixel_bounding_box().is_none() {
+                            continue;
                         }
+                        let queued = mesh::QueuedGlyph {
+                            font_id,
+                            glyph,
+                            transform,
+                            vertex_start: vertices.len(),
+                        };
+                        glyph_queue.push(&mut glyph_cache.cache, queued);
+                        let v = Vertex {
+                            position: [0.0, 0.0],
+                            tex_coords: [0.0, 0.0],
+                            color: color,
+                            mode: MODE_TEXT,
+                        };
+                        vertices.extend((0..mesh::GLYPH_VERTEX_COUNT).map(|_| v));
+                    }
+
+                    for (rect, color) in in_front {
+                        push_quad(vertices, rect, color);
                     }
                 }
 
@@ -812,6 +808,9 @@ impl Renderer {
                     color,
                     source_rect,
                 } => {
+                    // Images packed into the atlas are drawn from their page.
+                    let (image_id, source_rect) = image_map.atlas().resolve(image_id, source_rect);
+
                     // Switch to the `Image` state for this image if we're not in it already.
                     let new_image_id = image_id;
                     match current_state {
@@ -866,7 +865,7 @@ impl Renderer {
                             let x = (x * dpi_factor as Scalar / half_win_w) as f32;
                             let y = (y * dpi_factor as Scalar / half_win_h) as f32;
                             Vertex {
-                                position: [x, y],
+                                position: tv([x, y]),
                                 tex_coords: t,
                                 color: color,
                                 mode: MODE_IMAGE,
@@ -891,6 +890,9 @@ impl Renderer {
 
                 // We have no special case widgets to handle.
                 render::PrimitiveKind::Other(_) => (),
+
+                render::PrimitiveKind::BeginLayer { .. }
+                | render::PrimitiveKind::EndLayer { .. } => (),
             }
         }
 
@@ -901,6 +903,114 @@ impl Renderer {
                 commands.push(PreparedCommand::Image(image_id, start..vertices.len()))
             }
         }
+
+        // Cache the glyphs of all text within the frame on the GPU at once, growing the cache up
+        // to the largest texture supported. Glyphs that still do not fit are dropped.
+        let GlyphCache {
+            ref mut cache,
+            ref mut texture,
+        } = *glyph_cache;
+        let mut write_glyph =
+            |texture: &mut glium::texture::Texture2d, rect: text::rt::Rect<u32>, data: &[u8]| {
+                let w = rect.width();
+                let h = rect.height();
+                let glium_rect = glium::Rect {
+                    left: rect.min.x,
+                    bottom: rect.min.y,
+                    width: w,
+                    height: h,
+                };
+
+                let data = match client_format {
+                    // `rusttype` gives data in the `U8` format so we can use it directly.
+                    glium::texture::ClientFormat::U8 => std::borrow::Cow::Borrowed(data),
+                    // Otherwise we have to convert to the supported format.
+                    glium::texture::ClientFormat::U8U8U8 => {
+                        text_data_u8u8u8.clear();
+                        for &b in data.iter() {
+                            text_data_u8u8u8.push(b);
+                            text_data_u8u8u8.push(b);
+                            text_data_u8u8u8.push(b);
+                        }
+                        std::borrow::Cow::Borrowed(&text_data_u8u8u8[..])
+                    }
+                    // The text cache is only ever created with U8 or U8U8U8 formats.
+                    _ => unreachable!(),
+                };
+
+                let image = glium::texture::RawImage2d {
+                    data: data,
+                    width: w,
+                    height: h,
+                    format: client_format,
+                };
+                texture.main_level().write(glium_rect, image);
+            };
+        let max_size = display.get_context().get_capabilities().max_texture_size as u32;
+        let mut upload = |[w, h]: [u32; 2], rect, data: &[u8]| {
+            if texture.dimensions() != (w, h) {
+                *texture = glyph_cache_texture(display, w, h)
+                    .expect("failed to reallocate the glyph cache texture");
+            }
+            write_glyph(texture, rect, data);
+        };
+        if glyph_queue
+            .cache(cache, [max_size; 2], &mut upload)
+            .is_err()
+        {
+            glyph_queue.cache_fitting(cache, &mut upload);
+        }
+
+        // Position the vertices of each glyph now that its location within the cache is known.
+        let origin = text::rt::point(0.0, 0.0);
+        let to_gl_rect = |screen_rect: text::rt::Rect<i32>| text::rt::Rect {
+            min: origin
+                + (text::rt::vector(
+                    screen_rect.min.x as f32 / screen_w as f32 - 0.5,
+                    1.0 - screen_rect.min.y as f32 / screen_h as f32 - 0.5,
+                )) * 2.0,
+            max: origin
+                + (text::rt::vector(
+                    screen_rect.max.x as f32 / screen_w as f32 - 0.5,
+                    1.0 - screen_rect.max.y as f32 / screen_h as f32 - 0.5,
+                )) * 2.0,
+        };
+
+        for (queued, uv_rect, screen_rect) in glyph_queue.drain_cached(cache) {
+            let gl_rect = to_gl_rect(screen_rect);
+            let corners = [
+                (
+                    [gl_rect.min.x, gl_rect.max.y],
+                    [uv_rect.min.x, uv_rect.max.y],
+                ),
+                (
+                    [gl_rect.min.x, gl_rect.min.y],
+                    [uv_rect.min.x, uv_rect.min.y],
+                ),
+                (
+                    [gl_rect.max.x, gl_rect.min.y],
+                    [uv_rect.max.x, uv_rect.min.y],
+                ),
+                (
+                    [gl_rect.max.x, gl_rect.min.y],
+                    [uv_rect.max.x, uv_rect.min.y],
+                ),
+                (
+                    [gl_rect.max.x, gl_rect.max.y],
+                    [uv_rect.max.x, uv_rect.max.y],
+                ),
+                (
+                    [gl_rect.min.x, gl_rect.max.y],
+                    [uv_rect.min.x, uv_rect.max.y],
+                ),
+            ];
+            let end = queued.vertex_start + mesh::GLYPH_VERTEX_COUNT;
+            let glyph_vertices = &mut vertices[queued.vertex_start..end];
+            for (vertex, &(position, tex_coords)) in glyph_vertices.iter_mut().zip(corners.iter()) {
+                vertex.position = transform_vertex(queued.transform, position);
+                vertex.tex_coords = tex_coords;
+            }
+        }
     }
 
     /// Draws using the inner list of `Command`s to the given `display`.
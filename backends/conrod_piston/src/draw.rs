@@ -1,6 +1,6 @@
 //! A piston backend for rendering conrod primitives.
 
-use conrod_core::{image, render, text, utils, Rect};
+use conrod_core::{image, render, text, utils, widget, Rect};
 use piston_graphics;
 
 #[doc(inline)]
@@ -87,6 +87,7 @@ pub fn primitive<'a, Img, G, T, C, F>(
         kind,
         scizzor,
         rect,
+        transform,
         ..
     } = primitive;
     let view_size = context.get_view_size();
@@ -95,6 +96,8 @@ pub fn primitive<'a, Img, G, T, C, F>(
         .trans(view_size[0] / 2.0, view_size[1] / 2.0)
         .scale(1.0, -1.0);
     let context = crop_context(context, scizzor);
+    // Apply the widget's transform, which shares piston's row-major 2x3 matrix layout.
+    let context = context.append_transform(transform.matrix);
 
     match kind {
         render::PrimitiveKind::Rectangle { color } => {
@@ -130,6 +133,31 @@ pub fn primitive<'a, Img, G, T, C, F>(
             }
         }
 
+        // As with `TrianglesMultiColor`, each triangle of the shadow's gradient is filled with a
+        // single colour. The average of the vertex colours gives a stepped approximation.
+        render::PrimitiveKind::Shadow {
+            color,
+            offset,
+            blur_radius,
+            spread,
+        } => {
+            let mut triangles = Vec::new();
+            let color = color.to_rgb();
+            widget::shadow::triangles(&mut triangles, rect, offset, blur_radius, spread, color);
+            for triangle in triangles {
+                let mut color = [0.0; 4];
+                for &(_, rgba) in triangle.0.iter() {
+                    let rgba: [f32; 4] = rgba.into();
+                    for (c, v) in color.iter_mut().zip(rgba.iter()) {
+                        *c += v / 3.0;
+                    }
+                }
+                let polygon = piston_graphics::Polygon::new(color);
+                let points = [triangle[0].0, triangle[1].0, triangle[2].0];
+                polygon.draw(&points, &context.draw_state, context.transform, graphics);
+            }
+        }
+
         render::PrimitiveKind::Text {
             color,
             text,
@@ -142,6 +170,20 @@ pub fn primitive<'a, Img, G, T, C, F>(
                 .viewport
                 .map(|v| v.draw_size[0] as f32 / v.window_size[0] as f32)
                 .unwrap_or(1.0);
+
+            let (behind, in_front) = text.decoration_quads(color);
+            for (rect, color) in behind {
+                let (l, b, w, h) = rect.l_b_w_h();
+                let rectangle = piston_graphics::Rectangle::new(color.to_fsa());
+                rectangle.draw(
+                    [l, b, w, h],
+                    &context.draw_state,
+                    context.transform,
+                    graphics,
+                );
+            }
+            let conrod_context = context;
+
             let positioned_glyphs: Vec<_> = text.positioned_glyphs(dpi_factor).collect();
             // Re-orient the context to top-left origin with *y* facing downwards, as the
             // `positioned_glyphs` yield pixel positioning.
@@ -198,6 +240,15 @@ pub fn primitive<'a, Img, G, T, C, F>(
                 context.transform,
                 graphics,
             );
+
+            // Draw the lines within conrod's coordinates, as the `context` has been re-oriented.
+            let context = conrod_context;
+            for (rect, color) in in_front {
+                let (l, b, w, h) = rect.l_b_w_h();
+                let rectangle = piston_graphics::Rectangle::new(color.to_fsa());
+                let lbwh = [l, b, w, h];
+                rectangle.draw(lbwh, &context.draw_state, context.transform, graphics);
+            }
         }
 
         render::PrimitiveKind::Image {
@@ -205,6 +256,8 @@ pub fn primitive<'a, Img, G, T, C, F>(
             color,
             source_rect,
         } => {
+            // Images packed into the atlas are drawn from their page.
+            let (image_id, source_rect) = image_map.atlas().resolve(image_id, source_rect);
             if let Some(img) = image_map.get(&image_id) {
                 let mut image = piston_graphics::image::Image::new();
                 image.color = color.map(|c| c.to_fsa());
@@ -226,6 +279,8 @@ pub fn primitive<'a, Img, G, T, C, F>(
             // TODO: Perhaps add a function to the `primitives` params to allow a user to
             // handle these.
         }
+
+        render::PrimitiveKind::BeginLayer { .. } | render::PrimitiveKind::EndLayer { .. } => (),
     }
 }
 
@@ -78,6 +78,11 @@ where
 pub struct UiPipelineDesc {
     /// The dimensions with which the glyph cache should be initialised.
     pub glyph_cache_dimensions: [u32; 2],
+    /// The width in pixels of the feathered, anti-aliased edges produced for shape and line
+    /// geometry, or `None` to disable feathering.
+    ///
+    /// See `conrod_core::mesh::Mesh::set_feather_width` for details.
+    pub feather_width: Option<f64>,
 }
 
 #[derive(Debug)]
@@ -135,6 +140,7 @@ impl Default for UiPipelineDesc {
         let glyph_cache_dimensions = conrod_core::mesh::DEFAULT_GLYPH_CACHE_DIMS;
         UiPipelineDesc {
             glyph_cache_dimensions,
+            feather_width: None,
         }
     }
 }
@@ -261,10 +267,11 @@ where
         _images: Vec<NodeImage>,
         set_layouts: &[Handle<DescriptorSetLayout<B>>],
     ) -> Result<Self::Pipeline, CreationError> {
-        // TODO: Consider using `Mesh::with_glyph_cache_dimensions` and allowing user to specify
-        // glyph cache dimensions. Currently we just use the default size, but this is not always
-        // enough for large GUIs with lots of text.
-        let mesh = Mesh::with_glyph_cache_dimensions(self.glyph_cache_dimensions);
+        // The glyph cache texture cannot be reallocated during `prepare`, so the glyph cache may
+        // not grow beyond the dimensions given by the user.
+        let mut mesh = Mesh::with_glyph_cache_dimensions(self.glyph_cache_dimensions);
+        mesh.set_feather_width(self.feather_width);
+        mesh.set_max_glyph_cache_dimensions(self.glyph_cache_dimensions);
 
         // Create the texture used for caching glyphs on the GPU.
         let sampler_img_state = sampler_img_state(queue);
@@ -513,6 +520,11 @@ where
                         encoder.set_scissors(first_scissor, Some(&rect));
                     }
                 }
+
+                mesh::Command::BeginLayer | mesh::Command::EndLayer(_) => (),
+
+                // Clip masks are not supported, so children are only cropped to the scizzor.
+                mesh::Command::Mask(_) => (),
             }
         }
     }
@@ -0,0 +1,56 @@
+//! An example demonstrating the use of `conrod_software` to render a GUI to a PNG file without a
+//! window or GPU.
+
+use conrod_example_shared::{WIN_H, WIN_W};
+
+fn main() {
+    // Create Ui and Ids of widgets to instantiate
+    let mut ui = conrod_core::UiBuilder::new([WIN_W as f64, WIN_H as f64])
+        .theme(conrod_example_shared::theme())
+        .build();
+    let ids = conrod_example_shared::Ids::new(ui.widget_id_generator());
+
+    // Load font from file
+    let assets = find_folder::Search::KidsThenParents(3, 5)
+        .for_folder("assets")
+        .unwrap();
+    let font_path = assets.join("fonts/NotoSans/NotoSans-Regular.ttf");
+    ui.fonts.insert_from_file(font_path).unwrap();
+
+    // Load the Rust logo from our assets folder to use as an example image.
+    let logo_path = assets.join("images/rust.png");
+    let rgba_logo_image = image::open(logo_path)
+        .expect("Couldn't load logo")
+        .to_rgba8();
+    let (logo_w, logo_h) = rgba_logo_image.dimensions();
+    let logo = conrod_software::Image::from_rgba(logo_w, logo_h, rgba_logo_image.into_raw())
+        .expect("unexpected logo image length");
+    let mut image_map = conrod_core::image::Map::new();
+    let rust_logo = image_map.insert(logo);
+
+    // Demonstration app state that we'll control with our conrod GUI.
+    let mut app = conrod_example_shared::DemoApp::new(rust_logo);
+
+    // Instantiate a GUI demonstrating every widget type provided by conrod.
+    conrod_example_shared::gui(&mut ui.set_widgets(), &ids, &mut app);
+
+    // Render the GUI to an image.
+    let mut renderer = conrod_software::Renderer::new();
+    let mut target = conrod_software::Image::new(WIN_W, WIN_H);
+    target.clear(conrod_core::color::BLACK);
+    renderer
+        .render(&image_map, 1.0, ui.draw(), &mut target)
+        .expect("failed to render the GUI");
+
+    // Write the result to a PNG file.
+    let path = std::env::temp_dir().join("conrod_software.png");
+    image::save_buffer(
+        &path,
+        &target.data,
+        target.width,
+        target.height,
+        image::ColorType::Rgba8,
+    )
+    .expect("failed to save the rendered image");
+    println!("Rendered GUI to {}", path.display());
+}
@@ -0,0 +1,605 @@
+//! A pure-Rust software backend for rendering conrod UIs to an RGBA pixel buffer.
+//!
+//! The `Renderer` rasterizes the vertices and commands produced by `conrod_core`'s `mesh::Mesh`
+//! on the CPU, so no GPU or window is required. This is useful for rendering frames on headless
+//! machines, e.g. for tests or for generating screenshots.
+
+use conrod_core::{
+    color::{self, Color},
+    image,
+    mesh::{self, Mesh},
+    render,
+    text::rt,
+    Rect, Scalar,
+};
+
+#[cfg(feature = "snapshot")]
+pub mod snapshot;
+
+/// An image with four 8-bit channels (red, green, blue and alpha) per pixel.
+///
+/// Pixels are stored in rows from the top-left of the image. Color channels are encoded in the
+/// sRGB color space and are not premultiplied by alpha.
+///
+/// `Image` is used both as the target to which the `Renderer` draws and as the image type stored
+/// within the `image::Map`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Image {
+    /// The width of the image in pixels.
+    pub width: u32,
+    /// The height of the image in pixels.
+    pub height: u32,
+    /// The RGBA pixel data, `width * height * 4` bytes in length.
+    pub data: Vec<u8>,
+}
+
+/// A helper type aimed at simplifying the rendering of conrod primitives to an `Image` on the
+/// CPU.
+#[derive(Debug)]
+pub struct Renderer {
+    mesh: Mesh,
+}
+
+/// The number of channels per pixel within an `Image`.
+const CHANNELS: usize = 4;
+
+impl Image {
+    /// Construct a new, fully transparent `Image` with the given dimensions.
+    pub fn new(width: u32, height: u32) -> Self {
+        let data = vec![0; width as usize * height as usize * CHANNELS];
+        Image {
+            width,
+            height,
+            data,
+        }
+    }
+
+    /// Construct an `Image` from the given raw RGBA pixel data.
+    ///
+    /// Returns `None` if the length of `data` does not match the given dimensions.
+    pub fn from_rgba(width: u32, height: u32, data: Vec<u8>) -> Option<Self> {
+        if data.len() != width as usize * height as usize * CHANNELS {
+            return None;
+        }
+        Some(Image {
+            width,
+            height,
+            data,
+        })
+    }
+
+    /// Fill every pixel of the image with the given color.
+    pub fn clear(&mut self, color: Color) {
+        let rgba = color.to_byte_fsa();
+        for pixel in self.data.chunks_mut(CHANNELS) {
+            pixel.copy_from_slice(&rgba);
+        }
+    }
+
+    /// The RGBA value of the pixel at the given position from the top-left of the image.
+    ///
+    /// Returns `None` if the position lies outside of the image.
+    pub fn pixel(&self, x: u32, y: u32) -> Option<[u8; 4]> {
+        if x >= self.width || y >= self.height {
+            return None;
+        }
+        let ix = self.index(x, y);
+        let p = &self.data[ix..ix + CHANNELS];
+        Some([p[0], p[1], p[2], p[3]])
+    }
+
+    fn index(&self, x: u32, y: u32) -> usize {
+        (y as usize * self.width as usize + x as usize) * CHANNELS
+    }
+}
+
+impl mesh::ImageDimensions for Image {
+    fn dimensions(&self) -> [u32; 2] {
+        [self.width, self.height]
+    }
+}
+
+impl Renderer {
+    /// Construct a new `Renderer` with the default glyph cache dimensions.
+    pub fn new() -> Self {
+        Self::with_glyph_cache_dimensions(mesh::DEFAULT_GLYPH_CACHE_DIMS)
+    }
+
+    /// Construct a new `Renderer` with the given glyph cache dimensions.
+    pub fn with_glyph_cache_dimensions(glyph_cache_dims: [u32; 2]) -> Self {
+        let mesh = Mesh::with_glyph_cache_dimensions(glyph_cache_dims);
+        Renderer { mesh }
+    }
+
+    /// Enable or disable feathered, anti-aliased edges for shape and line geometry.
+    ///
+    /// The `width` of the fringe is given in pixels. See
+    /// `conrod_core::mesh::Mesh::set_feather_width` for details.
+    pub fn set_feather_width(&mut self, width: Option<f64>) {
+        self.mesh.set_feather_width(width);
+    }
+
+    /// Produce an `Iterator` yielding `Command`s.
+    pub fn commands(&self) -> mesh::Commands<'_> {
+        self.mesh.commands()
+    }
+
+    /// The inner mesh, holding the vertices and glyph cache produced by the last `fill`.
+    pub fn mesh(&self) -> &Mesh {
+        &self.mesh
+    }
+
+    /// Fill the inner vertex and command buffers by translating the given `primitives`.
+    ///
+    /// - `viewport`: the width and height in pixels of the `Image` that will be drawn to.
+    /// - `scale_factor`: the factor for converting from conrod's DPI agnostic point space to the
+    ///   pixel space of the viewport.
+    pub fn fill<P>(
+        &mut self,
+        image_map: &image::Map<Image>,
+        viewport: [u32; 2],
+        scale_factor: f64,
+        primitives: P,
+    ) -> Result<(), rt::gpu_cache::CacheWriteErr>
+    where
+        P: render::PrimitiveWalker,
+    {
+        let [w, h] = viewport;
+        let viewport = Rect::from_corners([0.0, 0.0], [w as Scalar, h as Scalar]);
+        self.mesh
+            .fill(viewport, scale_factor, image_map, primitives)?;
+        Ok(())
+    }
+
+    /// Rasterize the commands produced by the last call to `fill` onto the given `target`.
+    ///
+    /// Geometry is blended over the existing contents of `target`, so the user may wish to `clear`
+    /// the target first.
+    ///
+    /// Isolated layers are supported, so `render::Primitives::with_isolated_layers` may be used to
+    /// produce the primitives passed to `fill`. Clip masks produced via `Widget::crop_kids_to` are
+    /// applied using a stencil.
+    pub fn draw(&self, image_map: &image::Map<Image>, target: &mut Image) {
+        let vertices = self.mesh.vertices();
+        let glyph_cache = GlyphCacheTexture {
+            data: self.mesh.glyph_cache_pixel_buffer(),
+            dimensions: self.mesh.glyph_cache().dimensions(),
+        };
+
+        // The region of the target to which drawing is currently cropped.
+        let mut scizzor = Scizzor {
+            left: 0,
+            top: 0,
+            right: target.width,
+            bottom: target.height,
+        };
+
+        // The isolated layers currently being drawn to, from outermost to innermost.
+        let mut layers: Vec<Image> = vec![];
+
+        // The clip mask to which drawing is currently restricted, if any.
+        let mut stencil: Option<Stencil> = None;
+
+        for command in self.mesh.commands() {
+            match command {
+                mesh::Command::Scizzor(s) => scizzor = Scizzor::new(s, target),
+                mesh::Command::Mask(range) => {
+                    stencil = range.map(|range| {
+                        let mut stencil = Stencil::new(target.width, target.height);
+                        for tri in vertices[range].chunks_exact(3) {
+                            rasterize_stencil(&mut stencil, [&tri[0], &tri[1], &tri[2]]);
+                        }
+                        stencil
+                    });
+                }
+                mesh::Command::BeginLayer => layers.push(Image::new(target.width, target.height)),
+                mesh::Command::EndLayer(opacity) => {
+                    if let Some(layer) = layers.pop() {
+                        let below = layers.last_mut().unwrap_or(&mut *target);
+                        composite(below, &layer, opacity);
+                    }
+                }
+                mesh::Command::Draw(draw) => {
+                    let target = layers.last_mut().unwrap_or(&mut *target);
+                    match draw {
+                        mesh::Draw::Plain(range) => {
+                            let texture = Texture::Glyphs(&glyph_cache);
+                            for tri in vertices[range].chunks_exact(3) {
+                                rasterize_triangle(
+                                    target,
+                                    scizzor,
+                                    stencil.as_ref(),
+                                    [&tri[0], &tri[1], &tri[2]],
+                                    texture,
+                                );
+                            }
+                        }
+                        mesh::Draw::Image(image_id, range) => {
+                            let image = match image_map.get(&image_id) {
+                                Some(image) => image,
+                                None => continue,
+                            };
+                            let texture = Texture::Image(image);
+                            for tri in vertices[range].chunks_exact(3) {
+                                rasterize_triangle(
+                                    target,
+                                    scizzor,
+                                    stencil.as_ref(),
+                                    [&tri[0], &tri[1], &tri[2]],
+                                    texture,
+                                );
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    /// Fill the mesh from the given `primitives` and draw the result to the `target`.
+    ///
+    /// This is a convenience method that calls `fill` with the dimensions of the `target` before
+    /// calling `draw`.
+    pub fn render<P>(
+        &mut self,
+        image_map: &image::Map<Image>,
+        scale_factor: f64,
+        primitives: P,
+        target: &mut Image,
+    ) -> Result<(), rt::gpu_cache::CacheWriteErr>
+    where
+        P: render::PrimitiveWalker,
+    {
+        let viewport = [target.width, target.height];
+        self.fill(image_map, viewport, scale_factor, primitives)?;
+        self.draw(image_map, target);
+        Ok(())
+    }
+}
+
+impl Default for Renderer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// The single channel glyph cache produced by the `Mesh`.
+struct GlyphCacheTexture<'a> {
+    data: &'a [u8],
+    dimensions: (u32, u32),
+}
+
+// The texture sampled by the triangles of a single draw command.
+#[derive(Copy, Clone)]
+enum Texture<'a> {
+    Glyphs(&'a GlyphCacheTexture<'a>),
+    Image(&'a Image),
+}
+
+// A mask with one byte per pixel of the target, where drawing is restricted to non-zero pixels.
+struct Stencil {
+    width: u32,
+    height: u32,
+    data: Vec<u8>,
+}
+
+impl Stencil {
+    fn new(width: u32, height: u32) -> Self {
+        let data = vec![0; width as usize * height as usize];
+        Stencil {
+            width,
+            height,
+            data,
+        }
+    }
+
+    fn index(&self, x: u32, y: u32) -> usize {
+        y as usize * self.width as usize + x as usize
+    }
+}
+
+// The region of the target in pixels to which drawing is cropped.
+#[derive(Copy, Clone, Debug)]
+struct Scizzor {
+    left: u32,
+    top: u32,
+    right: u32,
+    bottom: u32,
+}
+
+impl Scizzor {
+    fn new(s: mesh::Scizzor, target: &Image) -> Self {
+        let left = (s.top_left[0].max(0) as u32).min(target.width);
+        let top = (s.top_left[1].max(0) as u32).min(target.height);
+        let right = left.saturating_add(s.dimensions[0]).min(target.width);
+        let bottom = top.saturating_add(s.dimensions[1]).min(target.height);
+        Scizzor {
+            left,
+            top,
+            right,
+            bottom,
+        }
+    }
+}
+
+// Rasterize a single triangle onto the target, sampling the given texture where necessary.
+//
+// Pixels are only drawn where the `stencil` is non-zero, if there is one.
+fn rasterize_triangle(
+    target: &mut Image,
+    scizzor: Scizzor,
+    stencil: Option<&Stencil>,
+    vs: [&mesh::Vertex; 3],
+    texture: Texture,
+) {
+    let dimensions = (target.width, target.height);
+    cover_triangle(dimensions, scizzor, vs, |x, y, b| {
+        if let Some(stencil) = stencil {
+            if stencil.data[stencil.index(x, y)] == 0 {
+                return;
+            }
+        }
+        let lerp2 = |f: fn(&mesh::Vertex) -> [f32; 2]| {
+            let (a, b_, c_) = (f(vs[0]), f(vs[1]), f(vs[2]));
+            [
+                a[0] * b[0] + b_[0] * b[1] + c_[0] * b[2],
+                a[1] * b[0] + b_[1] * b[1] + c_[1] * b[2],
+            ]
+        };
+        let mut rgba = [0.0; 4];
+        for (i, channel) in rgba.iter_mut().enumerate() {
+            *channel = vs[0].rgba[i] * b[0] + vs[1].rgba[i] * b[1] + vs[2].rgba[i] * b[2];
+        }
+        let src = match vs[0].mode {
+            mesh::MODE_TEXT => {
+                let uv = lerp2(|v| v.tex_coords);
+                let coverage = match texture {
+                    Texture::Glyphs(glyphs) => sample_glyph_cache(glyphs, uv),
+                    Texture::Image(_) => 0.0,
+                };
+                [rgba[0], rgba[1], rgba[2], rgba[3] * coverage]
+            }
+            mesh::MODE_IMAGE => {
+                let uv = lerp2(|v| v.tex_coords);
+                let texel = match texture {
+                    Texture::Image(image) => sample_image(image, uv),
+                    Texture::Glyphs(_) => return,
+                };
+                [
+                    texel[0] * rgba[0],
+                    texel[1] * rgba[1],
+                    texel[2] * rgba[2],
+                    texel[3] * rgba[3],
+                ]
+            }
+            mesh::MODE_GEOMETRY => rgba,
+            _ => return,
+        };
+        blend(target, x, y, src);
+    });
+}
+
+// Invert the stencil for every pixel covered by the triangle, so that overlapping triangles
+// produce the even-odd coverage of the polygon that they describe.
+fn rasterize_stencil(stencil: &mut Stencil, vs: [&mesh::Vertex; 3]) {
+    let dimensions = (stencil.width, stencil.height);
+    let scizzor = Scizzor {
+        left: 0,
+        top: 0,
+        right: stencil.width,
+        bottom: stencil.height,
+    };
+    cover_triangle(dimensions, scizzor, vs, |x, y, _| {
+        let idx = stencil.index(x, y);
+        stencil.data[idx] ^= 1;
+    });
+}
+
+// Call `f` with the position and barycentric coordinates of each pixel covered by the triangle.
+//
+// Pixels are covered if their centre lies within the triangle, with edges shared between two
+// triangles covered only once (the "top-left" rule).
+fn cover_triangle<F>(dimensions: (u32, u32), scizzor: Scizzor, vs: [&mesh::Vertex; 3], mut f: F)
+where
+    F: FnMut(u32, u32, [f32; 3]),
+{
+    let (w, h) = (dimensions.0 as f32, dimensions.1 as f32);
+
+    // Convert from normalised coords to pixel coords, where [0.0, 0.0] is the top-left.
+    let to_px = |v: &mesh::Vertex| {
+        [
+            (v.position[0] + 1.0) * 0.5 * w,
+            (v.position[1] + 1.0) * 0.5 * h,
+        ]
+    };
+    let mut p = [to_px(vs[0]), to_px(vs[1]), to_px(vs[2])];
+
+    // Ensure a consistent winding so that the edge functions are positive inside the triangle.
+    let area = edge(p[0], p[1], p[2]);
+    if area == 0.0 || !area.is_finite() {
+        return;
+    }
+    let swapped = area < 0.0;
+    if swapped {
+        p.swap(1, 2);
+    }
+    let area = area.abs();
+
+    // The bounding box of the triangle, cropped to the scizzor.
+    let min_x = p
+        .iter()
+        .map(|p| p[0])
+        .fold(f32::MAX, f32::min)
+        .floor()
+        .max(scizzor.left as f32);
+    let min_y = p
+        .iter()
+        .map(|p| p[1])
+        .fold(f32::MAX, f32::min)
+        .floor()
+        .max(scizzor.top as f32);
+    let max_x = p
+        .iter()
+        .map(|p| p[0])
+        .fold(f32::MIN, f32::max)
+        .ceil()
+        .min(scizzor.right as f32);
+    let max_y = p
+        .iter()
+        .map(|p| p[1])
+        .fold(f32::MIN, f32::max)
+        .ceil()
+        .min(scizzor.bottom as f32);
+    if min_x >= max_x || min_y >= max_y {
+        return;
+    }
+
+    let is_top_left = |a: [f32; 2], b: [f32; 2]| {
+        let (dx, dy) = (b[0] - a[0], b[1] - a[1]);
+        (dy == 0.0 && dx > 0.0) || dy < 0.0
+    };
+    let top_left = [
+        is_top_left(p[1], p[2]),
+        is_top_left(p[2], p[0]),
+        is_top_left(p[0], p[1]),
+    ];
+
+    for y in min_y as u32..max_y as u32 {
+        for x in min_x as u32..max_x as u32 {
+            let c = [x as f32 + 0.5, y as f32 + 0.5];
+            let ws = [
+                edge(p[1], p[2], c),
+                edge(p[2], p[0], c),
+                edge(p[0], p[1], c),
+            ];
+            let inside = ws
+                .iter()
+                .zip(top_left.iter())
+                .all(|(&w, &tl)| w > 0.0 || (w == 0.0 && tl));
+            if !inside {
+                continue;
+            }
+            // Map the barycentric coordinates back to the original order of the vertices.
+            let mut b = [ws[0] / area, ws[1] / area, ws[2] / area];
+            if swapped {
+                b.swap(1, 2);
+            }
+            f(x, y, b);
+        }
+    }
+}
+
+// Twice the signed area of the triangle `a`, `b`, `c`.
+fn edge(a: [f32; 2], b: [f32; 2], c: [f32; 2]) -> f32 {
+    (b[0] - a[0]) * (c[1] - a[1]) - (b[1] - a[1]) * (c[0] - a[0])
+}
+
+// Bilinearly sample a value from a texture of the given dimensions.
+fn sample_bilinear<F>(dimensions: (u32, u32), uv: [f32; 2], fetch: F) -> [f32; 4]
+where
+    F: Fn(u32, u32) -> [f32; 4],
+{
+    let (w, h) = dimensions;
+    if w == 0 || h == 0 {
+        return [0.0; 4];
+    }
+    let x = uv[0] * w as f32 - 0.5;
+    let y = uv[1] * h as f32 - 0.5;
+    let (x0, y0) = (x.floor(), y.floor());
+    let (tx, ty) = (x - x0, y - y0);
+    let clamp = |v: f32, max: u32| v.max(0.0).min((max - 1) as f32) as u32;
+    let (xa, xb) = (clamp(x0, w), clamp(x0 + 1.0, w));
+    let (ya, yb) = (clamp(y0, h), clamp(y0 + 1.0, h));
+    let (a, b, c, d) = (fetch(xa, ya), fetch(xb, ya), fetch(xa, yb), fetch(xb, yb));
+    let mut out = [0.0; 4];
+    for i in 0..4 {
+        let top = a[i] + (b[i] - a[i]) * tx;
+        let bottom = c[i] + (d[i] - c[i]) * tx;
+        out[i] = top + (bottom - top) * ty;
+    }
+    out
+}
+
+fn sample_glyph_cache(glyphs: &GlyphCacheTexture, uv: [f32; 2]) -> f32 {
+    let (w, _) = glyphs.dimensions;
+    let fetch = |x: u32, y: u32| {
+        let a = glyphs.data[y as usize * w as usize + x as usize] as f32 / 255.0;
+        [a, a, a, a]
+    };
+    sample_bilinear(glyphs.dimensions, uv, fetch)[3]
+}
+
+fn sample_image(image: &Image, uv: [f32; 2]) -> [f32; 4] {
+    let fetch = |x: u32, y: u32| {
+        let ix = image.index(x, y);
+        let p = &image.data[ix..ix + CHANNELS];
+        [
+            srgb_to_linear(p[0]),
+            srgb_to_linear(p[1]),
+            srgb_to_linear(p[2]),
+            p[3] as f32 / 255.0,
+        ]
+    };
+    sample_bilinear((image.width, image.height), uv, fetch)
+}
+
+// Blend the given linear, non-premultiplied color over the pixel at the given position.
+fn blend(target: &mut Image, x: u32, y: u32, src: [f32; 4]) {
+    let src_a = src[3].clamp(0.0, 1.0);
+    if src_a <= 0.0 {
+        return;
+    }
+    let ix = target.index(x, y);
+    let dst = &mut target.data[ix..ix + CHANNELS];
+    let dst_a = dst[3] as f32 / 255.0;
+    for i in 0..3 {
+        let d = srgb_to_linear(dst[i]);
+        let c = src[i] * src_a + d * (1.0 - src_a);
+        dst[i] = linear_to_srgb(c);
+    }
+    let a = src_a + dst_a * (1.0 - src_a);
+    dst[3] = color::f32_to_byte(a.clamp(0.0, 1.0));
+}
+
+// Composite a layer over the target with the given opacity.
+//
+// Layers begin fully transparent, so the colors blended onto them are premultiplied by alpha.
+fn composite(target: &mut Image, layer: &Image, opacity: f32) {
+    let opacity = opacity.clamp(0.0, 1.0);
+    let pixels = target.data.chunks_exact_mut(CHANNELS);
+    for (dst, src) in pixels.zip(layer.data.chunks_exact(CHANNELS)) {
+        let src_a = src[3] as f32 / 255.0 * opacity;
+        if src_a <= 0.0 {
+            continue;
+        }
+        let dst_a = dst[3] as f32 / 255.0;
+        for i in 0..3 {
+            let c = srgb_to_linear(src[i]) * opacity + srgb_to_linear(dst[i]) * (1.0 - src_a);
+            dst[i] = linear_to_srgb(c);
+        }
+        let a = src_a + dst_a * (1.0 - src_a);
+        dst[3] = color::f32_to_byte(a.clamp(0.0, 1.0));
+    }
+}
+
+fn srgb_to_linear(c: u8) -> f32 {
+    let f = c as f32 / 255.0;
+    if f <= 0.04045 {
+        f / 12.92
+    } else {
+        ((f + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+fn linear_to_srgb(f: f32) -> u8 {
+    let f = f.clamp(0.0, 1.0);
+    let s = if f <= 0.003_130_8 {
+        f * 12.92
+    } else {
+        1.055 * f.powf(1.0 / 2.4) - 0.055
+    };
+    (s * 255.0).round() as u8
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,396 @@
+//! Golden-image snapshot testing of `Ui` frames.
+//!
+//! A `Snapshot` describes a headless `Ui` with a fixed window size and font. Calling
+//! `Snapshot::compare` instantiates the user's widgets, renders the resulting frame with the
+//! software `Renderer` and compares it against a stored "golden" PNG. When the frame differs by
+//! more than the configured tolerance, an image highlighting the differing pixels is written next
+//! to the golden image so that the regression can be inspected.
+//!
+//! If the `CONROD_UPDATE_SNAPSHOTS` environment variable is set, the rendered frame is written as
+//! the new golden image instead. Otherwise a missing golden image is an error, so that a snapshot
+//! that was never committed cannot pass silently.
+//!
+//! ```no_run
+//! use conrod_core::{widget, widget_ids, Labelable, Positionable, Widget};
+//! use conrod_software::snapshot::Snapshot;
+//!
+//! widget_ids!(struct Ids { button });
+//!
+//! Snapshot::new([200, 100])
+//!     .font_from_file("assets/fonts/NotoSans/NotoSans-Regular.ttf")
+//!     .unwrap()
+//!     .assert_matches("tests/snapshots/button.png", |ui| {
+//!         let ids = Ids::new(ui.widget_id_generator());
+//!         widget::Button::new()
+//!             .label("Press")
+//!             .middle_of(ui.window)
+//!             .set(ids.button, ui);
+//!     });
+//! ```
+
+use crate::{Image, Renderer};
+use conrod_core::{color, image, text, Color, Theme, UiBuilder, UiCell};
+use std::path::{Path, PathBuf};
+
+/// The environment variable that, when set, causes golden images to be overwritten with the
+/// newly rendered frame.
+pub const UPDATE_ENV_VAR: &str = "CONROD_UPDATE_SNAPSHOTS";
+
+/// The default maximum difference allowed between two channels of a pixel.
+pub const DEFAULT_TOLERANCE: u8 = 2;
+
+/// The color used to highlight differing pixels within a diff image.
+const DIFF_HIGHLIGHT: [u8; 4] = [255, 0, 255, 255];
+
+/// Describes how to build, render and compare a single `Ui` frame.
+pub struct Snapshot {
+    dimensions: [u32; 2],
+    scale_factor: f64,
+    theme: Option<Box<dyn Fn() -> Theme>>,
+    font: Option<text::Font>,
+    background: Color,
+    tolerance: u8,
+    max_differing_pixels: usize,
+}
+
+/// The result of comparing two images of the same dimensions.
+#[derive(Clone, Debug)]
+pub struct Diff {
+    /// The number of pixels with at least one channel differing by more than the tolerance.
+    pub differing_pixels: usize,
+    /// The greatest difference found between any two channels.
+    pub max_channel_difference: u8,
+    /// A faded copy of the expected image with all differing pixels highlighted.
+    pub image: Image,
+}
+
+/// The possible errors that may occur while comparing a frame against a golden image.
+#[derive(Debug)]
+pub enum Error {
+    /// Failed to load the font used to render text.
+    Font(text::font::Error),
+    /// Failed to read or write an image file.
+    Image(::image::ImageError),
+    /// There is no golden image at the given path. Set `UPDATE_ENV_VAR` to write one.
+    MissingGolden(PathBuf),
+    /// The rendered frame's dimensions do not match those of the golden image.
+    DimensionMismatch {
+        /// The dimensions of the golden image.
+        expected: [u32; 2],
+        /// The dimensions of the rendered frame.
+        actual: [u32; 2],
+    },
+    /// The rendered frame differs from the golden image by more than the allowed tolerance.
+    Mismatch {
+        /// The number of pixels that differed beyond the tolerance.
+        differing_pixels: usize,
+        /// The greatest difference found between any two channels.
+        max_channel_difference: u8,
+        /// The path to which the diff image was written.
+        diff_path: PathBuf,
+    },
+}
+
+impl Snapshot {
+    /// Begin building a snapshot of a `Ui` with the given window dimensions in pixels.
+    pub fn new(dimensions: [u32; 2]) -> Self {
+        Snapshot {
+            dimensions,
+            scale_factor: 1.0,
+            theme: None,
+            font: None,
+            background: color::BLACK,
+            tolerance: DEFAULT_TOLERANCE,
+            max_differing_pixels: 0,
+        }
+    }
+
+    /// The factor used to convert from the `Ui`'s point space to pixels.
+    ///
+    /// The `Ui` is built with the window dimensions divided by this factor.
+    pub fn scale_factor(mut self, scale_factor: f64) -> Self {
+        self.scale_factor = scale_factor;
+        self
+    }
+
+    /// A function producing the `Theme` with which the `Ui` is built.
+    ///
+    /// A function is used rather than a `Theme` as a fresh `Ui` is built for every render.
+    pub fn theme<F>(mut self, theme: F) -> Self
+    where
+        F: 'static + Fn() -> Theme,
+    {
+        self.theme = Some(Box::new(theme));
+        self
+    }
+
+    /// The font used as the `Ui`'s default font.
+    pub fn font(mut self, font: text::Font) -> Self {
+        self.font = Some(font);
+        self
+    }
+
+    /// Load the `Ui`'s default font from the given file.
+    pub fn font_from_file<P>(self, path: P) -> Result<Self, Error>
+    where
+        P: AsRef<Path>,
+    {
+        let font = text::font::from_file(path).map_err(Error::Font)?;
+        Ok(self.font(font))
+    }
+
+    /// The color with which the frame is cleared before rendering.
+    pub fn background(mut self, color: Color) -> Self {
+        self.background = color;
+        self
+    }
+
+    /// The maximum difference allowed between two channels of a pixel before the pixel is
+    /// considered to differ.
+    ///
+    /// By default this is `DEFAULT_TOLERANCE`, leaving room for floating point differences in
+    /// rasterization between platforms.
+    pub fn tolerance(mut self, tolerance: u8) -> Self {
+        self.tolerance = tolerance;
+        self
+    }
+
+    /// The number of differing pixels allowed before the comparison fails.
+    ///
+    /// By default this is `0`.
+    pub fn max_differing_pixels(mut self, max: usize) -> Self {
+        self.max_differing_pixels = max;
+        self
+    }
+
+    /// Build the `Ui`, instantiate widgets using the given function and render the frame.
+    pub fn render<F>(&self, set_widgets: F) -> Image
+    where
+        F: FnOnce(&mut UiCell),
+    {
+        self.render_with_images(&image::Map::new(), set_widgets)
+    }
+
+    /// The same as `render` but with a map of images that may be referred to by `Image` widgets.
+    pub fn render_with_images<F>(&self, image_map: &image::Map<Image>, set_widgets: F) -> Image
+    where
+        F: FnOnce(&mut UiCell),
+    {
+        let [w, h] = self.dimensions;
+        let ui_dims = [w as f64 / self.scale_factor, h as f64 / self.scale_factor];
+        let mut builder = UiBuilder::new(ui_dims);
+        if let Some(ref theme) = self.theme {
+            builder = builder.theme(theme());
+        }
+        let mut ui = builder.build();
+        if let Some(ref font) = self.font {
+            ui.fonts.insert(font.clone());
+        }
+
+        set_widgets(&mut ui.set_widgets());
+
+        let mut target = Image::new(w, h);
+        target.clear(self.background);
+        Renderer::new()
+            .render(
+                image_map,
+                self.scale_factor,
+                ui.draw().with_isolated_layers(),
+                &mut target,
+            )
+            .expect("failed to cache glyphs");
+        target
+    }
+
+    /// Render the frame and compare it against the golden PNG image at the given path.
+    ///
+    /// If `UPDATE_ENV_VAR` is set, the rendered frame is written to the path and `Ok` is returned.
+    /// Otherwise, `Error::MissingGolden` is returned if there is no golden image.
+    ///
+    /// On mismatch, a diff image is written alongside the golden image with the `.diff.png`
+    /// extension.
+    pub fn compare<P, F>(&self, golden: P, set_widgets: F) -> Result<(), Error>
+    where
+        P: AsRef<Path>,
+        F: FnOnce(&mut UiCell),
+    {
+        let actual = self.render(set_widgets);
+        self.compare_image(golden, &actual)
+    }
+
+    /// Compare an already rendered frame against the golden PNG image at the given path.
+    ///
+    /// See `compare` for details.
+    pub fn compare_image<P>(&self, golden: P, actual: &Image) -> Result<(), Error>
+    where
+        P: AsRef<Path>,
+    {
+        let golden = golden.as_ref();
+        if std::env::var_os(UPDATE_ENV_VAR).is_some() {
+            return save_png(golden, actual);
+        }
+        if !golden.exists() {
+            return Err(Error::MissingGolden(golden.to_path_buf()));
+        }
+
+        let expected = load_png(golden)?;
+        if [expected.width, expected.height] != [actual.width, actual.height] {
+            return Err(Error::DimensionMismatch {
+                expected: [expected.width, expected.height],
+                actual: [actual.width, actual.height],
+            });
+        }
+
+        let diff = diff(&expected, actual, self.tolerance);
+        let diff_path = diff_path(golden);
+        if diff.differing_pixels <= self.max_differing_pixels {
+            // Remove any stale diff from a previous failure.
+            let _ = std::fs::remove_file(&diff_path);
+            return Ok(());
+        }
+
+        save_png(&diff_path, &diff.image)?;
+        Err(Error::Mismatch {
+            differing_pixels: diff.differing_pixels,
+            max_channel_difference: diff.max_channel_difference,
+            diff_path,
+        })
+    }
+
+    /// The same as `compare` but panics with a descriptive message on failure.
+    pub fn assert_matches<P, F>(&self, golden: P, set_widgets: F)
+    where
+        P: AsRef<Path>,
+        F: FnOnce(&mut UiCell),
+    {
+        let golden = golden.as_ref();
+        if let Err(err) = self.compare(golden, set_widgets) {
+            panic!("snapshot `{}` failed: {}", golden.display(), err);
+        }
+    }
+}
+
+/// Compare the two images pixel by pixel.
+///
+/// A pixel is considered to differ if any of its channels differ by more than `tolerance`.
+///
+/// **Panics** if the images do not have the same dimensions.
+pub fn diff(expected: &Image, actual: &Image, tolerance: u8) -> Diff {
+    assert_eq!(
+        [expected.width, expected.height],
+        [actual.width, actual.height],
+        "cannot diff images of differing dimensions"
+    );
+    let mut image = Image::new(expected.width, expected.height);
+    let mut differing_pixels = 0;
+    let mut max_channel_difference = 0;
+    let pixels = expected
+        .data
+        .chunks(4)
+        .zip(actual.data.chunks(4))
+        .zip(image.data.chunks_mut(4));
+    for ((e, a), d) in pixels {
+        let max = e
+            .iter()
+            .zip(a)
+            .map(|(&e, &a)| (e as i16 - a as i16).unsigned_abs() as u8)
+            .max()
+            .unwrap_or(0);
+        max_channel_difference = std::cmp::max(max_channel_difference, max);
+        if max > tolerance {
+            differing_pixels += 1;
+            d.copy_from_slice(&DIFF_HIGHLIGHT);
+        } else {
+            // Fade matching pixels so that the highlighted ones stand out.
+            for (d, &e) in d.iter_mut().zip(e).take(3) {
+                *d = e / 4;
+            }
+            d[3] = 255;
+        }
+    }
+    Diff {
+        differing_pixels,
+        max_channel_difference,
+        image,
+    }
+}
+
+/// Load a PNG file as an `Image`.
+pub fn load_png<P>(path: P) -> Result<Image, Error>
+where
+    P: AsRef<Path>,
+{
+    let rgba = ::image::open(path).map_err(Error::Image)?.to_rgba8();
+    let (w, h) = rgba.dimensions();
+    Ok(Image::from_rgba(w, h, rgba.into_raw()).expect("unexpected RGBA buffer length"))
+}
+
+/// Write the `Image` to the given path as a PNG file, creating parent directories if necessary.
+pub fn save_png<P>(path: P, image: &Image) -> Result<(), Error>
+where
+    P: AsRef<Path>,
+{
+    let path = path.as_ref();
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|err| Error::Image(::image::ImageError::IoError(err)))?;
+    }
+    ::image::save_buffer(
+        path,
+        &image.data,
+        image.width,
+        image.height,
+        ::image::ColorType::Rgba8,
+    )
+    .map_err(Error::Image)
+}
+
+// The path to which the diff image for the given golden image is written.
+fn diff_path(golden: &Path) -> PathBuf {
+    let stem = golden
+        .file_stem()
+        .map(|s| s.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    golden.with_file_name(format!("{}.diff.png", stem))
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match *self {
+            Error::Font(ref e) => Some(e),
+            Error::Image(ref e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match *self {
+            Error::Font(ref e) => std::fmt::Display::fmt(e, f),
+            Error::Image(ref e) => std::fmt::Display::fmt(e, f),
+            Error::MissingGolden(ref path) => write!(
+                f,
+                "no golden image at `{}`, run with `{}=1` to write it",
+                path.display(),
+                UPDATE_ENV_VAR
+            ),
+            Error::DimensionMismatch { expected, actual } => write!(
+                f,
+                "expected a {}x{} image but rendered {}x{}",
+                expected[0], expected[1], actual[0], actual[1]
+            ),
+            Error::Mismatch {
+                differing_pixels,
+                max_channel_difference,
+                ref diff_path,
+            } => write!(
+                f,
+                "{} pixels differ (max channel difference {}), see `{}`",
+                differing_pixels,
+                max_channel_difference,
+                diff_path.display()
+            ),
+        }
+    }
+}
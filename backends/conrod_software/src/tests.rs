@@ -0,0 +1,218 @@
+use super::{Image, Renderer};
+use conrod_core::{
+    color, position::ClipShape, widget, widget_ids, Borderable, Colorable, Positionable, Sizeable,
+    Widget,
+};
+
+widget_ids! {
+    struct Ids { rect, overlap, canvas }
+}
+
+fn render(w: u32, h: u32, set: impl FnOnce(&mut conrod_core::UiCell, &Ids)) -> Image {
+    let mut ui = conrod_core::UiBuilder::new([w as f64, h as f64]).build();
+    let ids = Ids::new(ui.widget_id_generator());
+    set(&mut ui.set_widgets(), &ids);
+    let image_map = conrod_core::image::Map::new();
+    let mut renderer = Renderer::new();
+    let mut target = Image::new(w, h);
+    target.clear(color::BLACK);
+    renderer
+        .render(
+            &image_map,
+            1.0,
+            ui.draw().with_isolated_layers(),
+            &mut target,
+        )
+        .unwrap();
+    target
+}
+
+#[test]
+fn image_clear_and_pixel() {
+    let mut image = Image::new(2, 3);
+    assert_eq!(image.data.len(), 2 * 3 * 4);
+    assert_eq!(image.pixel(1, 2), Some([0, 0, 0, 0]));
+    image.clear(color::rgba(1.0, 0.0, 0.0, 1.0));
+    assert_eq!(image.pixel(0, 0), Some([255, 0, 0, 255]));
+    assert_eq!(image.pixel(2, 0), None);
+    assert!(Image::from_rgba(2, 2, vec![0; 3]).is_none());
+}
+
+#[test]
+fn rectangle_fills_expected_pixels() {
+    let image = render(40, 30, |ui, ids| {
+        widget::Rectangle::fill([20.0, 10.0])
+            .top_left_of(ui.window)
+            .color(color::WHITE)
+            .set(ids.rect, ui);
+    });
+    // Inside the rectangle.
+    assert_eq!(image.pixel(0, 0), Some([255, 255, 255, 255]));
+    assert_eq!(image.pixel(19, 9), Some([255, 255, 255, 255]));
+    // Outside the rectangle.
+    assert_eq!(image.pixel(20, 0), Some([0, 0, 0, 255]));
+    assert_eq!(image.pixel(0, 10), Some([0, 0, 0, 255]));
+    assert_eq!(image.pixel(39, 29), Some([0, 0, 0, 255]));
+}
+
+#[test]
+fn translucent_geometry_blends_over_target() {
+    let image = render(10, 10, |ui, ids| {
+        widget::Rectangle::fill_with([10.0, 10.0], color::WHITE.alpha(0.5))
+            .middle_of(ui.window)
+            .set(ids.rect, ui);
+    });
+    let [r, g, b, a] = image.pixel(5, 5).unwrap();
+    assert_eq!(a, 255);
+    assert!(r == g && g == b);
+    assert!(r > 100 && r < 255, "unexpected blended value {}", r);
+}
+
+#[test]
+fn scizzor_crops_children() {
+    let image = render(40, 40, |ui, ids| {
+        widget::Canvas::new()
+            .wh([20.0, 20.0])
+            .top_left_of(ui.window)
+            .color(color::BLACK)
+            .border(0.0)
+            .crop_kids()
+            .set(ids.canvas, ui);
+        widget::Rectangle::fill_with([40.0, 40.0], color::WHITE)
+            .top_left_of(ids.canvas)
+            .parent(ids.canvas)
+            .set(ids.rect, ui);
+    });
+    assert_eq!(image.pixel(10, 10), Some([255, 255, 255, 255]));
+    assert_eq!(image.pixel(30, 30), Some([0, 0, 0, 255]));
+    assert_eq!(image.pixel(30, 5), Some([0, 0, 0, 255]));
+}
+
+#[test]
+fn isolated_layer_composites_children_once() {
+    let render_overlapping = |isolated: bool| {
+        render(30, 10, |ui, ids| {
+            let canvas = widget::Canvas::new()
+                .wh_of(ui.window)
+                .middle_of(ui.window)
+                .color(color::TRANSPARENT)
+                .border(0.0)
+                .opacity(0.5);
+            let canvas = if isolated {
+                canvas.isolated_layer()
+            } else {
+                canvas
+            };
+            canvas.set(ids.canvas, ui);
+            widget::Rectangle::fill_with([20.0, 10.0], color::WHITE)
+                .top_left_of(ids.canvas)
+                .parent(ids.canvas)
+                .set(ids.rect, ui);
+            widget::Rectangle::fill_with([20.0, 10.0], color::WHITE)
+                .top_right_of(ids.canvas)
+                .parent(ids.canvas)
+                .set(ids.overlap, ui);
+        })
+    };
+
+    // Without isolation, the overlapping region shows through and appears brighter.
+    let image = render_overlapping(false);
+    assert!(image.pixel(15, 5).unwrap()[0] > image.pixel(5, 5).unwrap()[0]);
+
+    // With isolation, the children are composited as one.
+    let image = render_overlapping(true);
+    let [r, _, _, a] = image.pixel(5, 5).unwrap();
+    assert!(r > 100 && r < 255, "unexpected composited value {}", r);
+    assert_eq!(a, 255);
+    assert_eq!(image.pixel(15, 5), image.pixel(5, 5));
+}
+
+#[test]
+fn clip_shape_masks_rounded_corners() {
+    let image = render(40, 40, |ui, ids| {
+        widget::Canvas::new()
+            .wh_of(ui.window)
+            .middle_of(ui.window)
+            .color(color::BLACK)
+            .border(0.0)
+            .crop_kids_to(ClipShape::RoundedRectangle { radius: 15.0 })
+            .set(ids.canvas, ui);
+        widget::Rectangle::fill_with([40.0, 40.0], color::WHITE)
+            .middle_of(ids.canvas)
+            .parent(ids.canvas)
+            .set(ids.rect, ui);
+    });
+    // The centre and the middle of each edge are within the rounded rectangle.
+    assert_eq!(image.pixel(20, 20), Some([255, 255, 255, 255]));
+    assert_eq!(image.pixel(0, 20), Some([255, 255, 255, 255]));
+    assert_eq!(image.pixel(20, 39), Some([255, 255, 255, 255]));
+    // The corners are clipped.
+    assert_eq!(image.pixel(1, 1), Some([0, 0, 0, 255]));
+    assert_eq!(image.pixel(38, 38), Some([0, 0, 0, 255]));
+}
+
+#[test]
+fn shadow_fades_outside_of_cropped_canvas() {
+    let image = render(60, 60, |ui, ids| {
+        widget::Canvas::new()
+            .wh_of(ui.window)
+            .middle_of(ui.window)
+            .color(color::WHITE)
+            .border(0.0)
+            .set(ids.canvas, ui);
+        let shadow = widget::shadow::Style {
+            color: Some(color::BLACK),
+            offset: Some([0.0, 0.0]),
+            blur_radius: Some(10.0),
+            spread: Some(0.0),
+        };
+        widget::Canvas::new()
+            .w_h(20.0, 20.0)
+            .middle_of(ids.canvas)
+            .parent(ids.canvas)
+            .color(color::WHITE)
+            .border(0.0)
+            .crop_kids()
+            .shadow(shadow)
+            .set(ids.rect, ui);
+    });
+    // The canvas covers its own shadow.
+    assert_eq!(image.pixel(30, 30), Some([255, 255, 255, 255]));
+    // The shadow is not cropped by the canvas and fades away from its edge.
+    let near = image.pixel(41, 30).unwrap();
+    let far = image.pixel(43, 30).unwrap();
+    assert!(near[0] < far[0]);
+    assert!(far[0] < 255);
+    assert_eq!(image.pixel(50, 30), Some([255, 255, 255, 255]));
+}
+
+#[test]
+fn atlased_images_sample_their_own_region() {
+    let mut image_map = conrod_core::image::Map::new();
+    let red: Vec<u8> = (0..4 * 4).flat_map(|_| vec![255, 0, 0, 255]).collect();
+    let blue: Vec<u8> = (0..4 * 4).flat_map(|_| vec![0, 0, 255, 255]).collect();
+    let red_id = image_map.insert_atlased([4, 4], &red);
+    let blue_id = image_map.insert_atlased([4, 4], &blue);
+    image_map.upload_atlas_pages(|[w, h], pixels| Image::from_rgba(w, h, pixels.to_vec()).unwrap());
+
+    let mut ui = conrod_core::UiBuilder::new([40.0, 20.0]).build();
+    let ids = Ids::new(ui.widget_id_generator());
+    {
+        let ui = &mut ui.set_widgets();
+        widget::Image::new(red_id)
+            .w_h(20.0, 20.0)
+            .x_y(-10.0, 0.0)
+            .set(ids.rect, ui);
+        widget::Image::new(blue_id)
+            .w_h(20.0, 20.0)
+            .x_y(10.0, 0.0)
+            .set(ids.overlap, ui);
+    }
+    let mut target = Image::new(40, 20);
+    target.clear(color::BLACK);
+    Renderer::new()
+        .render(&image_map, 1.0, ui.draw(), &mut target)
+        .unwrap();
+    assert_eq!(target.pixel(10, 10), Some([255, 0, 0, 255]));
+    assert_eq!(target.pixel(30, 10), Some([0, 0, 255, 255]));
+}
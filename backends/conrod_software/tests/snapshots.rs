@@ -0,0 +1,124 @@
+//! Golden-image tests for a selection of conrod's widgets.
+//!
+//! Run with `CONROD_UPDATE_SNAPSHOTS=1` to regenerate the images in `tests/snapshots` after an
+//! intentional visual change.
+
+use conrod_core::{
+    color, widget, widget_ids, Colorable, Labelable, Positionable, Sizeable, Widget,
+};
+use conrod_software::snapshot::{self, Snapshot};
+use std::path::PathBuf;
+
+const WIN_W: u32 = 240;
+const WIN_H: u32 = 160;
+
+fn snapshot() -> Snapshot {
+    let assets = find_folder::Search::KidsThenParents(3, 5)
+        .for_folder("assets")
+        .unwrap();
+    Snapshot::new([WIN_W, WIN_H])
+        .font_from_file(assets.join("fonts/NotoSans/NotoSans-Regular.ttf"))
+        .unwrap()
+        .background(color::DARK_CHARCOAL)
+}
+
+fn golden(name: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join("snapshots")
+        .join(format!("{}.png", name))
+}
+
+#[test]
+fn button() {
+    widget_ids!(struct Ids { button });
+    snapshot().assert_matches(golden("button"), |ui| {
+        let ids = Ids::new(ui.widget_id_generator());
+        widget::Button::new()
+            .w_h(120.0, 40.0)
+            .middle_of(ui.window)
+            .color(color::LIGHT_BLUE)
+            .label("Press me")
+            .set(ids.button, ui);
+    });
+}
+
+#[test]
+fn tabs() {
+    widget_ids!(struct Ids { tabs, foo, bar, baz });
+    snapshot().assert_matches(golden("tabs"), |ui| {
+        let ids = Ids::new(ui.widget_id_generator());
+        widget::Tabs::new(&[(ids.foo, "FOO"), (ids.bar, "BAR"), (ids.baz, "BAZ")])
+            .wh_of(ui.window)
+            .middle_of(ui.window)
+            .color(color::BLUE)
+            .label_color(color::WHITE)
+            .starting_canvas(ids.bar)
+            .set(ids.tabs, ui);
+    });
+}
+
+#[test]
+fn envelope_editor() {
+    widget_ids!(struct Ids { envelope_editor });
+    let envelope = vec![[0.0, 0.0], [0.25, 0.8], [0.6, 0.3], [1.0, 0.5]];
+    snapshot().assert_matches(golden("envelope_editor"), |ui| {
+        let ids = Ids::new(ui.widget_id_generator());
+        widget::EnvelopeEditor::new(&envelope, 0.0, 1.0, 0.0, 1.0)
+            .w_h(200.0, 120.0)
+            .middle_of(ui.window)
+            .color(color::LIGHT_YELLOW)
+            .point_radius(4.0)
+            .line_thickness(2.0)
+            .set(ids.envelope_editor, ui);
+    });
+}
+
+#[test]
+fn mismatch_writes_diff_image() {
+    widget_ids!(struct Ids { rect });
+    let dir = std::env::temp_dir().join("conrod_software_snapshot_mismatch");
+    let golden = dir.join("rect.png");
+    let diff = dir.join("rect.diff.png");
+    let _ = std::fs::remove_dir_all(&dir);
+
+    let set_rect = |color| {
+        move |ui: &mut conrod_core::UiCell| {
+            let ids = Ids::new(ui.widget_id_generator());
+            widget::Rectangle::fill_with([20.0, 20.0], color)
+                .middle_of(ui.window)
+                .set(ids.rect, ui);
+        }
+    };
+
+    // A missing golden image fails rather than being written.
+    match snapshot().compare(&golden, set_rect(color::WHITE)) {
+        Err(snapshot::Error::MissingGolden(path)) => assert_eq!(path, golden),
+        other => panic!("expected a missing golden image, found {:?}", other),
+    }
+    assert!(!golden.exists());
+
+    // Write a golden image of a white rectangle, then compare a red rectangle against it.
+    let white = snapshot().render(set_rect(color::WHITE));
+    snapshot::save_png(&golden, &white).unwrap();
+    match snapshot().compare(&golden, set_rect(color::RED)) {
+        Err(snapshot::Error::Mismatch {
+            differing_pixels,
+            diff_path,
+            ..
+        }) => {
+            assert_eq!(differing_pixels, 20 * 20);
+            assert_eq!(diff_path, diff);
+        }
+        other => panic!("expected a mismatch, found {:?}", other),
+    }
+    let diff_image = snapshot::load_png(&diff).unwrap();
+    let [cx, cy] = [WIN_W / 2, WIN_H / 2];
+    assert_eq!(diff_image.pixel(cx, cy), Some([255, 0, 255, 255]));
+    assert_ne!(diff_image.pixel(0, 0), Some([255, 0, 255, 255]));
+
+    // The same frame within tolerance succeeds and removes the stale diff.
+    snapshot().compare(&golden, set_rect(color::WHITE)).unwrap();
+    assert!(!diff.exists());
+    let _ = std::fs::remove_dir_all(&dir);
+}
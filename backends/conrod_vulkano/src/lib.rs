@@ -168,6 +168,8 @@ fn create_conrod_pipeline_layout_desc() -> PipelineLayoutDesc {
 /// A type used for translating `render::Primitives` into `Command`s that indicate how to draw the
 /// conrod GUI using `vulkano`.
 pub struct Renderer {
+    device: Arc<Device>,
+    graphics_queue_family_id: u32,
     pipeline: Arc<dyn GraphicsPipelineAbstract + Send + Sync>,
     glyph_uploads: Arc<CpuBufferPool<u8>>,
     glyph_cache_tex: Arc<StorageImage>,
@@ -310,39 +312,18 @@ impl Renderer {
         );
         let mesh = Mesh::with_glyph_cache_dimensions(glyph_cache_dims);
 
-        let glyph_cache_tex = {
-            let [width, height] = glyph_cache_dims;
-            StorageImage::with_usage(
-                device.clone(),
-                ImageDimensions::Dim2d {
-                    width,
-                    height,
-                    array_layers: 1,
-                },
-                R8Unorm,
-                ImageUsage {
-                    transfer_destination: true,
-                    sampled: true,
-                    ..ImageUsage::none()
-                },
-                ImageCreateFlags {
-                    sparse_binding: false,
-                    sparse_residency: false,
-                    sparse_aliased: false,
-                    mutable_format: false,
-                    cube_compatible: false,
-                    array_2d_compatible: false,
-                },
-                vec![graphics_queue_family],
-            )?
-        };
+        let glyph_cache_tex =
+            glyph_cache_tex(device.clone(), glyph_cache_dims, graphics_queue_family)?;
 
         let tex_descs = FixedSizeDescriptorSetsPool::new(
             pipeline.layout().descriptor_set_layout(0).unwrap().clone(),
         );
-        let glyph_uploads = Arc::new(CpuBufferPool::upload(device));
+        let glyph_uploads = Arc::new(CpuBufferPool::upload(device.clone()));
+        let graphics_queue_family_id = graphics_queue_family.id();
 
         Ok(Renderer {
+            device,
+            graphics_queue_family_id,
             pipeline,
             glyph_uploads,
             glyph_cache_tex,
@@ -352,6 +333,14 @@ impl Renderer {
         })
     }
 
+    /// Enable or disable feathered, anti-aliased edges for shape and line geometry.
+    ///
+    /// The `width` of the fringe is given in pixels. See
+    /// `conrod_core::mesh::Mesh::set_feather_width` for details.
+    pub fn set_feather_width(&mut self, width: Option<f64>) {
+        self.mesh.set_feather_width(width);
+    }
+
     /// Produce an `Iterator` yielding `Command`s.
     pub fn commands(&self) -> mesh::Commands {
         self.mesh.commands()
@@ -369,23 +358,32 @@ impl Renderer {
         dpi_factor: f64,
         primitives: P,
     ) -> Result<Option<GlyphCacheCommand>, rt::gpu_cache::CacheWriteErr> {
-        let Renderer {
-            ref glyph_uploads,
-            ref glyph_cache_tex,
-            ref mut mesh,
-            ..
-        } = *self;
         let [vp_l, vp_t, vp_r, vp_b] = viewport;
         let lt = [vp_l as Scalar, vp_t as Scalar];
         let rb = [vp_r as Scalar, vp_b as Scalar];
         let viewport = Rect::from_corners(lt, rb);
-        let fill = mesh.fill(viewport, dpi_factor, image_map, primitives)?;
+        let fill = self
+            .mesh
+            .fill(viewport, dpi_factor, image_map, primitives)?;
+
+        // Reallocate the glyph cache texture if the glyph cache has grown.
+        if fill.glyph_cache_resized {
+            let (width, height) = self.mesh.glyph_cache().dimensions();
+            let physical_device = self.device.physical_device();
+            let queue_family = physical_device
+                .queue_family_by_id(self.graphics_queue_family_id)
+                .expect("no queue family with the given id");
+            self.glyph_cache_tex =
+                glyph_cache_tex(self.device.clone(), [width, height], queue_family)
+                    .expect("failed to reallocate the glyph cache texture");
+        }
+
         let glyph_cache_cmd = match fill.glyph_cache_requires_upload {
             false => None,
             true => Some(GlyphCacheCommand {
-                glyph_cache_pixel_buffer: mesh.glyph_cache_pixel_buffer(),
-                glyph_cpu_buffer_pool: glyph_uploads.clone(),
-                glyph_cache_texture: glyph_cache_tex.clone(),
+                glyph_cache_pixel_buffer: self.mesh.glyph_cache_pixel_buffer(),
+                glyph_cpu_buffer_pool: self.glyph_uploads.clone(),
+                glyph_cache_texture: self.glyph_cache_tex.clone(),
             }),
         };
         Ok(glyph_cache_cmd)
@@ -452,6 +450,11 @@ impl Renderer {
                 // Update the `scizzor` before continuing to draw.
                 mesh::Command::Scizzor(scizzor) => current_scizzor = conv_scizzor(scizzor),
 
+                mesh::Command::BeginLayer | mesh::Command::EndLayer(_) => (),
+
+                // Clip masks are not supported, so children are only cropped to the scizzor.
+                mesh::Command::Mask(_) => (),
+
                 // Draw to the target with the given `draw` command.
                 mesh::Command::Draw(draw) => match draw {
                     // Draw text and plain 2D geometry.
@@ -603,3 +606,35 @@ impl fmt::Display for DrawError {
         }
     }
 }
+
+// Create the GPU image to which glyphs are cached.
+fn glyph_cache_tex(
+    device: Arc<Device>,
+    glyph_cache_dims: [u32; 2],
+    graphics_queue_family: QueueFamily,
+) -> Result<Arc<StorageImage>, ImageCreationError> {
+    let [width, height] = glyph_cache_dims;
+    StorageImage::with_usage(
+        device,
+        ImageDimensions::Dim2d {
+            width,
+            height,
+            array_layers: 1,
+        },
+        R8Unorm,
+        ImageUsage {
+            transfer_destination: true,
+            sampled: true,
+            ..ImageUsage::none()
+        },
+        ImageCreateFlags {
+            sparse_binding: false,
+            sparse_residency: false,
+            sparse_aliased: false,
+            mutable_format: false,
+            cube_compatible: false,
+            array_2d_compatible: false,
+        },
+        vec![graphics_queue_family],
+    )
+}
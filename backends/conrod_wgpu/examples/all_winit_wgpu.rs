@@ -63,11 +63,15 @@ fn main() {
 
     // Create the renderer for rendering conrod primitives.
     let mut renderer = conrod_wgpu::Renderer::new(&device, MSAA_SAMPLES, format);
+    renderer.set_clip_masks(&device, true);
 
     // The intermediary multisampled texture that will be resolved (MSAA).
     let mut multisampled_framebuffer =
         create_multisampled_framebuffer(&device, &surface_config, MSAA_SAMPLES);
 
+    // The stencil buffer to which clip masks are written.
+    let mut clip_mask = create_clip_mask(&device, &surface_config, MSAA_SAMPLES);
+
     // Create Ui and Ids of widgets to instantiate
     let mut ui = conrod_core::UiBuilder::new([WIN_W as f64, WIN_H as f64])
         .theme(conrod_example_shared::theme())
@@ -121,6 +125,7 @@ fn main() {
                     surface.configure(&device, &surface_config);
                     multisampled_framebuffer =
                         create_multisampled_framebuffer(&device, &surface_config, MSAA_SAMPLES);
+                    clip_mask = create_clip_mask(&device, &surface_config, MSAA_SAMPLES);
                 }
 
                 // Close on request or on Escape.
@@ -190,7 +195,7 @@ fn main() {
                 let [win_w, win_h]: [f32; 2] = [size.width as f32, size.height as f32];
                 let viewport = [0.0, 0.0, win_w, win_h];
                 if let Some(cmd) = renderer
-                    .fill(&image_map, viewport, scale_factor, primitives)
+                    .fill(&device, &image_map, viewport, scale_factor, primitives)
                     .unwrap()
                 {
                     cmd.load_buffer_and_encode(&device, &mut encoder);
@@ -218,10 +223,18 @@ fn main() {
                         },
                     };
 
+                    let clip_mask_attachment_desc = wgpu::RenderPassDepthStencilAttachment {
+                        view: &clip_mask,
+                        depth_ops: None,
+                        stencil_ops: Some(wgpu::Operations {
+                            load: wgpu::LoadOp::Clear(0),
+                            store: false,
+                        }),
+                    };
                     let render_pass_desc = wgpu::RenderPassDescriptor {
                         label: Some("conrod_render_pass_descriptor"),
                         color_attachments: &[color_attachment_desc],
-                        depth_stencil_attachment: None,
+                        depth_stencil_attachment: Some(clip_mask_attachment_desc),
                     };
                     let render = renderer.render(&device, &image_map);
 
@@ -249,6 +262,11 @@ fn main() {
                                 conrod_wgpu::RenderPassCommand::Draw { vertex_range } => {
                                     render_pass.draw(vertex_range, instance_range.clone());
                                 }
+                                conrod_wgpu::RenderPassCommand::SetStencilReference {
+                                    reference,
+                                } => {
+                                    render_pass.set_stencil_reference(reference);
+                                }
                             }
                         }
                     }
@@ -285,6 +303,18 @@ fn create_multisampled_framebuffer(
         .create_view(&wgpu::TextureViewDescriptor::default())
 }
 
+fn create_clip_mask(
+    device: &wgpu::Device,
+    surface_config: &wgpu::SurfaceConfiguration,
+    sample_count: u32,
+) -> wgpu::TextureView {
+    let dims = [surface_config.width, surface_config.height];
+    let desc = conrod_wgpu::clip_mask_texture_desc(dims, sample_count);
+    device
+        .create_texture(&desc)
+        .create_view(&wgpu::TextureViewDescriptor::default())
+}
+
 fn create_logo_texture(
     device: &wgpu::Device,
     queue: &mut wgpu::Queue,
@@ -65,6 +65,10 @@ pub struct Renderer {
     bind_groups: HashMap<image::Id, wgpu::BindGroup>,
     // We also need a unique
     render_pipelines: HashMap<wgpu::TextureSampleType, Pipeline>,
+    // The pipelines for writing clip masks to the stencil buffer, if enabled.
+    clip_mask_pipelines: Option<ClipMaskPipelines>,
+    // The dimensions of the viewport in pixels, used to write clip masks beyond the scizzor.
+    viewport_dims: [u32; 2],
 }
 
 /// Data that must be unique per `wgpu::TextureSampleType`, i.e. bind group layout and render
@@ -74,6 +78,14 @@ struct Pipeline {
     render_pipeline: wgpu::RenderPipeline,
 }
 
+/// The render pipelines used to write the outline of a clip mask to the stencil buffer.
+struct ClipMaskPipelines {
+    // Inverts the stencil value of each pixel covered by a triangle of the mask.
+    write: wgpu::RenderPipeline,
+    // Resets the stencil value of each pixel covered by a triangle of the previous mask.
+    clear: wgpu::RenderPipeline,
+}
+
 /// An command for uploading an individual glyph.
 pub struct GlyphCacheCommand<'a> {
     /// The CPU buffer containing the pixel data.
@@ -108,11 +120,18 @@ pub enum RenderPassCommand<'a> {
     /// An image requiring a different bind group layout requires drawing and in turn, we must set
     /// the necessary render pipeline.
     SetPipeline { pipeline: &'a wgpu::RenderPipeline },
+    /// A clip mask has begun or ended and in turn, the stencil reference must be updated.
+    ///
+    /// Only produced when clip masks are enabled via `Renderer::set_clip_masks`.
+    SetStencilReference { reference: u32 },
 }
 
 const GLYPH_TEX_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::R8Unorm;
 const DEFAULT_IMAGE_TEX_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::R8Unorm;
 
+/// The format of the depth-stencil attachment required when clip masks are enabled.
+pub const CLIP_MASK_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Depth24PlusStencil8;
+
 impl mesh::ImageDimensions for Image {
     fn dimensions(&self) -> [u32; 2] {
         [self.width, self.height]
@@ -190,6 +209,7 @@ impl Renderer {
             &fs_mod,
             dst_format,
             dst_sample_count,
+            false,
         );
         let default_bind_group = bind_group(
             device,
@@ -218,10 +238,82 @@ impl Renderer {
             dst_sample_count,
             bind_groups,
             render_pipelines,
+            clip_mask_pipelines: None,
+            viewport_dims: [0, 0],
             mesh,
         }
     }
 
+    /// Enable or disable clipping children to the outline of widgets built with
+    /// `Widget::clip_to_shape`.
+    ///
+    /// When enabled, each clip mask is written to the stencil buffer, so the render pass must have
+    /// a depth-stencil attachment of the `CLIP_MASK_FORMAT` with the same sample count as the
+    /// output attachment, whose stencil is cleared to `0`. See `clip_mask_texture_desc`. The
+    /// `RenderPassCommand::SetStencilReference` commands must also be applied to the render pass.
+    ///
+    /// By default, clip masks are disabled and children are only cropped to the scizzor.
+    pub fn set_clip_masks(&mut self, device: &wgpu::Device, enabled: bool) {
+        if enabled == self.clip_mask_pipelines.is_some() {
+            return;
+        }
+        let Renderer {
+            ref vs_mod,
+            ref fs_mod,
+            ref mut render_pipelines,
+            ref mut clip_mask_pipelines,
+            dst_format,
+            dst_sample_count,
+            ..
+        } = *self;
+
+        // Every pipeline must agree with the presence of the depth-stencil attachment.
+        for pipeline in render_pipelines.values_mut() {
+            let layout = pipeline_layout(device, &pipeline.bind_group_layout);
+            pipeline.render_pipeline = render_pipeline(
+                device,
+                &layout,
+                vs_mod,
+                fs_mod,
+                dst_format,
+                dst_sample_count,
+                enabled,
+            );
+        }
+
+        *clip_mask_pipelines = match enabled {
+            false => None,
+            true => {
+                let default_tct = DEFAULT_IMAGE_TEX_FORMAT.describe().sample_type;
+                let bind_group_layout = &render_pipelines[&default_tct].bind_group_layout;
+                let layout = pipeline_layout(device, bind_group_layout);
+                let pipeline = |op| {
+                    clip_mask_pipeline(
+                        device,
+                        &layout,
+                        vs_mod,
+                        fs_mod,
+                        dst_format,
+                        dst_sample_count,
+                        op,
+                    )
+                };
+                Some(ClipMaskPipelines {
+                    write: pipeline(wgpu::StencilOperation::Invert),
+                    clear: pipeline(wgpu::StencilOperation::Zero),
+                })
+            }
+        };
+    }
+
+    /// Enable or disable feathered, anti-aliased edges for shape and line geometry.
+    ///
+    /// The `width` of the fringe is given in pixels. See
+    /// `conrod_core::mesh::Mesh::set_feather_width` for details.
+    pub fn set_feather_width(&mut self, width: Option<f64>) {
+        self.mesh.set_feather_width(width);
+    }
+
     /// Produce an `Iterator` yielding `Command`s.
     pub fn commands(&self) -> mesh::Commands {
         self.mesh.commands()
@@ -232,8 +324,12 @@ impl Renderer {
     /// This method may return an `Option<GlyphCacheCommand>`, in which case the user should use
     /// the contained `glyph_cpu_buffer_pool` to write the pixel data to the GPU, and then use a
     /// `copy_buffer_to_image` command to write the data to the given `glyph_cache_texture` image.
+    ///
+    /// If the glyph cache had to grow in order to fit all text, the `device` is used to allocate
+    /// a new glyph cache texture with the new dimensions.
     pub fn fill<'a, P>(
         &'a mut self,
+        device: &wgpu::Device,
         image_map: &image::Map<Image>,
         viewport: [f32; 4],
         scale_factor: f64,
@@ -247,10 +343,28 @@ impl Renderer {
         let lt = [vp_l as Scalar, vp_t as Scalar];
         let rb = [vp_r as Scalar, vp_b as Scalar];
         let viewport = Rect::from_corners(lt, rb);
+        self.viewport_dims = [viewport.w() as u32, viewport.h() as u32];
         let fill = self
             .mesh
             .fill(viewport, scale_factor, image_map, primitives)?;
 
+        // Reallocate the glyph cache texture and the bind groups that refer to it if it has grown.
+        if fill.glyph_cache_resized {
+            let (width, height) = self.mesh.glyph_cache().dimensions();
+            let glyph_cache_tex_desc = glyph_cache_tex_desc([width, height]);
+            self.glyph_cache_tex = device.create_texture(&glyph_cache_tex_desc);
+            let default_tct = DEFAULT_IMAGE_TEX_FORMAT.describe().sample_type;
+            let default_pipeline = &self.render_pipelines[&default_tct];
+            self.default_bind_group = bind_group(
+                device,
+                &default_pipeline.bind_group_layout,
+                &self.glyph_cache_tex,
+                &self.sampler,
+                &self._default_image_tex,
+            );
+            self.bind_groups.clear();
+        }
+
         // Check whether or not we need a glyph cache update.
         let glyph_cache_cmd = match fill.glyph_cache_requires_upload {
             false => None,
@@ -280,8 +394,10 @@ impl Renderer {
             ref default_bind_group,
             ref glyph_cache_tex,
             ref sampler,
+            ref clip_mask_pipelines,
             dst_format,
             dst_sample_count,
+            viewport_dims,
             ..
         } = *self;
 
@@ -316,6 +432,7 @@ impl Renderer {
                     fs_mod,
                     dst_format,
                     dst_sample_count,
+                    clip_mask_pipelines.is_some(),
                 );
                 Pipeline {
                     bind_group_layout,
@@ -352,12 +469,19 @@ impl Renderer {
         }
         let mut bind_group = None;
 
+        // Keep track of the scizzor so that it may be restored after writing a clip mask.
+        let mut scissor = ([0, 0], viewport_dims);
+
+        // The vertices of the clip mask currently written to the stencil buffer.
+        let mut current_mask: Option<std::ops::Range<usize>> = None;
+
         for command in mesh.commands() {
             match command {
                 // Update the `scizzor` before continuing to draw.
                 mesh::Command::Scizzor(s) => {
                     let top_left = [s.top_left[0] as u32, s.top_left[1] as u32];
                     let dimensions = s.dimensions;
+                    scissor = (top_left, dimensions);
                     let cmd = RenderPassCommand::SetScissor {
                         top_left,
                         dimensions,
@@ -365,6 +489,50 @@ impl Renderer {
                     commands.push(cmd);
                 }
 
+                mesh::Command::BeginLayer | mesh::Command::EndLayer(_) => (),
+
+                // Replace the clip mask within the stencil buffer with the new one, if any.
+                mesh::Command::Mask(mask) => {
+                    // Without clip masks, children are only cropped to the scizzor.
+                    let pipelines = match *clip_mask_pipelines {
+                        Some(ref pipelines) => pipelines,
+                        None => continue,
+                    };
+
+                    // The mask is written across the whole viewport, as it outlives the scizzor.
+                    commands.push(RenderPassCommand::SetScissor {
+                        top_left: [0, 0],
+                        dimensions: viewport_dims,
+                    });
+                    commands.push(RenderPassCommand::SetBindGroup {
+                        bind_group: default_bind_group,
+                    });
+                    let draws = current_mask
+                        .take()
+                        .map(|range| (&pipelines.clear, range))
+                        .into_iter()
+                        .chain(mask.clone().map(|range| (&pipelines.write, range)));
+                    for (pipeline, range) in draws {
+                        commands.push(RenderPassCommand::SetPipeline { pipeline });
+                        commands.push(RenderPassCommand::Draw {
+                            vertex_range: range.start as u32..range.end as u32,
+                        });
+                    }
+                    current_mask = mask;
+
+                    // Only draw where the mask was written, if any.
+                    let reference = current_mask.is_some() as u32;
+                    commands.push(RenderPassCommand::SetStencilReference { reference });
+                    let (top_left, dimensions) = scissor;
+                    commands.push(RenderPassCommand::SetScissor {
+                        top_left,
+                        dimensions,
+                    });
+
+                    // The pipeline and bind group must be set again before the next draw.
+                    bind_group = None;
+                }
+
                 // Draw to the target with the given `draw` command.
                 mesh::Command::Draw(draw) => match draw {
                     // Draw text and plain 2D geometry.
@@ -686,6 +854,56 @@ fn vertex_attrs() -> [wgpu::VertexAttribute; 4] {
     ]
 }
 
+/// The descriptor for a depth-stencil texture to which clip masks may be written.
+///
+/// The `sample_count` must match that of the output attachment. See `Renderer::set_clip_masks`.
+pub fn clip_mask_texture_desc(
+    [width, height]: [u32; 2],
+    sample_count: u32,
+) -> wgpu::TextureDescriptor<'static> {
+    let depth_or_array_layers = 1;
+    let texture_extent = wgpu::Extent3d {
+        width,
+        height,
+        depth_or_array_layers,
+    };
+    wgpu::TextureDescriptor {
+        label: Some("conrod_wgpu_clip_mask_texture"),
+        size: texture_extent,
+        mip_level_count: 1,
+        sample_count,
+        dimension: wgpu::TextureDimension::D2,
+        format: CLIP_MASK_FORMAT,
+        usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
+    }
+}
+
+// The depth-stencil state for writing clip masks with the given `op`, or for testing against them.
+fn clip_mask_depth_stencil(
+    compare: wgpu::CompareFunction,
+    op: wgpu::StencilOperation,
+) -> wgpu::DepthStencilState {
+    let face = wgpu::StencilFaceState {
+        compare,
+        fail_op: wgpu::StencilOperation::Keep,
+        depth_fail_op: wgpu::StencilOperation::Keep,
+        pass_op: op,
+    };
+    wgpu::DepthStencilState {
+        format: CLIP_MASK_FORMAT,
+        depth_write_enabled: false,
+        depth_compare: wgpu::CompareFunction::Always,
+        stencil: wgpu::StencilState {
+            front: face,
+            back: face,
+            // Only the lowest bit is used, so that inverting it yields the even-odd rule.
+            read_mask: 1,
+            write_mask: 1,
+        },
+        bias: Default::default(),
+    }
+}
+
 fn render_pipeline(
     device: &wgpu::Device,
     layout: &wgpu::PipelineLayout,
@@ -693,8 +911,55 @@ fn render_pipeline(
     fs_mod: &wgpu::ShaderModule,
     dst_format: wgpu::TextureFormat,
     dst_sample_count: u32,
+    clip_masks: bool,
 ) -> wgpu::RenderPipeline {
-    let color_state = wgpu::ColorTargetState {
+    let depth_stencil = match clip_masks {
+        false => None,
+        true => Some(clip_mask_depth_stencil(
+            wgpu::CompareFunction::Equal,
+            wgpu::StencilOperation::Keep,
+        )),
+    };
+    let color_state = color_state(dst_format, wgpu::ColorWrites::ALL);
+    pipeline(
+        device,
+        layout,
+        vs_mod,
+        fs_mod,
+        color_state,
+        dst_sample_count,
+        depth_stencil,
+    )
+}
+
+fn clip_mask_pipeline(
+    device: &wgpu::Device,
+    layout: &wgpu::PipelineLayout,
+    vs_mod: &wgpu::ShaderModule,
+    fs_mod: &wgpu::ShaderModule,
+    dst_format: wgpu::TextureFormat,
+    dst_sample_count: u32,
+    op: wgpu::StencilOperation,
+) -> wgpu::RenderPipeline {
+    let depth_stencil = clip_mask_depth_stencil(wgpu::CompareFunction::Always, op);
+    // Only the stencil buffer is written to.
+    let color_state = color_state(dst_format, wgpu::ColorWrites::empty());
+    pipeline(
+        device,
+        layout,
+        vs_mod,
+        fs_mod,
+        color_state,
+        dst_sample_count,
+        Some(depth_stencil),
+    )
+}
+
+fn color_state(
+    dst_format: wgpu::TextureFormat,
+    write_mask: wgpu::ColorWrites,
+) -> wgpu::ColorTargetState {
+    wgpu::ColorTargetState {
         format: dst_format,
         blend: Some(wgpu::BlendState {
             color: wgpu::BlendComponent {
@@ -708,8 +973,19 @@ fn render_pipeline(
                 operation: wgpu::BlendOperation::Add,
             },
         }),
-        write_mask: wgpu::ColorWrites::ALL,
-    };
+        write_mask,
+    }
+}
+
+fn pipeline(
+    device: &wgpu::Device,
+    layout: &wgpu::PipelineLayout,
+    vs_mod: &wgpu::ShaderModule,
+    fs_mod: &wgpu::ShaderModule,
+    color_state: wgpu::ColorTargetState,
+    dst_sample_count: u32,
+    depth_stencil: Option<wgpu::DepthStencilState>,
+) -> wgpu::RenderPipeline {
     let vertex_attrs = vertex_attrs();
     let vertex_buffer_desc = wgpu::VertexBufferLayout {
         array_stride: std::mem::size_of::<Vertex>() as wgpu::BufferAddress,
@@ -743,7 +1019,7 @@ fn render_pipeline(
         layout: Some(layout),
         vertex: vertex_state,
         primitive: primitive_state,
-        depth_stencil: None,
+        depth_stencil,
         multisample: multisample_state,
         fragment: Some(fragment_state),
     };
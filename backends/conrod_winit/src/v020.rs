@@ -302,6 +302,27 @@ macro_rules! v020_convert_mouse_cursor {
     }};
 }
 
+/// Position the candidate window of the input method editor just below the given text caret.
+///
+/// Expects a reference to a `winit::window::Window` and the `Option<conrod_core::Rect>` returned
+/// by `Ui::caret_rect`. Does nothing if there is no caret.
+///
+/// Requires that both the `conrod_core` and `winit` crates are in the crate root.
+#[macro_export]
+macro_rules! v020_set_ime_position {
+    ($window:expr, $caret_rect:expr) => {{
+        if let Some(rect) = $caret_rect {
+            // The window size in points.
+            let (win_w, win_h): (f64, f64) = $window.inner_size().into();
+
+            // Translate the coordinates from centre-origin-with-y-up to top-left-origin-with-y-down.
+            let x = rect.left() + win_w / 2.0;
+            let y = win_h / 2.0 - rect.bottom();
+            $window.set_ime_position(winit::dpi::LogicalPosition::new(x, y));
+        }
+    }};
+}
+
 #[macro_export]
 macro_rules! v020_conversion_fns {
     () => {
@@ -347,5 +368,14 @@ macro_rules! v020_conversion_fns {
         ) -> Option<conrod_core::event::Input> {
             $crate::v020_convert_event!(event, window)
         }
+
+        /// Position the candidate window of the input method editor just below the given text
+        /// caret, e.g. `Ui::caret_rect`.
+        pub fn set_ime_position(
+            window: &winit::window::Window,
+            caret_rect: Option<conrod_core::Rect>,
+        ) {
+            $crate::v020_set_ime_position!(window, caret_rect)
+        }
     };
 }
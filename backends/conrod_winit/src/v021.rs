@@ -301,6 +301,29 @@ macro_rules! v021_convert_mouse_cursor {
     }};
 }
 
+/// Position the candidate window of the input method editor just below the given text caret.
+///
+/// Expects a reference to a `winit::window::Window` and the `Option<conrod_core::Rect>` returned
+/// by `Ui::caret_rect`. Does nothing if there is no caret.
+///
+/// Requires that both the `conrod_core` and `winit` crates are in the crate root.
+#[macro_export]
+macro_rules! v021_set_ime_position {
+    ($window:expr, $caret_rect:expr) => {{
+        if let Some(rect) = $caret_rect {
+            // The window size in points.
+            let scale_factor: f64 = $window.scale_factor();
+            let (win_w, win_h): (f64, f64) =
+                $window.inner_size().to_logical::<f64>(scale_factor).into();
+
+            // Translate the coordinates from centre-origin-with-y-up to top-left-origin-with-y-down.
+            let x = rect.left() + win_w / 2.0;
+            let y = win_h / 2.0 - rect.bottom();
+            $window.set_ime_position(winit::dpi::LogicalPosition::new(x, y));
+        }
+    }};
+}
+
 #[macro_export]
 macro_rules! v021_conversion_fns {
     () => {
@@ -346,5 +369,14 @@ macro_rules! v021_conversion_fns {
         ) -> Option<conrod_core::event::Input> {
             $crate::v021_convert_event!(event, window)
         }
+
+        /// Position the candidate window of the input method editor just below the given text
+        /// caret, e.g. `Ui::caret_rect`.
+        pub fn set_ime_position(
+            window: &winit::window::Window,
+            caret_rect: Option<conrod_core::Rect>,
+        ) {
+            $crate::v021_set_ime_position!(window, caret_rect)
+        }
     };
 }
@@ -277,6 +277,19 @@ macro_rules! v023_convert_mouse_cursor {
     }};
 }
 
+/// Position the candidate window of the input method editor just below the given text caret.
+///
+/// Expects a reference to a `winit::window::Window` and the `Option<conrod_core::Rect>` returned
+/// by `Ui::caret_rect`. Does nothing if there is no caret.
+///
+/// Requires that both the `conrod_core` and `winit` crates are in the crate root.
+#[macro_export]
+macro_rules! v023_set_ime_position {
+    ($window:expr, $caret_rect:expr) => {{
+        $crate::v021_set_ime_position!($window, $caret_rect)
+    }};
+}
+
 #[macro_export]
 macro_rules! v023_conversion_fns {
     () => {
@@ -322,5 +335,14 @@ macro_rules! v023_conversion_fns {
         ) -> Option<conrod_core::event::Input> {
             $crate::v023_convert_event!(event, window)
         }
+
+        /// Position the candidate window of the input method editor just below the given text
+        /// caret, e.g. `Ui::caret_rect`.
+        pub fn set_ime_position(
+            window: &winit::window::Window,
+            caret_rect: Option<conrod_core::Rect>,
+        ) {
+            $crate::v023_set_ime_position!(window, caret_rect)
+        }
     };
 }
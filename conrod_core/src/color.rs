@@ -15,6 +15,7 @@ use std::f32::consts::PI;
 use utils::{degrees, fmod, turns};
 
 /// Color supporting RGB and HSL variants.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[derive(PartialEq, Copy, Clone, Debug)]
 pub enum Color {
     /// Red, Green, Blue, Alpha - All values' scales represented between 0.0 and 1.0.
@@ -295,6 +296,7 @@ impl Color {
 }
 
 /// The parts of HSL along with an alpha for transparency.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[derive(Copy, Clone, Debug, PartialEq)]
 pub struct Hsla(pub f32, pub f32, pub f32, pub f32);
 
@@ -311,6 +313,7 @@ impl From<Hsla> for Color {
 }
 
 /// The parts of RGB along with an alpha for transparency.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[derive(Copy, Clone, Debug, PartialEq)]
 pub struct Rgba(pub f32, pub f32, pub f32, pub f32);
 
@@ -390,7 +393,10 @@ pub fn hsl_to_rgb(hue: f32, saturation: f32, lightness: f32) -> (f32, f32, f32)
 }
 
 /// Linear or Radial Gradient.
-#[derive(Clone, Debug)]
+///
+/// Color stops are given as `(offset, color)` pairs in order of ascending offset, where an offset
+/// of `0.0` lies at the start of the gradient and `1.0` lies at the end.
+#[derive(Clone, Debug, PartialEq)]
 pub enum Gradient {
     /// Takes a start and end point and then a series of color stops that indicate how to
     /// interpolate between the start and end points.
@@ -417,6 +423,96 @@ pub fn radial(
     Gradient::Radial(start, start_r, end, end_r, colors)
 }
 
+impl Gradient {
+    /// The series of color stops along the gradient.
+    pub fn stops(&self) -> &[(f64, Color)] {
+        match *self {
+            Gradient::Linear(_, _, ref stops) => stops,
+            Gradient::Radial(_, _, _, _, ref stops) => stops,
+        }
+    }
+
+    /// The offset along the gradient at which the given point lies.
+    ///
+    /// Points beyond either end of the gradient are clamped to `0.0` or `1.0` respectively.
+    pub fn offset_at(&self, (x, y): (f64, f64)) -> f64 {
+        let offset = match *self {
+            Gradient::Linear((sx, sy), (ex, ey), _) => {
+                let (dx, dy) = (ex - sx, ey - sy);
+                let len_sq = dx * dx + dy * dy;
+                if len_sq == 0.0 {
+                    return 0.0;
+                }
+                ((x - sx) * dx + (y - sy) * dy) / len_sq
+            }
+            Gradient::Radial((sx, sy), start_r, (ex, ey), end_r, _) => {
+                // Find the largest `t` for which the point lies on the circle interpolated
+                // between the start and end circles.
+                let (cdx, cdy, dr) = (ex - sx, ey - sy, end_r - start_r);
+                let (pdx, pdy) = (x - sx, y - sy);
+                let a = cdx * cdx + cdy * cdy - dr * dr;
+                let b = pdx * cdx + pdy * cdy + start_r * dr;
+                let c = pdx * pdx + pdy * pdy - start_r * start_r;
+                let radius_at = |t: f64| start_r + t * dr;
+                if a.abs() < f64::EPSILON {
+                    if b == 0.0 {
+                        return 0.0;
+                    }
+                    c / (2.0 * b)
+                } else {
+                    let discriminant = b * b - a * c;
+                    if discriminant < 0.0 {
+                        return 0.0;
+                    }
+                    let sqrt = discriminant.sqrt();
+                    let (t1, t2) = ((b + sqrt) / a, (b - sqrt) / a);
+                    let (hi, lo) = if t1 > t2 { (t1, t2) } else { (t2, t1) };
+                    if radius_at(hi) >= 0.0 {
+                        hi
+                    } else {
+                        lo
+                    }
+                }
+            }
+        };
+        offset.clamp(0.0, 1.0)
+    }
+
+    /// The color of the gradient at the given offset, interpolated between the nearest stops.
+    pub fn color_at_offset(&self, offset: f64) -> Color {
+        let stops = self.stops();
+        let (first, last) = match (stops.first(), stops.last()) {
+            (Some(first), Some(last)) => (first, last),
+            _ => return TRANSPARENT,
+        };
+        if offset <= first.0 {
+            return first.1;
+        }
+        if offset >= last.0 {
+            return last.1;
+        }
+        for pair in stops.windows(2) {
+            let ((start, a), (end, b)) = (pair[0], pair[1]);
+            if offset <= end {
+                let t = if end > start {
+                    ((offset - start) / (end - start)) as f32
+                } else {
+                    1.0
+                };
+                let (Rgba(ar, ag, ab, aa), Rgba(br, bg, bb, ba)) = (a.to_rgb(), b.to_rgb());
+                let lerp = |a: f32, b: f32| a + (b - a) * t;
+                return rgba(lerp(ar, br), lerp(ag, bg), lerp(ab, bb), lerp(aa, ba));
+            }
+        }
+        last.1
+    }
+
+    /// The color of the gradient at the given point.
+    pub fn color_at(&self, point: (f64, f64)) -> Color {
+        self.color_at_offset(self.offset_at(point))
+    }
+}
+
 /// Built-in colors.
 ///
 /// These colors come from the
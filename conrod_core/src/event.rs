@@ -69,6 +69,13 @@ pub enum Input {
     Touch(input::Touch),
     /// Text input was received, usually via the keyboard.
     Text(String),
+    /// Text is being composed via an input method editor (IME).
+    ///
+    /// No backend produces this event, as `winit` reports only the text that an IME commits
+    /// (as `Text`) and not the text being composed. Backends with access to the platform's IME
+    /// must feed it themselves: an `Ime::Preedit` upon each change to the composition, followed by
+    /// an `Ime::Commit` in place of `Text` once it finishes. See the glium `text_edit` example.
+    Ime(Ime),
     /// The window was focused or lost focus.
     Focus(bool),
     /// The backed requested to redraw.
@@ -91,6 +98,9 @@ pub enum Event {
 pub enum Ui {
     /// Entered text, along with the widget that was capturing the keyboard at the time.
     Text(Option<widget::Id>, Text),
+    /// Text composed via an input method editor, along with the widget that was capturing the
+    /// keyboard at the time.
+    Ime(Option<widget::Id>, Ime),
     /// Some button was pressed, along with the widget that was capturing the device whose button
     /// was pressed.
     Press(Option<widget::Id>, Press),
@@ -140,6 +150,8 @@ pub enum Ui {
 pub enum Widget {
     /// Entered text.
     Text(Text),
+    /// Text composed via an input method editor.
+    Ime(Ime),
     /// Represents all forms of motion input.
     Motion(Motion),
     /// Interaction with a touch screen.
@@ -177,6 +189,26 @@ pub struct Text {
     pub modifiers: input::keyboard::ModifierKey,
 }
 
+/// The state of text being composed via an input method editor (IME).
+///
+/// IMEs allow for entering text that cannot be typed directly, e.g. CJK characters, by composing
+/// it over several key presses. The composition is displayed inline (the "preedit") until the user
+/// commits it.
+#[derive(Clone, PartialEq, Debug)]
+pub enum Ime {
+    /// The text being composed has changed.
+    ///
+    /// An empty `string` indicates that the composition has been cancelled.
+    Preedit {
+        /// The text being composed.
+        string: String,
+        /// The byte range of the IME's cursor within the `string`, if the cursor is visible.
+        cursor: Option<(usize, usize)>,
+    },
+    /// The composition has finished, producing the given text.
+    Commit(String),
+}
+
 /// Contains all relevant information for a Motion event.
 #[derive(Copy, Clone, PartialEq, Debug)]
 pub struct Motion {
@@ -485,6 +517,12 @@ impl From<Text> for Widget {
     }
 }
 
+impl From<Ime> for Widget {
+    fn from(ime: Ime) -> Self {
+        Widget::Ime(ime)
+    }
+}
+
 impl From<Motion> for Widget {
     fn from(motion: Motion) -> Self {
         Widget::Motion(motion)
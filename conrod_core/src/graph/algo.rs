@@ -7,7 +7,7 @@
 use super::{EdgeIndex, Graph};
 use daggy::Walker;
 use fnv;
-use position::{Point, Rect};
+use position::{Point, Rect, Transform};
 use theme::Theme;
 use widget;
 
@@ -47,11 +47,16 @@ impl PickWidgets {
                 None => break,
                 Some(&idx) => idx,
             };
+            // Map the position into the untransformed space in which the widget was laid out.
+            let xy = match transform_of_widget(graph, idx).inverse() {
+                None => continue,
+                Some(inverse) => inverse.transform_point(self.xy),
+            };
             let visible_rect = match cropped_area_of_widget(graph, idx) {
                 None => continue,
                 Some(rect) => rect,
             };
-            if !visible_rect.is_over(self.xy) {
+            if !visible_rect.is_over(xy) {
                 continue;
             }
             // Now that we know we're over the bounding box, we can check the more
@@ -62,7 +67,11 @@ impl PickWidgets {
                     None => break,
                     Some(container) => container,
                 };
-                match (container.is_over.0)(&container, self.xy, theme) {
+                let xy = match transform_of_widget(graph, id).inverse() {
+                    None => break,
+                    Some(inverse) => inverse.transform_point(self.xy),
+                };
+                match (container.is_over.0)(container, xy, theme) {
                     widget::IsOver::Bool(false) => break,
                     widget::IsOver::Bool(true) => return Some(id),
                     widget::IsOver::Widget(w_id) => {
@@ -146,6 +155,26 @@ pub fn pick_scrollable_widgets(depth_order: &[widget::Id], xy: Point) -> PickScr
     }
 }
 
+/// The transform of the widget with the given index, composed with the transforms of each of its
+/// depth-wise parents.
+///
+/// The result maps points from the untransformed space in which the widget was laid out to the
+/// space in which it is rendered.
+pub fn transform_of_widget(graph: &Graph, idx: widget::Id) -> Transform {
+    let mut transform = Transform::identity();
+    let mut maybe_idx = Some(idx);
+    while let Some(idx) = maybe_idx {
+        if let Some(container) = graph.widget(idx) {
+            if !container.transform.is_identity() {
+                let local = container.transform.about(container.rect.xy());
+                transform = transform.then(local);
+            }
+        }
+        maybe_idx = graph.depth_parent(idx);
+    }
+    transform
+}
+
 /// The rectangle that represents the maximum visible area for the widget with the given index.
 ///
 /// Specifically, this considers the cropped scroll area for all parents.
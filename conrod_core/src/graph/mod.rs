@@ -4,7 +4,7 @@
 //! The primary type of interest in this module is the [**Graph**](./struct.Graph) type.
 
 use daggy;
-use position::{Axis, Depth, Point, Rect};
+use position::{Axis, ClipShape, Depth, Point, Rect, Transform};
 use std;
 use std::any::Any;
 use std::ops::{Index, IndexMut};
@@ -87,6 +87,8 @@ pub struct Container {
     pub maybe_floating: Option<widget::Floating>,
     /// Whether or not children widgets should be cropped to the `kid_area`.
     pub crop_kids: bool,
+    /// The shape within the `kid_area` to which children are clipped when `crop_kids` is `true`.
+    pub maybe_clip_shape: Option<ClipShape>,
     /// Scroll related state (is only `Some` if this axis is scrollable).
     pub maybe_x_scroll_state: Option<widget::scroll::StateX>,
     /// Scroll related state (is only `Some` if this axis is scrollable).
@@ -101,6 +103,13 @@ pub struct Container {
     ///
     /// NOTE: See `Wiget::is_over` for more details and a note on possible future plans.
     pub is_over: IsOverFn,
+    /// The opacity of the widget, not including that of its depth-wise parents.
+    pub opacity: f32,
+    /// Whether or not the widget and its children are rendered to an isolated layer.
+    pub is_isolated_layer: bool,
+    /// The transform of the widget about the centre of its `rect`, not including that of its
+    /// depth-wise parents.
+    pub transform: Transform,
 }
 
 /// A wrapper around a `widget::IsOverFn` to make implementing `Debug` easier for `Container`.
@@ -664,10 +673,14 @@ impl Graph {
             maybe_dragged_from,
             maybe_floating,
             crop_kids,
+            maybe_clip_shape,
             maybe_x_scroll_state,
             maybe_y_scroll_state,
             maybe_graphics_for,
             is_over,
+            opacity,
+            is_isolated_layer,
+            transform,
         } = widget;
 
         assert!(
@@ -686,10 +699,14 @@ impl Graph {
             maybe_dragged_from: maybe_dragged_from,
             maybe_floating: maybe_floating,
             crop_kids: crop_kids,
+            maybe_clip_shape: maybe_clip_shape,
             maybe_x_scroll_state: maybe_x_scroll_state,
             maybe_y_scroll_state: maybe_y_scroll_state,
             instantiation_order_idx: instantiation_order_idx,
             is_over: IsOverFn(is_over),
+            opacity: opacity,
+            is_isolated_layer: is_isolated_layer,
+            transform: transform,
         };
 
         // Retrieves the widget's parent index.
@@ -750,10 +767,14 @@ impl Graph {
                 container.maybe_dragged_from = maybe_dragged_from;
                 container.maybe_floating = maybe_floating;
                 container.crop_kids = crop_kids;
+                container.maybe_clip_shape = maybe_clip_shape;
                 container.maybe_x_scroll_state = maybe_x_scroll_state;
                 container.maybe_y_scroll_state = maybe_y_scroll_state;
                 container.instantiation_order_idx = instantiation_order_idx;
                 container.is_over = IsOverFn(is_over);
+                container.opacity = opacity;
+                container.is_isolated_layer = is_isolated_layer;
+                container.transform = transform;
             }
         }
 
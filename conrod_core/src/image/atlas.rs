@@ -0,0 +1,272 @@
+//! Packing of many small images into a few shared textures.
+//!
+//! See the `image::Map::insert_atlased` method for details.
+
+use super::Id;
+use fnv;
+use position::Rect;
+
+/// The default dimensions of each page of the `Atlas` in pixels.
+pub const DEFAULT_PAGE_DIMENSIONS: [u32; 2] = [1024, 1024];
+
+/// The number of transparent pixels left between neighbouring images on a page.
+///
+/// This avoids bleeding between images when textures are sampled with linear filtering.
+pub const PADDING: u32 = 1;
+
+/// Packs small RGBA images into shared pages, so that drawing them requires few texture switches.
+///
+/// Every image within the atlas has its own `image::Id`, while each page is also registered as an
+/// image under its own `image::Id`. Renderers resolve the `Id` of each atlased image to the `Id`
+/// of its page and rewrite the source rect to match via `Atlas::resolve`. The `mesh::Mesh` also
+/// batches consecutive images from the same page into a single draw command.
+#[derive(Clone, Debug)]
+pub struct Atlas {
+    page_dimensions: [u32; 2],
+    pages: Vec<Page>,
+    regions: fnv::FnvHashMap<Id, Region>,
+}
+
+/// A single texture onto which many images are packed.
+#[derive(Clone, Debug)]
+pub struct Page {
+    id: Id,
+    dimensions: [u32; 2],
+    pixels: Vec<u8>,
+    shelves: Vec<Shelf>,
+    image_count: usize,
+    requires_upload: bool,
+}
+
+// A row of images along the page, filled from left to right.
+#[derive(Copy, Clone, Debug)]
+struct Shelf {
+    top: u32,
+    height: u32,
+    width_used: u32,
+}
+
+// The location of an image within the atlas.
+#[derive(Copy, Clone, Debug)]
+struct Region {
+    page: usize,
+    // The area occupied on the page, with the origin at the bottom left, as for source rects.
+    rect: Rect,
+}
+
+impl Atlas {
+    /// Construct an empty `Atlas` whose pages have the given dimensions in pixels.
+    pub fn new(page_dimensions: [u32; 2]) -> Self {
+        Atlas {
+            page_dimensions,
+            pages: Vec::new(),
+            regions: fnv::FnvHashMap::default(),
+        }
+    }
+
+    /// The dimensions with which new pages are created.
+    pub fn page_dimensions(&self) -> [u32; 2] {
+        self.page_dimensions
+    }
+
+    /// Set the dimensions with which new pages are created.
+    ///
+    /// Existing pages keep their dimensions.
+    pub fn set_page_dimensions(&mut self, dimensions: [u32; 2]) {
+        self.page_dimensions = dimensions;
+    }
+
+    /// All pages that have been created so far.
+    pub fn pages(&self) -> &[Page] {
+        &self.pages
+    }
+
+    /// Whether or not the image with the given `Id` was packed into the atlas.
+    pub fn contains(&self, id: Id) -> bool {
+        self.regions.contains_key(&id)
+    }
+
+    /// The `Id` of the page on which the given image is packed, along with the area that it
+    /// occupies on the page in pixels.
+    ///
+    /// As with the `source_rect` of an `Image` primitive, the origin is the bottom left of the
+    /// page.
+    pub fn region(&self, id: Id) -> Option<(Id, Rect)> {
+        self.regions
+            .get(&id)
+            .map(|region| (self.pages[region.page].id, region.rect))
+    }
+
+    /// Resolve an image `Id` and its `source_rect` to the page on which it is packed.
+    ///
+    /// Images that are not within the atlas are returned unchanged.
+    pub fn resolve(&self, id: Id, source_rect: Option<Rect>) -> (Id, Option<Rect>) {
+        match self.region(id) {
+            None => (id, source_rect),
+            Some((page_id, rect)) => {
+                let source_rect = match source_rect {
+                    Some(src) => src.shift([rect.left(), rect.bottom()]),
+                    None => rect,
+                };
+                (page_id, Some(source_rect))
+            }
+        }
+    }
+
+    /// Copy the given RGBA image onto a page under the given `id`, creating a new page if
+    /// necessary.
+    ///
+    /// The `new_page_id` function is called for the `Id` of each new page. Images that are larger
+    /// than the page dimensions are given a page of their own.
+    ///
+    /// This is usually called via `image::Map::insert_atlased`, which also provides the `Id`s.
+    pub fn insert<F>(&mut self, id: Id, dimensions: [u32; 2], rgba: &[u8], new_page_id: F)
+    where
+        F: FnOnce() -> Id,
+    {
+        let [w, h] = dimensions;
+        let page_dimensions = self.page_dimensions;
+        let found = self
+            .pages
+            .iter_mut()
+            .enumerate()
+            .filter_map(|(i, page)| page.allocate(w, h).map(|xy| (i, xy)))
+            .next();
+        let (page_ix, [x, top]) = match found {
+            Some(found) => found,
+            None => {
+                let page_w = page_dimensions[0].max(w + PADDING);
+                let page_h = page_dimensions[1].max(h + PADDING);
+                let mut page = Page::new(new_page_id(), [page_w, page_h]);
+                let xy = page
+                    .allocate(w, h)
+                    .expect("image does not fit on a new page");
+                self.pages.push(page);
+                (self.pages.len() - 1, xy)
+            }
+        };
+
+        let page = &mut self.pages[page_ix];
+        page.write(x, top, dimensions, rgba);
+        page.image_count += 1;
+        let page_h = page.dimensions[1];
+        let bottom = page_h - top - h;
+        let rect = Rect::from_corners(
+            [x as f64, bottom as f64],
+            [(x + w) as f64, (bottom + h) as f64],
+        );
+        let region = Region {
+            page: page_ix,
+            rect,
+        };
+        self.regions.insert(id, region);
+    }
+
+    /// Remove the given image from the atlas, returning whether or not it was present.
+    ///
+    /// The space on a page is reclaimed once all of its images have been removed.
+    pub fn remove(&mut self, id: Id) -> bool {
+        match self.regions.remove(&id) {
+            None => false,
+            Some(region) => {
+                let page = &mut self.pages[region.page];
+                page.image_count -= 1;
+                if page.image_count == 0 {
+                    page.shelves.clear();
+                    for byte in page.pixels.iter_mut() {
+                        *byte = 0;
+                    }
+                }
+                true
+            }
+        }
+    }
+
+    /// Mark all pages as uploaded, i.e. `Page::requires_upload` returns `false` until more images
+    /// are inserted.
+    pub fn mark_uploaded(&mut self) {
+        for page in self.pages.iter_mut() {
+            page.requires_upload = false;
+        }
+    }
+}
+
+impl Page {
+    fn new(id: Id, dimensions: [u32; 2]) -> Self {
+        let [w, h] = dimensions;
+        Page {
+            id,
+            dimensions,
+            pixels: vec![0; w as usize * h as usize * 4],
+            shelves: Vec::new(),
+            image_count: 0,
+            requires_upload: true,
+        }
+    }
+
+    /// The `Id` under which the page is registered within the `image::Map`.
+    pub fn id(&self) -> Id {
+        self.id
+    }
+
+    /// The dimensions of the page in pixels.
+    pub fn dimensions(&self) -> [u32; 2] {
+        self.dimensions
+    }
+
+    /// The RGBA pixels of the page in rows from top to bottom.
+    pub fn pixels(&self) -> &[u8] {
+        &self.pixels
+    }
+
+    /// Whether or not the pixels of the page have changed since it was last uploaded.
+    pub fn requires_upload(&self) -> bool {
+        self.requires_upload
+    }
+
+    // Find a space for an image of the given size, returning its top left corner.
+    //
+    // Images are placed on the first shelf that is tall enough and has room, otherwise a new
+    // shelf is started beneath the last.
+    fn allocate(&mut self, w: u32, h: u32) -> Option<[u32; 2]> {
+        let [page_w, page_h] = self.dimensions;
+        let (padded_w, padded_h) = (w + PADDING, h + PADDING);
+        if padded_w > page_w || padded_h > page_h {
+            return None;
+        }
+        for shelf in self.shelves.iter_mut() {
+            if shelf.height >= padded_h && shelf.width_used + padded_w <= page_w {
+                let x = shelf.width_used;
+                shelf.width_used += padded_w;
+                return Some([x, shelf.top]);
+            }
+        }
+        let top = self
+            .shelves
+            .last()
+            .map(|shelf| shelf.top + shelf.height)
+            .unwrap_or(0);
+        if top + padded_h > page_h {
+            return None;
+        }
+        self.shelves.push(Shelf {
+            top,
+            height: padded_h,
+            width_used: padded_w,
+        });
+        Some([0, top])
+    }
+
+    // Copy the given image to the given position on the page.
+    fn write(&mut self, x: u32, top: u32, dimensions: [u32; 2], rgba: &[u8]) {
+        let [w, h] = dimensions;
+        let row_len = w as usize * 4;
+        let page_row_len = self.dimensions[0] as usize * 4;
+        for row in 0..h as usize {
+            let start = (top as usize + row) * page_row_len + x as usize * 4;
+            let src = &rgba[row * row_len..(row + 1) * row_len];
+            self.pixels[start..start + row_len].copy_from_slice(src);
+        }
+        self.requires_upload = true;
+    }
+}
@@ -1,15 +1,21 @@
 //! A type used to manage a user's image data and map them to `Image` widgets:
 //!
 //! - [Map](./struct.Map.html)
+//! - [Atlas](./atlas/struct.Atlas.html)
 
 use fnv;
 use std;
 
+pub use self::atlas::Atlas;
+
+pub mod atlas;
+
 /// Unique image identifier.
 ///
 /// Throughout conrod, images are referred to via their unique `Id`. By referring to images via
 /// `Id`s, conrod can remain agnostic of the actual image or texture types used to represent each
 /// image.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[derive(Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
 pub struct Id(u32);
 
@@ -20,6 +26,7 @@ pub struct Id(u32);
 pub struct Map<Img> {
     next_index: u32,
     map: HashMap<Img>,
+    atlas: Atlas,
     /// Whether or not the `image::Map` will trigger a redraw the next time `Ui::draw` is called.
     ///
     /// This is automatically set to `true` when any method that takes `&mut self` is called.
@@ -48,6 +55,7 @@ impl<Img> Map<Img> {
         Map {
             next_index: 0,
             map: HashMap::<Img>::default(),
+            atlas: Atlas::new(atlas::DEFAULT_PAGE_DIMENSIONS),
             trigger_redraw: std::cell::Cell::new(true),
         }
     }
@@ -90,9 +98,88 @@ impl<Img> Map<Img> {
     /// Note: Calling this will trigger a redraw the next time `Ui::draw_if_changed` is called.
     pub fn remove(&mut self, id: Id) -> Option<Img> {
         self.trigger_redraw.set(true);
+        self.atlas.remove(id);
         self.map.remove(&id)
     }
 
+    /// Packs the given RGBA image into the map's `Atlas`, returning its associated `image::Id`.
+    ///
+    /// Rather than being stored as its own `Img`, the image is copied onto a shared page of the
+    /// atlas. Each page is registered under its own `image::Id` once it is uploaded via
+    /// `upload_atlas_pages`. When drawn via the `mesh::Mesh`, consecutive images from the same
+    /// page are batched into a single draw command.
+    ///
+    /// The pixels are given in rows from top to bottom, with four bytes per pixel.
+    ///
+    /// Note: Calling this will trigger a redraw the next time `Ui::draw_if_changed` is called.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the length of `rgba` does not match the given `dimensions`.
+    pub fn insert_atlased(&mut self, dimensions: [u32; 2], rgba: &[u8]) -> Id {
+        assert_eq!(
+            rgba.len(),
+            dimensions[0] as usize * dimensions[1] as usize * 4,
+            "the length of the RGBA pixel data does not match the image dimensions"
+        );
+        self.trigger_redraw.set(true);
+        let id = self.next_id();
+        let Map {
+            ref mut next_index,
+            ref mut atlas,
+            ..
+        } = *self;
+        atlas.insert(id, dimensions, rgba, || {
+            let index = *next_index;
+            *next_index = index.wrapping_add(1);
+            Id(index)
+        });
+        id
+    }
+
+    /// Produce an `Img` for each page of the atlas whose pixels have changed since it was last
+    /// uploaded, inserting it into the map under the page's `image::Id`.
+    ///
+    /// The given function is called with the dimensions and the RGBA pixels of each page, in rows
+    /// from top to bottom, and should usually upload them to a texture.
+    ///
+    /// This should be called after inserting images via `insert_atlased` and before drawing.
+    pub fn upload_atlas_pages<F>(&mut self, mut upload: F)
+    where
+        F: FnMut([u32; 2], &[u8]) -> Img,
+    {
+        let Map {
+            ref mut map,
+            ref mut atlas,
+            ref trigger_redraw,
+            ..
+        } = *self;
+        for page in atlas.pages().iter().filter(|page| page.requires_upload()) {
+            trigger_redraw.set(true);
+            map.insert(page.id(), upload(page.dimensions(), page.pixels()));
+        }
+        atlas.mark_uploaded();
+    }
+
+    /// The `Atlas` into which images are packed via `insert_atlased`.
+    pub fn atlas(&self) -> &Atlas {
+        &self.atlas
+    }
+
+    /// Set the dimensions in pixels of pages that are created for the atlas from now on.
+    ///
+    /// By default, pages are `atlas::DEFAULT_PAGE_DIMENSIONS`.
+    pub fn set_atlas_page_dimensions(&mut self, dimensions: [u32; 2]) {
+        self.atlas.set_page_dimensions(dimensions);
+    }
+
+    // Produce the next unique `Id`.
+    fn next_id(&mut self) -> Id {
+        let index = self.next_index;
+        self.next_index = index.wrapping_add(1);
+        Id(index)
+    }
+
     /// Insert each of the images yielded by the given iterator and produce an iterator yielding
     /// their generated `Ids` in the same order.
     ///
@@ -439,6 +439,10 @@ impl<'a> Iterator for Events<'a> {
                     return Some(text.clone().into())
                 }
 
+                event::Ui::Ime(idx, ref ime) if idx == Some(self.idx) => {
+                    return Some(ime.clone().into())
+                }
+
                 event::Ui::Motion(idx, ref motion) if idx == Some(self.idx) => {
                     return Some(motion.clone().into())
                 }
@@ -1,10 +1,14 @@
 use color::{hsl, hsla, rgb, rgba, Color};
+use text;
 use ui::Ui;
 
 /// Font size used throughout Conrod.
 pub type FontSize = u32;
 
 /// Widgets that may display some label.
+///
+/// `label_ellipsis` is provided with a default that does nothing, so implementors must override it
+/// for their label to be truncated.
 pub trait Labelable<'a>: Sized {
     /// Set the label for the widget.
     fn label(self, text: &'a str) -> Self;
@@ -35,6 +39,14 @@ pub trait Labelable<'a>: Sized {
     /// Set the font size for the widget's label.
     fn label_font_size(self, size: FontSize) -> Self;
 
+    /// Truncate the widget's label with an ellipsis at the given position should it exceed the
+    /// width available to it.
+    ///
+    /// The default implementation ignores the ellipsis, leaving the label untruncated.
+    fn label_ellipsis(self, _ellipsis: text::Ellipsis) -> Self {
+        self
+    }
+
     /// Set a "small" font size for the widget's label.
     fn small_font(self, ui: &Ui) -> Self {
         self.label_font_size(ui.theme.font_size_small)
@@ -16,6 +16,18 @@ extern crate fnv;
 extern crate input as piston_input;
 extern crate num;
 extern crate rusttype;
+#[cfg(feature = "shaping")]
+extern crate rustybuzz;
+#[cfg(feature = "serde")]
+#[macro_use]
+extern crate serde;
+#[cfg(feature = "serde")]
+extern crate serde_json;
+extern crate ttf_parser;
+#[cfg(feature = "shaping")]
+extern crate unicode_bidi;
+extern crate unicode_linebreak;
+extern crate unicode_segmentation;
 
 pub use border::{Borderable, Bordering};
 pub use color::{Color, Colorable};
@@ -38,6 +50,7 @@ mod label;
 pub mod mesh;
 pub mod position;
 pub mod render;
+pub mod svg;
 pub mod text;
 pub mod theme;
 mod ui;
@@ -5,9 +5,11 @@
 //! produce a sequence of commands describing the order in which draw commands should occur and
 //! whether or not the `Scizzor` should be updated between draws.
 
+use crate::position::Transform;
 use crate::text::{self, rt};
-use crate::{color, image, render};
-use crate::{Rect, Scalar};
+use crate::{color, image, render, widget};
+use crate::{Point, Rect, Scalar};
+use fnv;
 use std::{fmt, ops};
 
 /// Images within the given image map must know their dimensions in pixels.
@@ -25,7 +27,11 @@ pub struct Mesh {
     glyph_cache_pixel_buffer: Vec<u8>,
     commands: Vec<PreparedCommand>,
     vertices: Vec<Vertex>,
-    positioned_glyphs: Vec<text::PositionedGlyph>,
+    glyph_queue: GlyphQueue,
+    shadow_triangles: Vec<widget::triangles::Triangle<widget::triangles::ColoredPoint>>,
+    feather_width: Option<Scalar>,
+    feathering: Feathering,
+    max_glyph_cache_dims: [u32; 2],
 }
 
 /// Represents the scizzor in pixel coordinates.
@@ -44,6 +50,22 @@ pub enum Command {
     Draw(Draw),
     /// Update the scizzor within the pipeline.
     Scizzor(Scizzor),
+    /// Begin drawing to a new, transparent layer with the same dimensions as the target.
+    ///
+    /// Only produced for `render::PrimitiveKind::BeginLayer`, i.e. when isolated layers are
+    /// requested via `render::Primitives::with_isolated_layers`.
+    BeginLayer,
+    /// Composite the current layer onto the layer or target beneath it with the given opacity.
+    EndLayer(f32),
+    /// Update the clip mask within the pipeline.
+    ///
+    /// When `Some`, the range of vertices describes plain triangles whose even-odd coverage makes
+    /// up the mask, e.g. by inverting a stencil buffer for each triangle. Subsequent draws should
+    /// only touch pixels within the mask. These vertices are not drawn by any `Draw` command.
+    ///
+    /// When `None`, the clip mask should be removed. Backends that do not support clip masks may
+    /// ignore this command, in which case drawing is only cropped by the `Scizzor`.
+    Mask(Option<std::ops::Range<usize>>),
 }
 
 /// An iterator yielding `Command`s, produced by the `Renderer::commands` method.
@@ -58,6 +80,8 @@ pub struct Commands<'a> {
 pub enum Draw {
     /// A range of vertices representing triangles textured with the image in the
     /// image_map at the given `widget::Id`.
+    ///
+    /// For images packed into the `image::Atlas`, this is the `image::Id` of the page.
     Image(image::Id, std::ops::Range<usize>),
     /// A range of vertices representing plain triangles.
     Plain(std::ops::Range<usize>),
@@ -97,16 +121,83 @@ pub struct Vertex {
 pub struct Fill {
     /// Whether or not the glyph cache pixel data should be written to the GPU.
     pub glyph_cache_requires_upload: bool,
+    /// Whether or not the glyph cache has grown in order to fit all text within the frame.
+    ///
+    /// When `true`, the GPU texture for the glyph cache should be reallocated with the new
+    /// `glyph_cache().dimensions()` before uploading the pixel data.
+    pub glyph_cache_resized: bool,
 }
 
 // A wrapper around an owned glyph cache, providing `Debug` and `Deref` impls.
 struct GlyphCache(text::GlyphCache<'static>);
 
+/// The glyphs of all text within a frame, queued so that they may be cached at once.
+///
+/// Caching the glyphs of a whole frame at once ensures that caching the glyphs of one `Text`
+/// cannot evict those of another. Used by the `Mesh` and by backends that produce their own
+/// vertices.
+#[derive(Debug, Default)]
+pub struct GlyphQueue {
+    glyphs: Vec<QueuedGlyph>,
+}
+
+/// A glyph queued for caching along with the vertices that will display it.
+#[derive(Debug)]
+pub struct QueuedGlyph {
+    /// The index of the glyph's font within the glyph cache.
+    pub font_id: usize,
+    /// The glyph, positioned in pixel space.
+    pub glyph: text::PositionedGlyph,
+    /// The transform to apply to the glyph's vertices.
+    pub transform: Transform,
+    /// The index of the first of the `GLYPH_VERTEX_COUNT` vertices that display the glyph.
+    pub vertex_start: usize,
+}
+
+/// The number of vertices used to display each glyph.
+pub const GLYPH_VERTEX_COUNT: usize = 6;
+
+// A point along with its linear RGBA color.
+type ColoredPoint = (Point, [f32; 4]);
+
+// A point's position as bits, for finding edges shared between triangles.
+type PointKey = (u64, u64);
+
+// Buffers used for producing the feathered fringe around the outline of a set of triangles.
+#[derive(Debug, Default)]
+struct Feathering {
+    // The triangles of the primitive whose outline is to be feathered.
+    triangles: Vec<[ColoredPoint; 3]>,
+    // Every unique edge within `triangles`.
+    edges: Vec<FringeEdge>,
+    // Maps the end points of an edge to its index within `edges`.
+    edge_indices: fnv::FnvHashMap<(PointKey, PointKey), usize>,
+    // The sum of the outward normals of the outline edges touching each point.
+    normals: fnv::FnvHashMap<PointKey, [Scalar; 2]>,
+    // The indices of `triangles` overlapping each cell of a uniform grid, sorted by cell.
+    cells: Vec<(GridCell, usize)>,
+}
+
+// The coordinates of a cell within the grid used to find the triangles near a point.
+type GridCell = (i64, i64);
+
+// An edge of a triangle, along with the number of triangles that share it.
+#[derive(Debug)]
+struct FringeEdge {
+    a: ColoredPoint,
+    b: ColoredPoint,
+    normal: [Scalar; 2],
+    count: u32,
+}
+
 #[derive(Debug)]
 enum PreparedCommand {
     Image(image::Id, std::ops::Range<usize>),
     Plain(std::ops::Range<usize>),
     Scizzor(Scizzor),
+    BeginLayer,
+    EndLayer(f32),
+    Mask(Option<std::ops::Range<usize>>),
 }
 
 /// Draw text from the text cache texture `tex` in the fragment shader.
@@ -119,6 +210,26 @@ pub const MODE_GEOMETRY: u32 = 2;
 /// Default dimensions to use for the glyph cache.
 pub const DEFAULT_GLYPH_CACHE_DIMS: [u32; 2] = [1_024; 2];
 
+/// The default dimensions up to which the glyph cache may grow in order to fit all text within a
+/// frame.
+///
+/// This matches the maximum texture size guaranteed by most GPUs.
+pub const MAX_GLYPH_CACHE_DIMS: [u32; 2] = [8_192; 2];
+
+/// A reasonable width in pixels for the fringe produced when feathering is enabled.
+///
+/// See `Mesh::set_feather_width`.
+pub const DEFAULT_FEATHER_WIDTH: Scalar = 1.0;
+
+// Limits the length of the fringe at sharp corners to this many times the feather width.
+const MAX_FEATHER_MITER: Scalar = 2.0;
+
+// The tolerance in barycentric coordinates for a point to be considered within a triangle.
+const FEATHER_COVER_TOLERANCE: Scalar = 1e-9;
+
+// Limits the number of grid cells spanned by a primitive when looking for covered edges.
+const MAX_FEATHER_GRID_CELLS: Scalar = 64.0;
+
 impl Mesh {
     /// Construct a new empty `Mesh` with default glyph cache dimensions.
     pub fn new() -> Self {
@@ -139,16 +250,61 @@ impl Mesh {
         let glyph_cache_pixel_buffer = vec![0u8; gc_width as usize * gc_height as usize];
         let commands = vec![];
         let vertices = vec![];
-        let positioned_glyphs = vec![];
+        let glyph_queue = GlyphQueue::new();
+        let shadow_triangles = vec![];
+        let feather_width = None;
+        let feathering = Feathering::default();
+        let max_glyph_cache_dims = MAX_GLYPH_CACHE_DIMS;
         Mesh {
             glyph_cache,
             glyph_cache_pixel_buffer,
             commands,
             vertices,
-            positioned_glyphs,
+            glyph_queue,
+            shadow_triangles,
+            feather_width,
+            feathering,
+            max_glyph_cache_dims,
         }
     }
 
+    /// Enable or disable feathered edges for `TrianglesSingleColor` and `TrianglesMultiColor`
+    /// primitives.
+    ///
+    /// When `Some`, a fringe of the given width in pixels is produced along the outline of each
+    /// primitive's triangles, fading from the color of the outline to transparent. This provides
+    /// smooth edges for shapes like circles, rounded rectangles and thick lines on backends that
+    /// cannot rely on multisampling. `DEFAULT_FEATHER_WIDTH` is a good starting point.
+    ///
+    /// The fringe is produced outside of the outline, so shapes will appear slightly larger.
+    ///
+    /// By default, feathering is disabled.
+    pub fn set_feather_width(&mut self, width: Option<Scalar>) {
+        self.feather_width = width;
+    }
+
+    /// The width of the feathered fringe in pixels, if feathering is enabled.
+    pub fn feather_width(&self) -> Option<Scalar> {
+        self.feather_width
+    }
+
+    /// Set the dimensions up to which the glyph cache may grow when the text within a frame does
+    /// not fit.
+    ///
+    /// Backends that cannot reallocate their glyph cache texture may pass the current dimensions
+    /// to disable growing, in which case `fill` returns an error when the text does not fit. The
+    /// glyph cache keeps its prior dimensions whenever `fill` returns an error.
+    ///
+    /// By default, this is `MAX_GLYPH_CACHE_DIMS`.
+    pub fn set_max_glyph_cache_dimensions(&mut self, dims: [u32; 2]) {
+        self.max_glyph_cache_dims = dims;
+    }
+
+    /// The dimensions up to which the glyph cache may grow.
+    pub fn max_glyph_cache_dimensions(&self) -> [u32; 2] {
+        self.max_glyph_cache_dims
+    }
+
     /// Fill the inner vertex buffer from the given primitives.
     ///
     /// - `viewport`: the window in which the UI is drawn. The width and height should be the
@@ -173,9 +329,16 @@ impl Mesh {
             ref mut glyph_cache_pixel_buffer,
             ref mut commands,
             ref mut vertices,
-            ref mut positioned_glyphs,
+            ref mut glyph_queue,
+            ref mut shadow_triangles,
+            feather_width,
+            ref mut feathering,
+            max_glyph_cache_dims,
         } = *self;
 
+        // The width of the feathered fringe in conrod's point space.
+        let feather_width = feather_width.filter(|&w| w > 0.0).map(|w| w / dpi_factor);
+
         commands.clear();
         vertices.clear();
 
@@ -194,10 +357,6 @@ impl Mesh {
         let half_viewport_w = viewport_w / 2.0;
         let half_viewport_h = viewport_h / 2.0;
 
-        // Width of the glyph cache is useful when writing to the pixel buffer.
-        let (glyph_cache_w, _) = glyph_cache.dimensions();
-        let glyph_cache_w = glyph_cache_w as usize;
-
         // Functions for converting for conrod scalar coords to normalised vertex coords (-1.0 to 1.0).
         let vx = |x: Scalar| (x * dpi_factor / half_viewport_w) as f32;
         let vy = |y: Scalar| -1.0 * (y * dpi_factor / half_viewport_h) as f32;
@@ -218,9 +377,45 @@ impl Mesh {
             }
         };
 
+        // Apply a transform to a position in normalised vertex coords.
+        let transform_vertex = |transform: Transform, p: [f32; 2]| {
+            if transform.is_identity() {
+                return p;
+            }
+            let x = p[0] as Scalar * half_viewport_w / dpi_factor;
+            let y = -p[1] as Scalar * half_viewport_h / dpi_factor;
+            let [x, y] = transform.transform_point([x, y]);
+            [vx(x), vy(y)]
+        };
+
+        // A vertex of a feathered fringe, whose position has already been transformed.
+        let fringe_vertex = |(p, rgba): ColoredPoint| Vertex {
+            position: [vx(p[0]), vy(p[1])],
+            tex_coords: [0.0, 0.0],
+            rgba,
+            mode: MODE_GEOMETRY,
+        };
+
         // Keep track of the scizzor as it changes.
         let mut current_scizzor = rect_to_scizzor(viewport);
 
+        // Keep track of the outline of the clip mask as it changes.
+        let mut current_clip: Option<Vec<Point>> = None;
+
+        // Completes the current `Command`.
+        macro_rules! finish_current_command {
+            () => {
+                match current_state {
+                    State::Plain { start } => {
+                        commands.push(PreparedCommand::Plain(start..vertices.len()))
+                    }
+                    State::Image { image_id, start } => {
+                        commands.push(PreparedCommand::Image(image_id, start..vertices.len()))
+                    }
+                }
+            };
+        }
+
         // Switches to the `Plain` state and completes the previous `Command` if not already in the
         // `Plain` state.
         macro_rules! switch_to_plain_state {
@@ -242,22 +437,36 @@ impl Mesh {
             let render::Primitive {
                 kind,
                 scizzor,
+                clip,
                 rect,
+                transform,
                 ..
             } = primitive;
 
+            // Apply the primitive's transform to a position in normalised vertex coords.
+            let tv = |p: [f32; 2]| transform_vertex(transform, p);
+
+            // Check for a layer command.
+            let layer_command = match kind {
+                render::PrimitiveKind::BeginLayer { .. } => Some(PreparedCommand::BeginLayer),
+                render::PrimitiveKind::EndLayer { opacity } => {
+                    Some(PreparedCommand::EndLayer(opacity))
+                }
+                _ => None,
+            };
+            if let Some(command) = layer_command {
+                finish_current_command!();
+                commands.push(command);
+                current_state = State::Plain {
+                    start: vertices.len(),
+                };
+                continue;
+            }
+
             // Check for a `Scizzor` command.
             let new_scizzor = rect_to_scizzor(scizzor);
             if new_scizzor != current_scizzor {
-                // Finish the current command.
-                match current_state {
-                    State::Plain { start } => {
-                        commands.push(PreparedCommand::Plain(start..vertices.len()))
-                    }
-                    State::Image { image_id, start } => {
-                        commands.push(PreparedCommand::Image(image_id, start..vertices.len()))
-                    }
-                }
+                finish_current_command!();
 
                 // Update the scizzor and produce a command.
                 current_scizzor = new_scizzor;
@@ -276,6 +485,34 @@ impl Mesh {
                 continue;
             }
 
+            // Check for a `Mask` command.
+            if clip != current_clip.as_ref().map(|points| &points[..]) {
+                finish_current_command!();
+
+                // Triangulate the outline as a fan, relying on the even-odd rule for concavity.
+                current_clip = clip.map(|points| points.to_vec());
+                let mask = clip.map(|points| {
+                    let start = vertices.len();
+                    let triangles = widget::polygon::triangles(points.iter().cloned());
+                    for triangle in triangles.into_iter().flatten() {
+                        for &[x, y] in triangle.points().iter() {
+                            vertices.push(Vertex {
+                                position: [vx(x), vy(y)],
+                                tex_coords: [0.0, 0.0],
+                                rgba: [1.0; 4],
+                                mode: MODE_GEOMETRY,
+                            });
+                        }
+                    }
+                    start..vertices.len()
+                });
+                commands.push(PreparedCommand::Mask(mask));
+
+                current_state = State::Plain {
+                    start: vertices.len(),
+                };
+            }
+
             match kind {
                 render::PrimitiveKind::Rectangle { color } => {
                     switch_to_plain_state!();
@@ -286,7 +523,7 @@ impl Mesh {
                     let v = |x, y| {
                         // Convert from conrod Scalar range to GL range -1.0 to 1.0.
                         Vertex {
-                            position: [vx(x), vy(y)],
+                            position: tv([vx(x), vy(y)]),
                             tex_coords: [0.0, 0.0],
                             rgba: color,
                             mode: MODE_GEOMETRY,
@@ -316,7 +553,7 @@ impl Mesh {
                     let color = gamma_srgb_to_linear(color.into());
 
                     let v = |p: [Scalar; 2]| Vertex {
-                        position: [vx(p[0]), vy(p[1])],
+                        position: tv([vx(p[0]), vy(p[1])]),
                         tex_coords: [0.0, 0.0],
                         rgba: color,
                         mode: MODE_GEOMETRY,
@@ -327,6 +564,15 @@ impl Mesh {
                         vertices.push(v(triangle[1]));
                         vertices.push(v(triangle[2]));
                     }
+
+                    if let Some(width) = feather_width {
+                        feathering.triangles.clear();
+                        let tp = |p| (transform.transform_point(p), color);
+                        feathering
+                            .triangles
+                            .extend(triangles.iter().map(|t| [tp(t[0]), tp(t[1]), tp(t[2])]));
+                        feathering.fringe(width, |cp| vertices.push(fringe_vertex(cp)));
+                    }
                 }
 
                 render::PrimitiveKind::TrianglesMultiColor { triangles } => {
@@ -336,14 +582,62 @@ impl Mesh {
 
                     switch_to_plain_state!();
 
-                    let v = |(p, c): ([Scalar; 2], color::Rgba)| Vertex {
-                        position: [vx(p[0]), vy(p[1])],
+                    let v = |(p, rgba): ColoredPoint| Vertex {
+                        position: tv([vx(p[0]), vy(p[1])]),
                         tex_coords: [0.0, 0.0],
-                        rgba: gamma_srgb_to_linear(c.into()),
+                        rgba,
                         mode: MODE_GEOMETRY,
                     };
+                    let linear = |(p, c): ([Scalar; 2], color::Rgba)| -> ColoredPoint {
+                        (p, gamma_srgb_to_linear(c.into()))
+                    };
 
                     for triangle in triangles {
+                        vertices.push(v(linear(triangle[0])));
+                        vertices.push(v(linear(triangle[1])));
+                        vertices.push(v(linear(triangle[2])));
+                    }
+
+                    if let Some(width) = feather_width {
+                        feathering.triangles.clear();
+                        let tp = |p| {
+                            let (p, rgba) = linear(p);
+                            (transform.transform_point(p), rgba)
+                        };
+                        feathering
+                            .triangles
+                            .extend(triangles.iter().map(|t| [tp(t[0]), tp(t[1]), tp(t[2])]));
+                        feathering.fringe(width, |cp| vertices.push(fringe_vertex(cp)));
+                    }
+                }
+
+                render::PrimitiveKind::Shadow {
+                    color,
+                    offset,
+                    blur_radius,
+                    spread,
+                } => {
+                    switch_to_plain_state!();
+
+                    shadow_triangles.clear();
+                    let color = color.to_rgb();
+                    widget::shadow::triangles(
+                        shadow_triangles,
+                        rect,
+                        offset,
+                        blur_radius,
+                        spread,
+                        color,
+                    );
+
+                    let v = |(p, c): widget::triangles::ColoredPoint| Vertex {
+                        position: tv([vx(p[0]), vy(p[1])]),
+                        tex_coords: [0.0, 0.0],
+                        rgba: gamma_srgb_to_linear(c.into()),
+                        mode: MODE_GEOMETRY,
+                    };
+
+                    for triangle in shadow_triangles.iter() {
                         vertices.push(v(triangle[0]));
                         vertices.push(v(triangle[1]));
                         vertices.push(v(triangle[2]));
@@ -357,85 +651,57 @@ impl Mesh {
                 } => {
                     switch_to_plain_state!();
 
-                    positioned_glyphs.clear();
-                    positioned_glyphs.extend(text.positioned_glyphs(dpi_factor as f32));
+                    // Pushes the two triangles of a decoration quad.
+                    let push_quad =
+                        |vertices: &mut Vec<Vertex>, rect: Rect, color: color::Color| {
+                            let (l, r, b, t) = rect.l_r_b_t();
+                            let v = |x, y| Vertex {
+                                position: tv([vx(x), vy(y)]),
+                                tex_coords: [0.0, 0.0],
+                                rgba: gamma_srgb_to_linear(color.to_fsa()),
+                                mode: MODE_GEOMETRY,
+                            };
+                            vertices.extend_from_slice(&[
+                                v(l, t),
+                                v(r, b),
+                                v(l, b),
+                                v(l, t),
+                                v(r, b),
+                                v(r, t),
+                            ]);
+                        };
 
-                    // Queue the glyphs to be cached
-                    for glyph in positioned_glyphs.iter() {
-                        glyph_cache.queue_glyph(font_id.index(), glyph.clone());
+                    let (behind, in_front) = text.decoration_quads(color);
+                    for (rect, color) in behind {
+                        push_quad(vertices, rect, color);
                     }
 
-                    glyph_cache.cache_queued(|rect, data| {
-                        let width = (rect.max.x - rect.min.x) as usize;
-                        let height = (rect.max.y - rect.min.y) as usize;
-                        let mut dst_ix = rect.min.y as usize * glyph_cache_w + rect.min.x as usize;
-                        let mut src_ix = 0;
-                        for _ in 0..height {
-                            let dst_range = dst_ix..dst_ix + width;
-                            let src_range = src_ix..src_ix + width;
-                            let dst_slice = &mut glyph_cache_pixel_buffer[dst_range];
-                            let src_slice = &data[src_range];
-                            dst_slice.copy_from_slice(src_slice);
-                            dst_ix += glyph_cache_w;
-                            src_ix += width;
+                    // The glyphs are cached once all text within the frame has been queued, at
+                    // which point the vertices of each glyph are positioned.
+                    let rgba = gamma_srgb_to_linear(color.to_fsa());
+                    let font_id = font_id.index();
+                    for glyph in text.positioned_glyphs(dpi_factor as f32) {
+                        if glyph.pixel_bounding_box().is_none() {
+                            continue;
                         }
-                        glyph_cache_requires_upload = true;
-                    })?;
-
-                    let color = gamma_srgb_to_linear(color.to_fsa());
-                    let cache_id = font_id.index();
-                    let origin = rt::point(0.0, 0.0);
-
-                    // A closure to convert RustType rects to GL rects
-                    let to_vk_rect = |screen_rect: rt::Rect<i32>| rt::Rect {
-                        min: origin
-                            + (rt::vector(
-                                screen_rect.min.x as f32 / viewport_w as f32 - 0.5,
-                                screen_rect.min.y as f32 / viewport_h as f32 - 0.5,
-                            )) * 2.0,
-                        max: origin
-                            + (rt::vector(
-                                screen_rect.max.x as f32 / viewport_w as f32 - 0.5,
-                                screen_rect.max.y as f32 / viewport_h as f32 - 0.5,
-                            )) * 2.0,
-                    };
+                        let queued = QueuedGlyph {
+                            font_id,
+                            glyph,
+                            transform,
+                            vertex_start: vertices.len(),
+                        };
+                        glyph_queue.push(glyph_cache, queued);
+                        let v = Vertex {
+                            position: [0.0, 0.0],
+                            tex_coords: [0.0, 0.0],
+                            rgba,
+                            mode: MODE_TEXT,
+                        };
+                        vertices.extend((0..GLYPH_VERTEX_COUNT).map(|_| v));
+                    }
 
-                    for g in positioned_glyphs.drain(..) {
-                        if let Ok(Some((uv_rect, screen_rect))) = glyph_cache.rect_for(cache_id, &g)
-                        {
-                            let vk_rect = to_vk_rect(screen_rect);
-                            let v = |p, t| Vertex {
-                                position: p,
-                                tex_coords: t,
-                                rgba: color,
-                                mode: MODE_TEXT,
-                            };
-                            let mut push_v = |p, t| vertices.push(v(p, t));
-                            push_v(
-                                [vk_rect.min.x, vk_rect.max.y],
-                                [uv_rect.min.x, uv_rect.max.y],
-                            );
-                            push_v(
-                                [vk_rect.min.x, vk_rect.min.y],
-                                [uv_rect.min.x, uv_rect.min.y],
-                            );
-                            push_v(
-                                [vk_rect.max.x, vk_rect.min.y],
-                                [uv_rect.max.x, uv_rect.min.y],
-                            );
-                            push_v(
-                                [vk_rect.max.x, vk_rect.min.y],
-                                [uv_rect.max.x, uv_rect.min.y],
-                            );
-                            push_v(
-                                [vk_rect.max.x, vk_rect.max.y],
-                                [uv_rect.max.x, uv_rect.max.y],
-                            );
-                            push_v(
-                                [vk_rect.min.x, vk_rect.max.y],
-                                [uv_rect.min.x, uv_rect.max.y],
-                            );
-                        }
+                    for (rect, color) in in_front {
+                        push_quad(vertices, rect, color);
                     }
                 }
 
@@ -444,6 +710,9 @@ impl Mesh {
                     color,
                     source_rect,
                 } => {
+                    // Images packed into the atlas are drawn from their page, so that consecutive
+                    // images from the same page are batched into a single command.
+                    let (image_id, source_rect) = image_map.atlas().resolve(image_id, source_rect);
                     let image_ref = match image_map.get(&image_id) {
                         None => continue,
                         Some(img) => img,
@@ -501,7 +770,7 @@ impl Mesh {
                         let x = (x * dpi_factor / half_viewport_w) as f32;
                         let y = -((y * dpi_factor / half_viewport_h) as f32);
                         Vertex {
-                            position: [x, y],
+                            position: tv([x, y]),
                             tex_coords: t,
                             rgba: color,
                             mode: MODE_IMAGE,
@@ -524,8 +793,10 @@ impl Mesh {
                     push_v(r, t, [uv_r, uv_t]);
                 }
 
-                // We have no special case widgets to handle.
-                render::PrimitiveKind::Other(_) => (),
+                // We have no special case widgets to handle. Layers are handled above.
+                render::PrimitiveKind::Other(_)
+                | render::PrimitiveKind::BeginLayer { .. }
+                | render::PrimitiveKind::EndLayer { .. } => (),
             }
         }
 
@@ -537,8 +808,98 @@ impl Mesh {
             }
         }
 
+        // Cache the glyphs of all text within the frame at once, growing the cache if necessary.
+        let (start_w, start_h) = glyph_cache.dimensions();
+        let result = glyph_queue.cache(glyph_cache, max_glyph_cache_dims, |[w, h], rect, data| {
+            let glyph_cache_w = w as usize;
+            let glyph_cache_len = glyph_cache_w * h as usize;
+            if glyph_cache_pixel_buffer.len() != glyph_cache_len {
+                *glyph_cache_pixel_buffer = vec![0u8; glyph_cache_len];
+            }
+            let width = (rect.max.x - rect.min.x) as usize;
+            let height = (rect.max.y - rect.min.y) as usize;
+            let mut dst_ix = rect.min.y as usize * glyph_cache_w + rect.min.x as usize;
+            let mut src_ix = 0;
+            for _ in 0..height {
+                let dst_range = dst_ix..dst_ix + width;
+                let src_range = src_ix..src_ix + width;
+                let dst_slice = &mut glyph_cache_pixel_buffer[dst_range];
+                let src_slice = &data[src_range];
+                dst_slice.copy_from_slice(src_slice);
+                dst_ix += glyph_cache_w;
+                src_ix += width;
+            }
+            glyph_cache_requires_upload = true;
+        });
+        if let Err(err) = result {
+            // Restore the dimensions last reported so that the next fill reports any growth.
+            glyph_queue.clear(glyph_cache);
+            if glyph_cache.dimensions() != (start_w, start_h) {
+                glyph_cache
+                    .to_builder()
+                    .dimensions(start_w, start_h)
+                    .rebuild(glyph_cache);
+            }
+            return Err(err);
+        }
+        let glyph_cache_resized = glyph_cache.dimensions() != (start_w, start_h);
+
+        // Position the vertices of each glyph now that its location within the cache is known.
+        let origin = rt::point(0.0, 0.0);
+
+        // A closure to convert RustType rects to GL rects
+        let to_vk_rect = |screen_rect: rt::Rect<i32>| rt::Rect {
+            min: origin
+                + (rt::vector(
+                    screen_rect.min.x as f32 / viewport_w as f32 - 0.5,
+                    screen_rect.min.y as f32 / viewport_h as f32 - 0.5,
+                )) * 2.0,
+            max: origin
+                + (rt::vector(
+                    screen_rect.max.x as f32 / viewport_w as f32 - 0.5,
+                    screen_rect.max.y as f32 / viewport_h as f32 - 0.5,
+                )) * 2.0,
+        };
+
+        for (queued, uv_rect, screen_rect) in glyph_queue.drain_cached(glyph_cache) {
+            let vk_rect = to_vk_rect(screen_rect);
+            let corners = [
+                (
+                    [vk_rect.min.x, vk_rect.max.y],
+                    [uv_rect.min.x, uv_rect.max.y],
+                ),
+                (
+                    [vk_rect.min.x, vk_rect.min.y],
+                    [uv_rect.min.x, uv_rect.min.y],
+                ),
+                (
+                    [vk_rect.max.x, vk_rect.min.y],
+                    [uv_rect.max.x, uv_rect.min.y],
+                ),
+                (
+                    [vk_rect.max.x, vk_rect.min.y],
+                    [uv_rect.max.x, uv_rect.min.y],
+                ),
+                (
+                    [vk_rect.max.x, vk_rect.max.y],
+                    [uv_rect.max.x, uv_rect.max.y],
+                ),
+                (
+                    [vk_rect.min.x, vk_rect.max.y],
+                    [uv_rect.min.x, uv_rect.max.y],
+                ),
+            ];
+            let end = queued.vertex_start + GLYPH_VERTEX_COUNT;
+            let glyph_vertices = &mut vertices[queued.vertex_start..end];
+            for (vertex, &(position, tex_coords)) in glyph_vertices.iter_mut().zip(corners.iter()) {
+                vertex.position = transform_vertex(queued.transform, position);
+                vertex.tex_coords = tex_coords;
+            }
+        }
+
         let fill = Fill {
             glyph_cache_requires_upload,
+            glyph_cache_resized,
         };
 
         Ok(fill)
@@ -573,6 +934,311 @@ impl Mesh {
     }
 }
 
+impl GlyphQueue {
+    /// Construct an empty `GlyphQueue`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Queue the given glyph within the `cache` for caching.
+    pub fn push(&mut self, cache: &mut text::GlyphCache<'static>, queued: QueuedGlyph) {
+        cache.queue_glyph(queued.font_id, queued.glyph.clone());
+        self.glyphs.push(queued);
+    }
+
+    /// Clear all queued glyphs, along with the `cache`'s queue.
+    pub fn clear(&mut self, cache: &mut text::GlyphCache<'static>) {
+        cache.clear_queue();
+        self.glyphs.clear();
+    }
+
+    /// Cache all queued glyphs, doubling the dimensions of the `cache` up to `max_dims` until
+    /// they fit.
+    ///
+    /// `upload` is called with the dimensions of the cache, along with the region within the
+    /// cache and the pixel data of each newly cached glyph. Growing empties the cache, so when
+    /// the dimensions given to `upload` change, the texture backing the cache should be
+    /// reallocated before writing the glyph.
+    ///
+    /// Returns an error if the glyphs do not fit within a cache of `max_dims`, in which case the
+    /// glyphs remain queued.
+    pub fn cache<F>(
+        &mut self,
+        cache: &mut text::GlyphCache<'static>,
+        max_dims: [u32; 2],
+        mut upload: F,
+    ) -> Result<(), rt::gpu_cache::CacheWriteErr>
+    where
+        F: FnMut([u32; 2], rt::Rect<u32>, &[u8]),
+    {
+        loop {
+            let (w, h) = cache.dimensions();
+            let err = match cache.cache_queued(|rect, data| upload([w, h], rect, data)) {
+                Ok(_) => return Ok(()),
+                Err(err) => err,
+            };
+            let [max_w, max_h] = max_dims;
+            if w >= max_w && h >= max_h {
+                return Err(err);
+            }
+            let (w, h) = ((w * 2).min(max_w), (h * 2).min(max_h));
+            cache.to_builder().dimensions(w, h).rebuild(cache);
+
+            // Rebuilding empties the cache, so queue all of the frame's glyphs once more.
+            self.requeue(cache, self.glyphs.len());
+        }
+    }
+
+    /// Cache as many of the queued glyphs as fit within the `cache` at its current dimensions,
+    /// dropping the rest.
+    ///
+    /// `upload` is called with the dimensions of the cache just as for `cache`. This empties the
+    /// cache before caching, so every glyph that remains queued is passed to `upload` once more.
+    /// Dropped glyphs are never positioned, leaving their vertices empty.
+    ///
+    /// Returns the number of glyphs that were dropped.
+    pub fn cache_fitting<F>(
+        &mut self,
+        cache: &mut text::GlyphCache<'static>,
+        mut upload: F,
+    ) -> usize
+    where
+        F: FnMut([u32; 2], rt::Rect<u32>, &[u8]),
+    {
+        // Binary search for the number of glyphs that fit. A failed attempt leaves the cache in
+        // an unknown state, so each attempt starts from an empty cache.
+        let (mut fits, mut overflows) = (0, self.glyphs.len() + 1);
+        while overflows - fits > 1 {
+            let count = fits + (overflows - fits) / 2;
+            cache.clear();
+            self.requeue(cache, count);
+            match cache.cache_queued(|_, _| ()) {
+                Ok(_) => fits = count,
+                Err(_) => overflows = count,
+            }
+        }
+        cache.clear();
+        self.requeue(cache, fits);
+        let (w, h) = cache.dimensions();
+        cache
+            .cache_queued(|rect, data| upload([w, h], rect, data))
+            .expect("glyphs that fit once must fit again");
+        let dropped = self.glyphs.len() - fits;
+        self.glyphs.truncate(fits);
+        dropped
+    }
+
+    /// Drain the queued glyphs along with their texture coordinates and pixel rectangles within
+    /// the `cache`.
+    ///
+    /// Glyphs that are not within the cache are skipped.
+    pub fn drain_cached<'a>(
+        &'a mut self,
+        cache: &'a text::GlyphCache<'static>,
+    ) -> impl Iterator<Item = (QueuedGlyph, rt::Rect<f32>, rt::Rect<i32>)> + 'a {
+        self.glyphs.drain(..).filter_map(move |queued| {
+            match cache.rect_for(queued.font_id, &queued.glyph) {
+                Ok(Some((uv_rect, screen_rect))) => Some((queued, uv_rect, screen_rect)),
+                _ => None,
+            }
+        })
+    }
+
+    // Replace the `cache`'s queue with the first `count` queued glyphs.
+    fn requeue(&self, cache: &mut text::GlyphCache<'static>, count: usize) {
+        cache.clear_queue();
+        for queued in &self.glyphs[..count] {
+            cache.queue_glyph(queued.font_id, queued.glyph.clone());
+        }
+    }
+}
+
+impl Feathering {
+    // Produce the vertices of a fringe of triangles along the outline of `self.triangles`.
+    //
+    // Edges that belong to only one triangle are candidates for the outline. Those that lie
+    // against another triangle of the same primitive, e.g. at a T-junction between gradient
+    // slices or where dash segments overlap, are interior and are skipped. Each remaining edge
+    // is extruded outwards by `width`, fading from the color of the edge to transparent. The
+    // extruded points are shared between neighbouring edges so that no gaps appear at corners.
+    //
+    // The triangles are expected to be in pixel-aligned space, i.e. already transformed, so that
+    // the fringe is `width` wide on screen.
+    fn fringe<F>(&mut self, width: Scalar, mut push: F)
+    where
+        F: FnMut(ColoredPoint),
+    {
+        let Feathering {
+            ref triangles,
+            ref mut edges,
+            ref mut edge_indices,
+            ref mut normals,
+            ref mut cells,
+        } = *self;
+
+        edges.clear();
+        edge_indices.clear();
+        normals.clear();
+        cells.clear();
+
+        // Collect the unique edges, counting the triangles that share each.
+        for tri in triangles {
+            let [a, b, c] = [tri[0].0, tri[1].0, tri[2].0];
+            if triangle_area(a, b, c).abs() <= f64::EPSILON {
+                continue;
+            }
+            for &(i, j, k) in &[(0, 1, 2), (1, 2, 0), (2, 0, 1)] {
+                let (a, b, opposite) = (tri[i], tri[j], tri[k].0);
+                let (ka, kb) = (point_key(a.0), point_key(b.0));
+                let key = if ka < kb { (ka, kb) } else { (kb, ka) };
+                let next_index = edges.len();
+                let index = *edge_indices.entry(key).or_insert(next_index);
+                if index < next_index {
+                    edges[index].count += 1;
+                    continue;
+                }
+                // The normal of the edge facing away from the opposite point.
+                let d = [b.0[0] - a.0[0], b.0[1] - a.0[1]];
+                let len = (d[0] * d[0] + d[1] * d[1]).sqrt();
+                let mut normal = [d[1] / len, -d[0] / len];
+                let to_opposite = [opposite[0] - a.0[0], opposite[1] - a.0[1]];
+                if normal[0] * to_opposite[0] + normal[1] * to_opposite[1] > 0.0 {
+                    normal = [-normal[0], -normal[1]];
+                }
+                edges.push(FringeEdge {
+                    a,
+                    b,
+                    normal,
+                    count: 1,
+                });
+            }
+        }
+
+        // Index the triangles by grid cell so that covered edges can be found cheaply.
+        let bounds = |tri: &[ColoredPoint; 3]| {
+            let xs = [tri[0].0[0], tri[1].0[0], tri[2].0[0]];
+            let ys = [tri[0].0[1], tri[1].0[1], tri[2].0[1]];
+            let min = |v: [Scalar; 3]| v[0].min(v[1]).min(v[2]);
+            let max = |v: [Scalar; 3]| v[0].max(v[1]).max(v[2]);
+            (min(xs), max(xs), min(ys), max(ys))
+        };
+        let (mut l, mut r, mut b, mut t) = (f64::MAX, f64::MIN, f64::MAX, f64::MIN);
+        let mut extent_sum = 0.0;
+        for tri in triangles {
+            let (tl, tr, tb, tt) = bounds(tri);
+            l = l.min(tl);
+            r = r.max(tr);
+            b = b.min(tb);
+            t = t.max(tt);
+            extent_sum += (tr - tl).max(tt - tb);
+        }
+        let average_extent = extent_sum / triangles.len().max(1) as Scalar;
+        let cell_size = average_extent
+            .max((r - l).max(t - b) / MAX_FEATHER_GRID_CELLS)
+            .max(f64::EPSILON);
+        let cell = |x: Scalar, y: Scalar| -> GridCell {
+            (
+                (x / cell_size).floor() as i64,
+                (y / cell_size).floor() as i64,
+            )
+        };
+        for (i, tri) in triangles.iter().enumerate() {
+            let (tl, tr, tb, tt) = bounds(tri);
+            let (min, max) = (cell(tl, tb), cell(tr, tt));
+            for cx in min.0..=max.0 {
+                for cy in min.1..=max.1 {
+                    cells.push(((cx, cy), i));
+                }
+            }
+        }
+        cells.sort_unstable();
+
+        // Whether or not `p` lies within or on the edge of any of the triangles.
+        let covered = |p: Point| {
+            let key = cell(p[0], p[1]);
+            let start = cells.partition_point(|&(c, _)| c < key);
+            cells[start..]
+                .iter()
+                .take_while(|&&(c, _)| c == key)
+                .any(|&(_, i)| {
+                    let tri = &triangles[i];
+                    let [a, b, c] = [tri[0].0, tri[1].0, tri[2].0];
+                    let area = triangle_area(a, b, c);
+                    if area.abs() <= f64::EPSILON {
+                        return false;
+                    }
+                    let (u, v, w) = (
+                        triangle_area(p, b, c) / area,
+                        triangle_area(a, p, c) / area,
+                        triangle_area(a, b, p) / area,
+                    );
+                    let min = -FEATHER_COVER_TOLERANCE;
+                    u >= min && v >= min && w >= min
+                })
+        };
+
+        // An edge is on the outline if the point just outside its middle is not covered.
+        let nudge = width * 0.01;
+        for edge in edges.iter_mut().filter(|e| e.count == 1) {
+            let (a, b) = (edge.a.0, edge.b.0);
+            let outside = [
+                (a[0] + b[0]) / 2.0 + edge.normal[0] * nudge,
+                (a[1] + b[1]) / 2.0 + edge.normal[1] * nudge,
+            ];
+            if covered(outside) {
+                edge.count += 1;
+            }
+        }
+
+        // Sum the normals of the outline edges touching each point.
+        for edge in edges.iter().filter(|e| e.count == 1) {
+            for p in &[edge.a.0, edge.b.0] {
+                let n = normals.entry(point_key(*p)).or_insert([0.0, 0.0]);
+                n[0] += edge.normal[0];
+                n[1] += edge.normal[1];
+            }
+        }
+
+        // The offset of the extruded point for `p` on an edge with the given normal.
+        let offset = |p: Point, edge_normal: [Scalar; 2]| {
+            let sum = normals[&point_key(p)];
+            let len = (sum[0] * sum[0] + sum[1] * sum[1]).sqrt();
+            if len <= f64::EPSILON {
+                return [edge_normal[0] * width, edge_normal[1] * width];
+            }
+            let n = [sum[0] / len, sum[1] / len];
+            let cos = n[0] * edge_normal[0] + n[1] * edge_normal[1];
+            let scale = width / cos.max(1.0 / MAX_FEATHER_MITER);
+            [n[0] * scale, n[1] * scale]
+        };
+
+        for edge in edges.iter().filter(|e| e.count == 1) {
+            let (a, b) = (edge.a, edge.b);
+            let (oa, ob) = (offset(a.0, edge.normal), offset(b.0, edge.normal));
+            let outer = |(p, c): ColoredPoint, o: [Scalar; 2]| {
+                ([p[0] + o[0], p[1] + o[1]], [c[0], c[1], c[2], 0.0])
+            };
+            let (a_out, b_out) = (outer(a, oa), outer(b, ob));
+            push(a);
+            push(b);
+            push(b_out);
+            push(a);
+            push(b_out);
+            push(a_out);
+        }
+    }
+}
+
+// Twice the signed area of the triangle `abc`.
+fn triangle_area(a: Point, b: Point, c: Point) -> Scalar {
+    (b[0] - a[0]) * (c[1] - a[1]) - (b[1] - a[1]) * (c[0] - a[0])
+}
+
+fn point_key(p: Point) -> PointKey {
+    // Adding zero normalises `-0.0` to `0.0` so that both produce the same key.
+    ((p[0] + 0.0).to_bits(), (p[1] + 0.0).to_bits())
+}
+
 impl<'a> Iterator for Commands<'a> {
     type Item = Command;
     fn next(&mut self) -> Option<Self::Item> {
@@ -581,6 +1247,9 @@ impl<'a> Iterator for Commands<'a> {
             PreparedCommand::Scizzor(scizzor) => Command::Scizzor(scizzor),
             PreparedCommand::Plain(ref range) => Command::Draw(Draw::Plain(range.clone())),
             PreparedCommand::Image(id, ref range) => Command::Draw(Draw::Image(id, range.clone())),
+            PreparedCommand::BeginLayer => Command::BeginLayer,
+            PreparedCommand::EndLayer(opacity) => Command::EndLayer(opacity),
+            PreparedCommand::Mask(ref range) => Command::Mask(range.clone()),
         })
     }
 }
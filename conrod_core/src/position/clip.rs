@@ -0,0 +1,81 @@
+//! Non-rectangular shapes to which the children of a widget may be cropped.
+
+use super::{Point, Rect, Scalar};
+use widget::rounded_rectangle;
+
+/// A shape to which the children of a widget are clipped, in addition to the widget's `kid_area`.
+///
+/// Clip shapes are specified via the `Widget::crop_kids_to` builder method and are described
+/// relative to the `Rect` of the widget's `kid_area`.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum ClipShape {
+    /// A rectangle with corners rounded by the given radius.
+    RoundedRectangle {
+        /// The radius of the circle used to round each corner.
+        radius: Scalar,
+    },
+    /// A polygon described by the points along its outline.
+    ///
+    /// Points are normalised to the area, where `[-1.0, -1.0]` is the bottom left corner and
+    /// `[1.0, 1.0]` is the top right corner. The polygon may be concave.
+    Polygon(&'static [Point]),
+}
+
+/// An iterator yielding the points along the outline of a `ClipShape`.
+#[derive(Clone)]
+pub enum Points {
+    /// The outline of a `ClipShape::RoundedRectangle`.
+    RoundedRectangle(rounded_rectangle::Points),
+    /// The outline of a `ClipShape::Polygon`.
+    Polygon {
+        /// The area to which the normalised points are scaled.
+        rect: Rect,
+        /// The remaining normalised points.
+        points: std::slice::Iter<'static, Point>,
+    },
+}
+
+impl ClipShape {
+    /// Produce the points along the outline of the shape when clipping the given `rect`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use conrod_core::position::{ClipShape, Rect};
+    ///
+    /// const DIAMOND: &[[f64; 2]] = &[[0.0, 1.0], [1.0, 0.0], [0.0, -1.0], [-1.0, 0.0]];
+    /// let rect = Rect::from_xy_dim([10.0, 0.0], [20.0, 10.0]);
+    /// let points: Vec<_> = ClipShape::Polygon(DIAMOND).points(rect).collect();
+    /// assert_eq!(points, vec![[10.0, 5.0], [20.0, 0.0], [10.0, -5.0], [0.0, 0.0]]);
+    /// ```
+    pub fn points(&self, rect: Rect) -> Points {
+        match *self {
+            ClipShape::RoundedRectangle { radius } => {
+                let (w, h) = rect.w_h();
+                let radius = radius.max(0.0).min(w.min(h) / 2.0);
+                let resolution = rounded_rectangle::DEFAULT_CORNER_RESOLUTION;
+                Points::RoundedRectangle(rounded_rectangle::points(rect, radius, resolution))
+            }
+            ClipShape::Polygon(points) => Points::Polygon {
+                rect: rect,
+                points: points.iter(),
+            },
+        }
+    }
+}
+
+impl Iterator for Points {
+    type Item = Point;
+    fn next(&mut self) -> Option<Self::Item> {
+        match *self {
+            Points::RoundedRectangle(ref mut points) => points.next(),
+            Points::Polygon {
+                rect,
+                ref mut points,
+            } => points.next().map(|&[x, y]| {
+                let (rx, ry, w, h) = rect.x_y_w_h();
+                [rx + x * w / 2.0, ry + y * h / 2.0]
+            }),
+        }
+    }
+}
@@ -3,13 +3,17 @@
 use widget;
 use Ui;
 
+pub use self::clip::ClipShape;
 pub use self::range::{Edge, Range};
 pub use self::rect::{Corner, Rect};
+pub use self::transform::Transform;
 //pub use self::matrix::Matrix;
 
 //pub mod matrix;
+pub mod clip;
 pub mod range;
 pub mod rect;
+pub mod transform;
 
 /// An alias over the Scalar type used throughout Conrod.
 ///
@@ -92,6 +96,7 @@ pub enum Direction {
 }
 
 /// The orientation of **Align**ment along some **Axis**.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
 pub enum Align {
     /// **Align** our **Start** with the **Start** of some other widget along the **Axis**.
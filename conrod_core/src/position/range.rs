@@ -6,6 +6,7 @@ use super::Scalar;
 ///
 /// As an example, a **Rect** is made up of two **Range**s; one along the *x* axis, and one along
 /// the *y* axis.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[derive(Copy, Clone, Debug, PartialEq)]
 pub struct Range {
     /// The start of some `Range` along an axis.
@@ -5,6 +5,7 @@ use super::{Dimensions, Padding, Point, Range, Scalar};
 /// Defines a Rectangle's bounds across the x and y axes.
 ///
 /// This is a conrod-specific Rectangle in that it's designed to help with layout.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[derive(Copy, Clone, Debug, PartialEq)]
 pub struct Rect {
     /// The start and end positions of the Rectangle on the x axis.
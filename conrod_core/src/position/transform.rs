@@ -0,0 +1,158 @@
+//! A type for describing 2D affine transformations, i.e. rotation, scaling, skewing and
+//! translation.
+
+use super::{Point, Rect, Scalar};
+
+/// A 2D affine transformation.
+///
+/// The `matrix` is stored in row-major order, where a point `[x, y]` is transformed to:
+///
+/// ```txt
+/// [m[0][0] * x + m[0][1] * y + m[0][2],
+///  m[1][0] * x + m[1][1] * y + m[1][2]]
+/// ```
+///
+/// Transforms are composed via the `then` method. Widgets may be transformed via the
+/// `Widget::transform` builder method.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Transform {
+    /// The 2x3 affine transformation matrix.
+    pub matrix: [[Scalar; 3]; 2],
+}
+
+impl Transform {
+    /// The transform that leaves all points unchanged.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use conrod_core::position::Transform;
+    ///
+    /// assert_eq!(Transform::identity().transform_point([3.0, 4.0]), [3.0, 4.0]);
+    /// ```
+    pub fn identity() -> Self {
+        Transform {
+            matrix: [[1.0, 0.0, 0.0], [0.0, 1.0, 0.0]],
+        }
+    }
+
+    /// A transform that translates points by the given amount along each axis.
+    pub fn translation(x: Scalar, y: Scalar) -> Self {
+        Transform {
+            matrix: [[1.0, 0.0, x], [0.0, 1.0, y]],
+        }
+    }
+
+    /// A transform that rotates points anti-clockwise about the origin by the given angle in
+    /// radians.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use conrod_core::position::Transform;
+    ///
+    /// let [x, y] = Transform::rotation(std::f64::consts::PI / 2.0).transform_point([1.0, 0.0]);
+    /// assert!(x.abs() < 1e-9 && (y - 1.0).abs() < 1e-9);
+    /// ```
+    pub fn rotation(radians: Scalar) -> Self {
+        let (sin, cos) = radians.sin_cos();
+        Transform {
+            matrix: [[cos, -sin, 0.0], [sin, cos, 0.0]],
+        }
+    }
+
+    /// A transform that scales points away from the origin by the given factor along each axis.
+    pub fn scale(x: Scalar, y: Scalar) -> Self {
+        Transform {
+            matrix: [[x, 0.0, 0.0], [0.0, y, 0.0]],
+        }
+    }
+
+    /// Produce a transform that applies `self` followed by `next`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use conrod_core::position::Transform;
+    ///
+    /// let t = Transform::scale(2.0, 2.0).then(Transform::translation(1.0, 0.0));
+    /// assert_eq!(t.transform_point([1.0, 1.0]), [3.0, 2.0]);
+    /// ```
+    pub fn then(self, next: Self) -> Self {
+        let a = self.matrix;
+        let b = next.matrix;
+        let mut matrix = [[0.0; 3]; 2];
+        for row in 0..2 {
+            for col in 0..3 {
+                matrix[row][col] = b[row][0] * a[0][col] + b[row][1] * a[1][col];
+            }
+            matrix[row][2] += b[row][2];
+        }
+        Transform { matrix: matrix }
+    }
+
+    /// Produce a transform that applies `self` about the given `origin` rather than about
+    /// `[0.0, 0.0]`.
+    ///
+    /// This is used to rotate and scale widgets about the centre of their `Rect`.
+    pub fn about(self, origin: Point) -> Self {
+        if self.is_identity() {
+            return self;
+        }
+        Transform::translation(-origin[0], -origin[1])
+            .then(self)
+            .then(Transform::translation(origin[0], origin[1]))
+    }
+
+    /// The transform that reverses `self`.
+    ///
+    /// Returns `None` if the transform cannot be inverted, e.g. if it scales some axis to zero.
+    pub fn inverse(self) -> Option<Self> {
+        let [[a, b, tx], [c, d, ty]] = self.matrix;
+        let det = a * d - b * c;
+        if det == 0.0 || !det.is_finite() {
+            return None;
+        }
+        let (ia, ib, ic, id) = (d / det, -b / det, -c / det, a / det);
+        let matrix = [
+            [ia, ib, -(ia * tx + ib * ty)],
+            [ic, id, -(ic * tx + id * ty)],
+        ];
+        Some(Transform { matrix: matrix })
+    }
+
+    /// Whether or not the transform leaves all points unchanged.
+    pub fn is_identity(&self) -> bool {
+        *self == Transform::identity()
+    }
+
+    /// Apply the transform to the given point.
+    pub fn transform_point(&self, [x, y]: Point) -> Point {
+        let [[a, b, tx], [c, d, ty]] = self.matrix;
+        [a * x + b * y + tx, c * x + d * y + ty]
+    }
+
+    /// The smallest axis-aligned `Rect` that contains the given `rect` once transformed.
+    pub fn bounding_rect(&self, rect: Rect) -> Rect {
+        if self.is_identity() {
+            return rect;
+        }
+        let (l, r, b, t) = rect.l_r_b_t();
+        let corners = [[l, b], [l, t], [r, t], [r, b]];
+        let first = self.transform_point(corners[0]);
+        let (mut min, mut max) = (first, first);
+        for &corner in &corners[1..] {
+            let [x, y] = self.transform_point(corner);
+            min = [min[0].min(x), min[1].min(y)];
+            max = [max[0].max(x), max[1].max(y)];
+        }
+        Rect::from_corners(min, max)
+    }
+}
+
+impl Default for Transform {
+    fn default() -> Self {
+        Transform::identity()
+    }
+}
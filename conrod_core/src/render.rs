@@ -9,9 +9,10 @@
 //! This is the only module in which the src graphics crate will be used directly.
 
 use color;
+use fnv;
 use graph::{self, Graph};
 use image;
-use position::{Align, Dimensions};
+use position::{Align, Dimensions, Range, Transform};
 use std;
 use text;
 use theme::Theme;
@@ -19,6 +20,9 @@ use widget::triangles::{ColoredPoint, Triangle};
 use widget::{self, Widget};
 use {Color, FontSize, Point, Rect, Scalar};
 
+#[cfg(feature = "serde")]
+pub mod stream;
+
 /// An iterator-like type that yields a reference to each primitive in order of depth for
 /// rendering.
 ///
@@ -28,7 +32,9 @@ use {Color, FontSize, Point, Rect, Scalar};
 /// require ownership over the sequence of primitives, consider using the `OwnedPrimitives` type.
 /// The `OwnedPrimitives` type can be produced by calling the `Primitives::owned` method.
 pub struct Primitives<'a> {
-    crop_stack: Vec<(widget::Id, Rect)>,
+    crop_stack: Vec<(widget::Id, Rect, Option<std::ops::Range<usize>>)>,
+    /// A buffer containing the outline of each clip shape within the `crop_stack`.
+    clip_points: Vec<Point>,
     depth_order: std::slice::Iter<'a, widget::Id>,
     graph: &'a Graph,
     theme: &'a Theme,
@@ -36,6 +42,49 @@ pub struct Primitives<'a> {
     window_rect: Rect,
     /// A buffer to use for triangulating polygons and lines for the `Triangles`.
     triangles: Vec<Triangle<Point>>,
+    /// A buffer to use for coloring the triangles of shapes filled with a gradient.
+    colored_triangles: Vec<Triangle<ColoredPoint>>,
+    /// A buffer to use for applying opacity to multicolored triangles.
+    faded_triangles: Vec<Triangle<ColoredPoint>>,
+    /// Whether or not `BeginLayer` and `EndLayer` primitives are produced for isolated layers.
+    isolate_layers: bool,
+    /// The isolated layers that are currently open, from outermost to innermost.
+    layer_stack: Vec<Layer>,
+    /// A widget that was retrieved from the `depth_order` but deferred by a layer primitive, by
+    /// the remaining slices of a nine-slice `Image` or by the remaining runs of a rich `Text`.
+    pending_widget: Option<VisibleWidget<'a>>,
+    /// The remaining `(rect, source_rect)` slices of the current nine-slice `Image` in reverse.
+    image_slices: Vec<(Rect, Rect)>,
+    /// The remaining parts of the runs of the current rich `Text` in reverse.
+    rich_text_parts: Vec<RichTextPart>,
+}
+
+// A primitive produced for the run at the given index within the runs of a rich `Text`.
+//
+// All highlights are produced first so that they never cover the glyphs of neighbouring runs.
+// Underlines are drawn along with the glyphs of each run as a decoration of its `Text`.
+#[derive(Copy, Clone)]
+enum RichTextPart {
+    Highlight(usize),
+    Glyphs(usize),
+}
+
+// A widget retrieved from the `depth_order` along with its scizzor and the range of its clip
+// outline within the `clip_points` buffer.
+type VisibleWidget<'a> = (
+    widget::Id,
+    Rect,
+    Option<std::ops::Range<usize>>,
+    &'a graph::Container,
+);
+
+// An isolated layer that has begun but not yet ended.
+#[derive(Copy, Clone)]
+struct Layer {
+    id: widget::Id,
+    scizzor: Rect,
+    rect: Rect,
+    opacity: f32,
 }
 
 /// An owned alternative to the `Primitives` type.
@@ -43,13 +92,22 @@ pub struct Primitives<'a> {
 /// This is particularly useful for sending rendering data across threads.
 ///
 /// Produce an `OwnedPrimitives` instance via the `Primitives::owned` method.
+///
+/// When the `serde` feature is enabled, `OwnedPrimitives` may be serialized and deserialized.
+/// Fonts are referred to by their `font::Id` and are not serialized. After deserializing, the
+/// fonts referenced by `font_ids` must be supplied via `insert_font`, otherwise the `Text`
+/// primitives that use them are skipped while walking.
 #[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct OwnedPrimitives {
     primitives: Vec<OwnedPrimitive>,
     triangles_single_color: Vec<Triangle<Point>>,
     triangles_multi_color: Vec<Triangle<ColoredPoint>>,
     line_infos: Vec<text::line::Info>,
     texts_string: String,
+    clip_points: Vec<Point>,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    fonts: fnv::FnvHashMap<text::font::Id, text::Font>,
 }
 
 /// A trait that allows the user to remain generic over types yielding `Primitive`s.
@@ -84,6 +142,23 @@ pub struct Primitive<'a> {
     pub scizzor: Rect,
     /// The bounding rectangle for the `Primitive`.
     pub rect: Rect,
+    /// The outline of a polygon to which the primitive widget should be clipped in addition to
+    /// the `scizzor`, produced by `Widget::crop_kids_to`.
+    ///
+    /// The polygon may be concave, so it should be filled using the even-odd rule, e.g. by
+    /// inverting a stencil buffer for each triangle in a fan about the first point. The outline
+    /// has already been transformed.
+    pub clip: Option<&'a [Point]>,
+    /// The transform that should be applied to the `rect` and to all vertices and glyph positions
+    /// of the `Primitive`, produced by `Widget::transform`.
+    ///
+    /// This is the identity transform for all untransformed widgets.
+    pub transform: Transform,
+    /// The opacity with which a `PrimitiveKind::Other` widget should be drawn, inherited from the
+    /// widget and its parents.
+    ///
+    /// This is always `1.0` for the other kinds, as their colors already have the opacity applied.
+    pub opacity: f32,
 }
 
 /// The unique kind for each primitive element in the Ui.
@@ -136,6 +211,21 @@ pub enum PrimitiveKind<'a> {
         font_id: text::font::Id,
     },
 
+    /// A soft drop `Shadow`, produced by the primitive `Shadow` widget.
+    ///
+    /// The shadow is cast by the primitive's `rect`. See `widget::shadow::triangles` for a
+    /// tessellation that requires no special support from the backend.
+    Shadow {
+        /// The colour of the shadow beneath the casting rectangle.
+        color: Color,
+        /// The distance by which the shadow is offset from the casting rectangle.
+        offset: Point,
+        /// The distance over which the edge of the shadow fades to transparent.
+        blur_radius: Scalar,
+        /// The distance by which the shadow is grown beyond the casting rectangle.
+        spread: Scalar,
+    },
+
     /// An `Other` variant will be yielded for every non-primitive widget in the list.
     ///
     /// Most of the time, this variant can be ignored, however it is useful for users who need to
@@ -147,12 +237,32 @@ pub enum PrimitiveKind<'a> {
     /// They can then retrieve the unique state of the widget and cast it to its actual type using
     /// either of the `Container::state_and_style` or `Container::unique_widget_state` methods.
     Other(&'a graph::Container),
+
+    /// Begins an isolated layer, produced for widgets built with `Widget::isolated_layer`.
+    ///
+    /// All primitives up until the matching `EndLayer` should be drawn to a new, transparent
+    /// render target which is then composited onto the target beneath it with the given `opacity`.
+    ///
+    /// This is only produced when requested via `Primitives::with_isolated_layers`.
+    BeginLayer {
+        /// The opacity with which the layer will be composited.
+        opacity: f32,
+    },
+
+    /// Ends the most recently begun isolated layer.
+    EndLayer {
+        /// The opacity with which the layer should be composited onto the target beneath it.
+        opacity: f32,
+    },
 }
 
 /// A type used for producing a `PositionedGlyph` iterator.
 ///
 /// We produce this type rather than the `&[PositionedGlyph]`s directly so that we can properly
 /// handle "HiDPI" scales when caching glyphs.
+///
+/// With the `shaping` feature, the text is shaped if the `font::Map` retains the data of its font.
+/// The fonts of `OwnedPrimitives` do not retain their data, so their text is never shaped.
 pub struct Text<'a> {
     window_dim: Dimensions,
     text: &'a str,
@@ -163,17 +273,26 @@ pub struct Text<'a> {
     justify: text::Justify,
     y_align: Align,
     line_spacing: Scalar,
+    decorations: text::Decorations,
+    decoration_metrics: text::DecorationMetrics,
+    #[cfg(feature = "shaping")]
+    font_data: Option<&'a [u8]>,
 }
 
 #[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 struct OwnedPrimitive {
+    #[cfg_attr(feature = "serde", serde(with = "serde_widget_id"))]
     id: widget::Id,
     kind: OwnedPrimitiveKind,
     scizzor: Rect,
+    clip: Option<std::ops::Range<usize>>,
     rect: Rect,
+    transform: Transform,
 }
 
 #[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 enum OwnedPrimitiveKind {
     Rectangle {
         color: Color,
@@ -195,19 +314,33 @@ enum OwnedPrimitiveKind {
         font_id: text::font::Id,
         text: OwnedText,
     },
+    Shadow {
+        color: Color,
+        offset: Point,
+        blur_radius: Scalar,
+        spread: Scalar,
+    },
+    BeginLayer {
+        opacity: f32,
+    },
+    EndLayer {
+        opacity: f32,
+    },
 }
 
 #[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 struct OwnedText {
     str_byte_range: std::ops::Range<usize>,
     line_infos_range: std::ops::Range<usize>,
     window_dim: Dimensions,
-    font: text::Font,
     font_size: FontSize,
     rect: Rect,
     justify: text::Justify,
     y_align: Align,
     line_spacing: Scalar,
+    decorations: text::Decorations,
+    decoration_metrics: text::DecorationMetrics,
 }
 
 /// An iterator-like type for yielding `Primitive`s from an `OwnedPrimitives`.
@@ -217,6 +350,8 @@ pub struct WalkOwnedPrimitives<'a> {
     triangles_multi_color: &'a [Triangle<ColoredPoint>],
     line_infos: &'a [text::line::Info],
     texts_str: &'a str,
+    clip_points: &'a [Point],
+    fonts: &'a fnv::FnvHashMap<text::font::Id, text::Font>,
 }
 
 impl<'a> Text<'a> {
@@ -235,6 +370,18 @@ impl<'a> Text<'a> {
         self,
         dpi_factor: f32,
     ) -> impl 'a + Iterator<Item = rusttype::PositionedGlyph<'static>> {
+        self.lines(dpi_factor).flat_map(|(_, glyphs)| glyphs)
+    }
+
+    /// Produces each line of the text along with the `PositionedGlyph` of each of its characters.
+    ///
+    /// Positions are in pixel coordinates with the origin at the top-left of the window, scaled by
+    /// the given `dpi_factor` (see `positioned_glyphs`). Lines aligned via `text::Justify::Full`
+    /// have extra space added between their words.
+    pub fn lines(
+        self,
+        dpi_factor: f32,
+    ) -> impl 'a + Iterator<Item = (&'a str, text::line::Layout<'static, 'a>)> {
         let Text {
             window_dim,
             text,
@@ -245,30 +392,178 @@ impl<'a> Text<'a> {
             justify,
             y_align,
             line_spacing,
+            #[cfg(feature = "shaping")]
+            font_data,
             ..
         } = self;
 
+        // Text is shaped if the data of its font is available, resolving its direction once for
+        // all lines.
+        #[cfg(feature = "shaping")]
+        let shaping = font_data
+            .and_then(|data| text::shaping::Face::new(font, data))
+            .map(|face| (face, text::shaping::Bidi::new(text)));
+
         // Convert conrod coordinates to pixel coordinates.
         let trans_x = move |x: Scalar| (x + window_dim[0] / 2.0) * dpi_factor as Scalar;
         let trans_y = move |y: Scalar| ((-y) + window_dim[1] / 2.0) * dpi_factor as Scalar;
 
         // Produce the text layout iterators.
+        let scale = text::f32_pt_to_scale(font_size as f32 * dpi_factor);
         let line_infos = line_infos.iter().cloned();
-        let lines = line_infos.clone().map(move |info| &text[info.byte_range()]);
-        let line_rects =
-            text::line::rects(line_infos, font_size, rect, justify, y_align, line_spacing);
+        let line_rects = text::line::rects(
+            line_infos.clone(),
+            font_size,
+            rect,
+            justify,
+            y_align,
+            line_spacing,
+        );
 
-        // Clear the existing glyphs and fill the buffer with glyphs for this Text.
-        let scale = text::f32_pt_to_scale(font_size as f32 * dpi_factor);
-        lines.zip(line_rects).flat_map(move |(line, line_rect)| {
+        line_infos.zip(line_rects).map(move |(info, line_rect)| {
+            let line = &text[info.byte_range()];
             let (x, y) = (
                 trans_x(line_rect.left()) as f32,
                 trans_y(line_rect.bottom()) as f32,
             );
-            let point = text::rt::Point { x: x, y: y };
-            font.layout(line, scale, point)
+            let point = text::rt::Point { x, y };
+            #[cfg(feature = "shaping")]
+            {
+                if let Some((ref face, ref bidi)) = shaping {
+                    let shaped =
+                        text::shaping::shape_line(bidi, info.byte_range(), face, scale, 0.0);
+                    // Stretch against the shaped width, which may differ from that of `line_infos`.
+                    let word_spacing = match (justify, info.end_break) {
+                        (text::Justify::Full, text::line::Break::Wrap { .. }) => {
+                            shaped.word_spacing(line_rect.w() * dpi_factor as Scalar) as f32
+                        }
+                        _ => 0.0,
+                    };
+                    let glyphs =
+                        text::line::shaped_layout(line, &shaped, font, scale, point, word_spacing);
+                    return (line, glyphs);
+                }
+            }
+            let word_spacing = text::line::word_spacing(text, &info, justify, line_rect);
+            let word_spacing = word_spacing as f32 * dpi_factor;
+            let glyphs = text::line::layout(line, font, scale, point, word_spacing);
+            (line, glyphs)
+        })
+    }
+
+    /// The font with which the text is laid out.
+    pub fn font(&self) -> &'a text::Font {
+        self.font
+    }
+
+    /// The font size of the text in points.
+    pub fn font_size(&self) -> FontSize {
+        self.font_size
+    }
+
+    /// The lines and background with which each line of the text is decorated.
+    pub fn decorations(&self) -> text::Decorations {
+        self.decorations
+    }
+
+    /// The area behind each non-empty line that is filled by the highlight of the `decorations`.
+    ///
+    /// Each spans from the font's descent below the baseline up to the top of the line. Yields
+    /// nothing if the text has no highlight.
+    pub fn highlight_rects(&self) -> impl 'a + Iterator<Item = Rect> {
+        let descent = self
+            .font
+            .v_metrics(text::pt_to_scale(self.font_size))
+            .descent as Scalar;
+        let line_infos = if self.decorations.highlight.is_some() {
+            self.line_infos
+        } else {
+            &[]
+        };
+        self.line_rects(line_infos).map(move |line_rect| {
+            let bottom = line_rect.bottom() + descent;
+            Rect {
+                x: line_rect.x,
+                y: Range::new(bottom, line_rect.top()),
+            }
+        })
+    }
+
+    /// The area covered by each underline, strikethrough and overline of the `decorations`
+    /// across each non-empty line, to be filled with the color of the text.
+    ///
+    /// Each line is placed relative to the baseline of the line of text via the font's
+    /// `text::DecorationMetrics`.
+    pub fn decoration_rects(&self) -> impl 'a + Iterator<Item = Rect> {
+        let Text {
+            decorations,
+            decoration_metrics: m,
+            ..
+        } = *self;
+        let lines = [
+            (
+                decorations.underline,
+                m.underline_offset,
+                m.underline_thickness,
+            ),
+            (
+                decorations.strikethrough,
+                m.strikethrough_offset,
+                m.strikethrough_thickness,
+            ),
+            (
+                decorations.overline,
+                m.overline_offset,
+                m.overline_thickness,
+            ),
+        ];
+        let lines: Vec<_> = lines
+            .iter()
+            .filter(|&&(enabled, _, _)| enabled)
+            .map(|&(_, offset, thickness)| (offset, thickness))
+            .collect();
+        self.line_rects(self.line_infos).flat_map(move |line_rect| {
+            let baseline = line_rect.bottom();
+            lines
+                .clone()
+                .into_iter()
+                .map(move |(offset, thickness)| Rect {
+                    x: line_rect.x,
+                    y: Range::from_pos_and_len(baseline + offset, thickness),
+                })
         })
     }
+
+    /// The rectangles with which the text is decorated, each paired with the color that fills it.
+    ///
+    /// Returns `(behind, in_front)`, where `behind` yields the `highlight_rects` filled with the
+    /// highlight, to be drawn behind the glyphs, and `in_front` yields the `decoration_rects`
+    /// filled with the given text `color`, to be drawn in front of them.
+    pub fn decoration_quads(
+        &self,
+        color: Color,
+    ) -> (
+        impl 'a + Iterator<Item = (Rect, Color)>,
+        impl 'a + Iterator<Item = (Rect, Color)>,
+    ) {
+        let highlight = self.decorations.highlight.unwrap_or(color::TRANSPARENT);
+        let behind = self.highlight_rects().map(move |rect| (rect, highlight));
+        let in_front = self.decoration_rects().map(move |rect| (rect, color));
+        (behind, in_front)
+    }
+
+    // The `Rect` of each of the given lines of the text that contains some characters.
+    fn line_rects(&self, line_infos: &'a [text::line::Info]) -> impl 'a + Iterator<Item = Rect> {
+        let line_rects = text::line::rects(
+            line_infos.iter().cloned(),
+            self.font_size,
+            self.rect,
+            self.justify,
+            self.y_align,
+            self.line_spacing,
+        );
+        line_rects.filter(|rect| rect.w() > 0.0)
+    }
 }
 
 impl<'a> Primitives<'a> {
@@ -282,28 +577,104 @@ impl<'a> Primitives<'a> {
     ) -> Self {
         Primitives {
             crop_stack: Vec::new(),
+            clip_points: Vec::new(),
             depth_order: depth_order.iter(),
             graph: graph,
             theme: theme,
             fonts: fonts,
             window_rect: Rect::from_xy_dim([0.0, 0.0], window_dim),
             triangles: Vec::new(),
+            colored_triangles: Vec::new(),
+            faded_triangles: Vec::new(),
+            isolate_layers: false,
+            layer_stack: Vec::new(),
+            pending_widget: None,
+            image_slices: Vec::new(),
+            rich_text_parts: Vec::new(),
         }
     }
 
+    /// Produce `BeginLayer` and `EndLayer` primitives around widgets that were built with
+    /// `Widget::isolated_layer`.
+    ///
+    /// Backends that support rendering to a texture may use this to composite each isolated
+    /// layer with its opacity, so that overlapping children do not show through each other. By
+    /// default, the opacity of each widget is instead applied to every primitive individually.
+    ///
+    /// `BeginLayer` and `EndLayer` primitives are never produced unless this is called, so
+    /// backends that cannot render to a texture may simply ignore them.
+    pub fn with_isolated_layers(mut self) -> Self {
+        self.isolate_layers = true;
+        self
+    }
+
     /// Yield the next `Primitive` for rendering.
     pub fn next(&mut self) -> Option<Primitive> {
         let Primitives {
             ref mut crop_stack,
+            ref mut clip_points,
             ref mut depth_order,
             ref mut triangles,
+            ref mut colored_triangles,
+            ref mut faded_triangles,
+            ref mut layer_stack,
+            ref mut pending_widget,
+            ref mut image_slices,
+            ref mut rich_text_parts,
+            isolate_layers,
             graph,
             theme,
             fonts,
             window_rect,
         } = *self;
 
-        while let Some(widget) = next_widget(depth_order, graph, crop_stack, window_rect) {
+        loop {
+            let widget = match pending_widget.take() {
+                Some(widget) => widget,
+                None => match next_widget(depth_order, graph, crop_stack, clip_points, window_rect)
+                {
+                    Some(widget) => widget,
+                    // End any layers that remain once all widgets have been yielded.
+                    None => return layer_stack.pop().map(end_layer),
+                },
+            };
+
+            if isolate_layers {
+                let (id, scizzor, _, container) = widget;
+
+                // If the widget is not a child of the innermost layer, that layer is complete.
+                if let Some(&layer) = layer_stack.last() {
+                    if layer.id != id && !graph.does_recursive_depth_edge_exist(layer.id, id) {
+                        layer_stack.pop();
+                        *pending_widget = Some(widget);
+                        return Some(end_layer(layer));
+                    }
+                }
+
+                // Begin a new layer before yielding the widget's own primitive.
+                let is_current_layer = layer_stack.last().map(|layer| layer.id) == Some(id);
+                if container.is_isolated_layer && !is_current_layer {
+                    let parent_opacity = graph
+                        .depth_parent(id)
+                        .map(|parent| inherited_opacity(graph, parent, layer_stack))
+                        .unwrap_or(1.0);
+                    let layer = Layer {
+                        id: id,
+                        scizzor: scizzor,
+                        rect: container.rect,
+                        opacity: container.opacity * parent_opacity,
+                    };
+                    layer_stack.push(layer);
+                    *pending_widget = Some(widget);
+                    let kind = PrimitiveKind::BeginLayer {
+                        opacity: layer.opacity,
+                    };
+                    let transform = Transform::identity();
+                    let rect = layer.rect;
+                    return Some(new_primitive(id, kind, scizzor, None, rect, transform));
+                }
+            }
+
             use widget::primitive::point_path::{State as PointPathState, Style as PointPathStyle};
             use widget::primitive::shape::polygon::State as PolygonState;
             use widget::primitive::shape::Style as ShapeStyle;
@@ -313,8 +684,27 @@ impl<'a> Primitives<'a> {
             type TrianglesMultiColorState =
                 widget::triangles::State<Vec<widget::triangles::Triangle<(Point, color::Rgba)>>>;
 
-            let (id, scizzor, container) = widget;
+            let (id, scizzor, clip, container) = widget;
             let rect = container.rect;
+            let opacity = inherited_opacity(graph, id, layer_stack);
+            let transform = graph::algo::transform_of_widget(graph, id);
+
+            // Produce a primitive with the widget's opacity applied to its colors.
+            macro_rules! primitive {
+                ($kind:expr) => {
+                    new_primitive(
+                        id,
+                        with_opacity($kind, opacity, faded_triangles),
+                        scizzor,
+                        match clip {
+                            Some(range) => Some(&clip_points[range]),
+                            None => None,
+                        },
+                        rect,
+                        transform,
+                    )
+                };
+            }
 
             fn state_type_id<W>() -> std::any::TypeId
             where
@@ -331,27 +721,42 @@ impl<'a> Primitives<'a> {
                     match *style {
                         ShapeStyle::Fill(_) => {
                             let kind = PrimitiveKind::Rectangle { color: color };
-                            return Some(new_primitive(id, kind, scizzor, rect));
+                            return Some(primitive!(kind));
+                        }
+                        ShapeStyle::Gradient(ref gradient) => {
+                            colored_triangles.clear();
+                            let (a, b) = widget::rectangle::triangles(rect);
+                            let tris = std::iter::once(a).chain(std::iter::once(b));
+                            widget::triangles::gradient(
+                                colored_triangles,
+                                tris,
+                                gradient,
+                                rect.xy(),
+                            );
+                            let kind = PrimitiveKind::TrianglesMultiColor {
+                                triangles: &colored_triangles[..],
+                            };
+                            return Some(primitive!(kind));
                         }
                         ShapeStyle::Outline(ref line_style) => {
+                            triangles.clear();
                             let (l, r, b, t) = rect.l_r_b_t();
                             let array = [[l, b], [l, t], [r, t], [r, b], [l, b]];
-                            let cap = line_style.get_cap(theme);
-                            let thickness = line_style.get_thickness(theme);
                             let points = array.iter().cloned();
-                            let triangles =
-                                match widget::point_path::triangles(points, cap, thickness) {
-                                    None => &[],
-                                    Some(iter) => {
-                                        triangles.extend(iter);
-                                        &triangles[..]
-                                    }
-                                };
+                            let triangles = match widget::point_path::styled_triangles(
+                                points, line_style, theme,
+                            ) {
+                                None => &[],
+                                Some(iter) => {
+                                    triangles.extend(iter);
+                                    &triangles[..]
+                                }
+                            };
                             let kind = PrimitiveKind::TrianglesSingleColor {
                                 color: color.to_rgb(),
                                 triangles: &triangles,
                             };
-                            return Some(new_primitive(id, kind, scizzor, rect));
+                            return Some(primitive!(kind));
                         }
                     }
                 }
@@ -368,7 +773,7 @@ impl<'a> Primitives<'a> {
                         color: color,
                         triangles: &state.triangles,
                     };
-                    return Some(new_primitive(id, kind, scizzor, rect));
+                    return Some(primitive!(kind));
                 }
             } else if container.type_id == std::any::TypeId::of::<TrianglesMultiColorState>() {
                 type Style = widget::triangles::MultiColor;
@@ -377,7 +782,7 @@ impl<'a> Primitives<'a> {
                     let kind = PrimitiveKind::TrianglesMultiColor {
                         triangles: &state.triangles,
                     };
-                    return Some(new_primitive(id, kind, scizzor, rect));
+                    return Some(primitive!(kind));
                 }
             } else if container.type_id == state_type_id::<widget::Oval<widget::oval::Full>>() {
                 if let Some(oval) =
@@ -400,25 +805,39 @@ impl<'a> Primitives<'a> {
                                 color: color.to_rgb(),
                                 triangles: &triangles,
                             };
-                            return Some(new_primitive(id, kind, scizzor, rect));
+                            return Some(primitive!(kind));
+                        }
+
+                        ShapeStyle::Gradient(ref gradient) => {
+                            colored_triangles.clear();
+                            let tris = points.triangles();
+                            widget::triangles::gradient(
+                                colored_triangles,
+                                tris,
+                                gradient,
+                                rect.xy(),
+                            );
+                            let kind = PrimitiveKind::TrianglesMultiColor {
+                                triangles: &colored_triangles[..],
+                            };
+                            return Some(primitive!(kind));
                         }
 
                         ShapeStyle::Outline(ref line_style) => {
-                            let cap = line_style.get_cap(theme);
-                            let thickness = line_style.get_thickness(theme);
-                            let triangles =
-                                match widget::point_path::triangles(points, cap, thickness) {
-                                    None => &[],
-                                    Some(iter) => {
-                                        triangles.extend(iter);
-                                        &triangles[..]
-                                    }
-                                };
+                            let triangles = match widget::point_path::styled_triangles(
+                                points, line_style, theme,
+                            ) {
+                                None => &[],
+                                Some(iter) => {
+                                    triangles.extend(iter);
+                                    &triangles[..]
+                                }
+                            };
                             let kind = PrimitiveKind::TrianglesSingleColor {
                                 color: color.to_rgb(),
                                 triangles: &triangles,
                             };
-                            return Some(new_primitive(id, kind, scizzor, rect));
+                            return Some(primitive!(kind));
                         }
                     }
                 }
@@ -447,28 +866,42 @@ impl<'a> Primitives<'a> {
                                 color: color.to_rgb(),
                                 triangles: &triangles,
                             };
-                            return Some(new_primitive(id, kind, scizzor, rect));
+                            return Some(primitive!(kind));
+                        }
+
+                        ShapeStyle::Gradient(ref gradient) => {
+                            colored_triangles.clear();
+                            let tris = points.triangles();
+                            widget::triangles::gradient(
+                                colored_triangles,
+                                tris,
+                                gradient,
+                                rect.xy(),
+                            );
+                            let kind = PrimitiveKind::TrianglesMultiColor {
+                                triangles: &colored_triangles[..],
+                            };
+                            return Some(primitive!(kind));
                         }
 
                         ShapeStyle::Outline(ref line_style) => {
                             use std::iter::once;
-                            let cap = line_style.get_cap(theme);
-                            let thickness = line_style.get_thickness(theme);
                             let middle = rect.xy();
                             let points = once(middle).chain(points).chain(once(middle));
-                            let triangles =
-                                match widget::point_path::triangles(points, cap, thickness) {
-                                    None => &[],
-                                    Some(iter) => {
-                                        triangles.extend(iter);
-                                        &triangles[..]
-                                    }
-                                };
+                            let triangles = match widget::point_path::styled_triangles(
+                                points, line_style, theme,
+                            ) {
+                                None => &[],
+                                Some(iter) => {
+                                    triangles.extend(iter);
+                                    &triangles[..]
+                                }
+                            };
                             let kind = PrimitiveKind::TrianglesSingleColor {
                                 color: color.to_rgb(),
                                 triangles: &triangles,
                             };
-                            return Some(new_primitive(id, kind, scizzor, rect));
+                            return Some(primitive!(kind));
                         }
                     }
                 }
@@ -496,25 +929,41 @@ impl<'a> Primitives<'a> {
                                 color: color.to_rgb(),
                                 triangles: &triangles,
                             };
-                            return Some(new_primitive(id, kind, scizzor, rect));
+                            return Some(primitive!(kind));
+                        }
+
+                        ShapeStyle::Gradient(ref gradient) => {
+                            colored_triangles.clear();
+                            if let Some(tris) = widget::polygon::triangles(points) {
+                                let origin = rect.xy();
+                                widget::triangles::gradient(
+                                    colored_triangles,
+                                    tris,
+                                    gradient,
+                                    origin,
+                                );
+                            }
+                            let kind = PrimitiveKind::TrianglesMultiColor {
+                                triangles: &colored_triangles[..],
+                            };
+                            return Some(primitive!(kind));
                         }
 
                         ShapeStyle::Outline(ref line_style) => {
-                            let cap = line_style.get_cap(theme);
-                            let thickness = line_style.get_thickness(theme);
-                            let triangles =
-                                match widget::point_path::triangles(points, cap, thickness) {
-                                    None => &[],
-                                    Some(iter) => {
-                                        triangles.extend(iter);
-                                        &triangles[..]
-                                    }
-                                };
+                            let triangles = match widget::point_path::styled_triangles(
+                                points, line_style, theme,
+                            ) {
+                                None => &[],
+                                Some(iter) => {
+                                    triangles.extend(iter);
+                                    &triangles[..]
+                                }
+                            };
                             let kind = PrimitiveKind::TrianglesSingleColor {
                                 color: color.to_rgb(),
                                 triangles: &triangles,
                             };
-                            return Some(new_primitive(id, kind, scizzor, rect));
+                            return Some(primitive!(kind));
                         }
                     }
                 }
@@ -526,10 +975,9 @@ impl<'a> Primitives<'a> {
                     } = *line;
                     triangles.clear();
                     let color = style.get_color(theme);
-                    let cap = style.get_cap(theme);
-                    let thickness = style.get_thickness(theme);
                     let points = std::iter::once(state.start).chain(std::iter::once(state.end));
-                    let triangles = match widget::point_path::triangles(points, cap, thickness) {
+                    let triangles = match widget::point_path::styled_triangles(points, style, theme)
+                    {
                         None => &[],
                         Some(iter) => {
                             triangles.extend(iter);
@@ -540,7 +988,7 @@ impl<'a> Primitives<'a> {
                         color: color.to_rgb(),
                         triangles: triangles,
                     };
-                    return Some(new_primitive(id, kind, scizzor, rect));
+                    return Some(primitive!(kind));
                 }
             } else if container.type_id == std::any::TypeId::of::<PointPathState>() {
                 if let Some(point_path) =
@@ -552,10 +1000,9 @@ impl<'a> Primitives<'a> {
                     } = *point_path;
                     triangles.clear();
                     let color = style.get_color(theme);
-                    let cap = style.get_cap(theme);
-                    let thickness = style.get_thickness(theme);
                     let points = state.points.iter().map(|&t| t);
-                    let triangles = match widget::point_path::triangles(points, cap, thickness) {
+                    let triangles = match widget::point_path::styled_triangles(points, style, theme)
+                    {
                         None => &[],
                         Some(iter) => {
                             triangles.extend(iter);
@@ -566,7 +1013,7 @@ impl<'a> Primitives<'a> {
                         color: color.to_rgb(),
                         triangles: triangles,
                     };
-                    return Some(new_primitive(id, kind, scizzor, rect));
+                    return Some(primitive!(kind));
                 }
             } else if container.type_id == state_type_id::<widget::Text>() {
                 if let Some(text) = container.unique_widget_state::<widget::Text>() {
@@ -574,7 +1021,81 @@ impl<'a> Primitives<'a> {
                         ref state,
                         ref style,
                     } = *text;
-                    let font_id = match style.font_id(theme).or_else(|| fonts.ids().next()) {
+
+                    // Rich text yields a primitive for each part of each run before moving on.
+                    if !state.runs.is_empty() {
+                        if rich_text_parts.is_empty() {
+                            let n = state.runs.len();
+                            let highlights = (0..n)
+                                .filter(|&i| state.runs[i].style.highlight.is_some())
+                                .map(RichTextPart::Highlight);
+                            let glyphs = (0..n).map(RichTextPart::Glyphs);
+                            let parts: Vec<_> = highlights.chain(glyphs).collect();
+                            rich_text_parts.extend(parts.into_iter().rev());
+                        }
+                        let part = match rich_text_parts.pop() {
+                            Some(part) => part,
+                            None => continue,
+                        };
+                        if !rich_text_parts.is_empty() {
+                            *pending_widget = Some((id, scizzor, clip.clone(), container));
+                        }
+                        let run_ix = match part {
+                            RichTextPart::Highlight(i) | RichTextPart::Glyphs(i) => i,
+                        };
+                        let run = &state.runs[run_ix];
+                        let font = match fonts.get(run.style.font_id) {
+                            Some(font) => font,
+                            None => continue,
+                        };
+                        // Runs are positioned relative to the top left of the widget.
+                        let top_left = [rect.left(), rect.top()];
+                        let (kind, part_rect) = match part {
+                            RichTextPart::Highlight(_) => {
+                                let color = run.style.highlight.unwrap_or(color::TRANSPARENT);
+                                let kind = PrimitiveKind::Rectangle { color: color };
+                                (kind, run.highlight_rect(font).shift(top_left))
+                            }
+                            RichTextPart::Glyphs(_) => {
+                                let run_rect = run.rect.shift(top_left);
+                                let font_data = fonts.data(run.style.font_id).map(|d| &d[..]);
+                                let font_size = run.style.font_size;
+                                let text = Text {
+                                    window_dim: window_rect.dim(),
+                                    text: &state.string,
+                                    line_infos: std::slice::from_ref(&run.info),
+                                    font: font,
+                                    font_size,
+                                    rect: run_rect,
+                                    justify: text::Justify::Full,
+                                    y_align: Align::End,
+                                    line_spacing: 0.0,
+                                    decorations: text::Decorations {
+                                        underline: run.style.underline,
+                                        highlight: None,
+                                        ..style.decorations(theme)
+                                    },
+                                    decoration_metrics: text::DecorationMetrics::new(
+                                        font, font_data, font_size,
+                                    ),
+                                    #[cfg(feature = "shaping")]
+                                    font_data,
+                                };
+                                let kind = PrimitiveKind::Text {
+                                    color: run.style.color,
+                                    text: text,
+                                    font_id: run.style.font_id,
+                                };
+                                (kind, run_rect)
+                            }
+                        };
+                        let clip_points = &**clip_points;
+                        let clip = clip.map(|r| &clip_points[r]);
+                        let kind = with_opacity(kind, opacity, faded_triangles);
+                        return Some(new_primitive(id, kind, scizzor, clip, part_rect, transform));
+                    }
+
+                    let font_id = match style.resolve_font_id(theme, fonts) {
                         Some(id) => id,
                         None => continue,
                     };
@@ -589,6 +1110,8 @@ impl<'a> Primitives<'a> {
                     let line_spacing = style.line_spacing(theme);
                     let justify = style.justify(theme);
                     let y_align = Align::End;
+                    let decorations = style.decorations(theme);
+                    let font_data = fonts.data(font_id).map(|d| &d[..]);
 
                     let text = Text {
                         window_dim: window_rect.dim(),
@@ -600,6 +1123,12 @@ impl<'a> Primitives<'a> {
                         justify: justify,
                         y_align: y_align,
                         line_spacing: line_spacing,
+                        decorations,
+                        decoration_metrics: text::DecorationMetrics::new(
+                            font, font_data, font_size,
+                        ),
+                        #[cfg(feature = "shaping")]
+                        font_data,
                     };
 
                     let kind = PrimitiveKind::Text {
@@ -607,7 +1136,7 @@ impl<'a> Primitives<'a> {
                         text: text,
                         font_id: font_id,
                     };
-                    return Some(new_primitive(id, kind, scizzor, rect));
+                    return Some(primitive!(kind));
                 }
             } else if container.type_id == state_type_id::<widget::Image>() {
                 use widget::primitive::image::{State, Style};
@@ -617,22 +1146,65 @@ impl<'a> Primitives<'a> {
                         ref style,
                     } = *image;
                     let color = style.maybe_color(theme);
+
+                    // Nine-slice images yield an `Image` for each slice before moving on. Without a
+                    // source rectangle the slices are unknown, so the image is drawn whole.
+                    if let (Some(nine_slice), Some(src_rect)) = (state.nine_slice, state.src_rect) {
+                        if image_slices.is_empty() {
+                            let slices = nine_slice.slices(rect, src_rect);
+                            let is_visible = |&&(r, s): &&(Rect, Rect)| {
+                                r.w() > 0.0 && r.h() > 0.0 && s.w() > 0.0 && s.h() > 0.0
+                            };
+                            image_slices.extend(slices.iter().rev().filter(is_visible));
+                        }
+                        let (slice_rect, source_rect) = match image_slices.pop() {
+                            Some(slice) => slice,
+                            None => continue,
+                        };
+                        if !image_slices.is_empty() {
+                            *pending_widget = Some((id, scizzor, clip.clone(), container));
+                        }
+                        let kind = PrimitiveKind::Image {
+                            color: color,
+                            image_id: state.image_id,
+                            source_rect: Some(source_rect),
+                        };
+                        let clip_points = &**clip_points;
+                        let clip = clip.map(|r| &clip_points[r]);
+                        let kind = with_opacity(kind, opacity, faded_triangles);
+                        return Some(new_primitive(
+                            id, kind, scizzor, clip, slice_rect, transform,
+                        ));
+                    }
+
                     let kind = PrimitiveKind::Image {
                         color: color,
                         image_id: state.image_id,
                         source_rect: state.src_rect,
                     };
-                    return Some(new_primitive(id, kind, scizzor, rect));
+                    return Some(primitive!(kind));
+                }
+            } else if container.type_id == state_type_id::<widget::Shadow>() {
+                use widget::primitive::shadow::{State, Style};
+                if let Some(shadow) = container.state_and_style::<State, Style>() {
+                    let style = &shadow.style;
+                    let kind = PrimitiveKind::Shadow {
+                        color: style.color(theme),
+                        offset: style.offset(theme),
+                        blur_radius: style.blur_radius(theme),
+                        spread: style.spread(theme),
+                    };
+                    return Some(primitive!(kind));
                 }
 
             // Return an `Other` variant for all non-primitive widgets.
             } else {
                 let kind = PrimitiveKind::Other(container);
-                return Some(new_primitive(id, kind, scizzor, rect));
+                let mut primitive = primitive!(kind);
+                primitive.opacity = opacity;
+                return Some(primitive);
             }
         }
-
-        None
     }
 
     /// Collect the `Primitives` list into an owned collection.
@@ -644,18 +1216,37 @@ impl<'a> Primitives<'a> {
         let mut primitive_triangles_single_color = Vec::new();
         let mut primitive_line_infos = Vec::new();
         let mut texts_string = String::new();
+        let mut fonts = fnv::FnvHashMap::default();
+        let mut clip_points: Vec<Point> = Vec::new();
+        let mut last_clip: Option<std::ops::Range<usize>> = None;
 
         while let Some(Primitive {
             id,
             rect,
             scizzor,
+            clip,
+            transform,
             kind,
+            ..
         }) = self.next()
         {
+            // Consecutive primitives usually share the same clip, so only pack each new outline.
+            let clip = clip.map(|points| match last_clip {
+                Some(ref range) if &clip_points[range.clone()] == points => range.clone(),
+                _ => {
+                    let start = clip_points.len();
+                    clip_points.extend_from_slice(points);
+                    start..clip_points.len()
+                }
+            });
+            last_clip = clip.clone();
+
             let new = |kind| OwnedPrimitive {
                 id: id,
                 rect: rect,
                 scizzor: scizzor,
+                clip: clip.clone(),
+                transform: transform,
                 kind: kind,
             };
 
@@ -714,6 +1305,8 @@ impl<'a> Primitives<'a> {
                         justify,
                         y_align,
                         line_spacing,
+                        decorations,
+                        decoration_metrics,
                         ..
                     } = text;
 
@@ -731,14 +1324,17 @@ impl<'a> Primitives<'a> {
                         str_byte_range: start_str_byte..end_str_byte,
                         line_infos_range: start_line_info_idx..end_line_info_idx,
                         window_dim: window_dim,
-                        font: font.clone(),
                         font_size: font_size,
                         rect: rect,
                         justify: justify,
                         y_align: y_align,
                         line_spacing: line_spacing,
+                        decorations,
+                        decoration_metrics,
                     };
 
+                    fonts.entry(font_id).or_insert_with(|| font.clone());
+
                     let kind = OwnedPrimitiveKind::Text {
                         color: color,
                         font_id: font_id,
@@ -747,6 +1343,31 @@ impl<'a> Primitives<'a> {
                     primitives.push(new(kind));
                 }
 
+                PrimitiveKind::BeginLayer { opacity } => {
+                    let kind = OwnedPrimitiveKind::BeginLayer { opacity: opacity };
+                    primitives.push(new(kind));
+                }
+
+                PrimitiveKind::Shadow {
+                    color,
+                    offset,
+                    blur_radius,
+                    spread,
+                } => {
+                    let kind = OwnedPrimitiveKind::Shadow {
+                        color: color,
+                        offset: offset,
+                        blur_radius: blur_radius,
+                        spread: spread,
+                    };
+                    primitives.push(new(kind));
+                }
+
+                PrimitiveKind::EndLayer { opacity } => {
+                    let kind = OwnedPrimitiveKind::EndLayer { opacity: opacity };
+                    primitives.push(new(kind));
+                }
+
                 // TODO: Not sure how we should handle this yet.
                 PrimitiveKind::Other(_) => (),
             }
@@ -758,6 +1379,8 @@ impl<'a> Primitives<'a> {
             triangles_multi_color: primitive_triangles_multi_color,
             line_infos: primitive_line_infos,
             texts_string: texts_string,
+            clip_points: clip_points,
+            fonts: fonts,
         }
     }
 }
@@ -771,6 +1394,8 @@ impl OwnedPrimitives {
             ref triangles_multi_color,
             ref line_infos,
             ref texts_string,
+            ref clip_points,
+            ref fonts,
         } = *self;
         WalkOwnedPrimitives {
             primitives: primitives.iter(),
@@ -778,8 +1403,109 @@ impl OwnedPrimitives {
             triangles_multi_color: triangles_multi_color,
             line_infos: line_infos,
             texts_str: texts_string,
+            clip_points: clip_points,
+            fonts: fonts,
         }
     }
+
+    /// The `Id` of each font referenced by the `Text` primitives, in ascending order.
+    pub fn font_ids(&self) -> Vec<text::font::Id> {
+        let mut ids: Vec<_> = self
+            .primitives
+            .iter()
+            .filter_map(|p| match p.kind {
+                OwnedPrimitiveKind::Text { font_id, .. } => Some(font_id),
+                _ => None,
+            })
+            .collect();
+        ids.sort();
+        ids.dedup();
+        ids
+    }
+
+    /// The `Id` of each image referenced by the `Image` primitives, in ascending order.
+    pub fn image_ids(&self) -> Vec<image::Id> {
+        let mut ids: Vec<_> = self
+            .primitives
+            .iter()
+            .filter_map(|p| match p.kind {
+                OwnedPrimitiveKind::Image { image_id, .. } => Some(image_id),
+                _ => None,
+            })
+            .collect();
+        ids.sort();
+        ids.dedup();
+        ids
+    }
+
+    /// Whether or not the font with the given `Id` is available for laying out text.
+    ///
+    /// This is always `true` for fonts referenced by `OwnedPrimitives` produced via
+    /// `Primitives::owned`, but may be `false` after deserializing.
+    pub fn has_font(&self, id: text::font::Id) -> bool {
+        self.fonts.contains_key(&id)
+    }
+
+    /// Supply the font used by `Text` primitives with the given `font::Id`.
+    ///
+    /// This is necessary after deserializing, as fonts are not serialized.
+    pub fn insert_font(&mut self, id: text::font::Id, font: text::Font) {
+        self.fonts.insert(id, font);
+    }
+
+    /// Whether or not every range stored by the primitives lies within the buffer into which it
+    /// indexes, and every range of text lies on `char` boundaries.
+    ///
+    /// This is always `true` for `OwnedPrimitives` produced via `Primitives::owned`, but should be
+    /// checked after deserializing data from an untrusted source, as walking invalid primitives
+    /// panics.
+    pub fn is_valid(&self) -> bool {
+        let OwnedPrimitives {
+            ref primitives,
+            ref triangles_single_color,
+            ref triangles_multi_color,
+            ref line_infos,
+            ref texts_string,
+            ref clip_points,
+            ..
+        } = *self;
+        primitives.iter().all(|primitive| {
+            let clip_is_valid = match primitive.clip {
+                Some(ref range) => clip_points.get(range.clone()).is_some(),
+                None => true,
+            };
+            let kind_is_valid = match primitive.kind {
+                OwnedPrimitiveKind::TrianglesSingleColor {
+                    ref triangle_range, ..
+                } => triangles_single_color.get(triangle_range.clone()).is_some(),
+                OwnedPrimitiveKind::TrianglesMultiColor { ref triangle_range } => {
+                    triangles_multi_color.get(triangle_range.clone()).is_some()
+                }
+                OwnedPrimitiveKind::Text { ref text, .. } => {
+                    let text_str = texts_string.get(text.str_byte_range.clone());
+                    let infos = line_infos.get(text.line_infos_range.clone());
+                    match (text_str, infos) {
+                        (Some(text_str), Some(infos)) => infos.iter().all(|info| {
+                            let end_of_break = match info.end_break {
+                                text::line::Break::Wrap { len_bytes, .. }
+                                | text::line::Break::Newline { len_bytes, .. } => {
+                                    info.end_byte().checked_add(len_bytes)
+                                }
+                                text::line::Break::End { byte, .. } => Some(byte),
+                            };
+                            text_str.get(info.byte_range()).is_some()
+                                && end_of_break
+                                    .and_then(|end| text_str.get(info.end_byte()..end))
+                                    .is_some()
+                        }),
+                        _ => false,
+                    }
+                }
+                _ => true,
+            };
+            clip_is_valid && kind_is_valid
+        })
+    }
 }
 
 impl<'a> WalkOwnedPrimitives<'a> {
@@ -791,111 +1517,261 @@ impl<'a> WalkOwnedPrimitives<'a> {
             triangles_multi_color,
             line_infos,
             texts_str,
+            clip_points,
+            fonts,
         } = *self;
 
-        primitives.next().map(
-            move |&OwnedPrimitive {
-                      id,
-                      rect,
-                      scizzor,
-                      ref kind,
-                  }| {
-                let new = |kind| Primitive {
-                    id: id,
-                    rect: rect,
-                    scizzor: scizzor,
-                    kind: kind,
-                };
+        for &OwnedPrimitive {
+            id,
+            rect,
+            scizzor,
+            ref clip,
+            transform,
+            ref kind,
+        } in primitives
+        {
+            let new = |kind| Primitive {
+                id: id,
+                rect: rect,
+                scizzor: scizzor,
+                clip: clip.clone().map(|range| &clip_points[range]),
+                transform: transform,
+                kind: kind,
+                opacity: 1.0,
+            };
 
-                match *kind {
-                    OwnedPrimitiveKind::Rectangle { color } => {
-                        let kind = PrimitiveKind::Rectangle { color: color };
-                        new(kind)
-                    }
+            let primitive = match *kind {
+                OwnedPrimitiveKind::Rectangle { color } => {
+                    let kind = PrimitiveKind::Rectangle { color: color };
+                    new(kind)
+                }
 
-                    OwnedPrimitiveKind::TrianglesSingleColor {
-                        color,
-                        ref triangle_range,
-                    } => {
-                        let kind = PrimitiveKind::TrianglesSingleColor {
-                            color: color,
-                            triangles: &triangles_single_color[triangle_range.clone()],
-                        };
-                        new(kind)
-                    }
+                OwnedPrimitiveKind::TrianglesSingleColor {
+                    color,
+                    ref triangle_range,
+                } => {
+                    let kind = PrimitiveKind::TrianglesSingleColor {
+                        color: color,
+                        triangles: &triangles_single_color[triangle_range.clone()],
+                    };
+                    new(kind)
+                }
 
-                    OwnedPrimitiveKind::TrianglesMultiColor { ref triangle_range } => {
-                        let kind = PrimitiveKind::TrianglesMultiColor {
-                            triangles: &triangles_multi_color[triangle_range.clone()],
-                        };
-                        new(kind)
-                    }
+                OwnedPrimitiveKind::TrianglesMultiColor { ref triangle_range } => {
+                    let kind = PrimitiveKind::TrianglesMultiColor {
+                        triangles: &triangles_multi_color[triangle_range.clone()],
+                    };
+                    new(kind)
+                }
 
-                    OwnedPrimitiveKind::Text {
-                        color,
-                        font_id,
-                        ref text,
-                    } => {
-                        let OwnedText {
-                            ref str_byte_range,
-                            ref line_infos_range,
-                            ref font,
-                            window_dim,
-                            font_size,
-                            rect,
-                            justify,
-                            y_align,
-                            line_spacing,
-                        } = *text;
-
-                        let text_str = &texts_str[str_byte_range.clone()];
-                        let line_infos = &line_infos[line_infos_range.clone()];
-
-                        let text = Text {
-                            window_dim: window_dim,
-                            text: text_str,
-                            line_infos: line_infos,
-                            font: font,
-                            font_size: font_size,
-                            rect: rect,
-                            justify: justify,
-                            y_align: y_align,
-                            line_spacing: line_spacing,
-                        };
+                OwnedPrimitiveKind::Text {
+                    color,
+                    font_id,
+                    ref text,
+                } => {
+                    // Text whose font is unavailable (e.g. after deserializing) is skipped.
+                    let font = match fonts.get(&font_id) {
+                        Some(font) => font,
+                        None => continue,
+                    };
 
-                        let kind = PrimitiveKind::Text {
-                            color: color,
-                            font_id: font_id,
-                            text: text,
-                        };
-                        new(kind)
-                    }
+                    let OwnedText {
+                        ref str_byte_range,
+                        ref line_infos_range,
+                        window_dim,
+                        font_size,
+                        rect,
+                        justify,
+                        y_align,
+                        line_spacing,
+                        decorations,
+                        decoration_metrics,
+                    } = *text;
 
-                    OwnedPrimitiveKind::Image {
-                        image_id,
-                        color,
-                        source_rect,
-                    } => {
-                        let kind = PrimitiveKind::Image {
-                            image_id: image_id,
-                            color: color,
-                            source_rect: source_rect,
-                        };
-                        new(kind)
-                    }
+                    let text_str = &texts_str[str_byte_range.clone()];
+                    let line_infos = &line_infos[line_infos_range.clone()];
+
+                    let text = Text {
+                        window_dim: window_dim,
+                        text: text_str,
+                        line_infos: line_infos,
+                        font: font,
+                        font_size: font_size,
+                        rect: rect,
+                        justify: justify,
+                        y_align: y_align,
+                        line_spacing: line_spacing,
+                        decorations,
+                        decoration_metrics,
+                        #[cfg(feature = "shaping")]
+                        font_data: None,
+                    };
+
+                    let kind = PrimitiveKind::Text {
+                        color: color,
+                        font_id: font_id,
+                        text: text,
+                    };
+                    new(kind)
+                }
+
+                OwnedPrimitiveKind::Image {
+                    image_id,
+                    color,
+                    source_rect,
+                } => {
+                    let kind = PrimitiveKind::Image {
+                        image_id: image_id,
+                        color: color,
+                        source_rect: source_rect,
+                    };
+                    new(kind)
+                }
+
+                OwnedPrimitiveKind::Shadow {
+                    color,
+                    offset,
+                    blur_radius,
+                    spread,
+                } => new(PrimitiveKind::Shadow {
+                    color: color,
+                    offset: offset,
+                    blur_radius: blur_radius,
+                    spread: spread,
+                }),
+
+                OwnedPrimitiveKind::BeginLayer { opacity } => {
+                    new(PrimitiveKind::BeginLayer { opacity: opacity })
+                }
+
+                OwnedPrimitiveKind::EndLayer { opacity } => {
+                    new(PrimitiveKind::EndLayer { opacity: opacity })
                 }
-            },
-        )
+            };
+            return Some(primitive);
+        }
+        None
     }
 }
 
 /// Simplify the constructor for a `Primitive`.
-fn new_primitive(id: widget::Id, kind: PrimitiveKind, scizzor: Rect, rect: Rect) -> Primitive {
+fn new_primitive<'a>(
+    id: widget::Id,
+    kind: PrimitiveKind<'a>,
+    scizzor: Rect,
+    clip: Option<&'a [Point]>,
+    rect: Rect,
+    transform: Transform,
+) -> Primitive<'a> {
     Primitive {
         id: id,
         kind: kind,
         scizzor: scizzor,
+        clip: clip,
         rect: rect,
+        transform: transform,
+        opacity: 1.0,
+    }
+}
+
+/// Produce the `EndLayer` primitive for the given layer.
+fn end_layer<'a>(layer: Layer) -> Primitive<'a> {
+    let kind = PrimitiveKind::EndLayer {
+        opacity: layer.opacity,
+    };
+    let transform = Transform::identity();
+    new_primitive(layer.id, kind, layer.scizzor, None, layer.rect, transform)
+}
+
+/// The opacity of the widget at the given `id` multiplied by that of each of its depth-wise
+/// parents.
+///
+/// The walk stops at the first of the `open_layers`, as the opacity of the layer is applied when
+/// it is composited. Layers that have already ended, e.g. those of a floating widget's parents,
+/// contribute their opacity as usual.
+fn inherited_opacity(graph: &Graph, id: widget::Id, open_layers: &[Layer]) -> f32 {
+    let mut opacity = 1.0;
+    let mut maybe_id = Some(id);
+    while let Some(id) = maybe_id {
+        if open_layers.iter().any(|layer| layer.id == id) {
+            break;
+        }
+        if let Some(container) = graph.widget(id) {
+            opacity *= container.opacity;
+        }
+        maybe_id = graph.depth_parent(id);
+    }
+    opacity
+}
+
+/// Multiply the alpha of each color within the given primitive `kind` by `opacity`.
+///
+/// Multicolored triangles are copied into the `faded` buffer, as they are borrowed from the widget.
+fn with_opacity<'a>(
+    kind: PrimitiveKind<'a>,
+    opacity: f32,
+    faded: &'a mut Vec<Triangle<ColoredPoint>>,
+) -> PrimitiveKind<'a> {
+    if opacity >= 1.0 {
+        return kind;
+    }
+    match kind {
+        PrimitiveKind::Rectangle { color } => PrimitiveKind::Rectangle {
+            color: color.alpha(opacity),
+        },
+        PrimitiveKind::TrianglesSingleColor { color, triangles } => {
+            let color::Rgba(r, g, b, a) = color;
+            PrimitiveKind::TrianglesSingleColor {
+                color: color::Rgba(r, g, b, a * opacity),
+                triangles: triangles,
+            }
+        }
+        PrimitiveKind::TrianglesMultiColor { triangles } => {
+            faded.clear();
+            faded.extend(triangles.iter().map(|triangle| {
+                let mut triangle = *triangle;
+                for &mut (_, ref mut color) in triangle.0.iter_mut() {
+                    color.3 *= opacity;
+                }
+                triangle
+            }));
+            PrimitiveKind::TrianglesMultiColor { triangles: faded }
+        }
+        PrimitiveKind::Image {
+            image_id,
+            color,
+            source_rect,
+        } => PrimitiveKind::Image {
+            image_id: image_id,
+            color: Some(color.unwrap_or(color::WHITE).alpha(opacity)),
+            source_rect: source_rect,
+        },
+        PrimitiveKind::Text {
+            color,
+            text,
+            font_id,
+        } => {
+            let mut text = text;
+            let highlight = text.decorations.highlight;
+            text.decorations.highlight = highlight.map(|color| color.alpha(opacity));
+            PrimitiveKind::Text {
+                color: color.alpha(opacity),
+                text,
+                font_id,
+            }
+        }
+        PrimitiveKind::Shadow {
+            color,
+            offset,
+            blur_radius,
+            spread,
+        } => PrimitiveKind::Shadow {
+            color: color.alpha(opacity),
+            offset: offset,
+            blur_radius: blur_radius,
+            spread: spread,
+        },
+        kind => kind,
     }
 }
 
@@ -904,19 +1780,23 @@ fn new_primitive(id: widget::Id, kind: PrimitiveKind, scizzor: Rect, rect: Rect)
 fn next_widget<'a>(
     depth_order: &mut std::slice::Iter<widget::Id>,
     graph: &'a Graph,
-    crop_stack: &mut Vec<(widget::Id, Rect)>,
+    crop_stack: &mut Vec<(widget::Id, Rect, Option<std::ops::Range<usize>>)>,
+    clip_points: &mut Vec<Point>,
     window_rect: Rect,
-) -> Option<(widget::Id, Rect, &'a graph::Container)> {
+) -> Option<VisibleWidget<'a>> {
     while let Some(&id) = depth_order.next() {
         let container = match graph.widget(id) {
             Some(container) => container,
             None => continue,
         };
 
+        // Scizzors are axis-aligned, so transformed widgets are cropped to their bounding box.
+        let transform = graph::algo::transform_of_widget(graph, id);
+
         // If we're currently using a cropped context and the current `crop_parent_idx` is
         // *not* a depth-wise parent of the widget at the current `idx`, we should pop that
         // cropped context from the stack as we are done with it.
-        while let Some(&(crop_parent_idx, _)) = crop_stack.last() {
+        while let Some(&(crop_parent_idx, _, _)) = crop_stack.last() {
             if graph.does_recursive_depth_edge_exist(crop_parent_idx, id) {
                 break;
             } else {
@@ -924,32 +1804,81 @@ fn next_widget<'a>(
             }
         }
 
-        // Check the stack for the current Context.
-        let scizzor = crop_stack
-            .last()
-            .map(|&(_, scizzor)| scizzor)
-            .unwrap_or(window_rect);
+        // Remove the outlines of any clip shapes that were popped from the stack.
+        let top_clip_end = match crop_stack.last() {
+            Some(&(_, _, Some(ref range))) => range.end,
+            _ => 0,
+        };
+        clip_points.truncate(top_clip_end);
+
+        // Shadows are drawn outside of the widget that casts them, so they are not cropped by it.
+        let crop_idx = match crop_stack.last() {
+            Some(&(crop_parent_idx, _, _))
+                if container.type_id == std::any::TypeId::of::<widget::shadow::State>()
+                    && graph.depth_parent(id) == Some(crop_parent_idx) =>
+            {
+                crop_stack.len() - 1
+            }
+            _ => crop_stack.len(),
+        };
+        let (scizzor, clip) = match crop_idx.checked_sub(1).map(|idx| &crop_stack[idx]) {
+            Some(&(_, scizzor, ref clip)) => (scizzor, clip.clone()),
+            None => (window_rect, None),
+        };
 
         // If the current widget should crop its children, we need to add a rect for it to
         // the top of the crop stack.
         if container.crop_kids {
-            let scizzor_rect = container
-                .kid_area
-                .rect
+            let scizzor_rect = transform
+                .bounding_rect(container.kid_area.rect)
                 .overlap(scizzor)
                 .unwrap_or_else(|| Rect::from_xy_dim([0.0, 0.0], [0.0, 0.0]));
-            crop_stack.push((id, scizzor_rect));
+            // Children are clipped to the innermost clip shape.
+            let kid_clip = match container.maybe_clip_shape {
+                Some(shape) => {
+                    let start = clip_points.len();
+                    let points = shape.points(container.kid_area.rect);
+                    clip_points.extend(points.map(|p| transform.transform_point(p)));
+                    Some(start..clip_points.len())
+                }
+                None => clip.clone(),
+            };
+            crop_stack.push((id, scizzor_rect, kid_clip));
         }
 
         // We only want to return primitives that are actually visible.
-        let is_visible = container.rect.overlap(scizzor).is_some()
+        let is_visible = transform
+            .bounding_rect(container.rect)
+            .overlap(scizzor)
+            .is_some()
             && graph::algo::cropped_area_of_widget(graph, id).is_some();
         if !is_visible {
             continue;
         }
 
-        return Some((id, scizzor, container));
+        return Some((id, scizzor, clip, container));
     }
 
     None
 }
+
+// `widget::Id`s are serialized as their index within the widget graph.
+#[cfg(feature = "serde")]
+mod serde_widget_id {
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+    use widget;
+
+    pub fn serialize<S>(id: &widget::Id, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        (id.index() as u32).serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<widget::Id, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        u32::deserialize(deserializer).map(|index| widget::Id::new(index as usize))
+    }
+}
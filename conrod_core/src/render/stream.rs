@@ -0,0 +1,229 @@
+//! A streaming format for recording, replaying and remotely displaying frames of primitives.
+//!
+//! A stream is a sequence of messages, each encoded as a single line of JSON. Two kinds of message
+//! are supported:
+//!
+//! - **Font** messages carry the raw data of a font along with the `font::Id` by which `Text`
+//!   primitives refer to it. As conrod does not retain the raw data of loaded fonts, these must be
+//!   written by the user via `Writer::write_font`, before any frame that uses them.
+//! - **Frame** messages carry the dimensions of the window along with the `OwnedPrimitives`
+//!   produced by the `Ui` for a single frame.
+//!
+//! The `Reader` loads fonts as they arrive and supplies them to every subsequent `Frame`, so that
+//! each yielded frame may be rendered with any backend via `frame.primitives.walk()`.
+//!
+//! Streams may be written to a file for later replay, or to a socket to drive a display in
+//! another process.
+
+use fnv;
+use render::OwnedPrimitives;
+use serde_json;
+use std;
+use std::io::{self, BufRead, Write};
+use text;
+use Dimensions;
+
+/// A single frame of primitives read from a stream.
+#[derive(Clone)]
+pub struct Frame {
+    /// The dimensions of the `Ui`'s window at the time the frame was produced.
+    pub window_dim: Dimensions,
+    /// The primitives that make up the frame.
+    pub primitives: OwnedPrimitives,
+}
+
+/// Writes fonts and frames to a stream.
+pub struct Writer<W> {
+    writer: W,
+}
+
+/// Reads frames from a stream, loading fonts as they are received.
+pub struct Reader<R> {
+    reader: R,
+    line: String,
+    fonts: fnv::FnvHashMap<text::font::Id, text::Font>,
+}
+
+/// The possible errors that may occur while reading or writing a stream.
+#[derive(Debug)]
+pub enum Error {
+    /// An error occurred while reading from or writing to the inner stream.
+    Io(io::Error),
+    /// A message could not be encoded or decoded.
+    Json(serde_json::Error),
+    /// The data received for a font could not be loaded.
+    Font(text::font::Id, text::font::Error),
+    /// A frame was received whose primitives index beyond their own data, e.g. due to a truncated
+    /// or malformed message.
+    InvalidFrame,
+}
+
+// A message as it is written to the stream.
+#[derive(Serialize)]
+enum MessageRef<'a> {
+    Font {
+        id: text::font::Id,
+        data: &'a [u8],
+    },
+    Frame {
+        window_dim: Dimensions,
+        primitives: &'a OwnedPrimitives,
+    },
+}
+
+// A message as it is read from the stream.
+#[derive(Deserialize)]
+enum Message {
+    Font {
+        id: text::font::Id,
+        data: Vec<u8>,
+    },
+    Frame {
+        window_dim: Dimensions,
+        primitives: OwnedPrimitives,
+    },
+}
+
+impl<W> Writer<W>
+where
+    W: Write,
+{
+    /// Begin writing a stream to the given writer.
+    pub fn new(writer: W) -> Self {
+        Writer { writer }
+    }
+
+    /// Write the raw data of the font with the given `Id`, e.g. the contents of a `.ttf` file.
+    ///
+    /// This should be called once for each font before writing any frame that uses it.
+    pub fn write_font(&mut self, id: text::font::Id, data: &[u8]) -> Result<(), Error> {
+        self.write_message(&MessageRef::Font { id, data })
+    }
+
+    /// Write a single frame of primitives, produced by a `Ui` with the given window dimensions.
+    pub fn write_frame(
+        &mut self,
+        window_dim: Dimensions,
+        primitives: &OwnedPrimitives,
+    ) -> Result<(), Error> {
+        self.write_message(&MessageRef::Frame {
+            window_dim,
+            primitives,
+        })
+    }
+
+    /// Flush the inner writer.
+    pub fn flush(&mut self) -> Result<(), Error> {
+        self.writer.flush().map_err(Error::Io)
+    }
+
+    /// Consume the `Writer` and return the inner writer.
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+
+    fn write_message(&mut self, message: &MessageRef) -> Result<(), Error> {
+        serde_json::to_writer(&mut self.writer, message).map_err(Error::Json)?;
+        self.writer.write_all(b"\n").map_err(Error::Io)
+    }
+}
+
+impl<R> Reader<R>
+where
+    R: BufRead,
+{
+    /// Begin reading a stream from the given reader.
+    pub fn new(reader: R) -> Self {
+        Reader {
+            reader,
+            line: String::new(),
+            fonts: fnv::FnvHashMap::default(),
+        }
+    }
+
+    /// Read the next `Frame` from the stream, loading any fonts that precede it.
+    ///
+    /// Returns `Ok(None)` once the end of the stream is reached.
+    pub fn read_frame(&mut self) -> Result<Option<Frame>, Error> {
+        loop {
+            self.line.clear();
+            if self.reader.read_line(&mut self.line).map_err(Error::Io)? == 0 {
+                return Ok(None);
+            }
+            if self.line.trim().is_empty() {
+                continue;
+            }
+            match serde_json::from_str(&self.line).map_err(Error::Json)? {
+                Message::Font { id, data } => {
+                    let font = text::Font::from_bytes(data)
+                        .map_err(|e| Error::Font(id, text::font::Error::Rusttype(e)))?;
+                    self.fonts.insert(id, font);
+                }
+                Message::Frame {
+                    window_dim,
+                    mut primitives,
+                } => {
+                    if !primitives.is_valid() {
+                        return Err(Error::InvalidFrame);
+                    }
+                    for id in primitives.font_ids() {
+                        if let Some(font) = self.fonts.get(&id) {
+                            primitives.insert_font(id, font.clone());
+                        }
+                    }
+                    let frame = Frame {
+                        window_dim,
+                        primitives,
+                    };
+                    return Ok(Some(frame));
+                }
+            }
+        }
+    }
+
+    /// The fonts that have been received so far.
+    pub fn fonts(&self) -> &fnv::FnvHashMap<text::font::Id, text::Font> {
+        &self.fonts
+    }
+
+    /// Consume the `Reader` and return the inner reader.
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+}
+
+impl<R> Iterator for Reader<R>
+where
+    R: BufRead,
+{
+    type Item = Result<Frame, Error>;
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.read_frame() {
+            Ok(Some(frame)) => Some(Ok(frame)),
+            Ok(None) => None,
+            Err(err) => Some(Err(err)),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match *self {
+            Error::Io(ref e) => Some(e),
+            Error::Json(ref e) => Some(e),
+            Error::Font(_, ref e) => Some(e),
+            Error::InvalidFrame => None,
+        }
+    }
+}
+
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> Result<(), std::fmt::Error> {
+        match *self {
+            Error::Io(ref e) => std::fmt::Display::fmt(e, f),
+            Error::Json(ref e) => std::fmt::Display::fmt(e, f),
+            Error::Font(id, ref e) => write!(f, "failed to load font {:?}: {}", id, e),
+            Error::InvalidFrame => write!(f, "received a frame with out of range primitive data"),
+        }
+    }
+}
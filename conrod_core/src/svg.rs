@@ -0,0 +1,598 @@
+//! Export a sequence of `render::Primitive`s as an SVG document.
+//!
+//! This is useful for producing vector screenshots of a UI for documentation or bug reports, or
+//! as a diffable, text-based format for snapshot tests.
+//!
+//! The document uses the `Ui`'s DPI agnostic point space, with the origin at the top-left of the
+//! window. Each primitive is mapped to an SVG element as follows:
+//!
+//! - `Rectangle` -> `<rect>`
+//! - `TrianglesSingleColor` -> a single `<path>` with a sub-path per triangle.
+//! - `TrianglesMultiColor` -> a `<path>` per run of triangles sharing the same color. SVG has no
+//!   notion of per-vertex colors, so each triangle is filled with the average of its vertex
+//!   colors.
+//! - `Image` -> `<image>` referring to the `href` registered with `Exporter::image`. Images that
+//!   have not been registered are skipped.
+//! - `Text` -> a `<text>` element per line, with the position of every glyph.
+//! - `Shadow` -> a `<rect>` blurred by a `<filter>` with an `<feGaussianBlur>`.
+//! - `Other` -> skipped.
+//!
+//! Primitives that are cropped by a scizzor rect are grouped within a `<g>` that refers to a
+//! matching `<clipPath>`. Primitives with a clip outline are further grouped within a `<g>` that
+//! refers to a `<clipPath>` containing the outline as a `<polygon>`.
+
+use color;
+use fnv;
+use image;
+use position::Transform;
+use render::{self, PrimitiveKind, PrimitiveWalker};
+use std::fmt::{self, Write};
+use text;
+use widget::triangles::{ColoredPoint, Triangle};
+use {Color, Dimensions, Point, Rect, Scalar};
+
+/// The font family used for text whose font has no family registered with the `Exporter`.
+pub const DEFAULT_FONT_FAMILY: &str = "sans-serif";
+
+/// Produces SVG documents from `render::Primitive`s.
+///
+/// As conrod only has access to the raw font data and image handles, the `Exporter` may be given
+/// a font family for each `font::Id` and an `href` for each `image::Id`.
+#[derive(Clone, Debug)]
+pub struct Exporter {
+    default_font_family: String,
+    font_families: fnv::FnvHashMap<text::font::Id, String>,
+    images: fnv::FnvHashMap<image::Id, Image>,
+}
+
+/// An image that may be referred to from within an exported document.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Image {
+    /// The URL used to refer to the image. This may be a `data:` URI in order to embed the image
+    /// within the document.
+    pub href: String,
+    /// The width and height of the image in pixels.
+    ///
+    /// These are necessary for mapping the `source_rect` of an `Image` primitive.
+    pub dimensions: [u32; 2],
+}
+
+// The state of the document while writing primitives.
+struct Writer<'a, W> {
+    exporter: &'a Exporter,
+    out: W,
+    window_rect: Rect,
+    // The clipping of the currently open groups, if any.
+    group: Option<Group>,
+    next_id: usize,
+}
+
+// The clipping applied by the currently open, nested `<g>` elements.
+struct Group {
+    scizzor: Option<Rect>,
+    outline: Option<Vec<Point>>,
+    depth: usize,
+}
+
+impl Exporter {
+    /// Construct a new `Exporter` with no registered fonts or images.
+    pub fn new() -> Self {
+        Exporter {
+            default_font_family: DEFAULT_FONT_FAMILY.to_string(),
+            font_families: fnv::FnvHashMap::default(),
+            images: fnv::FnvHashMap::default(),
+        }
+    }
+
+    /// The font family used for text whose font has no registered family.
+    ///
+    /// By default this is `DEFAULT_FONT_FAMILY`.
+    pub fn default_font_family<S>(mut self, family: S) -> Self
+    where
+        S: Into<String>,
+    {
+        self.default_font_family = family.into();
+        self
+    }
+
+    /// Specify the font family used for text with the given font.
+    pub fn font_family<S>(mut self, font_id: text::font::Id, family: S) -> Self
+    where
+        S: Into<String>,
+    {
+        self.font_families.insert(font_id, family.into());
+        self
+    }
+
+    /// Specify the `href` and pixel dimensions of the image with the given `image::Id`.
+    pub fn image<S>(mut self, image_id: image::Id, href: S, dimensions: [u32; 2]) -> Self
+    where
+        S: Into<String>,
+    {
+        let href = href.into();
+        self.images.insert(image_id, Image { href, dimensions });
+        self
+    }
+
+    /// Write the given `primitives` to `out` as an SVG document.
+    ///
+    /// `window_dim` should be the dimensions of the `Ui` that produced the primitives.
+    pub fn write<W, P>(&self, out: W, window_dim: Dimensions, mut primitives: P) -> fmt::Result
+    where
+        W: Write,
+        P: PrimitiveWalker,
+    {
+        let mut writer = Writer {
+            exporter: self,
+            out,
+            window_rect: Rect::from_xy_dim([0.0, 0.0], window_dim),
+            group: None,
+            next_id: 0,
+        };
+        writer.begin(window_dim)?;
+        while let Some(primitive) = primitives.next_primitive() {
+            writer.primitive(primitive)?;
+        }
+        writer.end()
+    }
+
+    /// Produce an SVG document from the given `primitives`.
+    ///
+    /// `window_dim` should be the dimensions of the `Ui` that produced the primitives.
+    pub fn export<P>(&self, window_dim: Dimensions, primitives: P) -> String
+    where
+        P: PrimitiveWalker,
+    {
+        let mut s = String::new();
+        self.write(&mut s, window_dim, primitives)
+            .expect("writing to a `String` should never fail");
+        s
+    }
+}
+
+impl Default for Exporter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Produce an SVG document from the given `primitives` using a default `Exporter`.
+pub fn export<P>(window_dim: Dimensions, primitives: P) -> String
+where
+    P: PrimitiveWalker,
+{
+    Exporter::new().export(window_dim, primitives)
+}
+
+impl<'a, W> Writer<'a, W>
+where
+    W: Write,
+{
+    fn begin(&mut self, [w, h]: Dimensions) -> fmt::Result {
+        writeln!(
+            self.out,
+            r#"<svg xmlns="http://www.w3.org/2000/svg" xmlns:xlink="http://www.w3.org/1999/xlink" width="{w}" height="{h}" viewBox="0 0 {w} {h}">"#,
+            w = Num(w),
+            h = Num(h),
+        )
+    }
+
+    fn end(&mut self) -> fmt::Result {
+        self.close_group()?;
+        writeln!(self.out, "</svg>")
+    }
+
+    fn close_group(&mut self) -> fmt::Result {
+        if let Some(group) = self.group.take() {
+            for _ in 0..group.depth {
+                writeln!(self.out, "</g>")?;
+            }
+        }
+        Ok(())
+    }
+
+    // Ensure that the primitive is written within a group clipped to the given scizzor and outline.
+    fn set_clip(&mut self, scizzor: Rect, outline: Option<&[Point]>) -> fmt::Result {
+        let scizzor = if contains(&scizzor, &self.window_rect) {
+            None
+        } else {
+            Some(scizzor)
+        };
+        match self.group {
+            Some(ref group)
+                if group.scizzor == scizzor
+                    && group.outline.as_ref().map(|o| &o[..]) == outline =>
+            {
+                return Ok(())
+            }
+            None if scizzor.is_none() && outline.is_none() => return Ok(()),
+            _ => self.close_group()?,
+        }
+        let mut depth = 0;
+        if let Some(scizzor) = scizzor {
+            let id = self.next_id();
+            let [x, y] = self.to_svg([scizzor.left(), scizzor.top()]);
+            writeln!(
+                self.out,
+                r#"<clipPath id="clip{}"><rect x="{}" y="{}" width="{}" height="{}"/></clipPath>"#,
+                id,
+                Num(x),
+                Num(y),
+                Num(scizzor.w()),
+                Num(scizzor.h()),
+            )?;
+            writeln!(self.out, r#"<g clip-path="url(#clip{})">"#, id)?;
+            depth += 1;
+        }
+        if let Some(outline) = outline {
+            let id = self.next_id();
+            write!(self.out, r#"<clipPath id="clip{}"><polygon points=""#, id)?;
+            for (i, &point) in outline.iter().enumerate() {
+                let [x, y] = self.to_svg(point);
+                let sep = if i == 0 { "" } else { " " };
+                write!(self.out, "{}{},{}", sep, Num(x), Num(y))?;
+            }
+            writeln!(self.out, r#"" clip-rule="evenodd"/></clipPath>"#)?;
+            writeln!(self.out, r#"<g clip-path="url(#clip{})">"#, id)?;
+            depth += 1;
+        }
+        if depth > 0 {
+            self.group = Some(Group {
+                scizzor,
+                outline: outline.map(|outline| outline.to_vec()),
+                depth,
+            });
+        }
+        Ok(())
+    }
+
+    fn primitive(&mut self, primitive: render::Primitive) -> fmt::Result {
+        let render::Primitive {
+            kind,
+            scizzor,
+            clip,
+            rect,
+            transform,
+            ..
+        } = primitive;
+        if transform.is_identity() {
+            return self.primitive_kind(kind, scizzor, clip, rect);
+        }
+        // Transforms are described in conrod's coordinate space, so map them into SVG's.
+        let to_svg = Transform {
+            matrix: [
+                [1.0, 0.0, -self.window_rect.left()],
+                [0.0, -1.0, self.window_rect.top()],
+            ],
+        };
+        let from_svg = to_svg
+            .inverse()
+            .expect("the SVG coordinate mapping is invertible");
+        let [[a, c, e], [b, d, f]] = from_svg.then(transform).then(to_svg).matrix;
+        self.set_clip(scizzor, clip)?;
+        writeln!(
+            self.out,
+            r#"<g transform="matrix({} {} {} {} {} {})">"#,
+            Num(a),
+            Num(b),
+            Num(c),
+            Num(d),
+            Num(e),
+            Num(f),
+        )?;
+        self.primitive_kind(kind, scizzor, clip, rect)?;
+        writeln!(self.out, "</g>")
+    }
+
+    fn primitive_kind(
+        &mut self,
+        kind: PrimitiveKind,
+        scizzor: Rect,
+        clip: Option<&[Point]>,
+        rect: Rect,
+    ) -> fmt::Result {
+        match kind {
+            PrimitiveKind::Rectangle { color } => {
+                self.set_clip(scizzor, clip)?;
+                self.rect(rect, color)
+            }
+
+            PrimitiveKind::TrianglesSingleColor { color, triangles } => {
+                if triangles.is_empty() {
+                    return Ok(());
+                }
+                self.set_clip(scizzor, clip)?;
+                let color: Color = color.into();
+                write!(self.out, r#"<path d=""#)?;
+                for &triangle in triangles {
+                    self.triangle_path(triangle.points())?;
+                }
+                writeln!(self.out, r#""{}/>"#, Fill(color))
+            }
+
+            PrimitiveKind::TrianglesMultiColor { triangles } => {
+                if triangles.is_empty() {
+                    return Ok(());
+                }
+                self.set_clip(scizzor, clip)?;
+                let mut current = None;
+                for &triangle in triangles {
+                    let color = average_color(triangle);
+                    if current != Some(color) {
+                        if let Some(current) = current {
+                            writeln!(self.out, r#""{}/>"#, Fill(current))?;
+                        }
+                        write!(self.out, r#"<path d=""#)?;
+                        current = Some(color);
+                    }
+                    self.triangle_path(triangle.points())?;
+                }
+                if let Some(color) = current {
+                    writeln!(self.out, r#""{}/>"#, Fill(color))?;
+                }
+                Ok(())
+            }
+
+            PrimitiveKind::Image {
+                image_id,
+                color,
+                source_rect,
+            } => {
+                let image = match self.exporter.images.get(&image_id) {
+                    Some(image) => image,
+                    None => return Ok(()),
+                };
+                self.set_clip(scizzor, clip)?;
+                let [img_w, img_h] = [image.dimensions[0] as Scalar, image.dimensions[1] as Scalar];
+                // Source rects are described from the bottom-left of the image.
+                let src =
+                    source_rect.unwrap_or_else(|| Rect::from_corners([0.0, 0.0], [img_w, img_h]));
+                let [x, y] = self.to_svg([rect.left(), rect.top()]);
+                let filter = match color {
+                    None => String::new(),
+                    Some(color) => {
+                        let id = self.next_id();
+                        let c = color.to_fsa();
+                        writeln!(
+                            self.out,
+                            r#"<filter id="tint{}"><feColorMatrix type="matrix" values="{} 0 0 0 0 0 {} 0 0 0 0 0 {} 0 0 0 0 0 {} 0"/></filter>"#,
+                            id,
+                            Num(c[0] as Scalar),
+                            Num(c[1] as Scalar),
+                            Num(c[2] as Scalar),
+                            Num(c[3] as Scalar),
+                        )?;
+                        format!(r#" filter="url(#tint{})""#, id)
+                    }
+                };
+                writeln!(
+                    self.out,
+                    r#"<svg x="{}" y="{}" width="{}" height="{}" viewBox="{} {} {} {}" preserveAspectRatio="none"><image xlink:href="{}" width="{}" height="{}"{}/></svg>"#,
+                    Num(x),
+                    Num(y),
+                    Num(rect.w()),
+                    Num(rect.h()),
+                    Num(src.left()),
+                    Num(img_h - src.top()),
+                    Num(src.w()),
+                    Num(src.h()),
+                    Escaped(&image.href),
+                    Num(img_w),
+                    Num(img_h),
+                    filter,
+                )
+            }
+
+            PrimitiveKind::Text {
+                color,
+                text,
+                font_id,
+            } => {
+                self.set_clip(scizzor, clip)?;
+                let font = text.font();
+                let scale = text::pt_to_scale(text.font_size());
+                let family = self
+                    .exporter
+                    .font_families
+                    .get(&font_id)
+                    .unwrap_or(&self.exporter.default_font_family);
+
+                // rusttype scales fonts by their height, while SVG expects the size of the em.
+                let v_metrics = font.v_metrics_unscaled();
+                let units_height = v_metrics.ascent - v_metrics.descent;
+                let font_size = if units_height > 0.0 {
+                    scale.y * font.units_per_em() as f32 / units_height
+                } else {
+                    scale.y
+                };
+
+                let (behind, in_front) = text.decoration_quads(color);
+                for (rect, color) in behind {
+                    self.rect(rect, color)?;
+                }
+
+                for (line, glyphs) in text.lines(1.0) {
+                    let mut y = None;
+                    for (i, glyph) in glyphs.enumerate() {
+                        let position = glyph.position();
+                        if i == 0 {
+                            write!(self.out, r#"<text x=""#)?;
+                            y = Some(position.y);
+                        } else {
+                            write!(self.out, " ")?;
+                        }
+                        write!(self.out, "{}", Num(position.x as Scalar))?;
+                    }
+                    // Empty lines produce no glyphs and in turn no element.
+                    let y = match y {
+                        Some(y) => y,
+                        None => continue,
+                    };
+                    writeln!(
+                        self.out,
+                        r#"" y="{}" font-family="{}" font-size="{}" xml:space="preserve"{}>{}</text>"#,
+                        Num(y as Scalar),
+                        Escaped(family),
+                        Num(font_size as Scalar),
+                        Fill(color),
+                        Escaped(line),
+                    )?;
+                }
+
+                for (rect, color) in in_front {
+                    self.rect(rect, color)?;
+                }
+                Ok(())
+            }
+
+            PrimitiveKind::Shadow {
+                color,
+                offset,
+                blur_radius,
+                spread,
+            } => {
+                self.set_clip(scizzor, clip)?;
+                let (x, y, w, h) = rect.x_y_w_h();
+                let xy = [x + offset[0], y + offset[1]];
+                let dim = [(w + spread * 2.0).max(0.0), (h + spread * 2.0).max(0.0)];
+                let shadow = Rect::from_xy_dim(xy, dim);
+                // The filter region must contain the entire blurred edge.
+                let blur_radius = blur_radius.max(0.0);
+                let region = shadow.pad(-blur_radius * 2.0);
+                let id = self.next_id();
+                let [fx, fy] = self.to_svg([region.left(), region.top()]);
+                writeln!(
+                    self.out,
+                    r#"<filter id="shadow{}" filterUnits="userSpaceOnUse" x="{}" y="{}" width="{}" height="{}"><feGaussianBlur stdDeviation="{}"/></filter>"#,
+                    id,
+                    Num(fx),
+                    Num(fy),
+                    Num(region.w()),
+                    Num(region.h()),
+                    Num(blur_radius / 2.0),
+                )?;
+                let [x, y] = self.to_svg([shadow.left(), shadow.top()]);
+                writeln!(
+                    self.out,
+                    r#"<rect x="{}" y="{}" width="{}" height="{}"{} filter="url(#shadow{})"/>"#,
+                    Num(x),
+                    Num(y),
+                    Num(shadow.w()),
+                    Num(shadow.h()),
+                    Fill(color),
+                    id,
+                )
+            }
+
+            PrimitiveKind::Other(_)
+            | PrimitiveKind::BeginLayer { .. }
+            | PrimitiveKind::EndLayer { .. } => Ok(()),
+        }
+    }
+
+    fn rect(&mut self, rect: Rect, color: Color) -> fmt::Result {
+        let [x, y] = self.to_svg([rect.left(), rect.top()]);
+        writeln!(
+            self.out,
+            r#"<rect x="{}" y="{}" width="{}" height="{}"{}/>"#,
+            Num(x),
+            Num(y),
+            Num(rect.w()),
+            Num(rect.h()),
+            Fill(color),
+        )
+    }
+
+    // Write the sub-path for a single triangle with a consistent winding, so that overlapping
+    // triangles within the same path do not cancel each other out.
+    fn triangle_path(&mut self, [a, b, c]: [Point; 3]) -> fmt::Result {
+        let cross = (b[0] - a[0]) * (c[1] - a[1]) - (b[1] - a[1]) * (c[0] - a[0]);
+        let (b, c) = if cross < 0.0 { (c, b) } else { (b, c) };
+        let [a, b, c] = [self.to_svg(a), self.to_svg(b), self.to_svg(c)];
+        write!(
+            self.out,
+            "M{} {}L{} {}L{} {}Z",
+            Num(a[0]),
+            Num(a[1]),
+            Num(b[0]),
+            Num(b[1]),
+            Num(c[0]),
+            Num(c[1]),
+        )
+    }
+
+    // Convert from conrod's centred, y-up coordinates to SVG's top-left, y-down coordinates.
+    fn to_svg(&self, [x, y]: Point) -> Point {
+        [x - self.window_rect.left(), self.window_rect.top() - y]
+    }
+
+    fn next_id(&mut self) -> usize {
+        let id = self.next_id;
+        self.next_id += 1;
+        id
+    }
+}
+
+// Whether or not `a` entirely contains `b`.
+fn contains(a: &Rect, b: &Rect) -> bool {
+    a.left() <= b.left() && a.right() >= b.right() && a.bottom() <= b.bottom() && a.top() >= b.top()
+}
+
+fn average_color(triangle: Triangle<ColoredPoint>) -> Color {
+    let mut sum = [0.0; 4];
+    for &(_, color::Rgba(r, g, b, a)) in triangle.0.iter() {
+        sum[0] += r;
+        sum[1] += g;
+        sum[2] += b;
+        sum[3] += a;
+    }
+    Color::Rgba(sum[0] / 3.0, sum[1] / 3.0, sum[2] / 3.0, sum[3] / 3.0)
+}
+
+// Formats a number with at most three decimal places, omitting trailing zeros.
+struct Num(Scalar);
+
+impl fmt::Display for Num {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let s = format!("{:.3}", self.0);
+        let s = s.trim_end_matches('0').trim_end_matches('.');
+        match s {
+            "-0" | "" => write!(f, "0"),
+            s => write!(f, "{}", s),
+        }
+    }
+}
+
+// Formats the `fill` and, if necessary, `fill-opacity` attributes for a color.
+struct Fill(Color);
+
+impl fmt::Display for Fill {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let [r, g, b, _] = self.0.to_byte_fsa();
+        let a = self.0.to_fsa()[3];
+        write!(f, r##" fill="#{:02x}{:02x}{:02x}""##, r, g, b)?;
+        if a < 1.0 {
+            write!(f, r#" fill-opacity="{}""#, Num(a as Scalar))?;
+        }
+        Ok(())
+    }
+}
+
+// Escapes the given string for use within XML text or attribute values.
+struct Escaped<'a>(&'a str);
+
+impl<'a> fmt::Display for Escaped<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for ch in self.0.chars() {
+            match ch {
+                '&' => f.write_str("&amp;")?,
+                '<' => f.write_str("&lt;")?,
+                '>' => f.write_str("&gt;")?,
+                '"' => f.write_str("&quot;")?,
+                '\'' => f.write_str("&apos;")?,
+                ch => f.write_char(ch)?,
+            }
+        }
+        Ok(())
+    }
+}
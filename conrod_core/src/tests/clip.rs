@@ -0,0 +1,80 @@
+use color;
+use image;
+use mesh::{self, Mesh};
+use position::ClipShape;
+use widget;
+use {Borderable, Positionable, Sizeable, Ui, UiBuilder, Widget};
+
+const DIAMOND: &[[f64; 2]] = &[[0.0, 1.0], [1.0, 0.0], [0.0, -1.0], [-1.0, 0.0]];
+
+// A canvas clipped to a diamond containing a nested, rectangularly cropped canvas with a child.
+fn ui_with_clipped_canvas() -> (Ui, widget::Id, widget::Id) {
+    let mut ui = UiBuilder::new([100.0, 100.0]).build();
+    let mut ids = ui.widget_id_generator();
+    let (canvas_id, inner_id, rect_id) = (ids.next(), ids.next(), ids.next());
+    {
+        let ui = &mut ui.set_widgets();
+        widget::Canvas::new()
+            .w_h(40.0, 20.0)
+            .x_y(10.0, 0.0)
+            .border(0.0)
+            .crop_kids_to(ClipShape::Polygon(DIAMOND))
+            .set(canvas_id, ui);
+        widget::Canvas::new()
+            .w_h(20.0, 20.0)
+            .middle_of(canvas_id)
+            .parent(canvas_id)
+            .border(0.0)
+            .crop_kids()
+            .set(inner_id, ui);
+        widget::Rectangle::fill_with([20.0, 20.0], color::WHITE)
+            .middle_of(inner_id)
+            .parent(inner_id)
+            .set(rect_id, ui);
+    }
+    (ui, canvas_id, rect_id)
+}
+
+#[test]
+fn children_are_clipped_to_the_innermost_clip_shape() {
+    let (ui, canvas_id, rect_id) = ui_with_clipped_canvas();
+    let outline = [[10.0, 10.0], [30.0, 0.0], [10.0, -10.0], [-10.0, 0.0]];
+
+    let mut primitives = ui.draw();
+    while let Some(primitive) = primitives.next() {
+        if primitive.id == canvas_id {
+            assert!(primitive.clip.is_none());
+        } else if primitive.id == rect_id {
+            assert_eq!(primitive.clip, Some(&outline[..]));
+        }
+    }
+
+    // The outline survives conversion to `OwnedPrimitives`.
+    let owned = ui.draw().owned();
+    let mut walk = owned.walk();
+    while let Some(primitive) = walk.next() {
+        if primitive.id == rect_id {
+            assert_eq!(primitive.clip, Some(&outline[..]));
+        }
+    }
+}
+
+#[test]
+fn mesh_produces_mask_commands() {
+    let (ui, _, _) = ui_with_clipped_canvas();
+    let mut mesh = Mesh::new();
+    let viewport = ui.rect_of(ui.window).unwrap();
+    let image_map = image::Map::<()>::new();
+    mesh.fill(viewport, 1.0, &image_map, ui.draw()).unwrap();
+
+    let masks: Vec<_> = mesh
+        .commands()
+        .filter_map(|command| match command {
+            mesh::Command::Mask(mask) => Some(mask),
+            _ => None,
+        })
+        .collect();
+    // The diamond is triangulated as a fan of two triangles.
+    assert_eq!(masks.len(), 1);
+    assert_eq!(masks[0].as_ref().map(|range| range.len()), Some(6));
+}
@@ -58,3 +58,86 @@ fn rgb_to_hsl_silver() {
     let actual = convert_rgb_to_hsl_to_rgb(r, g, b);
     assert!(compare_rgb_pairs((r, g, b), actual));
 }
+
+#[test]
+fn linear_gradient_offset() {
+    use color::{linear, BLACK, WHITE};
+    let gradient = linear((-10.0, 0.0), (10.0, 0.0), vec![(0.0, BLACK), (1.0, WHITE)]);
+    assert_eq!(gradient.offset_at((-10.0, 5.0)), 0.0);
+    assert_eq!(gradient.offset_at((0.0, -5.0)), 0.5);
+    assert_eq!(gradient.offset_at((10.0, 0.0)), 1.0);
+    // Points beyond either end are clamped.
+    assert_eq!(gradient.offset_at((-20.0, 0.0)), 0.0);
+    assert_eq!(gradient.offset_at((20.0, 0.0)), 1.0);
+}
+
+#[test]
+fn radial_gradient_offset() {
+    use color::{radial, BLACK, WHITE};
+    let gradient = radial(
+        (0.0, 0.0),
+        0.0,
+        (0.0, 0.0),
+        10.0,
+        vec![(0.0, BLACK), (1.0, WHITE)],
+    );
+    assert_eq!(gradient.offset_at((0.0, 0.0)), 0.0);
+    assert_eq!(gradient.offset_at((5.0, 0.0)), 0.5);
+    assert_eq!(gradient.offset_at((0.0, -10.0)), 1.0);
+    assert_eq!(gradient.offset_at((30.0, 0.0)), 1.0);
+}
+
+#[test]
+fn gradient_color_between_stops() {
+    use color::{linear, rgba, BLUE, RED};
+    let stops = vec![
+        (0.25, rgba(0.0, 0.0, 0.0, 1.0)),
+        (0.75, rgba(1.0, 0.5, 0.0, 1.0)),
+    ];
+    let gradient = linear((0.0, 0.0), (1.0, 0.0), stops);
+    assert_eq!(gradient.color_at_offset(0.0), rgba(0.0, 0.0, 0.0, 1.0));
+    assert_eq!(gradient.color_at_offset(0.5), rgba(0.5, 0.25, 0.0, 1.0));
+    assert_eq!(gradient.color_at_offset(1.0), rgba(1.0, 0.5, 0.0, 1.0));
+    let two_stops = linear((0.0, 0.0), (1.0, 0.0), vec![(0.0, RED), (1.0, BLUE)]);
+    assert_eq!(two_stops.color_at((0.0, 0.0)), RED);
+    assert_eq!(two_stops.color_at((1.0, 0.0)), BLUE);
+}
+
+#[test]
+fn linear_gradient_triangles_split_at_stops() {
+    use color::{linear, rgba};
+    use widget::triangles::{self, Triangle};
+    let stops = vec![
+        (0.0, rgba(0.0, 0.0, 0.0, 1.0)),
+        (0.5, rgba(1.0, 1.0, 1.0, 1.0)),
+        (1.0, rgba(0.0, 0.0, 0.0, 1.0)),
+    ];
+    let gradient = linear((-1.0, 0.0), (1.0, 0.0), stops);
+    let quad = [[-1.0, 1.0], [1.0, 1.0], [1.0, -1.0], [-1.0, -1.0]];
+    let (a, b) = triangles::from_quad(quad);
+    let mut colored = vec![];
+    triangles::gradient(&mut colored, vec![a, b], &gradient, [0.0, 0.0]);
+    // No triangle may span the middle stop, otherwise the white peak would be lost.
+    for Triangle(vs) in colored.iter() {
+        let min = vs.iter().map(|v| v.0[0]).fold(f64::MAX, f64::min);
+        let max = vs.iter().map(|v| v.0[0]).fold(f64::MIN, f64::max);
+        assert!(max <= 0.0 || min >= 0.0);
+    }
+    // Every vertex on the middle stop is white.
+    let peak = colored
+        .iter()
+        .flat_map(|tri| tri.0.iter())
+        .filter(|v| v.0[0] == 0.0);
+    for v in peak {
+        assert_eq!(v.1, rgba(1.0, 1.0, 1.0, 1.0).to_rgb());
+    }
+}
+
+#[test]
+fn setting_the_color_of_a_gradient_style_keeps_its_stops() {
+    use color::{linear, BLUE, GREEN, RED};
+    use widget::primitive::shape::Style;
+    let gradient = linear((-1.0, 0.0), (1.0, 0.0), vec![(0.0, RED), (1.0, BLUE)]);
+    let style = Style::gradient(gradient.clone()).color(GREEN);
+    assert_eq!(style, Style::Gradient(gradient));
+}
@@ -0,0 +1,169 @@
+use image;
+use mesh::{self, Mesh};
+use position::Rect;
+use render::PrimitiveKind;
+use widget::{self, image::NineSlice};
+use {Positionable, Sizeable, UiBuilder, Widget};
+
+// An image that only knows its dimensions, e.g. an uploaded atlas page.
+struct Texture([u32; 2]);
+
+impl mesh::ImageDimensions for Texture {
+    fn dimensions(&self) -> [u32; 2] {
+        self.0
+    }
+}
+
+fn nine_slice() -> NineSlice {
+    NineSlice {
+        left: 10.0,
+        right: 5.0,
+        top: 5.0,
+        bottom: 10.0,
+    }
+}
+
+#[test]
+#[cfg(debug_assertions)]
+#[should_panic(expected = "requires a `source_rectangle`")]
+fn nine_slice_image_requires_a_source_rectangle() {
+    let mut ui = UiBuilder::new([200.0, 200.0]).build();
+    let image_id = image::Map::<()>::new().insert(());
+    let id = ui.widget_id_generator().next();
+    let ui = &mut ui.set_widgets();
+    widget::Image::new(image_id)
+        .nine_slice(nine_slice())
+        .w_h(100.0, 50.0)
+        .set(id, ui);
+}
+
+#[test]
+fn nine_slice_image_yields_an_image_per_slice() {
+    let mut ui = UiBuilder::new([200.0, 200.0]).build();
+    let image_id = image::Map::<()>::new().insert(());
+    let id = ui.widget_id_generator().next();
+    {
+        let ui = &mut ui.set_widgets();
+        widget::Image::new(image_id)
+            .source_rectangle(Rect::from_corners([0.0, 0.0], [30.0, 30.0]))
+            .nine_slice(nine_slice())
+            .w_h(100.0, 50.0)
+            .x_y(0.0, 0.0)
+            .set(id, ui);
+    }
+
+    let mut slices = vec![];
+    let mut primitives = ui.draw();
+    while let Some(primitive) = primitives.next() {
+        if let PrimitiveKind::Image { source_rect, .. } = primitive.kind {
+            slices.push((primitive.rect, source_rect.unwrap()));
+        }
+    }
+    assert_eq!(slices.len(), 9);
+    // The bottom left corner keeps its original size.
+    let bottom_left = Rect::from_corners([-50.0, -25.0], [-40.0, -15.0]);
+    assert_eq!(
+        slices[0],
+        (bottom_left, Rect::from_corners([0.0, 0.0], [10.0, 10.0]))
+    );
+    // The centre is stretched to fill the remaining area.
+    let centre = Rect::from_corners([-40.0, -15.0], [45.0, 20.0]);
+    assert_eq!(
+        slices[4],
+        (centre, Rect::from_corners([10.0, 10.0], [25.0, 25.0]))
+    );
+    // The top right corner keeps its original size.
+    let top_right = Rect::from_corners([45.0, 20.0], [50.0, 25.0]);
+    assert_eq!(
+        slices[8],
+        (top_right, Rect::from_corners([25.0, 25.0], [30.0, 30.0]))
+    );
+}
+
+#[test]
+fn nine_slice_corners_shrink_to_fit() {
+    let rect = Rect::from_corners([0.0, 0.0], [6.0, 30.0]);
+    let source_rect = Rect::from_corners([0.0, 0.0], [30.0, 30.0]);
+    let slices = nine_slice().slices(rect, source_rect);
+    // The left and right columns share the available width in proportion to their insets.
+    assert_eq!(slices[0].0, Rect::from_corners([0.0, 0.0], [4.0, 10.0]));
+    assert_eq!(slices[1].0.w(), 0.0);
+    assert_eq!(slices[2].0, Rect::from_corners([4.0, 0.0], [6.0, 10.0]));
+    // The source rects are unaffected.
+    assert_eq!(slices[0].1, Rect::from_corners([0.0, 0.0], [10.0, 10.0]));
+}
+
+#[test]
+fn atlas_packs_images_onto_shared_pages() {
+    let mut image_map = image::Map::<Texture>::new();
+    image_map.set_atlas_page_dimensions([16, 16]);
+    let a = image_map.insert_atlased([4, 4], &[255; 4 * 4 * 4]);
+    let b = image_map.insert_atlased([8, 2], &[128; 8 * 2 * 4]);
+    // Too large for a shared page, so it is given a page of its own.
+    let c = image_map.insert_atlased([20, 4], &[64; 20 * 4 * 4]);
+
+    let (page_a, rect_a) = image_map.atlas().region(a).unwrap();
+    let (page_b, rect_b) = image_map.atlas().region(b).unwrap();
+    let (page_c, _) = image_map.atlas().region(c).unwrap();
+    assert_eq!(page_a, page_b);
+    assert!(page_a != page_c);
+    // Images are placed along the top of the page, separated by padding.
+    assert_eq!(rect_a, Rect::from_corners([0.0, 12.0], [4.0, 16.0]));
+    assert_eq!(rect_b, Rect::from_corners([5.0, 14.0], [13.0, 16.0]));
+    let page = &image_map.atlas().pages()[0];
+    assert_eq!(&page.pixels()[..4], &[255; 4]);
+    assert_eq!(&page.pixels()[5 * 4..5 * 4 + 4], &[128; 4]);
+
+    // Pages are only uploaded when their pixels have changed.
+    let mut uploads = 0;
+    image_map.upload_atlas_pages(|dims, _| {
+        uploads += 1;
+        Texture(dims)
+    });
+    image_map.upload_atlas_pages(|dims, _| {
+        uploads += 1;
+        Texture(dims)
+    });
+    assert_eq!(uploads, 2);
+    assert_eq!(image_map.get(&page_c).map(|t| t.0), Some([21, 16]));
+}
+
+#[test]
+fn mesh_batches_images_from_the_same_atlas_page() {
+    let mut image_map = image::Map::<Texture>::new();
+    let a = image_map.insert_atlased([4, 4], &[255; 4 * 4 * 4]);
+    let b = image_map.insert_atlased([4, 4], &[0; 4 * 4 * 4]);
+    image_map.upload_atlas_pages(|dims, _| Texture(dims));
+
+    let mut ui = UiBuilder::new([100.0, 100.0]).build();
+    let (id_a, id_b) = {
+        let mut ids = ui.widget_id_generator();
+        (ids.next(), ids.next())
+    };
+    {
+        let ui = &mut ui.set_widgets();
+        widget::Image::new(a)
+            .w_h(10.0, 10.0)
+            .x_y(0.0, 0.0)
+            .set(id_a, ui);
+        widget::Image::new(b)
+            .w_h(10.0, 10.0)
+            .down(0.0)
+            .set(id_b, ui);
+    }
+
+    let mut mesh = Mesh::new();
+    let viewport = ui.rect_of(ui.window).unwrap();
+    mesh.fill(viewport, 1.0, &image_map, ui.draw()).unwrap();
+    let draws: Vec<_> = mesh
+        .commands()
+        .filter_map(|command| match command {
+            mesh::Command::Draw(mesh::Draw::Image(id, range)) => Some((id, range)),
+            _ => None,
+        })
+        .collect();
+    let (page_id, _) = image_map.atlas().region(a).unwrap();
+    assert_eq!(draws.len(), 1);
+    assert_eq!(draws[0].0, page_id);
+    assert_eq!(draws[0].1.len(), 12);
+}
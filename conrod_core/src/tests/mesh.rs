@@ -0,0 +1,240 @@
+use super::noto_sans;
+use color;
+use image;
+use mesh::{self, Mesh};
+use position::Transform;
+use text;
+use widget;
+use widget::triangles::Triangle;
+use {Colorable, Positionable, Rect, Ui, UiBuilder, Widget};
+
+// A `Ui` with two lines of large text, more than fits within a small glyph cache.
+fn ui_with_large_text() -> Ui {
+    let mut ui = UiBuilder::new([400.0, 200.0]).build();
+    let font_id = ui.fonts.insert(noto_sans());
+    let mut ids = ui.widget_id_generator();
+    let (a, b) = (ids.next(), ids.next());
+    {
+        let ui = &mut ui.set_widgets();
+        widget::Text::new("Hello")
+            .font_id(font_id)
+            .font_size(48)
+            .mid_top_of(ui.window)
+            .set(a, ui);
+        widget::Text::new("World")
+            .font_id(font_id)
+            .font_size(48)
+            .mid_bottom_of(ui.window)
+            .set(b, ui);
+    }
+    ui
+}
+
+fn fill(ui: &mut Ui, feather_width: Option<f64>) -> Vec<mesh::Vertex> {
+    let mut mesh = Mesh::new();
+    mesh.set_feather_width(feather_width);
+    let viewport = Rect::from_corners([0.0, 0.0], [ui.win_w, ui.win_h]);
+    let image_map = image::Map::<()>::new();
+    mesh.fill(viewport, 1.0, &image_map, ui.draw()).unwrap();
+    mesh.vertices().to_vec()
+}
+
+impl mesh::ImageDimensions for () {
+    fn dimensions(&self) -> [u32; 2] {
+        [0, 0]
+    }
+}
+
+#[test]
+fn feathering_extrudes_only_the_outline() {
+    let mut ui = UiBuilder::new([100.0, 100.0]).build();
+    let id = ui.widget_id_generator().next();
+    {
+        let ui = &mut ui.set_widgets();
+        // A quad made of two triangles that share a diagonal edge.
+        let points = vec![[0.0, 0.0], [20.0, 0.0], [20.0, 20.0], [0.0, 20.0]];
+        widget::Polygon::centred_fill(points)
+            .color(color::WHITE)
+            .middle_of(ui.window)
+            .set(id, ui);
+    }
+
+    let plain = fill(&mut ui, None);
+    let feathered = fill(&mut ui, Some(mesh::DEFAULT_FEATHER_WIDTH));
+
+    // Two triangles for each of the four outer edges, but none for the shared diagonal.
+    let fringe = &feathered[plain.len()..];
+    assert_eq!(fringe.len(), 4 * 6);
+    assert_eq!(&feathered[..plain.len()], &plain[..]);
+
+    // Each fringe triangle has two opaque points on the outline and one transparent point.
+    for tri in fringe.chunks(3) {
+        let opaque = tri.iter().filter(|v| v.rgba[3] == 1.0).count();
+        let transparent = tri.iter().filter(|v| v.rgba[3] == 0.0).count();
+        assert_eq!(opaque + transparent, 3);
+        assert!(opaque >= 1 && transparent >= 1);
+    }
+
+    // Transparent points lie one pixel outside the 20x20 quad, or further at the corners.
+    let half_px = 2.0 / 100.0;
+    for v in fringe.iter().filter(|v| v.rgba[3] == 0.0) {
+        let [x, y] = v.position;
+        let outside_x = x.abs() >= 10.0 * half_px + half_px - 1e-6;
+        let outside_y = y.abs() >= 10.0 * half_px + half_px - 1e-6;
+        assert!(outside_x || outside_y, "{:?}", v.position);
+    }
+}
+
+#[test]
+fn feathering_skips_edges_against_t_junctions() {
+    let mut ui = UiBuilder::new([100.0, 100.0]).build();
+    let id = ui.widget_id_generator().next();
+    {
+        let ui = &mut ui.set_widgets();
+        // A square whose diagonal is a single edge on one side and split in two on the other.
+        let triangles = vec![
+            Triangle([[-10.0, -10.0], [10.0, -10.0], [10.0, 10.0]]),
+            Triangle([[-10.0, -10.0], [0.0, 0.0], [-10.0, 10.0]]),
+            Triangle([[0.0, 0.0], [10.0, 10.0], [-10.0, 10.0]]),
+        ];
+        widget::Triangles::single_color(color::WHITE, triangles)
+            .calc_bounding_rect()
+            .set(id, ui);
+    }
+
+    let plain = fill(&mut ui, None);
+    let feathered = fill(&mut ui, Some(mesh::DEFAULT_FEATHER_WIDTH));
+
+    // Only the four outer edges are fringed.
+    assert_eq!(feathered.len() - plain.len(), 4 * 6);
+}
+
+#[test]
+fn feathering_width_is_unaffected_by_transforms() {
+    let mut ui = UiBuilder::new([100.0, 100.0]).build();
+    let id = ui.widget_id_generator().next();
+    {
+        let ui = &mut ui.set_widgets();
+        let points = vec![[0.0, 0.0], [10.0, 0.0], [10.0, 10.0], [0.0, 10.0]];
+        widget::Polygon::centred_fill(points)
+            .color(color::WHITE)
+            .middle_of(ui.window)
+            .transform(Transform::scale(2.0, 2.0))
+            .set(id, ui);
+    }
+
+    let plain = fill(&mut ui, None);
+    let feathered = fill(&mut ui, Some(mesh::DEFAULT_FEATHER_WIDTH));
+
+    // The quad is drawn 20x20 and its fringe is one pixel wide, not two.
+    let half_px = 2.0 / 100.0;
+    for v in feathered[plain.len()..].iter().filter(|v| v.rgba[3] == 0.0) {
+        let [x, y] = v.position;
+        let max = x.abs().max(y.abs());
+        assert!(max > 10.0 * half_px, "{:?}", v.position);
+        assert!(max < 10.0 * half_px + 2.0 * half_px, "{:?}", v.position);
+    }
+}
+
+#[test]
+fn glyph_cache_grows_to_fit_the_frame() {
+    let ui = ui_with_large_text();
+    let mut mesh = Mesh::with_glyph_cache_dimensions([32, 32]);
+    let viewport = Rect::from_corners([0.0, 0.0], [ui.win_w, ui.win_h]);
+    let image_map = image::Map::<()>::new();
+    let fill = mesh.fill(viewport, 1.0, &image_map, ui.draw()).unwrap();
+    assert!(fill.glyph_cache_resized);
+    assert!(fill.glyph_cache_requires_upload);
+    let (w, h) = mesh.glyph_cache().dimensions();
+    assert!(w > 32 && h > 32);
+    assert_eq!(
+        mesh.glyph_cache_pixel_buffer().len(),
+        w as usize * h as usize
+    );
+
+    // Every glyph is positioned, i.e. no text was dropped.
+    let text: Vec<_> = mesh
+        .vertices()
+        .iter()
+        .filter(|v| v.mode == mesh::MODE_TEXT)
+        .collect();
+    assert_eq!(text.len(), 10 * 6);
+    for glyph in text.chunks(6) {
+        assert!(glyph[0].position != glyph[2].position);
+        assert!(glyph[0].tex_coords != glyph[2].tex_coords);
+    }
+
+    // Once grown, the following frame fits without resizing.
+    let fill = mesh.fill(viewport, 1.0, &image_map, ui.draw()).unwrap();
+    assert!(!fill.glyph_cache_resized);
+    assert_eq!(mesh.glyph_cache().dimensions(), (w, h));
+}
+
+#[test]
+fn glyph_cache_does_not_grow_beyond_its_maximum() {
+    let ui = ui_with_large_text();
+    let mut mesh = Mesh::with_glyph_cache_dimensions([32, 32]);
+    mesh.set_max_glyph_cache_dimensions([32, 32]);
+    let viewport = Rect::from_corners([0.0, 0.0], [ui.win_w, ui.win_h]);
+    let image_map = image::Map::<()>::new();
+    assert!(mesh.fill(viewport, 1.0, &image_map, ui.draw()).is_err());
+    assert_eq!(mesh.glyph_cache().dimensions(), (32, 32));
+}
+
+#[test]
+fn failing_to_grow_the_glyph_cache_restores_its_dimensions() {
+    let ui = ui_with_large_text();
+    let mut mesh = Mesh::with_glyph_cache_dimensions([32, 32]);
+    mesh.set_max_glyph_cache_dimensions([64, 64]);
+    let viewport = Rect::from_corners([0.0, 0.0], [ui.win_w, ui.win_h]);
+    let image_map = image::Map::<()>::new();
+    assert!(mesh.fill(viewport, 1.0, &image_map, ui.draw()).is_err());
+    assert_eq!(mesh.glyph_cache().dimensions(), (32, 32));
+
+    // Growth on the next fill, to dimensions the failed fill already reached, is still reported.
+    let mut ui = UiBuilder::new([400.0, 200.0]).build();
+    let font_id = ui.fonts.insert(noto_sans());
+    let id = ui.widget_id_generator().next();
+    {
+        let ui = &mut ui.set_widgets();
+        widget::Text::new("Hello")
+            .font_id(font_id)
+            .font_size(24)
+            .middle_of(ui.window)
+            .set(id, ui);
+    }
+    let fill = mesh.fill(viewport, 1.0, &image_map, ui.draw()).unwrap();
+    assert!(fill.glyph_cache_resized);
+    assert_eq!(mesh.glyph_cache().dimensions(), (64, 64));
+}
+
+#[test]
+fn glyph_queue_drops_the_glyphs_that_do_not_fit() {
+    let font = noto_sans();
+    let scale = text::Scale::uniform(48.0);
+    let glyphs: Vec<_> = font
+        .layout("HelloWorld", scale, text::rt::point(0.0, 48.0))
+        .collect();
+    let mut cache = text::GlyphCache::builder().dimensions(32, 32).build();
+    let mut queue = mesh::GlyphQueue::new();
+    for (i, glyph) in glyphs.iter().enumerate() {
+        let queued = mesh::QueuedGlyph {
+            font_id: 0,
+            glyph: glyph.clone(),
+            transform: Transform::identity(),
+            vertex_start: i * mesh::GLYPH_VERTEX_COUNT,
+        };
+        queue.push(&mut cache, queued);
+    }
+    assert!(queue.cache(&mut cache, [64, 64], |_, _, _| ()).is_err());
+
+    // The cache was grown before failing, so glyphs are uploaded at its new dimensions.
+    let mut uploaded = 0;
+    let dropped = queue.cache_fitting(&mut cache, |dims, _, _| {
+        assert_eq!(dims, [64, 64]);
+        uploaded += 1;
+    });
+    assert!(dropped > 0 && dropped < glyphs.len());
+    assert!(uploaded > 0);
+    assert_eq!(queue.drain_cached(&cache).count(), glyphs.len() - dropped);
+}
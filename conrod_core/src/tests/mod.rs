@@ -1,4 +1,29 @@
+mod clip;
 mod color;
 mod global_input;
+mod image;
+mod mesh;
+mod opacity;
+mod point_path;
+#[cfg(feature = "serde")]
+mod render_stream;
+mod shadow;
+#[cfg(feature = "shaping")]
+mod shaping;
+mod svg;
+mod text;
+mod transform;
 mod ui;
 mod widget_input;
+
+// The path of the regular Noto Sans font within the repository's assets.
+fn font_path() -> &'static str {
+    concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/../assets/fonts/NotoSans/NotoSans-Regular.ttf"
+    )
+}
+
+fn noto_sans() -> ::text::Font {
+    ::text::font::from_file(font_path()).unwrap()
+}
@@ -0,0 +1,126 @@
+use color;
+use render::{PrimitiveKind, Primitives};
+use widget;
+use {Borderable, Positionable, Sizeable, Ui, UiBuilder, Widget};
+
+// A translucent canvas containing a white rectangle.
+fn ui_with_translucent_canvas(isolated: bool) -> (Ui, widget::Id) {
+    let mut ui = UiBuilder::new([100.0, 80.0]).build();
+    let mut ids = ui.widget_id_generator();
+    let (canvas_id, rect_id) = (ids.next(), ids.next());
+    {
+        let ui = &mut ui.set_widgets();
+        let canvas = widget::Canvas::new()
+            .w_h(50.0, 50.0)
+            .middle_of(ui.window)
+            .border(0.0)
+            .opacity(0.5);
+        let canvas = if isolated {
+            canvas.isolated_layer()
+        } else {
+            canvas
+        };
+        canvas.set(canvas_id, ui);
+        widget::Rectangle::fill_with([10.0, 10.0], color::WHITE)
+            .middle_of(canvas_id)
+            .parent(canvas_id)
+            .opacity(0.5)
+            .set(rect_id, ui);
+    }
+    (ui, rect_id)
+}
+
+// Describe the layers and the alpha of the rectangle with the given id.
+fn describe(mut primitives: Primitives, rect_id: widget::Id) -> Vec<String> {
+    let mut descriptions = vec![];
+    while let Some(primitive) = primitives.next() {
+        match primitive.kind {
+            PrimitiveKind::BeginLayer { opacity } => {
+                descriptions.push(format!("begin {}", opacity))
+            }
+            PrimitiveKind::EndLayer { opacity } => descriptions.push(format!("end {}", opacity)),
+            PrimitiveKind::Rectangle { color } if primitive.id == rect_id => {
+                descriptions.push(format!("rect {}", color.to_rgb().3))
+            }
+            _ => (),
+        }
+    }
+    descriptions
+}
+
+#[test]
+fn opacity_multiplies_down_the_widget_graph() {
+    let (ui, rect_id) = ui_with_translucent_canvas(false);
+    assert_eq!(describe(ui.draw(), rect_id), vec!["rect 0.25"]);
+
+    // Layers are only produced for isolated widgets.
+    let described = describe(ui.draw().with_isolated_layers(), rect_id);
+    assert_eq!(described, vec!["rect 0.25"]);
+}
+
+#[test]
+fn isolated_layers_wrap_children_and_apply_opacity_once() {
+    let (ui, rect_id) = ui_with_translucent_canvas(true);
+
+    // Without isolated layers, the opacity is applied to each primitive instead.
+    assert_eq!(describe(ui.draw(), rect_id), vec!["rect 0.25"]);
+
+    let described = describe(ui.draw().with_isolated_layers(), rect_id);
+    assert_eq!(described, vec!["begin 0.5", "rect 0.5", "end 0.5"]);
+
+    // Layers survive conversion to `OwnedPrimitives`.
+    let owned = ui.draw().with_isolated_layers().owned();
+    let mut walk = owned.walk();
+    let mut layers = 0;
+    while let Some(primitive) = walk.next() {
+        match primitive.kind {
+            PrimitiveKind::BeginLayer { .. } | PrimitiveKind::EndLayer { .. } => layers += 1,
+            _ => (),
+        }
+    }
+    assert_eq!(layers, 2);
+}
+
+#[test]
+fn floating_children_of_ended_layers_inherit_their_opacity() {
+    let mut ui = UiBuilder::new([100.0, 80.0]).build();
+    let mut ids = ui.widget_id_generator();
+    let (canvas_id, sibling_id, rect_id) = (ids.next(), ids.next(), ids.next());
+    {
+        let ui = &mut ui.set_widgets();
+        widget::Canvas::new()
+            .w_h(50.0, 50.0)
+            .middle_of(ui.window)
+            .opacity(0.5)
+            .isolated_layer()
+            .set(canvas_id, ui);
+        widget::Rectangle::fill_with([10.0, 10.0], color::WHITE)
+            .top_left_of(ui.window)
+            .set(sibling_id, ui);
+        // Floating widgets are drawn last, after the canvas' layer has ended.
+        widget::Rectangle::fill_with([10.0, 10.0], color::WHITE)
+            .middle_of(canvas_id)
+            .parent(canvas_id)
+            .floating(true)
+            .set(rect_id, ui);
+    }
+
+    let described = describe(ui.draw().with_isolated_layers(), rect_id);
+    assert_eq!(described, vec!["begin 0.5", "end 0.5", "rect 0.5"]);
+}
+
+#[test]
+fn other_primitives_carry_their_inherited_opacity() {
+    let (ui, _) = ui_with_translucent_canvas(false);
+    let mut primitives = ui.draw();
+    let mut others = vec![];
+    while let Some(primitive) = primitives.next() {
+        match primitive.kind {
+            PrimitiveKind::Other(_) => others.push(primitive.opacity),
+            _ => assert_eq!(primitive.opacity, 1.0),
+        }
+    }
+    // The window is opaque, while the canvas and its inner widgets inherit its opacity.
+    assert_eq!(others[0], 1.0);
+    assert!(others.len() > 1 && others[1..].iter().all(|&opacity| opacity == 0.5));
+}
@@ -0,0 +1,77 @@
+use widget::line::{Cap, Dashes, Pattern};
+use widget::point_path;
+use widget::triangles::Triangle;
+use {Point, Scalar};
+
+fn pattern_triangles(points: &[Point], pattern: Pattern, dashes: Dashes) -> Vec<Triangle<Point>> {
+    point_path::triangles(points.iter().cloned(), Cap::Flat, 2.0)
+        .unwrap()
+        .pattern(pattern, dashes)
+        .collect()
+}
+
+fn dashes(dash: Scalar, gap: Scalar, phase: Scalar) -> Dashes {
+    Dashes { dash, gap, phase }
+}
+
+// The range covered along the x axis by each pair of triangles describing a dash.
+fn dash_x_ranges(triangles: &[Triangle<Point>]) -> Vec<(Scalar, Scalar)> {
+    triangles
+        .chunks(2)
+        .map(|pair| {
+            let xs = pair.iter().flat_map(|tri| tri.0.iter().map(|p| p[0]));
+            let min = xs.clone().fold(f64::MAX, f64::min);
+            let max = xs.fold(f64::MIN, f64::max);
+            (min, max)
+        })
+        .collect()
+}
+
+#[test]
+fn solid_path_yields_two_triangles_per_segment() {
+    let points = [[0.0, 0.0], [10.0, 0.0], [10.0, 10.0]];
+    let tris = pattern_triangles(&points, Pattern::Solid, dashes(2.0, 2.0, 0.0));
+    assert_eq!(tris.len(), 4);
+}
+
+#[test]
+fn dashed_line() {
+    let points = [[0.0, 0.0], [10.0, 0.0]];
+    let tris = pattern_triangles(&points, Pattern::Dashed, dashes(2.0, 2.0, 0.0));
+    let ranges = dash_x_ranges(&tris);
+    assert_eq!(ranges, vec![(0.0, 2.0), (4.0, 6.0), (8.0, 10.0)]);
+}
+
+#[test]
+fn dashed_line_with_phase() {
+    let points = [[0.0, 0.0], [10.0, 0.0]];
+    let tris = pattern_triangles(&points, Pattern::Dashed, dashes(2.0, 2.0, 1.0));
+    let ranges = dash_x_ranges(&tris);
+    assert_eq!(ranges, vec![(0.0, 1.0), (3.0, 5.0), (7.0, 9.0)]);
+}
+
+#[test]
+fn dashes_continue_around_corners() {
+    let points = [[0.0, 0.0], [5.0, 0.0], [5.0, 5.0]];
+    let tris = pattern_triangles(&points, Pattern::Dashed, dashes(2.0, 2.0, 0.0));
+    // The second dash is split across the corner, producing a quad on either side.
+    assert_eq!(tris.len(), 2 * 4);
+}
+
+#[test]
+fn dotted_line() {
+    let points = [[0.0, 0.0], [10.0, 0.0]];
+    let tris = pattern_triangles(&points, Pattern::Dotted, dashes(0.0, 2.0, 0.0));
+    // Each dot is a fan of triangles around its centre.
+    let mut centres: Vec<Point> = tris.iter().map(|tri| tri.0[0]).collect();
+    centres.dedup();
+    // Dots of diameter 2.0 are centred every 4.0 units starting at 1.0.
+    assert_eq!(centres, vec![[1.0, 0.0], [5.0, 0.0], [9.0, 0.0]]);
+}
+
+#[test]
+fn dashed_without_gap_is_solid() {
+    let points = [[0.0, 0.0], [10.0, 0.0]];
+    let tris = pattern_triangles(&points, Pattern::Dashed, dashes(2.0, 0.0, 0.0));
+    assert_eq!(tris.len(), 2);
+}
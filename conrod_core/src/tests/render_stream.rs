@@ -0,0 +1,122 @@
+use super::font_path;
+use color;
+use render::{self, stream, OwnedPrimitives, PrimitiveKind};
+use serde_json;
+use std;
+use text;
+use widget;
+use {Colorable, Positionable, Ui, UiBuilder, Widget};
+
+// A `Ui` with a rectangle and some text.
+fn ui_with_text() -> (Ui, text::font::Id) {
+    let mut ui = UiBuilder::new([100.0, 80.0]).build();
+    let font_id = ui.fonts.insert_from_file(font_path()).unwrap();
+    let mut ids = ui.widget_id_generator();
+    let (rect, text) = (ids.next(), ids.next());
+    {
+        let ui = &mut ui.set_widgets();
+        widget::Rectangle::fill_with([20.0, 10.0], color::RED)
+            .middle_of(ui.window)
+            .set(rect, ui);
+        widget::Text::new("Hello")
+            .color(color::WHITE)
+            .middle_of(ui.window)
+            .set(text, ui);
+    }
+    (ui, font_id)
+}
+
+// Describe each primitive as a string for comparison.
+fn describe(owned: &OwnedPrimitives) -> Vec<String> {
+    let mut walk = owned.walk();
+    let mut descriptions = vec![];
+    while let Some(render::Primitive { id, kind, rect, .. }) = walk.next() {
+        let kind = match kind {
+            PrimitiveKind::Rectangle { color } => format!("rect {:?}", color),
+            PrimitiveKind::Text { text, font_id, .. } => {
+                let glyphs = text.positioned_glyphs(1.0).count();
+                format!("text {:?} {} glyphs", font_id, glyphs)
+            }
+            _ => "other".to_string(),
+        };
+        descriptions.push(format!("{:?} {:?} {}", id, rect, kind));
+    }
+    descriptions
+}
+
+#[test]
+fn owned_primitives_round_trip_through_serde() {
+    let (ui, font_id) = ui_with_text();
+    let owned = ui.draw().owned();
+    assert_eq!(owned.font_ids(), vec![font_id]);
+
+    let json = serde_json::to_string(&owned).unwrap();
+    let mut deserialized: OwnedPrimitives = serde_json::from_str(&json).unwrap();
+
+    // Fonts are not serialized, so text is skipped until the font is supplied.
+    assert!(!deserialized.has_font(font_id));
+    let without_text: Vec<_> = describe(&owned)
+        .into_iter()
+        .filter(|d| !d.contains(" text "))
+        .collect();
+    assert_eq!(describe(&deserialized), without_text);
+
+    let font = ui.fonts.get(font_id).unwrap().clone();
+    deserialized.insert_font(font_id, font);
+    assert_eq!(describe(&deserialized), describe(&owned));
+}
+
+#[test]
+fn stream_replays_fonts_and_frames() {
+    let (ui, font_id) = ui_with_text();
+    let owned = ui.draw().owned();
+    let font_data = std::fs::read(font_path()).unwrap();
+
+    let mut writer = stream::Writer::new(vec![]);
+    writer.write_font(font_id, &font_data).unwrap();
+    writer.write_frame([ui.win_w, ui.win_h], &owned).unwrap();
+    writer.write_frame([ui.win_w, ui.win_h], &owned).unwrap();
+    let bytes = writer.into_inner();
+    assert_eq!(bytes.iter().filter(|&&b| b == b'\n').count(), 3);
+
+    let reader = stream::Reader::new(&bytes[..]);
+    let frames: Vec<_> = reader.map(Result::unwrap).collect();
+    assert_eq!(frames.len(), 2);
+    for frame in frames {
+        assert_eq!(frame.window_dim, [100.0, 80.0]);
+        assert_eq!(describe(&frame.primitives), describe(&owned));
+    }
+}
+
+#[test]
+fn stream_reports_invalid_messages() {
+    let mut reader = stream::Reader::new(&b"not json\n"[..]);
+    match reader.read_frame() {
+        Err(stream::Error::Json(_)) => (),
+        _ => panic!("expected a JSON error"),
+    }
+}
+
+#[test]
+fn stream_rejects_frames_indexing_beyond_their_data() {
+    let (ui, _) = ui_with_text();
+    let owned = ui.draw().owned();
+    let json = serde_json::to_value(&owned).unwrap();
+
+    // Text that is too short, or whose line ranges split a `char`, is rejected before walking.
+    for texts_string in &["He", "Hell\u{e9}"] {
+        let mut json = json.clone();
+        json["texts_string"] = serde_json::Value::from(*texts_string);
+        let tampered: OwnedPrimitives = serde_json::from_value(json).unwrap();
+        assert!(!tampered.is_valid());
+
+        let mut writer = stream::Writer::new(vec![]);
+        writer.write_frame([ui.win_w, ui.win_h], &tampered).unwrap();
+        let bytes = writer.into_inner();
+        match stream::Reader::new(&bytes[..]).read_frame() {
+            Err(stream::Error::InvalidFrame) => (),
+            _ => panic!("expected an invalid frame error"),
+        }
+    }
+    assert!(owned.is_valid());
+}
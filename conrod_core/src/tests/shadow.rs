@@ -0,0 +1,60 @@
+use color;
+use render::PrimitiveKind;
+use widget;
+use {Borderable, Sizeable, UiBuilder, Widget};
+
+#[test]
+fn canvas_shadow_is_not_cropped_by_the_canvas() {
+    let mut ui = UiBuilder::new([100.0, 100.0]).build();
+    let canvas_id = ui.widget_id_generator().next();
+    {
+        let ui = &mut ui.set_widgets();
+        widget::Canvas::new()
+            .w_h(40.0, 20.0)
+            .border(0.0)
+            .crop_kids()
+            .shadow(widget::shadow::Style::default())
+            .set(canvas_id, ui);
+    }
+
+    let window_rect = ui.rect_of(ui.window).unwrap();
+    let mut shadows = 0;
+    let mut primitives = ui.draw();
+    while let Some(primitive) = primitives.next() {
+        if let PrimitiveKind::Shadow { offset, .. } = primitive.kind {
+            assert_eq!(offset, [0.0, -4.0]);
+            assert_eq!(primitive.scizzor, window_rect);
+            assert_eq!(primitive.rect, ui.rect_of(canvas_id).unwrap());
+            shadows += 1;
+        }
+    }
+    assert_eq!(shadows, 1);
+}
+
+#[test]
+fn shadow_triangles_fade_to_transparent() {
+    let rect = ::Rect::from_xy_dim([0.0, 0.0], [40.0, 20.0]);
+    let mut triangles = vec![];
+    widget::shadow::triangles(
+        &mut triangles,
+        rect,
+        [2.0, -2.0],
+        8.0,
+        1.0,
+        color::BLACK.to_rgb(),
+    );
+    assert!(!triangles.is_empty());
+    // The shadow's rect spans -19..23 and -13..9, with a ring reaching half of the blur beyond.
+    let mut transparent = 0;
+    for triangle in &triangles {
+        for &([x, y], color::Rgba(_, _, _, a)) in triangle.0.iter() {
+            assert!((-23.0 - 1e-9..=27.0 + 1e-9).contains(&x));
+            assert!((-17.0 - 1e-9..=13.0 + 1e-9).contains(&y));
+            assert!(a == 0.0 || a == 1.0);
+            if a == 0.0 {
+                transparent += 1;
+            }
+        }
+    }
+    assert!(transparent > 0);
+}
@@ -0,0 +1,105 @@
+use super::font_path;
+use text;
+
+const FONT_SIZE: u32 = 14;
+
+// Mixed left-to-right and right-to-left text within a left-to-right paragraph.
+const BIDI: &'static str = "ab \u{5d0}\u{5d1} cd";
+
+fn noto_sans() -> (text::font::Map, text::font::Id) {
+    let mut fonts = text::font::Map::new();
+    let id = fonts.insert_from_file(font_path()).unwrap();
+    (fonts, id)
+}
+
+#[test]
+fn visual_order_reverses_right_to_left_runs() {
+    let bidi = text::shaping::Bidi::new(BIDI);
+    let order = text::shaping::visual_order(&bidi, 0..BIDI.len());
+    let expected = vec![
+        (0, false),
+        (1, false),
+        (2, false),
+        (4, true),
+        (3, true),
+        (5, false),
+        (6, false),
+        (7, false),
+    ];
+    assert_eq!(order, expected);
+}
+
+#[test]
+fn cursor_steps_visually_through_bidi_text() {
+    let (fonts, id) = noto_sans();
+    let font = fonts.get(id).unwrap();
+    let infos: Vec<_> = text::line::infos(BIDI, font, FONT_SIZE).collect();
+    let infos = || infos.iter().cloned();
+
+    let mut idx = text::cursor::Index { line: 0, char: 0 };
+    let mut chars = vec![idx.char];
+    while let Some(next) = idx.right(BIDI, infos()) {
+        idx = next;
+        chars.push(idx.char);
+    }
+    // Moving right through the right-to-left run moves backwards through the text.
+    assert_eq!(chars, vec![0, 1, 2, 4, 3, 5, 6, 7, 8]);
+
+    let mut chars_left = vec![idx.char];
+    while let Some(prev) = idx.left(BIDI, infos()) {
+        idx = prev;
+        chars_left.push(idx.char);
+    }
+    chars_left.reverse();
+    assert_eq!(chars, chars_left);
+}
+
+#[test]
+fn shaped_line_has_a_caret_per_cursor_index() {
+    let (fonts, id) = noto_sans();
+    let font = fonts.get(id).unwrap();
+    let data = fonts.data(id).unwrap();
+    let face = text::shaping::Face::new(font, data).unwrap();
+    let scale = text::pt_to_scale(FONT_SIZE);
+
+    let line = "Hello, world!";
+    let bidi = text::shaping::Bidi::new(line);
+    let shaped = text::shaping::shape_line(&bidi, 0..line.len(), &face, scale, 0.0);
+    let carets = shaped.caret_xs();
+    assert_eq!(carets.len(), line.chars().count() + 1);
+    assert!(carets.windows(2).all(|w| w[0] < w[1]));
+    assert_eq!(*carets.last().unwrap(), shaped.width);
+
+    // Shaping latin text matches the width measured per `char`, give or take kerning.
+    let width = text::line::infos(line, font, FONT_SIZE)
+        .next()
+        .unwrap()
+        .width;
+    assert!((shaped.width - width).abs() < 1.0);
+}
+
+#[test]
+fn shaped_lines_wrap_by_their_shaped_advances() {
+    let (fonts, id) = noto_sans();
+    let font = fonts.get(id).unwrap();
+    let data = fonts.data(id).unwrap();
+    let face = text::shaping::Face::new(font, data).unwrap();
+    let scale = text::pt_to_scale(FONT_SIZE);
+
+    let text = "The quick brown fox jumps over the lazy dog.\nAVAVAVAVAVAVAVAVAVAVAVAV";
+    let max_width = 80.0;
+    let infos: Vec<_> = text::line::infos(text, font, FONT_SIZE)
+        .wrap_by_character(max_width)
+        .shaped(Some(&face))
+        .collect();
+    assert!(infos.len() > 3);
+
+    // Each line fits once shaped, give or take the kerning across the wrap, and is measured by
+    // the width at which it is displayed.
+    let bidi = text::shaping::Bidi::new(text);
+    for info in infos {
+        let line = text::shaping::shape_line(&bidi, info.byte_range(), &face, scale, 0.0);
+        assert!(line.width <= max_width + 1.0);
+        assert_eq!(line.width, info.width);
+    }
+}
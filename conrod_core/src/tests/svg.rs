@@ -0,0 +1,129 @@
+use super::noto_sans;
+use color;
+use image;
+use svg;
+use widget;
+use {Borderable, Colorable, Positionable, Sizeable, Ui, UiBuilder, Widget};
+
+fn windowless_ui() -> Ui {
+    UiBuilder::new([100.0, 80.0]).build()
+}
+
+#[test]
+fn rectangle_is_exported_in_top_left_coordinates() {
+    let mut ui = windowless_ui();
+    let id = ui.widget_id_generator().next();
+    {
+        let ui = &mut ui.set_widgets();
+        widget::Rectangle::fill_with([20.0, 10.0], color::rgba(1.0, 0.0, 0.0, 0.5))
+            .top_left_with_margins_on(ui.window, 5.0, 10.0)
+            .set(id, ui);
+    }
+    let doc = svg::export([ui.win_w, ui.win_h], ui.draw());
+    assert!(doc.starts_with("<svg "));
+    assert!(doc.contains(r#"viewBox="0 0 100 80""#));
+    assert!(doc.contains(
+        r##"<rect x="10" y="5" width="20" height="10" fill="#ff0000" fill-opacity="0.5"/>"##
+    ));
+    assert!(doc.trim_end().ends_with("</svg>"));
+    // Nothing is cropped, so no clip paths should be produced.
+    assert!(!doc.contains("<clipPath"));
+}
+
+#[test]
+fn triangles_are_exported_as_paths() {
+    let mut ui = windowless_ui();
+    let id = ui.widget_id_generator().next();
+    {
+        let ui = &mut ui.set_widgets();
+        let points = vec![[0.0, 0.0], [10.0, 0.0], [0.0, 10.0]];
+        widget::Polygon::centred_fill(points)
+            .color(color::rgb(0.0, 0.0, 1.0))
+            .top_left_of(ui.window)
+            .set(id, ui);
+    }
+    let doc = svg::export([ui.win_w, ui.win_h], ui.draw());
+    assert!(
+        doc.contains(r##"<path d="M0 10L10 10L0 0Z" fill="#0000ff"/>"##),
+        "{}",
+        doc
+    );
+}
+
+#[test]
+fn cropped_primitives_are_clipped() {
+    let mut ui = windowless_ui();
+    let mut ids = ui.widget_id_generator();
+    let (canvas, rect) = (ids.next(), ids.next());
+    {
+        let ui = &mut ui.set_widgets();
+        widget::Canvas::new()
+            .w_h(40.0, 40.0)
+            .top_left_of(ui.window)
+            .border(0.0)
+            .crop_kids()
+            .set(canvas, ui);
+        widget::Rectangle::fill([80.0, 80.0])
+            .top_left_of(canvas)
+            .parent(canvas)
+            .set(rect, ui);
+    }
+    let doc = svg::export([ui.win_w, ui.win_h], ui.draw());
+    assert!(doc
+        .contains(r#"<clipPath id="clip0"><rect x="0" y="0" width="40" height="40"/></clipPath>"#));
+    assert!(doc.contains(r#"<g clip-path="url(#clip0)">"#));
+    assert!(doc.contains(r#"width="80" height="80""#));
+    assert_eq!(doc.matches("<g ").count(), doc.matches("</g>").count());
+}
+
+#[test]
+fn text_is_exported_with_family_and_escaped() {
+    let mut ui = windowless_ui();
+    let font_id = ui.fonts.insert(noto_sans());
+    let id = ui.widget_id_generator().next();
+    {
+        let ui = &mut ui.set_widgets();
+        widget::Text::new("a<b")
+            .font_size(12)
+            .color(color::WHITE)
+            .middle_of(ui.window)
+            .set(id, ui);
+    }
+    let exporter = svg::Exporter::new().font_family(font_id, "Noto Sans");
+    let doc = exporter.export([ui.win_w, ui.win_h], ui.draw());
+    let text = doc.lines().find(|l| l.starts_with("<text")).unwrap();
+    assert!(text.contains(r#"font-family="Noto Sans""#));
+    assert!(text.contains(r##"fill="#ffffff""##));
+    assert!(text.ends_with(">a&lt;b</text>"));
+    // One x position per glyph.
+    let xs = text
+        .split(r#"x=""#)
+        .nth(1)
+        .unwrap()
+        .split('"')
+        .next()
+        .unwrap();
+    assert_eq!(xs.split(' ').count(), 3);
+}
+
+#[test]
+fn unregistered_images_are_skipped() {
+    let mut ui = windowless_ui();
+    let mut image_map = image::Map::<()>::new();
+    let image_id = image_map.insert(());
+    let id = ui.widget_id_generator().next();
+    {
+        let ui = &mut ui.set_widgets();
+        widget::Image::new(image_id)
+            .w_h(10.0, 10.0)
+            .middle_of(ui.window)
+            .set(id, ui);
+    }
+    let doc = svg::export([ui.win_w, ui.win_h], ui.draw());
+    assert!(!doc.contains("<image"));
+
+    let exporter = svg::Exporter::new().image(image_id, "logo.png", [20, 10]);
+    let doc = exporter.export([ui.win_w, ui.win_h], ui.draw());
+    assert!(doc.contains(r#"<image xlink:href="logo.png" width="20" height="10"/>"#));
+    assert!(doc.contains(r#"viewBox="0 0 20 10""#));
+}
@@ -0,0 +1,681 @@
+use super::{font_path, noto_sans};
+use color;
+use event::Input;
+use input::{Button, Key};
+use position::{Align, Rect};
+use render::PrimitiveKind;
+use text;
+use text::rich::Span;
+use widget;
+use {Labelable, Positionable, Sizeable, UiBuilder, Widget};
+
+const TEXT: &'static str = "The quick brown fox jumps over the lazy dog.\nThe end.";
+const FONT_SIZE: u32 = 14;
+
+fn line_infos(font: &text::Font, max_width: f64) -> Vec<text::line::Info> {
+    text::line::infos(TEXT, font, FONT_SIZE)
+        .wrap_by_whitespace(max_width)
+        .collect()
+}
+
+#[test]
+fn full_justify_stretches_all_but_the_last_line_of_each_paragraph() {
+    let font = noto_sans();
+    let rect = Rect::from_xy_dim([0.0, 0.0], [150.0, 200.0]);
+    let infos = line_infos(&font, rect.w());
+    assert!(infos.len() > 2);
+    let line_rects: Vec<_> = text::line::rects(
+        infos.iter().cloned(),
+        FONT_SIZE,
+        rect,
+        text::Justify::Full,
+        Align::End,
+        0.0,
+    )
+    .collect();
+
+    for (info, line_rect) in infos.iter().zip(&line_rects) {
+        assert_eq!(line_rect.left(), rect.left());
+        match info.end_break {
+            text::line::Break::Wrap { .. } => assert_eq!(line_rect.x, rect.x),
+            _ => assert_eq!(line_rect.w(), info.width),
+        }
+    }
+
+    // The last glyph of each wrapped line reaches the end of the line, while the glyphs of
+    // unwrapped lines are laid out as usual.
+    let scale = text::pt_to_scale(FONT_SIZE);
+    for (info, &line_rect) in infos.iter().zip(&line_rects) {
+        let line = &TEXT[info.byte_range()];
+        let spacing = text::line::word_spacing(TEXT, info, text::Justify::Full, line_rect);
+        let point = text::rt::point(line_rect.left() as f32, 0.0);
+        let last = text::line::layout(line, &font, scale, point, spacing as f32)
+            .last()
+            .unwrap();
+        let end = last.position().x + last.unpositioned().h_metrics().advance_width;
+        match info.end_break {
+            text::line::Break::Wrap { .. } => {
+                assert!(spacing > 0.0);
+                assert!((end as f64 - rect.right()).abs() < 0.01);
+            }
+            _ => assert_eq!(spacing, 0.0),
+        }
+    }
+}
+
+#[test]
+fn full_justify_cursor_follows_stretched_spacing() {
+    let font = noto_sans();
+    let rect = Rect::from_xy_dim([0.0, 0.0], [150.0, 200.0]);
+    let infos = line_infos(&font, rect.w());
+    let xys = |justify| {
+        text::cursor::xys_per_line_from_text(
+            TEXT,
+            &infos,
+            &font,
+            FONT_SIZE,
+            justify,
+            Align::End,
+            0.0,
+            rect,
+        )
+        .map(|(xs, _)| xs.collect::<Vec<_>>())
+        .collect::<Vec<_>>()
+    };
+    let left = xys(text::Justify::Left);
+    let full = xys(text::Justify::Full);
+
+    // The first line is wrapped, so its cursor positions move further right after each space.
+    let first_line = &TEXT[infos[0].byte_range()];
+    let first_space = first_line.find(' ').unwrap();
+    assert_eq!(left[0][..first_space + 1], full[0][..first_space + 1]);
+    assert!(full[0][first_space + 1] > left[0][first_space + 1]);
+    let full_end = *full[0].last().unwrap();
+    assert!(full_end > *left[0].last().unwrap());
+
+    // The last line of the text is not stretched.
+    assert_eq!(left.last(), full.last());
+}
+
+#[test]
+fn full_justify_adds_no_space_after_control_characters() {
+    let font = noto_sans();
+    let scale = text::pt_to_scale(FONT_SIZE);
+    let point = text::rt::point(0.0, 0.0);
+    let xs = |word_spacing| -> Vec<f32> {
+        text::line::layout("a\tb c", &font, scale, point, word_spacing)
+            .map(|g| g.position().x)
+            .collect()
+    };
+    let (plain, stretched) = (xs(0.0), xs(10.0));
+    assert_eq!(plain.len(), 5);
+    assert_eq!(plain[..4], stretched[..4]);
+    assert_eq!(stretched[4], plain[4] + 10.0);
+}
+
+fn rich_spans() -> Vec<Span<'static>> {
+    vec![
+        Span::new("The quick "),
+        Span::new("brown fox")
+            .font_size(24)
+            .color(color::RED)
+            .highlight(color::YELLOW),
+        Span::new(" jumps over the lazy dog.").underline(),
+    ]
+}
+
+#[test]
+fn rich_layout_breaks_lines_across_spans() {
+    let mut fonts = text::font::Map::new();
+    let font_id = fonts.insert(noto_sans());
+    let base = text::rich::Style {
+        color: color::BLACK,
+        font_id: font_id,
+        font_size: FONT_SIZE,
+        underline: false,
+        highlight: None,
+    };
+    let spans = rich_spans();
+    let wrap = Some(text::line::Wrap::Whitespace);
+    let layout = text::rich::layout(
+        &spans,
+        base,
+        &fonts,
+        &[],
+        150.0,
+        wrap,
+        text::Justify::Left,
+        0.0,
+    );
+    assert_eq!(layout.text, "The quick brown fox jumps over the lazy dog.");
+    assert!(layout.line_infos.len() > 1);
+
+    // Runs are ordered, lie within a single line and never span two differently styled spans.
+    let mut last_end = 0;
+    for run in &layout.runs {
+        let range = run.info.byte_range();
+        assert!(range.start >= last_end);
+        last_end = range.end;
+        let line = layout
+            .line_infos
+            .iter()
+            .find(|info| info.start_byte <= range.start && range.end <= info.end_byte())
+            .unwrap();
+        assert!(run.rect.right() <= 150.0 + 1e-6);
+        let (second_start, second_end) = ("The quick ".len(), "The quick brown fox".len());
+        let in_second_span = second_start <= range.start && range.end <= second_end;
+        assert_eq!(run.style.font_size == 24, in_second_span);
+        assert_eq!(run.style.underline, range.start >= second_end);
+        assert!(line.width >= run.info.width);
+    }
+
+    // Consecutive runs on a line are placed end to end on a shared baseline.
+    for pair in layout.runs.windows(2) {
+        if pair[0].rect.bottom() == pair[1].rect.bottom() {
+            assert_eq!(pair[0].rect.right(), pair[1].rect.left());
+        }
+    }
+
+    // The first line is as tall as the large span within it.
+    assert_eq!(layout.runs[0].rect.bottom(), -24.0);
+}
+
+#[test]
+fn rich_text_yields_highlights_before_ordered_runs() {
+    let mut ui = UiBuilder::new([400.0, 200.0]).build();
+    ui.fonts.insert(noto_sans());
+    let id = ui.widget_id_generator().next();
+    let spans = rich_spans();
+    {
+        let ui = &mut ui.set_widgets();
+        widget::Text::rich(&spans)
+            .w(150.0)
+            .top_left_of(ui.window)
+            .set(id, ui);
+    }
+
+    let mut primitives = ui.draw();
+    let mut kinds = Vec::new();
+    while let Some(primitive) = primitives.next() {
+        if primitive.id != id {
+            continue;
+        }
+        let kind = match primitive.kind {
+            PrimitiveKind::Rectangle { color } => format!("rect {:?}", color),
+            PrimitiveKind::Text { text, color, .. } => {
+                let underline = text.decorations().underline;
+                let glyphs = text.positioned_glyphs(1.0).count();
+                format!("text {} {:?} {}", glyphs, color, underline)
+            }
+            _ => "other".to_string(),
+        };
+        kinds.push(kind);
+    }
+
+    let text = ui.widget_graph().widget(id).unwrap();
+    let state = text.unique_widget_state::<widget::Text>().unwrap();
+    let runs = &state.state.runs;
+    assert!(runs.len() > 3);
+    assert_eq!(kinds[0], format!("rect {:?}", color::YELLOW));
+    let expected: Vec<_> = runs
+        .iter()
+        .map(|run| {
+            let num_chars = run.info.char_range().len();
+            let style = run.style;
+            format!("text {} {:?} {}", num_chars, style.color, style.underline)
+        })
+        .collect();
+    assert_eq!(kinds[1..], expected[..]);
+
+    // Sizing the height and updating the widget share a single cached layout.
+    assert_eq!(ui.text_cache().len(), 1);
+}
+
+#[test]
+fn font_chain_prefers_fallbacks_set_for_the_font() {
+    let mut fonts = text::font::Map::new();
+    let a = fonts.insert(noto_sans());
+    let b = fonts.insert(noto_sans());
+    let c = fonts.insert(noto_sans());
+
+    // Without fallbacks of its own, a font falls back to the given defaults.
+    assert_eq!(fonts.chain(a, &[c, a]), vec![a, c]);
+    fonts.set_fallbacks(a, vec![b, a]);
+    assert_eq!(fonts.fallbacks(a), Some(&[b, a][..]));
+    assert_eq!(fonts.chain(a, &[c]), vec![a, b]);
+
+    // Characters that no font contains, along with whitespace, remain with the font itself.
+    let text = "ab \u{6f22}\u{5b57} cd\n";
+    let runs = fonts.fallback_runs(text, a, &[c]);
+    assert_eq!(runs, vec![(0..text.len(), a)]);
+}
+
+#[test]
+fn truncated_lines_fit_the_width_with_an_ellipsis() {
+    let font = noto_sans();
+    let fonts = [&font];
+    let max_w = 120.0;
+    let width = |line: &str| text::line::width(line, &font, FONT_SIZE);
+    assert!(text::line::truncate(TEXT, &fonts, FONT_SIZE, 1000.0, text::Ellipsis::End).is_none());
+
+    let truncate = |ellipsis| text::line::truncate(TEXT, &fonts, FONT_SIZE, max_w, ellipsis);
+    let end = truncate(text::Ellipsis::End).unwrap();
+    let start = truncate(text::Ellipsis::Start).unwrap();
+    let middle = truncate(text::Ellipsis::Middle).unwrap();
+    for truncated in &[&end, &start, &middle] {
+        let lines: Vec<_> = truncated.lines().collect();
+        assert_eq!(lines.len(), 2);
+        assert!(width(lines[0]) <= max_w);
+        assert!(lines[0].contains('\u{2026}'));
+        // Lines that already fit are left untouched.
+        assert_eq!(lines[1], "The end.");
+    }
+    assert!(end.starts_with("The quick") && end.contains("\u{2026}\n"));
+    assert!(start.starts_with("\u{2026}") && start.contains("lazy dog.\n"));
+    assert!(middle.starts_with("The") && middle.contains("dog.\n"));
+}
+
+#[test]
+fn truncated_text_is_cached_across_frames() {
+    let mut ui = UiBuilder::new([400.0, 200.0]).build();
+    let font_id = ui.fonts.insert(noto_sans());
+    let id = ui.widget_id_generator().next();
+    for _ in 0..2 {
+        let ui = &mut ui.set_widgets();
+        widget::Text::new(TEXT)
+            .ellipsis(text::Ellipsis::End)
+            .w(120.0)
+            .top_left_of(ui.window)
+            .set(id, ui);
+    }
+    let key = text::cache::Key::truncated(TEXT, &[font_id], 18, 120.0, text::Ellipsis::End);
+    let cached = ui
+        .text_cache()
+        .truncated(key, TEXT, || unreachable!())
+        .unwrap();
+    let widget = ui.widget_graph().widget(id).unwrap();
+    let state = widget.unique_widget_state::<widget::Text>().unwrap();
+    assert_eq!(state.state.string, &cached[..]);
+    assert_eq!(state.state.full_string.as_ref().unwrap(), TEXT);
+}
+
+#[test]
+fn lines_wrap_at_break_opportunities_without_splitting_graphemes() {
+    let font = noto_sans();
+    let width = |text: &str| text::line::width(text, &font, FONT_SIZE);
+    fn lines<I>(text: &str, infos: I) -> Vec<&str>
+    where
+        I: Iterator<Item = text::line::Info>,
+    {
+        infos.map(|info| &text[info.byte_range()]).collect()
+    }
+
+    // A hyphen allows a break while a no-break space does not.
+    let text = "well-known\u{a0}fact";
+    let infos = text::line::infos(text, &font, FONT_SIZE).wrap_by_whitespace(width("well-kno"));
+    let wrapped = lines(text, infos);
+    assert_eq!(wrapped[0], "well-");
+    assert!(wrapped.iter().all(|line| !line.starts_with('\u{a0}')));
+    assert_eq!(wrapped.concat(), text);
+
+    // Combining marks remain with the letter that they modify.
+    let text = "ae\u{301}\u{301}b";
+    let infos = text::line::infos(text, &font, FONT_SIZE).wrap_by_character(width("a") + 0.5);
+    assert_eq!(lines(text, infos), vec!["a", "e\u{301}\u{301}", "b"]);
+}
+
+#[test]
+fn cursor_steps_over_graphemes_and_words() {
+    let font = noto_sans();
+    let text = "cafe\u{301} \u{1f469}\u{200d}\u{1f4bb} na\u{ef}ve";
+    let infos = || text::line::infos(text, &font, FONT_SIZE);
+    let at = |char| text::cursor::Index { line: 0, char };
+
+    let mut idx = at(0);
+    let mut chars = vec![idx.char];
+    while let Some(next) = idx.next(text, infos()) {
+        chars.push(next.char);
+        idx = next;
+    }
+    assert_eq!(chars, vec![0, 1, 2, 3, 5, 6, 9, 10, 11, 12, 13, 14, 15]);
+    assert_eq!(at(5).previous(text, infos()), Some(at(3)));
+    assert_eq!(at(9).previous(text, infos()), Some(at(6)));
+
+    // Only segments containing letters or digits are treated as words.
+    assert_eq!(at(0).next_word_end(text, infos()), Some(at(5)));
+    assert_eq!(at(5).next_word_end(text, infos()), Some(at(15)));
+    assert_eq!(at(15).previous_word_start(text, infos()), Some(at(10)));
+    assert_eq!(at(10).previous_word_start(text, infos()), Some(at(0)));
+}
+
+#[test]
+fn decoration_lines_are_placed_on_each_baseline_via_font_metrics() {
+    let path = concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/../assets/fonts/NotoSans/NotoSans-Regular.ttf"
+    );
+    let mut ui = UiBuilder::new([400.0, 200.0]).build();
+    let font_id = ui.fonts.insert_from_file(path).unwrap();
+    let id = ui.widget_id_generator().next();
+    {
+        let ui = &mut ui.set_widgets();
+        widget::Text::new("Underlined\nand struck")
+            .font_size(FONT_SIZE)
+            .underline()
+            .strikethrough()
+            .highlight(color::YELLOW)
+            .top_left_of(ui.window)
+            .set(id, ui);
+    }
+
+    // The metrics are read from the font's tables rather than estimated.
+    let font = ui.fonts.get(font_id).unwrap();
+    let data = ui.fonts.data(font_id).map(|d| &d[..]);
+    let metrics = text::DecorationMetrics::new(font, data, FONT_SIZE);
+    let estimated = text::DecorationMetrics::new(font, None, FONT_SIZE);
+    assert!(metrics.underline_offset < 0.0);
+    assert!(metrics.strikethrough_offset > 0.0);
+    assert_ne!(metrics, estimated);
+
+    let mut primitives = ui.draw();
+    let (text, color) = loop {
+        let primitive = primitives.next().unwrap();
+        if let (true, PrimitiveKind::Text { text, color, .. }) =
+            (primitive.id == id, primitive.kind)
+        {
+            break (text, color);
+        }
+    };
+    assert_eq!(text.decorations().highlight, Some(color::YELLOW));
+    let highlights: Vec<_> = text.highlight_rects().collect();
+    let lines: Vec<_> = text.decoration_rects().collect();
+    assert_eq!(highlights.len(), 2);
+    assert_eq!(lines.len(), 4);
+
+    // Highlights are filled with their own color and lines with that of the text.
+    let (behind, in_front) = text.decoration_quads(color);
+    let behind: Vec<_> = behind.collect();
+    let in_front: Vec<_> = in_front.collect();
+    assert!(behind.iter().all(|&(_, c)| c == color::YELLOW));
+    assert!(in_front.iter().all(|&(_, c)| c == color));
+    assert_eq!(behind.len(), highlights.len());
+    assert_eq!(in_front.len(), lines.len());
+
+    // Each line's underline and strikethrough lie within its highlight, relative to one baseline.
+    for (highlight, pair) in highlights.iter().zip(lines.chunks(2)) {
+        let (underline, strikethrough) = (pair[0], pair[1]);
+        assert_eq!(underline.x, highlight.x);
+        assert_eq!(underline.h(), metrics.underline_thickness);
+        let baseline = underline.y() - metrics.underline_offset;
+        let strikethrough_y = baseline + metrics.strikethrough_offset;
+        assert!((strikethrough.y() - strikethrough_y).abs() < 1e-9);
+        assert!(highlight.bottom() < underline.bottom());
+        assert!(strikethrough.top() < highlight.top());
+    }
+    assert!(highlights[1].y() < highlights[0].y());
+}
+
+#[test]
+fn fonts_loaded_from_a_directory_are_matched_by_family_weight_and_style() {
+    use text::font::{Properties, Query, Style, Weight};
+
+    let dir = std::path::Path::new(font_path()).parent().unwrap();
+    let mut ui = UiBuilder::new([400.0, 200.0]).build();
+    let fonts = ui.fonts.insert_from_dir(dir).unwrap();
+    let ids: Vec<_> = fonts.into_iter().map(|(_, id)| id.unwrap()).collect();
+    assert_eq!(ids.len(), 4);
+    let (bold, bold_italic, italic, regular) = (ids[0], ids[1], ids[2], ids[3]);
+    assert_eq!(ui.fonts.family(regular), Some("Noto Sans"));
+    assert_eq!(
+        ui.fonts.properties(bold_italic).unwrap().style,
+        Style::Italic
+    );
+
+    // The nearest weight is preferred in the direction of the requested weight.
+    let fonts = &ui.fonts;
+    let find = |weight, style| {
+        let properties = Properties {
+            weight,
+            style,
+            ..Properties::default()
+        };
+        fonts.find("noto sans", properties)
+    };
+    assert_eq!(find(Weight::NORMAL, Style::Normal), Some(regular));
+    assert_eq!(find(Weight::SEMI_BOLD, Style::Normal), Some(bold));
+    assert_eq!(find(Weight::LIGHT, Style::Normal), Some(regular));
+    assert_eq!(find(Weight::BLACK, Style::Oblique), Some(bold_italic));
+    assert_eq!(find(Weight::MEDIUM, Style::Italic), Some(italic));
+    assert_eq!(fonts.find("Noto Serif", Properties::default()), None);
+
+    // A query without a family searches that of the base font.
+    assert_eq!(
+        fonts.query(Query::default().bold(), Some(italic)),
+        Some(bold)
+    );
+    assert_eq!(fonts.query(Query::default().italic(), None), None);
+
+    // Text widgets resolve their query against the default font of the theme, while labels may
+    // query a family named at runtime.
+    ui.theme.font_query = Some(Query::new("Noto Sans"));
+    let family = String::from("noto sans");
+    let [a, b, c] = [
+        ui.widget_id_generator().next(),
+        ui.widget_id_generator().next(),
+        ui.widget_id_generator().next(),
+    ];
+    {
+        let ui = &mut ui.set_widgets();
+        widget::Text::new("Regular")
+            .top_left_of(ui.window)
+            .set(a, ui);
+        widget::Text::new("Bold italic")
+            .bold()
+            .italic()
+            .down_from(a, 10.0)
+            .set(b, ui);
+        widget::Button::new()
+            .label("Bold")
+            .label_font_query(Query::new(&family).bold())
+            .down_from(b, 10.0)
+            .set(c, ui);
+    }
+    let mut primitives = ui.draw();
+    let mut font_ids = Vec::new();
+    while let Some(primitive) = primitives.next() {
+        if let PrimitiveKind::Text { font_id, .. } = primitive.kind {
+            font_ids.push(font_id);
+        }
+    }
+    assert_eq!(font_ids, vec![regular, bold_italic, bold]);
+}
+
+#[test]
+fn fonts_that_fail_to_load_from_a_directory_do_not_prevent_the_rest() {
+    let dir = std::env::temp_dir().join(format!("conrod_fonts_{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(dir.join("a_broken.ttf"), b"not a font").unwrap();
+    std::fs::copy(font_path(), dir.join("b_noto_sans.ttf")).unwrap();
+
+    let mut fonts = text::font::Map::new();
+    let loaded = fonts.insert_from_dir(&dir);
+    std::fs::remove_dir_all(&dir).unwrap();
+    let loaded = loaded.unwrap();
+    assert_eq!(loaded.len(), 2);
+    assert!(loaded[0].1.is_err());
+    let id = *loaded[1].1.as_ref().unwrap();
+    assert_eq!(fonts.family(id), Some("Noto Sans"));
+    assert_eq!(fonts.ids().count(), 1);
+}
+
+#[test]
+fn text_layout_is_cached_until_unused_for_too_many_frames() {
+    let mut ui = UiBuilder::new([400.0, 200.0]).build();
+    let font_id = ui.fonts.insert(noto_sans());
+    ui.text_cache_mut().set_max_unused_frames(2);
+    let id = ui.widget_id_generator().next();
+
+    // Sizing and updating the widget share a single layout, which is reused on later frames.
+    for _ in 0..3 {
+        let ui = &mut ui.set_widgets();
+        widget::Text::new(TEXT)
+            .w(150.0)
+            .top_left_of(ui.window)
+            .set(id, ui);
+        assert_eq!(ui.text_cache().len(), 1);
+    }
+    let wrap = Some((text::line::Wrap::Whitespace, 150.0));
+    let key = text::cache::Key::new(TEXT, &[font_id], 18, wrap, text::Justify::Left);
+    let cached = ui
+        .text_cache()
+        .line_infos(key, TEXT, || -> Vec<text::line::Info> { unreachable!() });
+    let widget = ui.widget_graph().widget(id).unwrap();
+    let state = widget.unique_widget_state::<widget::Text>().unwrap();
+    assert_eq!(state.state.line_infos[..], cached[..]);
+
+    // Text whose key collides with that of a cached layout is laid out anew.
+    let other = ui.text_cache().line_infos(key, "Other", Vec::new);
+    assert!(other.is_empty());
+
+    // The layout is evicted once it has gone unused for more than two frames.
+    for frames in 1..4 {
+        ui.set_widgets();
+        assert_eq!(ui.text_cache().is_empty(), frames > 2);
+    }
+}
+
+#[test]
+fn text_edit_undoes_and_redoes_typing_by_word() {
+    let mut ui = UiBuilder::new([400.0, 200.0]).build();
+    ui.fonts.insert(noto_sans());
+    let id = ui.widget_id_generator().next();
+    let mut text = String::new();
+    let set = |ui: &mut ::Ui, text: &mut String| {
+        let ui = &mut ui.set_widgets();
+        if let Some(new_text) = widget::TextEdit::new(text)
+            .w_h(300.0, 100.0)
+            .top_left_of(ui.window)
+            .set(id, ui)
+        {
+            *text = new_text;
+        }
+    };
+    let press = |ui: &mut ::Ui, keys: &[Key]| {
+        for &key in keys {
+            ui.handle_event(Input::Press(Button::Keyboard(key)));
+        }
+        for &key in keys.iter().rev() {
+            ui.handle_event(Input::Release(Button::Keyboard(key)));
+        }
+    };
+    set(&mut ui, &mut text);
+    ui.keyboard_capture(id);
+
+    for c in "hello world".chars() {
+        ui.handle_event(Input::Text(c.to_string()));
+        set(&mut ui, &mut text);
+    }
+    press(&mut ui, &[Key::Backspace]);
+    set(&mut ui, &mut text);
+    assert_eq!(text, "hello worl");
+
+    // The deletion is undone on its own, then each typed word.
+    let undone = ["hello world", "hello ", ""];
+    for expected in &undone {
+        press(&mut ui, &[Key::LCtrl, Key::Z]);
+        set(&mut ui, &mut text);
+        assert_eq!(text, *expected);
+    }
+    press(&mut ui, &[Key::LCtrl, Key::Z]);
+    set(&mut ui, &mut text);
+    assert_eq!(text, "");
+
+    press(&mut ui, &[Key::LCtrl, Key::LShift, Key::Z]);
+    set(&mut ui, &mut text);
+    assert_eq!(text, "hello ");
+    press(&mut ui, &[Key::LCtrl, Key::Y]);
+    set(&mut ui, &mut text);
+    assert_eq!(text, "hello world");
+
+    // Typing after an undo discards the edits that could have been redone.
+    ui.handle_event(Input::Text("!".to_string()));
+    set(&mut ui, &mut text);
+    assert_eq!(text, "hello world!");
+    let state = ui.widget_graph().widget(id).unwrap();
+    let state = &state
+        .unique_widget_state::<widget::TextEdit>()
+        .unwrap()
+        .state;
+    let history = state.history();
+    assert_eq!((history.undo_len(), history.redo_len()), (3, 0));
+
+    // Apps may clear the history, e.g. after loading a new text.
+    {
+        let ui = &mut ui.set_widgets();
+        widget::TextEdit::new(&text)
+            .w_h(300.0, 100.0)
+            .top_left_of(ui.window)
+            .clear_history()
+            .set(id, ui);
+    }
+    press(&mut ui, &[Key::LCtrl, Key::Z]);
+    set(&mut ui, &mut text);
+    assert_eq!(text, "hello world!");
+}
+
+#[test]
+fn text_edit_displays_ime_preedit_until_committed() {
+    use event::Ime;
+    use graph::Walker;
+
+    let mut ui = UiBuilder::new([400.0, 200.0]).build();
+    ui.fonts.insert(noto_sans());
+    let id = ui.widget_id_generator().next();
+    let mut text = "ab".to_string();
+    let set = |ui: &mut ::Ui, text: &mut String| {
+        let ui = &mut ui.set_widgets();
+        if let Some(new_text) = widget::TextEdit::new(text)
+            .w_h(300.0, 100.0)
+            .top_left_of(ui.window)
+            .set(id, ui)
+        {
+            *text = new_text;
+        }
+    };
+    let displayed = |ui: &::Ui| {
+        let graph = ui.widget_graph();
+        graph
+            .depth_children(id)
+            .iter(graph)
+            .nodes()
+            .filter_map(|kid| graph.widget(kid)?.unique_widget_state::<widget::Text>())
+            .map(|text| text.state.string.clone())
+            .next()
+            .unwrap()
+    };
+    set(&mut ui, &mut text);
+    assert_eq!(ui.caret_rect(), None);
+    ui.keyboard_capture(id);
+    set(&mut ui, &mut text);
+    let caret = ui.caret_rect().unwrap();
+
+    // The composition is displayed at the cursor without changing the text.
+    let string = "にほ".to_string();
+    let cursor = Some((string.len(), string.len()));
+    ui.handle_event(Input::Ime(Ime::Preedit { string, cursor }));
+    set(&mut ui, &mut text);
+    assert_eq!(text, "ab");
+    assert_eq!(displayed(&ui), "にほab");
+    let composing_caret = ui.caret_rect().unwrap();
+    assert!(composing_caret.x() > caret.x());
+
+    // Committing inserts the composed text, which may be undone as a whole.
+    ui.handle_event(Input::Ime(Ime::Commit("日本".to_string())));
+    set(&mut ui, &mut text);
+    assert_eq!(text, "日本ab");
+    assert_eq!(displayed(&ui), "日本ab");
+    ui.handle_event(Input::Press(Button::Keyboard(Key::LCtrl)));
+    ui.handle_event(Input::Press(Button::Keyboard(Key::Z)));
+    set(&mut ui, &mut text);
+    assert_eq!(text, "ab");
+}
@@ -0,0 +1,88 @@
+use color;
+use event::Input;
+use input::Motion;
+use position::Transform;
+use std::f64::consts::PI;
+use widget;
+use {Positionable, Sizeable, Ui, UiBuilder, Widget};
+
+fn assert_close(a: [f64; 2], b: [f64; 2]) {
+    assert!(
+        (a[0] - b[0]).abs() < 1e-9 && (a[1] - b[1]).abs() < 1e-9,
+        "{:?} != {:?}",
+        a,
+        b
+    );
+}
+
+// A canvas rotated by a quarter turn, containing a wide rectangle scaled along its x axis.
+fn ui_with_transformed_widgets() -> (Ui, widget::Id, widget::Id) {
+    let mut ui = UiBuilder::new([200.0, 200.0]).build();
+    let mut ids = ui.widget_id_generator();
+    let (canvas_id, rect_id) = (ids.next(), ids.next());
+    {
+        let ui = &mut ui.set_widgets();
+        widget::Canvas::new()
+            .w_h(100.0, 100.0)
+            .x_y(50.0, 0.0)
+            .transform(Transform::rotation(PI / 2.0))
+            .set(canvas_id, ui);
+        widget::Rectangle::fill_with([20.0, 10.0], color::WHITE)
+            .x_y(60.0, 0.0)
+            .parent(canvas_id)
+            .transform(Transform::scale(2.0, 1.0))
+            .set(rect_id, ui);
+    }
+    (ui, canvas_id, rect_id)
+}
+
+#[test]
+fn transform_inverse_reverses_composition() {
+    let t = Transform::rotation(0.3)
+        .then(Transform::scale(2.0, 0.5))
+        .then(Transform::translation(4.0, -1.0));
+    let inverse = t.inverse().unwrap();
+    assert_close(
+        inverse.transform_point(t.transform_point([3.0, 7.0])),
+        [3.0, 7.0],
+    );
+    assert_close(t.then(inverse).transform_point([-2.0, 5.0]), [-2.0, 5.0]);
+    assert!(Transform::scale(0.0, 1.0).inverse().is_none());
+}
+
+#[test]
+fn transforms_compose_through_parents() {
+    let (ui, canvas_id, rect_id) = ui_with_transformed_widgets();
+
+    // The rectangle is scaled about its own centre and then rotated about the canvas centre.
+    let transform = ui.transform_of(rect_id).unwrap();
+    assert_close(transform.transform_point([60.0, 0.0]), [50.0, 10.0]);
+    assert_close(transform.transform_point([70.0, 0.0]), [50.0, 30.0]);
+    assert_close(
+        ui.transform_of(canvas_id)
+            .unwrap()
+            .transform_point([50.0, 0.0]),
+        [50.0, 0.0],
+    );
+
+    let mut primitives = ui.draw();
+    while let Some(primitive) = primitives.next() {
+        if primitive.id == rect_id {
+            assert_eq!(primitive.transform, transform);
+        }
+    }
+}
+
+#[test]
+fn picking_accounts_for_transforms() {
+    let (mut ui, _canvas_id, rect_id) = ui_with_transformed_widgets();
+    let mut widget_under_mouse = |[x, y]: [f64; 2]| {
+        ui.handle_event(Input::Motion(Motion::MouseCursor { x: x, y: y }));
+        ui.global_input().current.widget_under_mouse
+    };
+
+    // Within the transformed rectangle, but outside of its layout `Rect`.
+    assert_eq!(widget_under_mouse([50.0, 28.0]), Some(rect_id));
+    // Within the layout `Rect`, which the transformed rectangle no longer covers.
+    assert_ne!(widget_under_mouse([60.0, 0.0]), Some(rect_id));
+}
@@ -1,7 +1,7 @@
 //! Text layout logic.
 
 use std;
-use {FontSize, Scalar};
+use {Color, FontSize, Scalar};
 
 // Re-export all relevant rusttype types here.
 pub use rusttype::gpu_cache::Cache as GlyphCache;
@@ -26,7 +26,8 @@ pub struct Lines<'a, I> {
 }
 
 /// A type used for referring to typographic alignment of `Text`.
-#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Copy, Clone, Debug, Hash, PartialEq, Eq)]
 pub enum Justify {
     /// Align text to the start of the bounding `Rect`'s *x* axis.
     Left,
@@ -34,10 +35,58 @@ pub enum Justify {
     Center,
     /// Align text to the end of the bounding `Rect`'s *x* axis.
     Right,
-    // /// Align wrapped text to both the start and end of the bounding `Rect`s *x* axis.
-    // ///
-    // /// Extra space is added between words in order to achieve this alignment.
-    // TODO: Full,
+    /// Align wrapped text to both the start and end of the bounding `Rect`s *x* axis.
+    ///
+    /// Extra space is added between words in order to achieve this alignment. The last line of
+    /// each paragraph, i.e. any line that is not broken by wrapping, is aligned to the start.
+    Full,
+}
+
+/// The position at which a line of text that is too wide to fit is truncated, where the removed
+/// text is replaced by an ellipsis.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Copy, Clone, Debug, Hash, PartialEq, Eq)]
+pub enum Ellipsis {
+    /// Remove text from the start of the line, e.g. "…brown fox".
+    Start,
+    /// Remove text from the middle of the line, e.g. "The qu…n fox".
+    Middle,
+    /// Remove text from the end of the line, e.g. "The quick…".
+    End,
+}
+
+/// The lines and background with which each line of text is decorated.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct Decorations {
+    /// Whether or not a line is drawn beneath the text.
+    pub underline: bool,
+    /// Whether or not a line is drawn through the middle of the text.
+    pub strikethrough: bool,
+    /// Whether or not a line is drawn above the text.
+    pub overline: bool,
+    /// The color of the background highlight drawn behind the text, if any.
+    pub highlight: Option<Color>,
+}
+
+/// The placement of the lines with which text may be decorated at some font size.
+///
+/// Each offset is the distance from the baseline up to the centre of the line.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct DecorationMetrics {
+    /// The offset of the underline, typically below the baseline.
+    pub underline_offset: Scalar,
+    /// The thickness of the underline.
+    pub underline_thickness: Scalar,
+    /// The offset of the strikethrough.
+    pub strikethrough_offset: Scalar,
+    /// The thickness of the strikethrough.
+    pub strikethrough_thickness: Scalar,
+    /// The offset of the overline, at the font's ascent.
+    pub overline_offset: Scalar,
+    /// The thickness of the overline, matching that of the underline.
+    pub overline_thickness: Scalar,
 }
 
 /// Determine the total height of a block of text with the given number of lines, font size and
@@ -84,6 +133,62 @@ pub fn pt_to_scale(font_size_in_points: FontSize) -> Scale {
     Scale::uniform(pt_to_px(font_size_in_points))
 }
 
+impl Decorations {
+    /// Whether or not any line is drawn, ignoring the highlight.
+    pub fn has_lines(&self) -> bool {
+        self.underline || self.strikethrough || self.overline
+    }
+}
+
+impl DecorationMetrics {
+    /// The decoration metrics of the given `font` at the given `font_size`.
+    ///
+    /// The underline and strikethrough are read from the `post` and `OS/2` tables of the font's
+    /// raw `data` where available (see `font::Map::data`), otherwise they are estimated from its
+    /// descent and ascent. Every line is at least one unit thick.
+    pub fn new(font: &Font, data: Option<&[u8]>, font_size: FontSize) -> Self {
+        let scale = pt_to_scale(font_size);
+        let v_metrics = font.v_metrics(scale);
+        let (ascent, descent) = (v_metrics.ascent as Scalar, v_metrics.descent as Scalar);
+        let unscaled = font.v_metrics_unscaled();
+        let units_height = (unscaled.ascent - unscaled.descent) as Scalar;
+        let units_to_scalar = |units: i16| {
+            if units_height > 0.0 {
+                units as Scalar * scale.y as Scalar / units_height
+            } else {
+                0.0
+            }
+        };
+
+        // A line's `position` within the font's tables is the top of the line.
+        let face = data.and_then(|data| ::ttf_parser::Face::parse(data, 0).ok());
+        let line = |metrics: Option<::ttf_parser::LineMetrics>| {
+            metrics.map(|m| {
+                let thickness = units_to_scalar(m.thickness).max(1.0);
+                (units_to_scalar(m.position) - thickness / 2.0, thickness)
+            })
+        };
+        let default_thickness = (font_size as Scalar / 14.0).max(1.0);
+        let (underline_offset, underline_thickness) = face
+            .as_ref()
+            .and_then(|face| line(face.underline_metrics()))
+            .unwrap_or((descent / 2.0, default_thickness));
+        let (strikethrough_offset, strikethrough_thickness) = face
+            .as_ref()
+            .and_then(|face| line(face.strikeout_metrics()))
+            .unwrap_or((ascent * 0.3, default_thickness));
+
+        DecorationMetrics {
+            underline_offset,
+            underline_thickness,
+            strikethrough_offset,
+            strikethrough_thickness,
+            overline_offset: ascent - underline_thickness / 2.0,
+            overline_thickness: underline_thickness,
+        }
+    }
+}
+
 impl<'a, I> Iterator for Lines<'a, I>
 where
     I: Iterator<Item = std::ops::Range<usize>>,
@@ -109,16 +214,92 @@ pub mod font {
     ///
     /// - The key for the `font::Map`'s inner `HashMap`.
     /// - The `font_id` field for the rusttype::gpu_cache::Cache.
+    #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
     #[derive(Copy, Clone, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
     pub struct Id(usize);
 
     /// A collection of mappings from `font::Id`s to `rusttype::Font`s.
+    ///
+    /// Each font may be given a chain of fallback fonts via `set_fallbacks`, which are tried in
+    /// order for any `char` that the font itself has no glyph for.
+    ///
+    /// Fonts may also be registered under a family name along with their `Properties`, allowing
+    /// the font of a family that most closely matches some weight, style and stretch to be found
+    /// via `find` or `query`. Fonts loaded from files or bytes are registered automatically using
+    /// their name and `OS/2` tables.
+    ///
+    /// The raw data of fonts loaded from files or bytes is retained for use by the
+    /// `text::shaping` module and for reading the font's tables.
     #[derive(Debug)]
     pub struct Map {
         next_index: usize,
         map: fnv::FnvHashMap<Id, super::Font>,
+        fallbacks: fnv::FnvHashMap<Id, Vec<Id>>,
+        data: fnv::FnvHashMap<Id, std::sync::Arc<[u8]>>,
+        // The family name and properties of each registered font.
+        faces: fnv::FnvHashMap<Id, (String, Properties)>,
+        // The registered fonts of each family, keyed by the lowercase family name.
+        families: fnv::FnvHashMap<String, Vec<Id>>,
+    }
+
+    /// The weight, or boldness, of a font as a number between 1 and 1000.
+    #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+    #[derive(Copy, Clone, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
+    pub struct Weight(pub u16);
+
+    /// The slant of a font.
+    #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+    #[derive(Copy, Clone, Debug, Default, Hash, PartialEq, Eq)]
+    pub enum Style {
+        /// Upright glyphs.
+        #[default]
+        Normal,
+        /// Cursive glyphs designed to be slanted.
+        Italic,
+        /// Upright glyphs that have been artificially slanted.
+        Oblique,
+    }
+
+    /// The width of a font as its `OS/2` width class, between 1 (ultra-condensed) and 9
+    /// (ultra-expanded).
+    #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+    #[derive(Copy, Clone, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
+    pub struct Stretch(pub u16);
+
+    /// The properties that distinguish the fonts of a single family.
+    #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+    #[derive(Copy, Clone, Debug, Default, Hash, PartialEq, Eq)]
+    pub struct Properties {
+        /// The weight of the font.
+        pub weight: Weight,
+        /// The slant of the font.
+        pub style: Style,
+        /// The width of the font.
+        pub stretch: Stretch,
+    }
+
+    /// A request for the font of a family that most closely matches a weight and slant.
+    ///
+    /// Used to select the font of a `Theme` or text widget without tracking raw `Id`s.
+    #[derive(Copy, Clone, Debug, Default, Hash, PartialEq, Eq)]
+    pub struct Query {
+        /// The name of the font family.
+        ///
+        /// If `None`, the family of the font that would otherwise be used is searched instead.
+        pub family: Option<Family>,
+        /// The weight of the font.
+        pub weight: Weight,
+        /// Whether or not the font should be italic.
+        pub italic: bool,
     }
 
+    /// The interned name of a font family, as requested by a `Query`.
+    ///
+    /// Each distinct name is allocated once and retained for the remainder of the program, so
+    /// that a `Query` may be copied freely along with the style of the widget that holds it.
+    #[derive(Copy, Clone, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
+    pub struct Family(&'static str);
+
     /// An iterator yielding an `Id` for each new `rusttype::Font` inserted into the `Map` via the
     /// `insert_collection` method.
     pub struct NewIds {
@@ -142,6 +323,12 @@ pub mod font {
         Rusttype(rusttype::Error),
     }
 
+    /// The path of each font file within a directory along with the `Id` under which it was
+    /// inserted, or the error that prevented it from loading.
+    ///
+    /// Produced by `Map::insert_from_dir`.
+    pub type DirFonts = Vec<(std::path::PathBuf, Result<Id, Error>)>;
+
     impl Id {
         /// Returns the inner `usize` from the `Id`.
         pub fn index(self) -> usize {
@@ -149,12 +336,161 @@ pub mod font {
         }
     }
 
+    impl Weight {
+        /// Thin, 100.
+        pub const THIN: Self = Weight(100);
+        /// Extra light, 200.
+        pub const EXTRA_LIGHT: Self = Weight(200);
+        /// Light, 300.
+        pub const LIGHT: Self = Weight(300);
+        /// Normal, 400.
+        pub const NORMAL: Self = Weight(400);
+        /// Medium, 500.
+        pub const MEDIUM: Self = Weight(500);
+        /// Semi bold, 600.
+        pub const SEMI_BOLD: Self = Weight(600);
+        /// Bold, 700.
+        pub const BOLD: Self = Weight(700);
+        /// Extra bold, 800.
+        pub const EXTRA_BOLD: Self = Weight(800);
+        /// Black, 900.
+        pub const BLACK: Self = Weight(900);
+    }
+
+    impl Stretch {
+        /// Ultra condensed, 1.
+        pub const ULTRA_CONDENSED: Self = Stretch(1);
+        /// Condensed, 3.
+        pub const CONDENSED: Self = Stretch(3);
+        /// Normal, 5.
+        pub const NORMAL: Self = Stretch(5);
+        /// Expanded, 7.
+        pub const EXPANDED: Self = Stretch(7);
+        /// Ultra expanded, 9.
+        pub const ULTRA_EXPANDED: Self = Stretch(9);
+    }
+
+    impl Default for Weight {
+        fn default() -> Self {
+            Weight::NORMAL
+        }
+    }
+
+    impl Default for Stretch {
+        fn default() -> Self {
+            Stretch::NORMAL
+        }
+    }
+
+    impl Properties {
+        /// Read the properties of the font from the `OS/2` table of its raw `data`, along with its
+        /// family name from its `name` table.
+        ///
+        /// The typographic family name is preferred, as it groups faces such as "Light" and
+        /// "Black" that older software expects to find in separate families.
+        pub fn from_data(data: &[u8]) -> Option<(String, Self)> {
+            let face = ::ttf_parser::Face::parse(data, 0).ok()?;
+            let family_name = |name_id| {
+                face.names()
+                    .into_iter()
+                    .filter(|name| name.name_id == name_id)
+                    .filter_map(|name| name.to_string())
+                    .next()
+            };
+            let family = family_name(::ttf_parser::name_id::TYPOGRAPHIC_FAMILY)
+                .or_else(|| family_name(::ttf_parser::name_id::FAMILY))?;
+            let style = match face.style() {
+                ::ttf_parser::Style::Normal => Style::Normal,
+                ::ttf_parser::Style::Italic => Style::Italic,
+                ::ttf_parser::Style::Oblique => Style::Oblique,
+            };
+            let properties = Properties {
+                weight: Weight(face.weight().to_number()),
+                style,
+                stretch: Stretch(face.width().to_number()),
+            };
+            Some((family, properties))
+        }
+    }
+
+    impl Family {
+        /// Intern the given family name.
+        pub fn new(name: &str) -> Self {
+            static NAMES: std::sync::Mutex<std::collections::BTreeSet<&'static str>> =
+                std::sync::Mutex::new(std::collections::BTreeSet::new());
+            let mut names = NAMES.lock().unwrap_or_else(|err| err.into_inner());
+            if let Some(&interned) = names.get(name) {
+                return Family(interned);
+            }
+            let interned: &'static str = Box::leak(name.into());
+            names.insert(interned);
+            Family(interned)
+        }
+
+        /// The name of the family.
+        pub fn as_str(&self) -> &'static str {
+            self.0
+        }
+    }
+
+    impl<'a> From<&'a str> for Family {
+        fn from(name: &'a str) -> Self {
+            Family::new(name)
+        }
+    }
+
+    impl Query {
+        /// A query for the normal font of the given family.
+        pub fn new(family: &str) -> Self {
+            Query {
+                family: Some(Family::new(family)),
+                ..Query::default()
+            }
+        }
+
+        /// Request the given weight.
+        pub fn weight(self, weight: Weight) -> Self {
+            Query { weight, ..self }
+        }
+
+        /// Request a bold font.
+        pub fn bold(self) -> Self {
+            self.weight(Weight::BOLD)
+        }
+
+        /// Request an italic font.
+        pub fn italic(self) -> Self {
+            Query {
+                italic: true,
+                ..self
+            }
+        }
+
+        /// The properties requested by the query, with a normal stretch.
+        pub fn properties(&self) -> Properties {
+            let style = if self.italic {
+                Style::Italic
+            } else {
+                Style::Normal
+            };
+            Properties {
+                weight: self.weight,
+                style,
+                stretch: Stretch::NORMAL,
+            }
+        }
+    }
+
     impl Map {
         /// Construct the new, empty `Map`.
         pub fn new() -> Self {
             Map {
                 next_index: 0,
                 map: fnv::FnvHashMap::default(),
+                fallbacks: fnv::FnvHashMap::default(),
+                data: fnv::FnvHashMap::default(),
+                faces: fnv::FnvHashMap::default(),
+                families: fnv::FnvHashMap::default(),
             }
         }
 
@@ -177,8 +513,136 @@ pub mod font {
         where
             P: AsRef<std::path::Path>,
         {
-            let font = from_file(path)?;
-            Ok(self.insert(font))
+            let bytes = std::fs::read(path)?;
+            self.insert_from_bytes(bytes)
+        }
+
+        /// Insert a single `Font` into the map by loading it from the given bytes.
+        ///
+        /// The font is registered under the family name and with the `Properties` read from its
+        /// tables, if any.
+        pub fn insert_from_bytes(&mut self, bytes: Vec<u8>) -> Result<Id, Error> {
+            let data: std::sync::Arc<[u8]> = bytes.into();
+            let font = super::Font::from_bytes(data.clone())?;
+            let id = self.insert(font);
+            if let Some((family, properties)) = Properties::from_data(&data) {
+                self.register(id, &family, properties);
+            }
+            self.data.insert(id, data);
+            Ok(id)
+        }
+
+        /// Insert every TrueType and OpenType font (i.e. each `.ttf` and `.otf` file) within the
+        /// given directory, in order of their file names.
+        ///
+        /// Each font is registered under its family as with `insert_from_bytes`. Produces the path
+        /// of each font file along with the `Id` under which it was inserted, or the error that
+        /// prevented it from loading. A file that fails to load does not prevent the rest from
+        /// being inserted.
+        ///
+        /// Returns an error without inserting any fonts if the directory cannot be read.
+        pub fn insert_from_dir<P>(&mut self, path: P) -> Result<DirFonts, Error>
+        where
+            P: AsRef<std::path::Path>,
+        {
+            let mut paths = vec![];
+            for entry in std::fs::read_dir(path)? {
+                let path = entry?.path();
+                let is_font = path
+                    .extension()
+                    .and_then(|ext| ext.to_str())
+                    .map(|ext| ext.eq_ignore_ascii_case("ttf") || ext.eq_ignore_ascii_case("otf"))
+                    .unwrap_or(false);
+                if is_font && path.is_file() {
+                    paths.push(path);
+                }
+            }
+            paths.sort();
+            let fonts = paths
+                .into_iter()
+                .map(|path| {
+                    let id = self.insert_from_file(&path);
+                    (path, id)
+                })
+                .collect();
+            Ok(fonts)
+        }
+
+        /// Register the font with the given `id` as a member of the given family with the given
+        /// `properties`, replacing any previous registration of the font.
+        ///
+        /// Family names are matched case-insensitively.
+        pub fn register(&mut self, id: Id, family: &str, properties: Properties) {
+            self.unregister(id);
+            let key = family.to_lowercase();
+            self.families.entry(key).or_default().push(id);
+            self.faces.insert(id, (family.to_string(), properties));
+        }
+
+        // Remove the font with the given `id` from its family, if it has one.
+        fn unregister(&mut self, id: Id) {
+            let key = match self.faces.remove(&id) {
+                Some((family, _)) => family.to_lowercase(),
+                None => return,
+            };
+            let is_empty = match self.families.get_mut(&key) {
+                Some(ids) => {
+                    ids.retain(|&other| other != id);
+                    ids.is_empty()
+                }
+                None => false,
+            };
+            if is_empty {
+                self.families.remove(&key);
+            }
+        }
+
+        /// The name of the family under which the font with the given `id` is registered.
+        pub fn family(&self, id: Id) -> Option<&str> {
+            self.faces.get(&id).map(|(family, _)| &family[..])
+        }
+
+        /// The properties with which the font with the given `id` is registered.
+        pub fn properties(&self, id: Id) -> Option<Properties> {
+            self.faces.get(&id).map(|&(_, properties)| properties)
+        }
+
+        /// The font of the given family that most closely matches the given `properties`.
+        ///
+        /// Fonts are matched similarly to the CSS font matching algorithm: the closest stretch is
+        /// preferred first, then the closest style, then the closest weight. When the requested
+        /// weight is not available, weights between 400 and 500 prefer lighter fonts before
+        /// heavier ones, while greater weights prefer heavier fonts and lesser weights prefer
+        /// lighter fonts.
+        pub fn find(&self, family: &str, properties: Properties) -> Option<Id> {
+            let ids = self.families.get(&family.to_lowercase())?;
+            ids.iter().cloned().min_by_key(|&id| {
+                let candidate = self.faces[&id].1;
+                (
+                    stretch_distance(candidate.stretch, properties.stretch),
+                    style_distance(candidate.style, properties.style),
+                    weight_distance(candidate.weight, properties.weight),
+                    id,
+                )
+            })
+        }
+
+        /// The font that most closely matches the given `query`.
+        ///
+        /// If the query has no family, the family of the given `base` font is searched. See
+        /// `find`.
+        pub fn query(&self, query: Query, base: Option<Id>) -> Option<Id> {
+            let family = match query.family {
+                Some(family) => family.as_str(),
+                None => self.family(base?)?,
+            };
+            self.find(family, query.properties())
+        }
+
+        /// The raw data of the font with the given `Id`, if it was loaded via `insert_from_file`
+        /// or `insert_from_bytes`.
+        pub fn data(&self, id: Id) -> Option<&std::sync::Arc<[u8]>> {
+            self.data.get(&id)
         }
 
         /// Produces an iterator yielding the `Id` for each `Font` within the `Map`.
@@ -187,6 +651,150 @@ pub mod font {
                 keys: self.map.keys(),
             }
         }
+
+        /// Specify the fonts that are tried in order for any `char` missing from the font with the
+        /// given `id`.
+        ///
+        /// These take precedence over any default fallbacks given to `chain`, such as those of the
+        /// `Theme`.
+        pub fn set_fallbacks<I>(&mut self, id: Id, fallbacks: I)
+        where
+            I: IntoIterator<Item = Id>,
+        {
+            self.fallbacks.insert(id, fallbacks.into_iter().collect());
+        }
+
+        /// The fallbacks specified for the font with the given `id` via `set_fallbacks`, if any.
+        pub fn fallbacks(&self, id: Id) -> Option<&[Id]> {
+            self.fallbacks.get(&id).map(|ids| &ids[..])
+        }
+
+        /// The fonts tried in order for each `char` of text displayed with the font of the given
+        /// `id`.
+        ///
+        /// This is the font itself followed by its fallbacks, or by the given `defaults` if it has
+        /// none. `Id`s that are repeated or that are not within the `Map` are skipped.
+        pub fn chain(&self, id: Id, defaults: &[Id]) -> Vec<Id> {
+            let fallbacks = self.fallbacks(id).unwrap_or(defaults);
+            let mut chain: Vec<Id> = Vec::with_capacity(fallbacks.len() + 1);
+            for &id in std::iter::once(&id).chain(fallbacks) {
+                if self.map.contains_key(&id) && !chain.contains(&id) {
+                    chain.push(id);
+                }
+            }
+            chain
+        }
+
+        /// Split the given `text` into runs that are each displayed with a single font from the
+        /// `chain` of the font with the given `id`.
+        ///
+        /// Produces the byte range of each run along with the `Id` of its font. See
+        /// `font::fallback_runs`.
+        pub fn fallback_runs(
+            &self,
+            text: &str,
+            id: Id,
+            defaults: &[Id],
+        ) -> Vec<(std::ops::Range<usize>, Id)> {
+            let chain = self.chain(id, defaults);
+            let fonts = chain.iter().map(|id| &self.map[id]);
+            fallback_runs(text, fonts)
+                .into_iter()
+                .map(|(range, ix)| (range, chain[ix]))
+                .collect()
+        }
+    }
+
+    // Order stretches by their distance from the requested stretch, preferring narrower fonts for
+    // normal or narrower requests and wider fonts otherwise.
+    fn stretch_distance(candidate: Stretch, requested: Stretch) -> (u16, bool) {
+        let (c, r) = (candidate.0, requested.0);
+        let distance = c.abs_diff(r);
+        let preferred = if r <= Stretch::NORMAL.0 {
+            c <= r
+        } else {
+            c >= r
+        };
+        (distance, !preferred)
+    }
+
+    // Order styles by preference for the requested style.
+    fn style_distance(candidate: Style, requested: Style) -> u8 {
+        let order = match requested {
+            Style::Normal => [Style::Normal, Style::Oblique, Style::Italic],
+            Style::Italic => [Style::Italic, Style::Oblique, Style::Normal],
+            Style::Oblique => [Style::Oblique, Style::Italic, Style::Normal],
+        };
+        order
+            .iter()
+            .position(|&style| style == candidate)
+            .unwrap_or(0) as u8
+    }
+
+    // Order weights by group and then by their distance from the requested weight.
+    fn weight_distance(candidate: Weight, requested: Weight) -> (u8, u16) {
+        let (c, r) = (candidate.0, requested.0);
+        let distance = c.abs_diff(r);
+        let group = if c == r {
+            0
+        } else if (400..=500).contains(&r) {
+            // Up to 500 first, then lighter, then heavier.
+            if c > r && c <= 500 {
+                1
+            } else if c < r {
+                2
+            } else {
+                3
+            }
+        } else if r > 500 {
+            if c > r {
+                1
+            } else {
+                2
+            }
+        } else if c < r {
+            1
+        } else {
+            2
+        };
+        (group, distance)
+    }
+
+    /// Whether or not the given `font` has a glyph for the given `char`.
+    pub fn has_glyph(font: &super::Font, ch: char) -> bool {
+        font.glyph(ch).id() != super::GlyphId(0)
+    }
+
+    /// Split the given `text` into runs of consecutive `char`s that are displayed with the same
+    /// font, where each `char` is displayed with the first of the given `fonts` that has a glyph
+    /// for it.
+    ///
+    /// Produces the byte range of each run along with the index of its font within `fonts`.
+    /// Whitespace and control characters continue the preceding run, while `char`s missing from
+    /// every font are displayed with the first.
+    pub fn fallback_runs<'a, I>(text: &str, fonts: I) -> Vec<(std::ops::Range<usize>, usize)>
+    where
+        I: IntoIterator<Item = &'a super::Font>,
+        I::IntoIter: Clone,
+    {
+        let fonts = fonts.into_iter();
+        let mut runs: Vec<(std::ops::Range<usize>, usize)> = Vec::new();
+        for (i, ch) in text.char_indices() {
+            let end = i + ch.len_utf8();
+            let ix = if ch.is_whitespace() || ch.is_control() {
+                runs.last().map(|&(_, ix)| ix).unwrap_or(0)
+            } else {
+                fonts
+                    .clone()
+                    .position(|font| has_glyph(font, ch))
+                    .unwrap_or(0)
+            };
+            match runs.last_mut() {
+                Some(&mut (ref mut range, last)) if last == ix => range.end = end,
+                _ => runs.push((i..end, ix)),
+            }
+        }
+        runs
     }
 
     /// Load a single `Font` from a file at the given path.
@@ -265,16 +873,18 @@ pub mod glyph {
         y: Range,
         /// The position of the next `Rect`'s left edge along the *x* axis.
         next_left: Scalar,
-        /// `PositionedGlyphs` yielded by the RustType `LayoutIter`.
-        layout: super::LayoutIter<'font, 'b>,
+        /// `PositionedGlyphs` yielded by the line's `Layout`.
+        layout: super::line::Layout<'font, 'b>,
     }
 
-    /// An iterator that, for every `(line, line_rect)` pair yielded by the given iterator,
+    /// An iterator that, for every `(line_info, line_rect)` pair yielded by the given iterator,
     /// produces an iterator that yields a `Rect` for every character in that line.
     pub struct RectsPerLine<'a, I> {
         lines_with_rects: I,
         font: &'a super::Font,
+        text: &'a str,
         font_size: FontSize,
+        justify: super::Justify,
     }
 
     /// Yields an iteraor yielding `Rect`s for each selected character in each line of text within
@@ -321,23 +931,29 @@ pub mod glyph {
         })
     }
 
-    /// Produce an iterator that, for every `(line, line_rect)` pair yielded by the given iterator,
-    /// produces an iterator that yields a `Rect` for every character in that line.
+    /// Produce an iterator that, for every `(line_info, line_rect)` pair yielded by the given
+    /// iterator, produces an iterator that yields a `Rect` for every character in that line.
+    ///
+    /// The `justify` should be that with which the `line_rect`s were produced via `line::rects`.
     ///
     /// This is useful when information about character positioning is needed when reasoning about
     /// text layout.
     pub fn rects_per_line<'a, I>(
         lines_with_rects: I,
         font: &'a super::Font,
+        text: &'a str,
         font_size: FontSize,
+        justify: super::Justify,
     ) -> RectsPerLine<'a, I>
     where
-        I: Iterator<Item = (&'a str, Rect)>,
+        I: Iterator<Item = (super::line::Info, Rect)>,
     {
         RectsPerLine {
             lines_with_rects: lines_with_rects,
             font: font,
+            text,
             font_size: font_size,
+            justify,
         }
     }
 
@@ -351,16 +967,18 @@ pub mod glyph {
     pub fn selected_rects_per_line<'a, I>(
         lines_with_rects: I,
         font: &'a super::Font,
+        text: &'a str,
         font_size: FontSize,
+        justify: super::Justify,
         start: super::cursor::Index,
         end: super::cursor::Index,
     ) -> SelectedRectsPerLine<'a, I>
     where
-        I: Iterator<Item = (&'a str, Rect)>,
+        I: Iterator<Item = (super::line::Info, Rect)>,
     {
+        let rects_per_line = rects_per_line(lines_with_rects, font, text, font_size, justify);
         SelectedRectsPerLine {
-            enumerated_rects_per_line: rects_per_line(lines_with_rects, font, font_size)
-                .enumerate(),
+            enumerated_rects_per_line: rects_per_line.enumerate(),
             start_cursor_idx: start,
             end_cursor_idx: end,
         }
@@ -368,22 +986,26 @@ pub mod glyph {
 
     impl<'a, I> Iterator for RectsPerLine<'a, I>
     where
-        I: Iterator<Item = (&'a str, Rect)>,
+        I: Iterator<Item = (super::line::Info, Rect)>,
     {
         type Item = Rects<'a, 'a>;
         fn next(&mut self) -> Option<Self::Item> {
             let RectsPerLine {
                 ref mut lines_with_rects,
                 font,
+                text,
                 font_size,
+                justify,
             } = *self;
             let scale = super::pt_to_scale(font_size);
-            lines_with_rects.next().map(|(line, line_rect)| {
+            lines_with_rects.next().map(|(info, line_rect)| {
+                let line = &text[info.byte_range()];
                 let (x, y) = (line_rect.left() as f32, line_rect.top() as f32);
                 let point = super::rt::Point { x: x, y: y };
+                let word_spacing = super::line::word_spacing(text, &info, justify, line_rect);
                 Rects {
                     next_left: line_rect.x.start,
-                    layout: font.layout(line, scale, point),
+                    layout: super::line::layout(line, font, scale, point, word_spacing as f32),
                     y: line_rect.y,
                 }
             })
@@ -392,7 +1014,7 @@ pub mod glyph {
 
     impl<'a, I> Iterator for SelectedRectsPerLine<'a, I>
     where
-        I: Iterator<Item = (&'a str, Rect)>,
+        I: Iterator<Item = (super::line::Info, Rect)>,
     {
         type Item = SelectedRects<'a, 'a>;
         fn next(&mut self) -> Option<Self::Item> {
@@ -445,7 +1067,11 @@ pub mod glyph {
                 let right = g
                     .pixel_bounding_box()
                     .map(|bb| bb.max.x as Scalar)
-                    .unwrap_or_else(|| left + g.unpositioned().h_metrics().advance_width as Scalar);
+                    .unwrap_or_else(|| {
+                        let advance =
+                            g.unpositioned().h_metrics().advance_width + layout.space_after();
+                        left + advance as Scalar
+                    });
                 *next_left = right;
                 let x = Range::new(left, right);
                 Rect { x: x, y: y }
@@ -477,6 +1103,7 @@ pub mod glyph {
 pub mod cursor {
     use position::{Align, Point, Range, Rect, Scalar};
     use std;
+    use unicode_segmentation::UnicodeSegmentation;
     use FontSize;
 
     /// Every possible cursor position within each line of text yielded by the given iterator.
@@ -489,6 +1116,15 @@ pub mod cursor {
         font: &'a super::Font,
         text: &'a str,
         font_size: FontSize,
+        justify: super::Justify,
+        spans: Option<&'a [super::line::SpanMetrics<'a>]>,
+        // The face with which each line is shaped along with the bidirectional structure of the
+        // whole text, resolved once and shared between lines.
+        #[cfg(feature = "shaping")]
+        shaped: Option<(
+            &'a super::shaping::Face<'a>,
+            std::rc::Rc<super::shaping::Bidi<'a>>,
+        )>,
     }
 
     /// Similarly to `XysPerLine`, yields every possible cursor position within each line of text
@@ -516,7 +1152,10 @@ pub mod cursor {
     /// `Xs` iterators are produced by the `XysPerLine` iterator.
     pub struct Xs<'font, 'b> {
         next_x: Option<Scalar>,
-        layout: super::LayoutIter<'font, 'b>,
+        layout: super::line::Layout<'font, 'b>,
+        // The position of each cursor within a line that is shaped or made up of spans, in
+        // logical order.
+        carets: Option<std::vec::IntoIter<Scalar>>,
     }
 
     /// An index representing the position of a cursor within some text.
@@ -532,13 +1171,16 @@ pub mod cursor {
     }
 
     impl Index {
-        /// The cursor index of the beginning of the word (block of non-whitespace) before `self`.
+        /// The cursor index of the beginning of the word before `self`.
+        ///
+        /// Words are delimited by the word boundaries described by UAX#29, where only segments
+        /// containing letters or digits are considered words.
         ///
         /// If `self` is at the beginning of the line, call previous, which returns the last
         /// index position of the previous line, or None if it's the first line
         ///
-        /// If `self` points to whitespace, skip past that whitespace, then return the index of
-        /// the start of the word that precedes the whitespace
+        /// If `self` is between words, skip back to the start of the word that precedes it, or
+        /// to the beginning of the line if there is none.
         ///
         /// If `self` is in the middle or end of a word, return the index of the start of that word
         pub fn previous_word_start<I>(self, text: &str, mut line_infos: I) -> Option<Self>
@@ -547,43 +1189,33 @@ pub mod cursor {
         {
             let Index { line, char } = self;
             if char > 0 {
-                line_infos.nth(line).and_then(|line_info| {
-                    let line_count = line_info.char_range().count();
-                    let mut chars_rev = (&text[line_info.byte_range()]).chars().rev();
-                    if char != line_count {
-                        chars_rev.nth(line_count - char - 1);
-                    }
-                    let mut new_char = 0;
-                    let mut hit_non_whitespace = false;
-                    for (i, char_) in chars_rev.enumerate() {
-                        // loop until word starts, then continue until the word ends
-                        if !char_.is_whitespace() {
-                            hit_non_whitespace = true;
-                        }
-                        if char_.is_whitespace() && hit_non_whitespace {
-                            new_char = char - i;
-                            break;
-                        }
-                    }
-                    Some(Index {
+                line_infos.nth(line).map(|line_info| {
+                    let new_char = words(&text[line_info.byte_range()])
+                        .filter(|&(start, _)| start < char)
+                        .last()
+                        .map_or(0, |(start, _)| start);
+                    Index {
                         line: line,
                         char: new_char,
-                    })
+                    }
                 })
             } else {
-                self.previous(line_infos)
+                self.previous(text, line_infos)
             }
         }
 
-        /// The cursor index of the end of the first word (block of non-whitespace) after `self`.
+        /// The cursor index of the end of the first word after `self`.
+        ///
+        /// Words are delimited by the word boundaries described by UAX#29, where only segments
+        /// containing letters or digits are considered words.
         ///
         /// If `self` is at the end of the text, this returns `None`.
         ///
         /// If `self` is at the end of a line other than the last, this returns the first index of
         /// the next line.
         ///
-        /// If `self` points to whitespace, skip past that whitespace, then return the index of
-        /// the end of the word after the whitespace
+        /// If `self` is between words, skip forward to the end of the word that follows it, or to
+        /// the end of the line if there is none.
         ///
         /// If `self` is in the middle or start of a word, return the index of the end of that word
         pub fn next_word_end<I>(self, text: &str, mut line_infos: I) -> Option<Self>
@@ -594,22 +1226,10 @@ pub mod cursor {
             line_infos.nth(line).and_then(|line_info| {
                 let line_count = line_info.char_range().count();
                 if char < line_count {
-                    let mut chars = (&text[line_info.byte_range()]).chars();
-                    let mut new_char = line_count;
-                    let mut hit_non_whitespace = false;
-                    if char != 0 {
-                        chars.nth(char - 1);
-                    }
-                    for (i, char_) in chars.enumerate() {
-                        // loop until word starts, then continue until the word ends
-                        if !char_.is_whitespace() {
-                            hit_non_whitespace = true;
-                        }
-                        if char_.is_whitespace() && hit_non_whitespace {
-                            new_char = char + i;
-                            break;
-                        }
-                    }
+                    let new_char = words(&text[line_info.byte_range()])
+                        .map(|(_, end)| end)
+                        .find(|&end| end > char)
+                        .unwrap_or(line_count);
                     Some(Index {
                         line: line,
                         char: new_char,
@@ -631,16 +1251,20 @@ pub mod cursor {
         /// index position of the previous line.
         ///
         /// If `self` is a position other than the start of a line, it will return the position
-        /// that is immediately to the left.
-        pub fn previous<I>(self, mut line_infos: I) -> Option<Self>
+        /// at the start of the grapheme cluster (as described by UAX#29) that is immediately to
+        /// the left, e.g. stepping over a letter along with its combining accents as a whole.
+        pub fn previous<I>(self, text: &str, mut line_infos: I) -> Option<Self>
         where
             I: Iterator<Item = super::line::Info>,
         {
             let Index { line, char } = self;
             if char > 0 {
-                let new_char = char - 1;
                 line_infos.nth(line).and_then(|info| {
-                    if new_char <= info.char_range().count() {
+                    if char <= info.char_range().count() {
+                        let new_char = grapheme_boundaries(&text[info.byte_range()])
+                            .take_while(|&boundary| boundary < char)
+                            .last()
+                            .unwrap_or(0);
                         Some(Index {
                             line: line,
                             char: new_char,
@@ -670,28 +1294,122 @@ pub mod cursor {
         /// If `self` is at the end of a line other than the last, this returns the first index of
         /// the next line.
         ///
-        /// If `self` is a position other than the end of a line, it will return the position that
-        /// is immediately to the right.
-        pub fn next<I>(self, mut line_infos: I) -> Option<Self>
+        /// If `self` is a position other than the end of a line, it will return the position at
+        /// the end of the grapheme cluster (as described by UAX#29) that is immediately to the
+        /// right.
+        pub fn next<I>(self, text: &str, mut line_infos: I) -> Option<Self>
         where
             I: Iterator<Item = super::line::Info>,
         {
             let Index { line, char } = self;
             line_infos.nth(line).and_then(|info| {
-                if char >= info.char_range().count() {
+                let line_count = info.char_range().count();
+                if char >= line_count {
                     line_infos.next().map(|_| Index {
                         line: line + 1,
                         char: 0,
                     })
                 } else {
+                    let new_char = grapheme_boundaries(&text[info.byte_range()])
+                        .find(|&boundary| boundary > char)
+                        .unwrap_or(line_count);
                     Some(Index {
                         line: line,
-                        char: char + 1,
+                        char: new_char,
                     })
                 }
             })
         }
 
+        /// The cursor index displayed immediately to the left of `self`.
+        ///
+        /// With the `shaping` feature, this follows the visual order of bidirectional text within
+        /// each line, e.g. moving left through right-to-left text advances through the `text`.
+        /// Otherwise, this is equivalent to `previous`.
+        pub fn left<I>(self, text: &str, line_infos: I) -> Option<Self>
+        where
+            I: Iterator<Item = super::line::Info>,
+        {
+            self.step_visually(text, line_infos, false)
+        }
+
+        /// The cursor index displayed immediately to the right of `self`.
+        ///
+        /// With the `shaping` feature, this follows the visual order of bidirectional text within
+        /// each line. Otherwise, this is equivalent to `next`.
+        pub fn right<I>(self, text: &str, line_infos: I) -> Option<Self>
+        where
+            I: Iterator<Item = super::line::Info>,
+        {
+            self.step_visually(text, line_infos, true)
+        }
+
+        #[cfg(not(feature = "shaping"))]
+        fn step_visually<I>(self, text: &str, line_infos: I, right: bool) -> Option<Self>
+        where
+            I: Iterator<Item = super::line::Info>,
+        {
+            if right {
+                self.next(text, line_infos)
+            } else {
+                self.previous(text, line_infos)
+            }
+        }
+
+        // Move one cursor position to the left or right in visual order.
+        //
+        // Visual "slots" lie between the `char`s of the line as displayed, where slot `i` is the
+        // left edge of the `i`th `char` from the left. Each cursor index lies at the leading edge
+        // of its `char`, or the trailing edge of the last `char` at the end of the line. Indices
+        // sharing a slot at the boundary between directions are visited in logical order.
+        #[cfg(feature = "shaping")]
+        fn step_visually<I>(self, text: &str, line_infos: I, right: bool) -> Option<Self>
+        where
+            I: Iterator<Item = super::line::Info>,
+        {
+            let line_infos: Vec<_> = line_infos.collect();
+            let info = *line_infos.get(self.line)?;
+            let bidi = super::shaping::Bidi::new(text);
+            let order = super::shaping::visual_order(&bidi, info.byte_range());
+            let n = order.len();
+            let mut slots = vec![(0, 0); n + 1];
+            for (slot, &(c, rtl)) in order.iter().enumerate() {
+                slots[c] = (slot + rtl as usize, c);
+                if c + 1 == n {
+                    slots[n] = (slot + !rtl as usize, n);
+                }
+            }
+            slots.sort();
+
+            // The cursor never lands within a grapheme cluster.
+            let boundaries: Vec<_> = grapheme_boundaries(&text[info.byte_range()]).collect();
+            slots.retain(|&(_, c)| c == self.char || boundaries.binary_search(&c).is_ok());
+
+            let pos = slots.iter().position(|&(_, c)| c == self.char)?;
+            let new_pos = if right { pos + 1 } else { pos.wrapping_sub(1) };
+            if let Some(&(_, char)) = slots.get(new_pos) {
+                return Some(Index {
+                    line: self.line,
+                    char: char,
+                });
+            }
+
+            // Cross onto the neighbouring line.
+            if right {
+                line_infos.get(self.line + 1).map(|_| Index {
+                    line: self.line + 1,
+                    char: 0,
+                })
+            } else {
+                let line = self.line.checked_sub(1)?;
+                let char = line_infos[line].char_range().len();
+                Some(Index {
+                    line: line,
+                    char: char,
+                })
+            }
+        }
+
         /// Clamps `self` to the given lines.
         ///
         /// If `self` would lie after the end of the last line, return the index at the end of the
@@ -724,21 +1442,51 @@ pub mod cursor {
         }
     }
 
+    // The `char` index of each boundary between the grapheme clusters of the given line, including
+    // the start and end of the line.
+    fn grapheme_boundaries<'a>(line: &'a str) -> impl 'a + Iterator<Item = usize> {
+        let graphemes = line.graphemes(true).scan(0, |end, grapheme| {
+            *end += grapheme.chars().count();
+            Some(*end)
+        });
+        std::iter::once(0).chain(graphemes)
+    }
+
+    // The start and end `char` index of each word within the given line, where words are the
+    // segments between UAX#29 word boundaries that contain letters or digits.
+    fn words<'a>(line: &'a str) -> impl 'a + Iterator<Item = (usize, usize)> {
+        line.split_word_bounds()
+            .scan(0, |end, segment| {
+                let start = *end;
+                *end += segment.chars().count();
+                Some((start, *end, segment))
+            })
+            .filter(|&(_, _, segment)| segment.chars().any(char::is_alphanumeric))
+            .map(|(start, end, _)| (start, end))
+    }
+
     /// Every possible cursor position within each line of text yielded by the given iterator.
     ///
     /// Yields `(xs, y_range)`, where `y_range` is the `Range` occupied by the line across the *y*
     /// axis and `xs` is every possible cursor position along the *x* axis
+    ///
+    /// The `justify` should be that with which the line `Rect`s were produced via `line::rects`.
     pub fn xys_per_line<'a, I>(
         lines_with_rects: I,
         font: &'a super::Font,
         text: &'a str,
         font_size: FontSize,
+        justify: super::Justify,
     ) -> XysPerLine<'a, I> {
         XysPerLine {
             lines_with_rects: lines_with_rects,
             font: font,
             text: text,
             font_size: font_size,
+            justify,
+            spans: None,
+            #[cfg(feature = "shaping")]
+            shaped: None,
         }
     }
 
@@ -773,7 +1521,13 @@ pub mod cursor {
         let lines = line_infos.clone();
         let lines_with_rects = lines.zip(line_rects.clone());
         XysPerLineFromText {
-            xys_per_line: super::cursor::xys_per_line(lines_with_rects, font, text, font_size),
+            xys_per_line: super::cursor::xys_per_line(
+                lines_with_rects,
+                font,
+                text,
+                font_size,
+                x_align,
+            ),
         }
     }
 
@@ -874,42 +1628,143 @@ pub mod cursor {
         let first_diff = (x_pos - first_x).abs();
         let mut closest = (first_idx, first_x);
         let mut closest_diff = first_diff;
+        // Positions are not necessarily ordered along the *x* axis for shaped bidirectional text,
+        // so every position is checked.
         for (i, x) in xs_enumerated {
             let diff = (x_pos - x).abs();
             if diff < closest_diff {
                 closest = (i, x);
                 closest_diff = diff;
-            } else {
-                break;
             }
         }
         closest
     }
 
-    impl<'a, I> Iterator for XysPerLine<'a, I>
+    /// The `Rect` spanning the selected cursor positions between `start` and `end` within each
+    /// line, given every cursor position of each line as yielded by `XysPerLine`.
+    ///
+    /// Lines that do not contain any selected text are skipped.
+    pub fn selected_rects<'a, I>(xys_per_line: I, start: Index, end: Index) -> Vec<Rect>
     where
-        I: Iterator<Item = (super::line::Info, Rect)>,
+        I: Iterator<Item = (Xs<'a, 'a>, Range)>,
     {
-        // The `Range` occupied by the line across the *y* axis, along with an iterator yielding
-        // each possible cursor position along the *x* axis.
-        type Item = (Xs<'a, 'a>, Range);
-        fn next(&mut self) -> Option<Self::Item> {
-            let XysPerLine {
-                ref mut lines_with_rects,
-                font,
-                text,
-                font_size,
-            } = *self;
+        let mut rects = Vec::new();
+        for (i, (xs, y)) in xys_per_line.enumerate().skip(start.line) {
+            if i > end.line {
+                break;
+            }
+            let xs: Vec<_> = xs.collect();
+            let start_char = if i == start.line { start.char } else { 0 };
+            let end_char = if i == end.line {
+                end.char
+            } else {
+                xs.len() - 1
+            };
+            if let (Some(&left), Some(&right)) = (xs.get(start_char), xs.get(end_char)) {
+                if start_char < end_char {
+                    rects.push(Rect {
+                        x: Range::new(left, right),
+                        y: y,
+                    });
+                }
+            }
+        }
+        rects
+    }
+
+    impl<'a, I> XysPerLine<'a, I> {
+        /// Position the cursors of each line by measuring each `char` with the font of the span
+        /// in which it lies, if some `spans` are given.
+        ///
+        /// This is necessary for text laid out via `line::span_infos`, e.g. text that uses
+        /// fallback fonts. The `spans` take precedence over any `shaped` face.
+        pub fn spans(mut self, spans: Option<&'a [super::line::SpanMetrics<'a>]>) -> Self {
+            self.spans = spans;
+            self
+        }
+    }
+
+    impl<'a> XysPerLineFromText<'a> {
+        /// Position the cursors of each line by measuring each `char` with the font of the span
+        /// in which it lies, if some `spans` are given.
+        ///
+        /// See `XysPerLine::spans`.
+        pub fn spans(mut self, spans: Option<&'a [super::line::SpanMetrics<'a>]>) -> Self {
+            self.xys_per_line = self.xys_per_line.spans(spans);
+            self
+        }
+    }
+
+    #[cfg(feature = "shaping")]
+    impl<'a, I> XysPerLine<'a, I> {
+        /// Position the cursors of each line as shaped with the given `face`, if any.
+        ///
+        /// Cursor positions are yielded in logical order, so may not be ordered along the *x*
+        /// axis for bidirectional text.
+        pub fn shaped(mut self, face: Option<&'a super::shaping::Face<'a>>) -> Self {
+            let text = self.text;
+            self.shaped =
+                face.map(|face| (face, std::rc::Rc::new(super::shaping::Bidi::new(text))));
+            self
+        }
+    }
+
+    #[cfg(feature = "shaping")]
+    impl<'a> XysPerLineFromText<'a> {
+        /// Position the cursors of each line as shaped with the given `face`, if any.
+        ///
+        /// See `XysPerLine::shaped`.
+        pub fn shaped(mut self, face: Option<&'a super::shaping::Face<'a>>) -> Self {
+            self.xys_per_line = self.xys_per_line.shaped(face);
+            self
+        }
+    }
+
+    impl<'a, I> Iterator for XysPerLine<'a, I>
+    where
+        I: Iterator<Item = (super::line::Info, Rect)>,
+    {
+        // The `Range` occupied by the line across the *y* axis, along with an iterator yielding
+        // each possible cursor position along the *x* axis.
+        type Item = (Xs<'a, 'a>, Range);
+        fn next(&mut self) -> Option<Self::Item> {
+            let XysPerLine {
+                ref mut lines_with_rects,
+                font,
+                text,
+                font_size,
+                justify,
+                spans,
+                #[cfg(feature = "shaping")]
+                ref shaped,
+            } = *self;
             let scale = super::pt_to_scale(font_size);
             lines_with_rects.next().map(|(line_info, line_rect)| {
                 let line = &text[line_info.byte_range()];
                 let (x, y) = (line_rect.left() as f32, line_rect.top() as f32);
                 let point = super::rt::Point { x: x, y: y };
                 let y = line_rect.y;
-                let layout = font.layout(line, scale, point);
+                let word_spacing = super::line::word_spacing(text, &line_info, justify, line_rect);
+                let layout = super::line::layout(line, font, scale, point, word_spacing as f32);
+                let span_carets = spans
+                    .map(|spans| super::line::span_caret_xs(text, &line_info, spans, word_spacing));
+                #[cfg(feature = "shaping")]
+                let span_carets = span_carets.or_else(|| {
+                    shaped.as_ref().map(|&(face, ref bidi)| {
+                        let shaped = super::shaping::shape_line_in_rect(
+                            bidi, &line_info, justify, line_rect, face, scale,
+                        );
+                        shaped.caret_xs()
+                    })
+                });
+                let carets = span_carets.map(|xs| {
+                    let xs: Vec<_> = xs.into_iter().map(|x| line_rect.left() + x).collect();
+                    xs.into_iter()
+                });
                 let xs = Xs {
                     next_x: Some(line_rect.x.start),
                     layout: layout,
+                    carets: carets,
                 };
                 (xs, y)
             })
@@ -927,11 +1782,19 @@ pub mod cursor {
         // Each possible cursor position along the *x* axis.
         type Item = Scalar;
         fn next(&mut self) -> Option<Self::Item> {
+            if let Some(ref mut carets) = self.carets {
+                return carets.next();
+            }
             self.next_x.map(|x| {
-                self.next_x = self.layout.next().map(|g| {
+                let layout = &mut self.layout;
+                self.next_x = layout.next().map(|g| {
                     g.pixel_bounding_box()
                         .map(|r| r.max.x as Scalar)
-                        .unwrap_or_else(|| x + g.unpositioned().h_metrics().advance_width as Scalar)
+                        .unwrap_or_else(|| {
+                            let advance =
+                                g.unpositioned().h_metrics().advance_width + layout.space_after();
+                            x + advance as Scalar
+                        })
                 });
                 x
             })
@@ -945,9 +1808,11 @@ pub mod cursor {
 pub mod line {
     use position::{Align, Range, Rect, Scalar};
     use std;
+    use unicode_segmentation::UnicodeSegmentation;
     use FontSize;
 
     /// The two types of **Break** indices returned by the **WrapIndicesBy** iterators.
+    #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
     #[derive(Copy, Clone, Debug, PartialEq)]
     pub enum Break {
         /// A break caused by the text exceeding some maximum width.
@@ -983,6 +1848,7 @@ pub mod line {
     /// `Info` is a minimal amount of information that can be stored for efficient reasoning about
     /// blocks of text given some `&str`. The `start` and `end_break` can be used for indexing into
     /// the `&str`, and the `width` can be used for calculating line `Rect`s, alignment, etc.
+    #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
     #[derive(Copy, Clone, Debug, PartialEq)]
     pub struct Info {
         /// The index into the `&str` that represents the first character within the line.
@@ -1017,6 +1883,22 @@ pub mod line {
         start_char: usize,
         /// The break type of the previously yielded line
         last_break: Option<Break>,
+        /// The shaped text by which lines are wrapped and measured, if any.
+        #[cfg(feature = "shaping")]
+        shaped: Option<Shaped<'a>>,
+        /// The way in which lines are wrapped by the advances of the `shaped` text.
+        #[cfg(feature = "shaping")]
+        maybe_wrap: Option<Wrap>,
+    }
+
+    // The face with which the text of an `Infos` is shaped, along with the bidirectional structure
+    // and shaped advance of each `char` of the whole text.
+    #[cfg(feature = "shaping")]
+    #[derive(Clone)]
+    struct Shaped<'a> {
+        face: &'a super::shaping::Face<'a>,
+        bidi: std::sync::Arc<super::shaping::Bidi<'a>>,
+        advances: std::sync::Arc<[Scalar]>,
     }
 
     /// An iterator yielding a `Rect` for each line in
@@ -1025,9 +1907,60 @@ pub mod line {
         infos: I,
         x_align: super::Justify,
         line_spacing: Scalar,
+        bounding_x: Range,
         next: Option<Rect>,
     }
 
+    /// An iterator yielding the `PositionedGlyph` for each character in a single line of text.
+    ///
+    /// This behaves like RustType's `LayoutIter`, while also adding the given `word_spacing` after
+    /// each word space in order to stretch lines aligned via `Justify::Full`.
+    pub struct Layout<'font, 'b> {
+        layout: super::LayoutIter<'font, 'b>,
+        chars: std::str::Chars<'b>,
+        word_spacing: f32,
+        offset: f32,
+        space_after: f32,
+        // Glyphs produced by the shaper along with whether or not each is a word space.
+        #[cfg(feature = "shaping")]
+        shaped: Option<std::vec::IntoIter<(::rusttype::PositionedGlyph<'font>, bool)>>,
+    }
+
+    /// The way in which text should wrap around some maximum width.
+    #[derive(Copy, Clone, Debug, Hash, PartialEq, Eq)]
+    pub enum Wrap {
+        /// Wrap at the first character that exceeds the width.
+        Character,
+        /// Wrap at the first word that exceeds the width.
+        Whitespace,
+    }
+
+    /// The font and size used for a span of text, as given to `span_infos`.
+    #[derive(Copy, Clone, Debug)]
+    pub struct SpanMetrics<'a> {
+        /// The byte index within the text at which the span ends.
+        pub end_byte: usize,
+        /// The font used to lay out the span.
+        pub font: &'a super::Font,
+        /// The size of the font used to lay out the span.
+        pub font_size: FontSize,
+    }
+
+    /// An iterator yielding an `Info` for each line of some text made up of consecutive spans,
+    /// each with their own font and size.
+    ///
+    /// Construct a `SpanInfos` via the [span_infos function](./fn.span_infos.html).
+    #[derive(Clone)]
+    pub struct SpanInfos<'a> {
+        text: &'a str,
+        spans: &'a [SpanMetrics<'a>],
+        max_width: Scalar,
+        maybe_wrap: Option<Wrap>,
+        start_byte: usize,
+        start_char: usize,
+        last_break: Option<Break>,
+    }
+
     /// An iterator yielding a `Rect` for each selected line in a block of text.
     ///
     /// The yielded `Rect`s represent the selected range within each line of text.
@@ -1077,6 +2010,10 @@ pub mod line {
                 start_byte: self.start_byte,
                 start_char: self.start_char,
                 last_break: None,
+                #[cfg(feature = "shaping")]
+                shaped: self.shaped.clone(),
+                #[cfg(feature = "shaping")]
+                maybe_wrap: self.maybe_wrap,
             }
         }
     }
@@ -1109,6 +2046,10 @@ pub mod line {
         pub fn wrap_by_character(mut self, max_width: Scalar) -> Self {
             self.next_break_fn = next_break_by_character;
             self.max_width = max_width;
+            #[cfg(feature = "shaping")]
+            {
+                self.maybe_wrap = Some(Wrap::Character);
+            }
             self
         }
 
@@ -1117,6 +2058,31 @@ pub mod line {
         pub fn wrap_by_whitespace(mut self, max_width: Scalar) -> Self {
             self.next_break_fn = next_break_by_whitespace;
             self.max_width = max_width;
+            #[cfg(feature = "shaping")]
+            {
+                self.maybe_wrap = Some(Wrap::Whitespace);
+            }
+            self
+        }
+
+        /// Wrap and measure each line as shaped with the given `face`, if any.
+        ///
+        /// Lines are wrapped by the advances of each paragraph shaped as a whole, as given by
+        /// `shaping::char_advances`, and measured by their width once shaped alone. Kerning across
+        /// a wrap may cause shaped lines to slightly exceed the maximum width.
+        #[cfg(feature = "shaping")]
+        pub fn shaped(mut self, face: Option<&'a super::shaping::Face<'a>>) -> Self {
+            let scale = super::pt_to_scale(self.font_size);
+            let text = self.text;
+            self.shaped = face.map(|face| {
+                let bidi = super::shaping::Bidi::new(text);
+                let advances = super::shaping::char_advances(&bidi, face, scale).into();
+                Shaped {
+                    face,
+                    bidi: std::sync::Arc::new(bidi),
+                    advances,
+                }
+            });
             self
         }
     }
@@ -1148,33 +2114,28 @@ pub mod line {
     /// along with the width of the line.
     fn next_break(text: &str, font: &super::Font, font_size: FontSize) -> (Break, Scalar) {
         let scale = super::pt_to_scale(font_size);
+        let mut last_glyph = None;
+        next_break_with(text, |_, ch| {
+            advance_width(ch, font, scale, &mut last_glyph)
+        })
+    }
+
+    // As `next_break`, but measuring the character at each byte index via the given `advance`.
+    fn next_break_with<A>(text: &str, mut advance: A) -> (Break, Scalar)
+    where
+        A: FnMut(usize, char) -> Scalar,
+    {
         let mut width = 0.0;
         let mut char_i = 0;
-        let mut char_indices = text.char_indices().peekable();
-        let mut last_glyph = None;
-        while let Some((byte_i, ch)) = char_indices.next() {
+        for (byte_i, grapheme) in text.grapheme_indices(true) {
             // Check for a newline.
-            if ch == '\r' {
-                if let Some(&(_, '\n')) = char_indices.peek() {
-                    let break_ = Break::Newline {
-                        byte: byte_i,
-                        char: char_i,
-                        len_bytes: 2,
-                    };
-                    return (break_, width);
-                }
-            } else if ch == '\n' {
-                let break_ = Break::Newline {
-                    byte: byte_i,
-                    char: char_i,
-                    len_bytes: 1,
-                };
+            if let Some(break_) = newline(grapheme, byte_i, char_i) {
                 return (break_, width);
             }
 
             // Update the width.
-            width += advance_width(ch, font, scale, &mut last_glyph);
-            char_i += 1;
+            width += grapheme_width(grapheme, byte_i, &mut advance);
+            char_i += grapheme.chars().count();
         }
         let break_ = Break::End {
             byte: text.len(),
@@ -1195,35 +2156,35 @@ pub mod line {
         max_width: Scalar,
     ) -> (Break, Scalar) {
         let scale = super::pt_to_scale(font_size);
+        let mut last_glyph = None;
+        next_break_by_character_with(text, max_width, |_, ch| {
+            advance_width(ch, font, scale, &mut last_glyph)
+        })
+    }
+
+    // As `next_break_by_character`, but measuring the character at each byte index via the given
+    // `advance`.
+    fn next_break_by_character_with<A>(
+        text: &str,
+        max_width: Scalar,
+        mut advance: A,
+    ) -> (Break, Scalar)
+    where
+        A: FnMut(usize, char) -> Scalar,
+    {
         let mut width = 0.0;
         let mut char_i = 0;
-        let mut char_indices = text.char_indices().peekable();
-        let mut last_glyph = None;
-        while let Some((byte_i, ch)) = char_indices.next() {
+        for (byte_i, grapheme) in text.grapheme_indices(true) {
             // Check for a newline.
-            if ch == '\r' {
-                if let Some(&(_, '\n')) = char_indices.peek() {
-                    let break_ = Break::Newline {
-                        byte: byte_i,
-                        char: char_i,
-                        len_bytes: 2,
-                    };
-                    return (break_, width);
-                }
-            } else if ch == '\n' {
-                let break_ = Break::Newline {
-                    byte: byte_i,
-                    char: char_i,
-                    len_bytes: 1,
-                };
+            if let Some(break_) = newline(grapheme, byte_i, char_i) {
                 return (break_, width);
             }
 
-            // Add the character's width to the width so far.
-            let new_width = width + advance_width(ch, font, scale, &mut last_glyph);
+            // Add the grapheme's width to the width so far.
+            let new_width = width + grapheme_width(grapheme, byte_i, &mut advance);
 
-            // Check for a line wrap.
-            if new_width > max_width {
+            // Check for a line wrap, keeping at least one grapheme on each line.
+            if new_width > max_width && char_i > 0 {
                 let break_ = Break::Wrap {
                     byte: byte_i,
                     char: char_i,
@@ -1233,7 +2194,7 @@ pub mod line {
             }
 
             width = new_width;
-            char_i += 1;
+            char_i += grapheme.chars().count();
         }
 
         let break_ = Break::End {
@@ -1245,10 +2206,11 @@ pub mod line {
 
     /// Returns the next index at which the text will break by either:
     /// - A newline character.
-    /// - A line wrap at the beginning of the whitespace that precedes the first word
-    /// exceeding the `max_width`.
-    /// - A line wrap at the beginning of the first character exceeding the `max_width`,
-    /// if no whitespace appears for `max_width` characters.
+    /// - A line wrap at the last line break opportunity (as described by UAX#14) before the
+    ///   first word exceeding the `max_width`. Where whitespace precedes the opportunity, the line
+    ///   wraps at the beginning of the last whitespace character.
+    /// - A line wrap at the beginning of the first grapheme exceeding the `max_width`, if no
+    ///   break opportunity appears for `max_width`.
     ///
     /// Also returns the width the line alongside the Break.
     fn next_break_by_whitespace(
@@ -1257,43 +2219,67 @@ pub mod line {
         font_size: FontSize,
         max_width: Scalar,
     ) -> (Break, Scalar) {
+        let scale = super::pt_to_scale(font_size);
+        let mut last_glyph = None;
+        next_break_by_whitespace_with(text, max_width, |_, ch| {
+            advance_width(ch, font, scale, &mut last_glyph)
+        })
+    }
+
+    // As `next_break_by_whitespace`, but measuring the character at each byte index via the given
+    // `advance`.
+    fn next_break_by_whitespace_with<A>(
+        text: &str,
+        max_width: Scalar,
+        mut advance: A,
+    ) -> (Break, Scalar)
+    where
+        A: FnMut(usize, char) -> Scalar,
+    {
         struct Last {
             byte: usize,
             char: usize,
             width_before: Scalar,
         }
-        let scale = super::pt_to_scale(font_size);
         let mut last_whitespace_start = None;
+        let mut last_break_opportunity = None;
+        let mut break_opportunities = ::unicode_linebreak::linebreaks(text).peekable();
         let mut width = 0.0;
         let mut char_i = 0;
-        let mut char_indices = text.char_indices().peekable();
-        let mut last_glyph = None;
-        while let Some((byte_i, ch)) = char_indices.next() {
+        for (byte_i, grapheme) in text.grapheme_indices(true) {
             // Check for a newline.
-            if ch == '\r' {
-                if let Some(&(_, '\n')) = char_indices.peek() {
-                    let break_ = Break::Newline {
-                        byte: byte_i,
-                        char: char_i,
-                        len_bytes: 2,
-                    };
-                    return (break_, width);
+            if let Some(break_) = newline(grapheme, byte_i, char_i) {
+                return (break_, width);
+            }
+
+            // Check whether the line may break before this grapheme, in which case any whitespace
+            // that precedes it is skipped.
+            while let Some(&(byte, _)) = break_opportunities.peek() {
+                if byte >= byte_i {
+                    break;
                 }
-            } else if ch == '\n' {
-                let break_ = Break::Newline {
-                    byte: byte_i,
-                    char: char_i,
-                    len_bytes: 1,
+                break_opportunities.next();
+            }
+            if byte_i > 0 && break_opportunities.peek().map(|&(byte, _)| byte) == Some(byte_i) {
+                last_break_opportunity = match last_whitespace_start.take() {
+                    Some(whitespace) => Some(whitespace),
+                    None => Some((
+                        Last {
+                            byte: byte_i,
+                            char: char_i,
+                            width_before: width,
+                        },
+                        0,
+                    )),
                 };
-                return (break_, width);
             }
 
-            // Add the character's width to the width so far.
-            let new_width = width + advance_width(ch, font, scale, &mut last_glyph);
+            // Add the grapheme's width to the width so far.
+            let new_width = width + grapheme_width(grapheme, byte_i, &mut advance);
 
             // Check for a line wrap.
-            if width > max_width {
-                match last_whitespace_start {
+            if width > max_width && char_i > 0 {
+                match last_break_opportunity {
                     Some((
                         Last {
                             byte,
@@ -1321,19 +2307,21 @@ pub mod line {
             }
 
             // Check for a new whitespace.
-            if ch.is_whitespace() {
+            if grapheme.chars().all(char::is_whitespace) {
                 last_whitespace_start = Some((
                     Last {
                         byte: byte_i,
                         char: char_i,
                         width_before: width,
                     },
-                    ch.len_utf8(),
+                    grapheme.len(),
                 ));
+            } else {
+                last_whitespace_start = None;
             }
 
             width = new_width;
-            char_i += 1;
+            char_i += grapheme.chars().count();
         }
 
         let break_ = Break::End {
@@ -1343,6 +2331,238 @@ pub mod line {
         (break_, width)
     }
 
+    // The `Break` for the given grapheme at the given byte and char index if it is a newline.
+    fn newline(grapheme: &str, byte: usize, char: usize) -> Option<Break> {
+        match grapheme {
+            "\r\n" | "\n" => Some(Break::Newline {
+                byte,
+                char,
+                len_bytes: grapheme.len(),
+            }),
+            _ => None,
+        }
+    }
+
+    // The total advance of the `char`s of the given grapheme starting at the given byte index.
+    fn grapheme_width<A>(grapheme: &str, byte: usize, advance: &mut A) -> Scalar
+    where
+        A: FnMut(usize, char) -> Scalar,
+    {
+        grapheme
+            .char_indices()
+            .map(|(i, ch)| advance(byte + i, ch))
+            .sum()
+    }
+
+    /// Lay out the glyphs of a single line of text starting from the given `point`.
+    ///
+    /// The given `word_spacing` is added after each word space, in pixels. See `word_spacing`.
+    pub fn layout<'font, 'b>(
+        text: &'b str,
+        font: &'b ::rusttype::Font<'font>,
+        scale: super::Scale,
+        point: super::rt::Point<f32>,
+        word_spacing: f32,
+    ) -> Layout<'font, 'b> {
+        Layout {
+            layout: font.layout(text, scale, point),
+            chars: text.chars(),
+            word_spacing: word_spacing,
+            offset: 0.0,
+            space_after: 0.0,
+            #[cfg(feature = "shaping")]
+            shaped: None,
+        }
+    }
+
+    /// Lay out the glyphs of the given `shaped` line of text starting from the given `point`.
+    ///
+    /// Glyphs are yielded in visual order from left to right. The given `word_spacing` is added
+    /// after each word space glyph. See `layout` and `shaping::shape_line`.
+    #[cfg(feature = "shaping")]
+    pub fn shaped_layout<'font, 'b>(
+        text: &'b str,
+        shaped: &super::shaping::Line,
+        font: &'b ::rusttype::Font<'font>,
+        scale: super::Scale,
+        point: super::rt::Point<f32>,
+        word_spacing: f32,
+    ) -> Layout<'font, 'b> {
+        let glyphs: Vec<_> = shaped
+            .glyphs
+            .iter()
+            .map(|g| {
+                let [x, y] = g.position;
+                let position = super::rt::point(point.x + x as f32, point.y + y as f32);
+                let glyph = font.glyph(g.id).scaled(scale).positioned(position);
+                (glyph, g.is_whitespace)
+            })
+            .collect();
+        Layout {
+            layout: font.layout(text, scale, point),
+            chars: text.chars(),
+            word_spacing: word_spacing,
+            offset: 0.0,
+            space_after: 0.0,
+            shaped: Some(glyphs.into_iter()),
+        }
+    }
+
+    /// The space added after each word space within the line of the `text` described by `info`
+    /// in order to stretch it across the width of the given `line_rect`, as yielded by `rects`.
+    ///
+    /// Only lines aligned via `Justify::Full` that are broken by wrapping are stretched, so this
+    /// returns `0.0` for all others without inspecting the line. See `is_word_space`.
+    pub fn word_spacing(
+        text: &str,
+        info: &Info,
+        justify: super::Justify,
+        line_rect: Rect,
+    ) -> Scalar {
+        match (justify, info.end_break) {
+            (super::Justify::Full, Break::Wrap { .. }) => (),
+            _ => return 0.0,
+        }
+        let extra = line_rect.w() - info.width;
+        let num_spaces = text[info.byte_range()]
+            .chars()
+            .filter(|&ch| is_word_space(ch))
+            .count();
+        if num_spaces == 0 || extra < 1e-6 {
+            return 0.0;
+        }
+        extra / num_spaces as Scalar
+    }
+
+    /// Whether or not `Justify::Full` adds space after the given `char` when stretching a line.
+    ///
+    /// This is any whitespace other than control characters such as `'\t'` and `'\r'`.
+    pub fn is_word_space(ch: char) -> bool {
+        ch.is_whitespace() && !ch.is_control()
+    }
+
+    /// Truncate each line of the given `text` that is wider than `max_width`, replacing the removed
+    /// grapheme clusters with an ellipsis ("…") at the given position.
+    ///
+    /// Each `char` is measured with the first of the given `fonts` that has a glyph for it, as for
+    /// `font::fallback_runs`. Whitespace adjacent to the ellipsis is removed, and lines too narrow
+    /// to fit the ellipsis itself become empty.
+    ///
+    /// Returns `None` if no line is wider than `max_width`.
+    pub fn truncate(
+        text: &str,
+        fonts: &[&super::Font],
+        font_size: FontSize,
+        max_width: Scalar,
+        ellipsis: super::Ellipsis,
+    ) -> Option<String> {
+        const ELLIPSIS: &str = "\u{2026}";
+        let scale = super::pt_to_scale(font_size);
+
+        // The advance of each `char` within the given line.
+        let advances = |line: &str| -> Vec<Scalar> {
+            let mut last = None;
+            line.chars()
+                .map(|ch| {
+                    let ix = fonts
+                        .iter()
+                        .position(|font| super::font::has_glyph(font, ch))
+                        .unwrap_or(0);
+                    let last_glyph = match last {
+                        Some((last_ix, glyph)) if last_ix == ix => Some(glyph),
+                        _ => None,
+                    };
+                    let mut next_glyph = last_glyph;
+                    let advance = advance_width(ch, fonts[ix], scale, &mut next_glyph);
+                    last = next_glyph.map(|glyph| (ix, glyph));
+                    advance
+                })
+                .collect()
+        };
+
+        if fonts.is_empty() {
+            return None;
+        }
+        let ellipsis_width: Scalar = advances(ELLIPSIS).iter().sum();
+        let mut truncated = String::with_capacity(text.len());
+        let mut any_truncated = false;
+        for (i, line) in text.split('\n').enumerate() {
+            if i > 0 {
+                truncated.push('\n');
+            }
+            let (line, cr) = match line.ends_with('\r') {
+                true => (&line[..line.len() - 1], "\r"),
+                false => (line, ""),
+            };
+            let char_advances = advances(line);
+            if char_advances.iter().sum::<Scalar>() <= max_width {
+                truncated.push_str(line);
+                truncated.push_str(cr);
+                continue;
+            }
+            any_truncated = true;
+            let budget = max_width - ellipsis_width;
+            if budget < 0.0 {
+                truncated.push_str(cr);
+                continue;
+            }
+
+            // The start byte and width of each grapheme, such that none are split.
+            let mut char_advances = char_advances.into_iter();
+            let graphemes: Vec<(usize, Scalar)> = line
+                .grapheme_indices(true)
+                .map(|(byte, grapheme)| {
+                    let n = grapheme.chars().count();
+                    (byte, char_advances.by_ref().take(n).sum())
+                })
+                .collect();
+
+            // The byte index up to which the leading graphemes fit within the given width, along
+            // with their total width.
+            let head = |width: Scalar| {
+                let mut total = 0.0;
+                for &(byte, advance) in &graphemes {
+                    if total + advance > width {
+                        return (byte, total);
+                    }
+                    total += advance;
+                }
+                (line.len(), total)
+            };
+            // The byte index from which the trailing graphemes fit within the given width.
+            let tail = |width: Scalar| {
+                let mut total = 0.0;
+                let mut start = line.len();
+                for &(byte, advance) in graphemes.iter().rev() {
+                    if total + advance > width {
+                        break;
+                    }
+                    total += advance;
+                    start = byte;
+                }
+                start
+            };
+            let (start, end) = match ellipsis {
+                super::Ellipsis::Start => ("", &line[tail(budget)..]),
+                super::Ellipsis::Middle => {
+                    let (head_end, head_width) = head(budget / 2.0);
+                    (&line[..head_end], &line[tail(budget - head_width)..])
+                }
+                super::Ellipsis::End => (&line[..head(budget).0], ""),
+            };
+            truncated.push_str(start.trim_end());
+            truncated.push_str(ELLIPSIS);
+            truncated.push_str(end.trim_start());
+            truncated.push_str(cr);
+        }
+
+        if any_truncated {
+            Some(truncated)
+        } else {
+            None
+        }
+    }
+
     /// Produce the width of the given line of text including spaces (i.e. ' ').
     pub fn width(text: &str, font: &super::Font, font_size: FontSize) -> Scalar {
         let scale = super::Scale::uniform(super::pt_to_px(font_size));
@@ -1379,6 +2599,10 @@ pub mod line {
             start_byte: 0,
             start_char: 0,
             last_break: None,
+            #[cfg(feature = "shaping")]
+            shaped: None,
+            #[cfg(feature = "shaping")]
+            maybe_wrap: None,
         }
     }
 
@@ -1403,6 +2627,53 @@ pub mod line {
         infos_wrapped_by(text, font, font_size, std::f64::MAX, no_wrap)
     }
 
+    /// Produce a `SpanInfos` iterator that yields an `Info` for every line in the given text,
+    /// where each of the given `spans` is laid out with its own font and size.
+    ///
+    /// The `spans` must be ordered and their `end_byte`s must cover the whole `text`. Lines are
+    /// broken across span boundaries as though the text were a single string. If some `Wrap` is
+    /// given, lines wrap around the `max_width`.
+    pub fn span_infos<'a>(
+        text: &'a str,
+        spans: &'a [SpanMetrics<'a>],
+        max_width: Scalar,
+        maybe_wrap: Option<Wrap>,
+    ) -> SpanInfos<'a> {
+        SpanInfos {
+            text: text,
+            spans: spans,
+            max_width: max_width,
+            maybe_wrap: maybe_wrap,
+            start_byte: 0,
+            start_char: 0,
+            last_break: None,
+        }
+    }
+
+    /// The position of every cursor within the line of the `text` described by `info`, relative to
+    /// the start of the line.
+    ///
+    /// Each `char` is measured with the font of the span in which it lies, as for `span_infos`.
+    /// The given `word_spacing` is added after each word space (see `is_word_space`).
+    pub fn span_caret_xs(
+        text: &str,
+        info: &Info,
+        spans: &[SpanMetrics],
+        word_spacing: Scalar,
+    ) -> Vec<Scalar> {
+        let mut advance = span_advance(spans, info.start_byte);
+        let mut x = 0.0;
+        let mut xs = vec![x];
+        for (byte_i, ch) in text[info.byte_range()].char_indices() {
+            x += advance(byte_i, ch);
+            if is_word_space(ch) {
+                x += word_spacing;
+            }
+            xs.push(x);
+        }
+        xs
+    }
+
     /// Produce an iterator yielding the bounding `Rect` for each line in the text.
     ///
     /// This function assumes that `font_size` is the same `FontSize` used to produce the `Info`s
@@ -1426,6 +2697,7 @@ pub mod line {
                 super::Justify::Left => range.align_start_of(bounding_rect.x),
                 super::Justify::Center => range.align_middle_of(bounding_rect.x),
                 super::Justify::Right => range.align_end_of(bounding_rect.x),
+                super::Justify::Full => full_x(first_info, bounding_rect.x),
             };
 
             // Calculate the `y` `Range` of the first line `Rect`.
@@ -1447,6 +2719,20 @@ pub mod line {
             next: first_rect,
             x_align: x_align,
             line_spacing: line_spacing,
+            bounding_x: bounding_rect.x,
+        }
+    }
+
+    // The `x` `Range` of a line aligned via `Justify::Full`.
+    //
+    // Lines that are broken by wrapping span the whole `bounding_x`, while all others are aligned
+    // to its start.
+    fn full_x(info: Info, bounding_x: Range) -> Range {
+        match info.end_break {
+            Break::Wrap { .. } => bounding_x,
+            Break::Newline { .. } | Break::End { .. } => {
+                Range::new(0.0, info.width).align_start_of(bounding_x)
+            }
         }
     }
 
@@ -1459,18 +2745,22 @@ pub mod line {
     pub fn selected_rects<'a, I>(
         lines_with_rects: I,
         font: &'a super::Font,
+        text: &'a str,
         font_size: FontSize,
+        justify: super::Justify,
         start: super::cursor::Index,
         end: super::cursor::Index,
     ) -> SelectedRects<'a, I>
     where
-        I: Iterator<Item = (&'a str, Rect)>,
+        I: Iterator<Item = (Info, Rect)>,
     {
         SelectedRects {
             selected_char_rects_per_line: super::glyph::selected_rects_per_line(
                 lines_with_rects,
                 font,
+                text,
                 font_size,
+                justify,
                 start,
                 end,
             ),
@@ -1492,99 +2782,200 @@ pub mod line {
                 ref mut start_byte,
                 ref mut start_char,
                 ref mut last_break,
+                #[cfg(feature = "shaping")]
+                ref shaped,
+                #[cfg(feature = "shaping")]
+                maybe_wrap,
             } = *self;
-
-            match next_break_fn(&text[*start_byte..], font, font_size, max_width) {
-                (next @ Break::Newline { .. }, width) | (next @ Break::Wrap { .. }, width) => {
-                    let next_break = match next {
-                        Break::Newline {
-                            byte,
-                            char,
-                            len_bytes,
-                        } => Break::Newline {
-                            byte: *start_byte + byte,
-                            char: *start_char + char,
-                            len_bytes: len_bytes,
-                        },
-                        Break::Wrap {
-                            byte,
-                            char,
-                            len_bytes,
-                        } => Break::Wrap {
-                            byte: *start_byte + byte,
-                            char: *start_char + char,
-                            len_bytes: len_bytes,
-                        },
-                        _ => unreachable!(),
-                    };
-
-                    let info = Info {
-                        start_byte: *start_byte,
-                        start_char: *start_char,
-                        end_break: next_break,
-                        width: width,
-                    };
-
-                    match next {
-                        Break::Newline {
-                            byte,
-                            char,
-                            len_bytes,
-                        }
-                        | Break::Wrap {
-                            byte,
-                            char,
-                            len_bytes,
-                        } => {
-                            *start_byte = info.start_byte + byte + len_bytes;
-                            *start_char = info.start_char + char + 1;
+            #[cfg(feature = "shaping")]
+            {
+                if let Some(ref shaped) = *shaped {
+                    let offset = *start_byte;
+                    let rest = &text[offset..];
+                    let advance = |byte_i: usize, _| shaped.advances[offset + byte_i];
+                    let next = match maybe_wrap {
+                        None => next_break_with(rest, advance),
+                        Some(Wrap::Character) => {
+                            next_break_by_character_with(rest, max_width, advance)
                         }
-                        _ => unreachable!(),
-                    };
-                    *last_break = Some(next_break);
-                    Some(info)
-                }
-
-                (Break::End { char, .. }, width) => {
-                    // if the last line ends in a new line, or the entire text is empty, return an empty line Info
-                    let empty_line = {
-                        match *last_break {
-                            Some(last_break_) => match last_break_ {
-                                Break::Newline { .. } => true,
-                                _ => false,
-                            },
-                            None => true,
+                        Some(Wrap::Whitespace) => {
+                            next_break_by_whitespace_with(rest, max_width, advance)
                         }
                     };
-                    if *start_byte < text.len() || empty_line {
-                        let total_bytes = text.len();
-                        let total_chars = *start_char + char;
-                        let end_break = Break::End {
-                            byte: total_bytes,
-                            char: total_chars,
-                        };
-                        let info = Info {
-                            start_byte: *start_byte,
-                            start_char: *start_char,
-                            end_break: end_break,
-                            width: width,
-                        };
-                        *start_byte = total_bytes;
-                        *start_char = total_chars;
-                        *last_break = Some(end_break);
-                        Some(info)
-                    } else {
-                        None
-                    }
+                    let info = next_info(text, start_byte, start_char, last_break, next);
+                    return info.map(|mut info| {
+                        let scale = super::pt_to_scale(font_size);
+                        let range = info.byte_range();
+                        let line = super::shaping::shape_line(
+                            &shaped.bidi,
+                            range,
+                            shaped.face,
+                            scale,
+                            0.0,
+                        );
+                        info.width = line.width;
+                        info
+                    });
                 }
             }
+            let next = next_break_fn(&text[*start_byte..], font, font_size, max_width);
+            next_info(text, start_byte, start_char, last_break, next)
         }
     }
 
-    impl<I> Iterator for Rects<I>
-    where
-        I: Iterator<Item = Info>,
-    {
+    impl<'a> Iterator for SpanInfos<'a> {
+        type Item = Info;
+        fn next(&mut self) -> Option<Self::Item> {
+            let SpanInfos {
+                text,
+                spans,
+                max_width,
+                maybe_wrap,
+                ref mut start_byte,
+                ref mut start_char,
+                ref mut last_break,
+            } = *self;
+
+            let offset = *start_byte;
+            let advance = span_advance(spans, offset);
+            let rest = &text[offset..];
+            let next = match maybe_wrap {
+                None => next_break_with(rest, advance),
+                Some(Wrap::Character) => next_break_by_character_with(rest, max_width, advance),
+                Some(Wrap::Whitespace) => next_break_by_whitespace_with(rest, max_width, advance),
+            };
+            next_info(text, start_byte, start_char, last_break, next)
+        }
+    }
+
+    // Measure each character at a byte index relative to the given `offset` with the font of the
+    // span in which it lies. Kerning is not applied between characters of different spans.
+    fn span_advance<'a>(
+        spans: &'a [SpanMetrics<'a>],
+        offset: usize,
+    ) -> impl 'a + FnMut(usize, char) -> Scalar {
+        let mut span_ix = 0;
+        let mut last_glyph = None;
+        move |byte_i: usize, ch: char| {
+            let byte = offset + byte_i;
+            let mut changed = false;
+            while span_ix + 1 < spans.len() && spans[span_ix].end_byte <= byte {
+                span_ix += 1;
+                changed = true;
+            }
+            if changed {
+                last_glyph = None;
+            }
+            let span = match spans.get(span_ix) {
+                Some(span) => span,
+                None => return 0.0,
+            };
+            let scale = super::pt_to_scale(span.font_size);
+            advance_width(ch, span.font, scale, &mut last_glyph)
+        }
+    }
+
+    // Produce the `Info` for the line starting at `start_byte` given the `next` break within the
+    // remaining text, advancing the `start_byte` and `start_char` to the start of the next line.
+    fn next_info(
+        text: &str,
+        start_byte: &mut usize,
+        start_char: &mut usize,
+        last_break: &mut Option<Break>,
+        next: (Break, Scalar),
+    ) -> Option<Info> {
+        match next {
+            (next @ Break::Newline { .. }, width) | (next @ Break::Wrap { .. }, width) => {
+                let next_break = match next {
+                    Break::Newline {
+                        byte,
+                        char,
+                        len_bytes,
+                    } => Break::Newline {
+                        byte: *start_byte + byte,
+                        char: *start_char + char,
+                        len_bytes: len_bytes,
+                    },
+                    Break::Wrap {
+                        byte,
+                        char,
+                        len_bytes,
+                    } => Break::Wrap {
+                        byte: *start_byte + byte,
+                        char: *start_char + char,
+                        len_bytes: len_bytes,
+                    },
+                    _ => unreachable!(),
+                };
+
+                let info = Info {
+                    start_byte: *start_byte,
+                    start_char: *start_char,
+                    end_break: next_break,
+                    width: width,
+                };
+
+                match next {
+                    Break::Newline {
+                        byte,
+                        char,
+                        len_bytes,
+                    }
+                    | Break::Wrap {
+                        byte,
+                        char,
+                        len_bytes,
+                    } => {
+                        let break_byte = info.start_byte + byte;
+                        let skipped = &text[break_byte..break_byte + len_bytes];
+                        *start_byte = break_byte + len_bytes;
+                        *start_char = info.start_char + char + skipped.chars().count();
+                    }
+                    _ => unreachable!(),
+                };
+                *last_break = Some(next_break);
+                Some(info)
+            }
+
+            (Break::End { char, .. }, width) => {
+                // if the last line ends in a new line, or the entire text is empty, return an empty line Info
+                let empty_line = {
+                    match *last_break {
+                        Some(last_break_) => match last_break_ {
+                            Break::Newline { .. } => true,
+                            _ => false,
+                        },
+                        None => true,
+                    }
+                };
+                if *start_byte < text.len() || empty_line {
+                    let total_bytes = text.len();
+                    let total_chars = *start_char + char;
+                    let end_break = Break::End {
+                        byte: total_bytes,
+                        char: total_chars,
+                    };
+                    let info = Info {
+                        start_byte: *start_byte,
+                        start_char: *start_char,
+                        end_break: end_break,
+                        width: width,
+                    };
+                    *start_byte = total_bytes;
+                    *start_char = total_chars;
+                    *last_break = Some(end_break);
+                    Some(info)
+                } else {
+                    None
+                }
+            }
+        }
+    }
+
+    impl<I> Iterator for Rects<I>
+    where
+        I: Iterator<Item = Info>,
+    {
         type Item = Rect;
         fn next(&mut self) -> Option<Self::Item> {
             let Rects {
@@ -1592,6 +2983,7 @@ pub mod line {
                 ref mut infos,
                 x_align,
                 line_spacing,
+                bounding_x,
             } = *self;
             next.map(|line_rect| {
                 *next = infos.next().map(|info| {
@@ -1607,6 +2999,7 @@ pub mod line {
                             super::Justify::Left => range.align_start_of(line_rect.x),
                             super::Justify::Center => range.align_middle_of(line_rect.x),
                             super::Justify::Right => range.align_end_of(line_rect.x),
+                            super::Justify::Full => full_x(info, bounding_x),
                         }
                     };
 
@@ -1618,9 +3011,52 @@ pub mod line {
         }
     }
 
+    impl<'font, 'b> Layout<'font, 'b> {
+        /// The space added after the most recently yielded glyph.
+        ///
+        /// This is the `word_spacing` if the glyph is a word space, otherwise `0.0`.
+        pub fn space_after(&self) -> f32 {
+            self.space_after
+        }
+
+        // The next glyph before word spacing is applied, along with whether or not it is a word
+        // space.
+        //
+        // RustType yields a glyph for every `char`, including control characters, so each glyph
+        // is paired with the `char` at the same position.
+        fn next_glyph(&mut self) -> Option<(::rusttype::PositionedGlyph<'font>, bool)> {
+            #[cfg(feature = "shaping")]
+            {
+                if let Some(ref mut shaped) = self.shaped {
+                    return shaped.next();
+                }
+            }
+            let g = self.layout.next()?;
+            let is_word_space = self.chars.next().map(is_word_space).unwrap_or(false);
+            Some((g, is_word_space))
+        }
+    }
+
+    impl<'font, 'b> Iterator for Layout<'font, 'b> {
+        type Item = ::rusttype::PositionedGlyph<'font>;
+        fn next(&mut self) -> Option<Self::Item> {
+            let (g, is_word_space) = self.next_glyph()?;
+            let offset = self.offset;
+            self.space_after = if is_word_space {
+                self.word_spacing
+            } else {
+                0.0
+            };
+            self.offset += self.space_after;
+            let position = g.position();
+            let point = super::rt::point(position.x + offset, position.y);
+            Some(g.into_unpositioned().positioned(point))
+        }
+    }
+
     impl<'a, I> Iterator for SelectedRects<'a, I>
     where
-        I: Iterator<Item = (&'a str, Rect)>,
+        I: Iterator<Item = (Info, Rect)>,
     {
         type Item = Rect;
         fn next(&mut self) -> Option<Self::Item> {
@@ -1637,3 +3073,992 @@ pub mod line {
         }
     }
 }
+
+/// Text made up of consecutive spans, each with their own color, font, size and decorations.
+///
+/// This is the layout logic behind the `widget::Text::rich` constructor.
+pub mod rich {
+    use super::{font, line, Justify};
+    use position::{Range, Rect, Scalar};
+    use utils;
+    use {Color, FontSize};
+
+    /// A slice of text along with the styling with which it should be displayed.
+    ///
+    /// Any styling that is not specified falls back to the style of the `Text` widget.
+    #[derive(Copy, Clone, Debug, PartialEq)]
+    pub struct Span<'a> {
+        /// The text within the span.
+        pub text: &'a str,
+        /// The color of the text.
+        pub color: Option<Color>,
+        /// The font used to display the text.
+        pub font_id: Option<font::Id>,
+        /// The size of the font.
+        pub font_size: Option<FontSize>,
+        /// Whether or not a line is drawn beneath the text.
+        pub underline: bool,
+        /// The color of the background highlight drawn behind the text, if any.
+        pub highlight: Option<Color>,
+    }
+
+    /// The styling of a `Span` once resolved against the style of the `Text` widget.
+    #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+    #[derive(Copy, Clone, Debug, PartialEq)]
+    pub struct Style {
+        /// The color of the text.
+        pub color: Color,
+        /// The font used to display the text.
+        pub font_id: font::Id,
+        /// The size of the font.
+        pub font_size: FontSize,
+        /// Whether or not a line is drawn beneath the text.
+        pub underline: bool,
+        /// The color of the background highlight drawn behind the text, if any.
+        pub highlight: Option<Color>,
+    }
+
+    /// A portion of a single line of text that is displayed with a single `Style`.
+    #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+    #[derive(Copy, Clone, Debug, PartialEq)]
+    pub struct Run {
+        /// The range of the run within the text of all spans, along with its natural width.
+        ///
+        /// The `end_break` is a `Break::Wrap` if the run is stretched to the width of its `rect`
+        /// via `Justify::Full`, otherwise it is a `Break::End`.
+        pub info: line::Info,
+        /// The area occupied by the run, relative to the top left corner of the laid out text.
+        ///
+        /// The bottom of the `Rect` is the baseline of the line, while its height is the font size.
+        pub rect: Rect,
+        /// The styling with which the run is displayed.
+        pub style: Style,
+    }
+
+    /// The result of laying out a list of `Span`s.
+    #[derive(Clone, Debug, PartialEq)]
+    pub struct Layout {
+        /// The text of all spans concatenated together.
+        pub text: String,
+        /// Each line of the `text`.
+        pub line_infos: Vec<line::Info>,
+        /// Each run of text in order of lines from top to bottom, and from left to right within each
+        /// line.
+        pub runs: Vec<Run>,
+        /// The natural width of the widest line.
+        pub width: Scalar,
+        /// The total height of all lines.
+        pub height: Scalar,
+    }
+
+    impl<'a> Span<'a> {
+        /// A span of text that is displayed with the style of the `Text` widget.
+        pub fn new(text: &'a str) -> Self {
+            Span {
+                text: text,
+                color: None,
+                font_id: None,
+                font_size: None,
+                underline: false,
+                highlight: None,
+            }
+        }
+
+        /// Display the span with the given color.
+        pub fn color(mut self, color: Color) -> Self {
+            self.color = Some(color);
+            self
+        }
+
+        /// Display the span with the given font.
+        pub fn font_id(mut self, font_id: font::Id) -> Self {
+            self.font_id = Some(font_id);
+            self
+        }
+
+        /// Display the span with the given font size.
+        pub fn font_size(mut self, font_size: FontSize) -> Self {
+            self.font_size = Some(font_size);
+            self
+        }
+
+        /// Draw a line beneath the span.
+        pub fn underline(mut self) -> Self {
+            self.underline = true;
+            self
+        }
+
+        /// Draw a background of the given color behind the span.
+        pub fn highlight(mut self, color: Color) -> Self {
+            self.highlight = Some(color);
+            self
+        }
+
+        /// Resolve the styling of the span, falling back to the given `base` style.
+        pub fn style(&self, base: Style) -> Style {
+            Style {
+                color: self.color.unwrap_or(base.color),
+                font_id: self.font_id.unwrap_or(base.font_id),
+                font_size: self.font_size.unwrap_or(base.font_size),
+                underline: self.underline || base.underline,
+                highlight: self.highlight.or(base.highlight),
+            }
+        }
+    }
+
+    impl Run {
+        /// The area behind the run that is filled by its highlight, given the run's font.
+        ///
+        /// This extends the `rect` down to the font's descent, relative to the same origin.
+        pub fn highlight_rect(&self, font: &super::Font) -> Rect {
+            let descent = font
+                .v_metrics(super::pt_to_scale(self.style.font_size))
+                .descent;
+            let bottom = self.rect.bottom() + descent as Scalar;
+            Rect {
+                x: self.rect.x,
+                y: Range::new(bottom, self.rect.top()),
+            }
+        }
+    }
+
+    /// Lay out the given `spans` within the given `width`.
+    ///
+    /// Lines are broken across spans as though their text were a single string, wrapping via the
+    /// given `maybe_wrap` if some. Each line is as tall as the largest font size within it, and
+    /// consecutive lines are separated by the `line_spacing`. Spans whose font cannot be found
+    /// within the `fonts` are skipped.
+    ///
+    /// Each span is split into runs displayed with the fallbacks of its font wherever its font
+    /// is missing a `char`, trying the given default `fallbacks` if its font has none of its own.
+    /// See `font::Map::chain`.
+    pub fn layout(
+        spans: &[Span],
+        base: Style,
+        fonts: &font::Map,
+        fallbacks: &[font::Id],
+        width: Scalar,
+        maybe_wrap: Option<line::Wrap>,
+        justify: Justify,
+        line_spacing: Scalar,
+    ) -> Layout {
+        // Concatenate the text of all spans and resolve their styles.
+        let mut text = String::new();
+        let mut styles = Vec::with_capacity(spans.len());
+        let mut metrics = Vec::with_capacity(spans.len());
+        for span in spans {
+            let style = span.style(base);
+            if fonts.get(style.font_id).is_none() || span.text.is_empty() {
+                continue;
+            }
+            for (range, font_id) in fonts.fallback_runs(span.text, style.font_id, fallbacks) {
+                let style = Style {
+                    font_id: font_id,
+                    ..style
+                };
+                let start_byte = text.len();
+                text.push_str(&span.text[range]);
+                styles.push((start_byte, style));
+                metrics.push(line::SpanMetrics {
+                    end_byte: text.len(),
+                    font: fonts
+                        .get(font_id)
+                        .expect("chained fonts are within the map"),
+                    font_size: style.font_size,
+                });
+            }
+        }
+
+        let line_infos: Vec<_> = line::span_infos(&text, &metrics, width, maybe_wrap).collect();
+
+        let mut runs = Vec::new();
+        let mut max_width = 0.0;
+        let mut top = 0.0;
+        for (i, info) in line_infos.iter().enumerate() {
+            let (line_start, line_end) = (info.start_byte, info.end_byte());
+            let line_text = &text[line_start..line_end];
+
+            // The spans that overlap the line, along with the byte range of each overlap.
+            let overlaps = styles
+                .iter()
+                .zip(&metrics)
+                .map(|(&(start, style), m)| {
+                    (start.max(line_start), m.end_byte.min(line_end), style)
+                })
+                .filter(|&(start, end, _)| start < end);
+
+            // Empty lines take the size of the span in which they begin, or of the last span.
+            let line_height = overlaps
+                .clone()
+                .map(|(_, _, style)| style.font_size)
+                .max()
+                .or_else(|| {
+                    let ix = metrics.iter().position(|m| line_start < m.end_byte);
+                    let ix = ix.unwrap_or(styles.len().saturating_sub(1));
+                    styles.get(ix).map(|&(_, style)| style.font_size)
+                })
+                .unwrap_or(base.font_size) as Scalar;
+            if i > 0 {
+                top -= line_spacing;
+            }
+            let baseline = top - line_height;
+            top = baseline;
+
+            // Wrapped lines are stretched by adding space after each whitespace character.
+            let word_spacing = match (justify, info.end_break) {
+                (Justify::Full, line::Break::Wrap { .. }) => {
+                    let num_spaces = line_text
+                        .chars()
+                        .filter(|&ch| line::is_word_space(ch))
+                        .count();
+                    let extra = width - info.width;
+                    if num_spaces == 0 || extra < 1e-6 {
+                        0.0
+                    } else {
+                        extra / num_spaces as Scalar
+                    }
+                }
+                _ => 0.0,
+            };
+            let mut x = match justify {
+                Justify::Left | Justify::Full => 0.0,
+                Justify::Center => (width - info.width) / 2.0,
+                Justify::Right => width - info.width,
+            };
+            max_width = utils::partial_max(max_width, info.width);
+
+            for (start, end, style) in overlaps {
+                let run_text = &text[start..end];
+                let font = fonts.get(style.font_id).expect("font was resolved above");
+                let run_width = line::infos(run_text, font, style.font_size)
+                    .next()
+                    .map(|info| info.width)
+                    .unwrap_or(0.0);
+                let num_spaces = run_text
+                    .chars()
+                    .filter(|&ch| line::is_word_space(ch))
+                    .count();
+                let stretched_width = run_width + word_spacing * num_spaces as Scalar;
+                let start_char = info.start_char + text[line_start..start].chars().count();
+                let end_char = start_char + run_text.chars().count();
+                let end_break = if word_spacing > 0.0 && num_spaces > 0 {
+                    line::Break::Wrap {
+                        byte: end,
+                        char: end_char,
+                        len_bytes: 0,
+                    }
+                } else {
+                    line::Break::End {
+                        byte: end,
+                        char: end_char,
+                    }
+                };
+                let run = Run {
+                    info: line::Info {
+                        start_byte: start,
+                        start_char: start_char,
+                        end_break: end_break,
+                        width: run_width,
+                    },
+                    rect: Rect {
+                        x: Range::new(x, x + stretched_width),
+                        y: Range::new(baseline, baseline + style.font_size as Scalar),
+                    },
+                    style: style,
+                };
+                runs.push(run);
+                x += stretched_width;
+            }
+        }
+
+        Layout {
+            text: text,
+            line_infos: line_infos,
+            runs: runs,
+            width: max_width,
+            height: -top,
+        }
+    }
+}
+
+/// A cache of the layout of text that is shared across frames via the `Ui`.
+///
+/// Text whose string and styling are unchanged since a previous call to `Ui::set_widgets` is not
+/// measured again, which matters when many lines of text are displayed at once.
+pub mod cache {
+    use super::{font, line, rich, Ellipsis, Justify};
+    use fnv;
+    use std;
+    use std::hash::{Hash, Hasher};
+    use {Color, FontSize, Scalar};
+
+    /// The number of frames for which an unused entry is retained by default.
+    pub const DEFAULT_MAX_UNUSED_FRAMES: u64 = 60;
+
+    /// Identifies the layout of some text with some styling.
+    ///
+    /// The text is identified by its hash and length rather than retained. The `Cache` compares
+    /// the text of each entry upon lookup, so that texts whose keys collide never share a layout.
+    #[derive(Copy, Clone, Debug, Hash, PartialEq, Eq)]
+    pub struct Key {
+        text_hash: u64,
+        text_len: usize,
+        fonts: u64,
+        font_size: FontSize,
+        wrap: Option<(line::Wrap, u64)>,
+        justify: Justify,
+        style: u64,
+    }
+
+    /// The layout of recently laid out text, i.e. the `line::Info` of each line of plain text, the
+    /// `rich::Layout` of rich text or the truncation of text that exceeds its width.
+    ///
+    /// Each call to `next_frame` (made by `Ui::set_widgets`) evicts the entries that have gone
+    /// unused for more than `max_unused_frames` frames.
+    #[derive(Debug)]
+    pub struct Cache {
+        entries: std::cell::RefCell<fnv::FnvHashMap<Key, Entry>>,
+        frame: u64,
+        max_unused_frames: u64,
+    }
+
+    #[derive(Debug)]
+    struct Entry {
+        // The text that was laid out, compared upon lookup in case of a colliding `Key`.
+        text: Box<str>,
+        layout: Layout,
+        last_used: u64,
+    }
+
+    #[derive(Clone, Debug)]
+    enum Layout {
+        Lines(std::sync::Arc<[line::Info]>),
+        Rich(std::sync::Arc<rich::Layout>),
+        Truncated(Option<std::sync::Arc<str>>),
+    }
+
+    impl Key {
+        /// The key for the given `text` laid out with the given chain of `fonts` (see
+        /// `font::Map::chain`) at the given `font_size`, wrapped at some maximum width if any, and
+        /// aligned with the given `justify`.
+        pub fn new(
+            text: &str,
+            fonts: &[font::Id],
+            font_size: FontSize,
+            maybe_wrap: Option<(line::Wrap, Scalar)>,
+            justify: Justify,
+        ) -> Self {
+            Key {
+                text_hash: hash(text),
+                text_len: text.len(),
+                fonts: hash(fonts),
+                font_size,
+                wrap: maybe_wrap.map(|(wrap, max_width)| (wrap, max_width.to_bits())),
+                justify,
+                style: 0,
+            }
+        }
+
+        /// The key for the given `text` with each line wider than `max_width` truncated via
+        /// `line::truncate` with the given chain of `fonts`, `font_size` and `ellipsis`.
+        pub fn truncated(
+            text: &str,
+            fonts: &[font::Id],
+            font_size: FontSize,
+            max_width: Scalar,
+            ellipsis: Ellipsis,
+        ) -> Self {
+            Key {
+                text_hash: hash(text),
+                text_len: text.len(),
+                fonts: hash(fonts),
+                font_size,
+                wrap: None,
+                justify: Justify::Left,
+                style: hash(&(ellipsis, max_width.to_bits())),
+            }
+        }
+
+        /// The key for the given rich text `spans` resolved against the `base` style, laid out
+        /// via `rich::layout` with the given default `fallbacks` and remaining arguments.
+        ///
+        /// As with `new`, fallbacks set for individual fonts via `font::Map::set_fallbacks` are not
+        /// part of the key, so the cache should be cleared after changing them.
+        pub fn rich(
+            spans: &[rich::Span],
+            base: rich::Style,
+            fallbacks: &[font::Id],
+            width: Scalar,
+            maybe_wrap: Option<line::Wrap>,
+            justify: Justify,
+            line_spacing: Scalar,
+        ) -> Self {
+            let mut text = fnv::FnvHasher::default();
+            let mut style = fnv::FnvHasher::default();
+            for span in spans {
+                span.text.hash(&mut text);
+                let span_style = span.style(base);
+                hash_color(span_style.color, &mut style);
+                span_style.font_id.hash(&mut style);
+                span_style.font_size.hash(&mut style);
+                span_style.underline.hash(&mut style);
+                span_style.highlight.is_some().hash(&mut style);
+                if let Some(highlight) = span_style.highlight {
+                    hash_color(highlight, &mut style);
+                }
+            }
+            width.to_bits().hash(&mut style);
+            line_spacing.to_bits().hash(&mut style);
+            Key {
+                text_hash: text.finish(),
+                text_len: spans.iter().map(|span| span.text.len()).sum(),
+                fonts: hash(fallbacks),
+                font_size: base.font_size,
+                wrap: maybe_wrap.map(|wrap| (wrap, width.to_bits())),
+                justify,
+                style: style.finish(),
+            }
+        }
+    }
+
+    impl Cache {
+        /// An empty cache that retains entries for the given number of unused frames.
+        pub fn new(max_unused_frames: u64) -> Self {
+            Cache {
+                entries: std::cell::RefCell::new(fnv::FnvHashMap::default()),
+                frame: 0,
+                max_unused_frames,
+            }
+        }
+
+        /// The number of frames for which an unused entry is retained.
+        pub fn max_unused_frames(&self) -> u64 {
+            self.max_unused_frames
+        }
+
+        /// Specify the number of frames for which an unused entry is retained.
+        pub fn set_max_unused_frames(&mut self, frames: u64) {
+            self.max_unused_frames = frames;
+        }
+
+        /// The number of cached layouts.
+        pub fn len(&self) -> usize {
+            self.entries.borrow().len()
+        }
+
+        /// Whether or not the cache is empty.
+        pub fn is_empty(&self) -> bool {
+            self.len() == 0
+        }
+
+        /// Remove every cached layout, e.g. after the fonts of a `font::Map` have been replaced.
+        pub fn clear(&mut self) {
+            self.entries.get_mut().clear();
+        }
+
+        /// The `line::Info`s cached for the given `text` and its `key`, produced via the given
+        /// `layout` function and cached if there are none.
+        pub fn line_infos<F, I>(
+            &self,
+            key: Key,
+            text: &str,
+            layout: F,
+        ) -> std::sync::Arc<[line::Info]>
+        where
+            F: FnOnce() -> I,
+            I: IntoIterator<Item = line::Info>,
+        {
+            if let Some(Layout::Lines(line_infos)) = self.get(key, |cached| cached == text) {
+                return line_infos;
+            }
+            let line_infos: std::sync::Arc<[line::Info]> =
+                layout().into_iter().collect::<Vec<_>>().into();
+            self.insert(key, text.into(), Layout::Lines(line_infos.clone()));
+            line_infos
+        }
+
+        /// The `rich::Layout` cached for the given `spans` and their `key`, produced via the given
+        /// `layout` function and cached if there is none.
+        pub fn rich_layout<F>(
+            &self,
+            key: Key,
+            spans: &[rich::Span],
+            layout: F,
+        ) -> std::sync::Arc<rich::Layout>
+        where
+            F: FnOnce() -> rich::Layout,
+        {
+            let is_text = |cached: &str| {
+                let mut rest = cached;
+                for span in spans {
+                    if !rest.starts_with(span.text) {
+                        return false;
+                    }
+                    rest = &rest[span.text.len()..];
+                }
+                rest.is_empty()
+            };
+            if let Some(Layout::Rich(rich_layout)) = self.get(key, is_text) {
+                return rich_layout;
+            }
+            let text: String = spans.iter().map(|span| span.text).collect();
+            let rich_layout = std::sync::Arc::new(layout());
+            self.insert(key, text.into(), Layout::Rich(rich_layout.clone()));
+            rich_layout
+        }
+
+        /// The truncation cached for the given `text` and its `key`, produced via the given
+        /// `truncate` function and cached if there is none.
+        ///
+        /// As for `line::truncate`, `None` indicates that no line of the `text` was truncated.
+        pub fn truncated<F>(&self, key: Key, text: &str, truncate: F) -> Option<std::sync::Arc<str>>
+        where
+            F: FnOnce() -> Option<String>,
+        {
+            if let Some(Layout::Truncated(truncated)) = self.get(key, |cached| cached == text) {
+                return truncated;
+            }
+            let truncated: Option<std::sync::Arc<str>> = truncate().map(Into::into);
+            self.insert(key, text.into(), Layout::Truncated(truncated.clone()));
+            truncated
+        }
+
+        /// Begin the next frame, evicting each entry that has gone unused for more than
+        /// `max_unused_frames` frames.
+        pub fn next_frame(&mut self) {
+            self.frame += 1;
+            let (frame, max_unused_frames) = (self.frame, self.max_unused_frames);
+            self.entries
+                .get_mut()
+                .retain(|_, entry| frame - entry.last_used <= max_unused_frames);
+        }
+
+        // The layout cached for the given `key`, marking it as used within the current frame.
+        //
+        // Returns `None` if the text of the entry is not that for which the layout is requested.
+        fn get<F>(&self, key: Key, is_text: F) -> Option<Layout>
+        where
+            F: FnOnce(&str) -> bool,
+        {
+            let frame = self.frame;
+            let mut entries = self.entries.borrow_mut();
+            let entry = entries.get_mut(&key)?;
+            if !is_text(&entry.text) {
+                return None;
+            }
+            entry.last_used = frame;
+            Some(entry.layout.clone())
+        }
+
+        fn insert(&self, key: Key, text: Box<str>, layout: Layout) {
+            let last_used = self.frame;
+            let entry = Entry {
+                text,
+                layout,
+                last_used,
+            };
+            self.entries.borrow_mut().insert(key, entry);
+        }
+    }
+
+    impl Default for Cache {
+        fn default() -> Self {
+            Cache::new(DEFAULT_MAX_UNUSED_FRAMES)
+        }
+    }
+
+    fn hash<T: ?Sized + Hash>(value: &T) -> u64 {
+        let mut hasher = fnv::FnvHasher::default();
+        value.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    fn hash_color<H: Hasher>(color: Color, state: &mut H) {
+        for component in &color.to_fsa() {
+            component.to_bits().hash(state);
+        }
+    }
+}
+
+/// Complex text shaping and bidirectional reordering, available via the `shaping` feature.
+///
+/// By default, conrod lays out text with one glyph per `char`, which cannot display scripts such
+/// as Arabic or Devanagari, ligatures or right-to-left text correctly. This module shapes each
+/// line with `rustybuzz` after splitting it into runs of a single direction via the Unicode
+/// Bidirectional Algorithm, and reorders those runs for display.
+///
+/// Shaping requires the raw data of each font, which is retained by the `font::Map` for fonts
+/// loaded via `insert_from_file` or `insert_from_bytes`. Text using other fonts is laid out as
+/// usual. Lines are wrapped using the advances of each paragraph shaped as a whole, while the
+/// shaped width of each line is used for its alignment.
+#[cfg(feature = "shaping")]
+pub mod shaping {
+    use position::{Range, Rect, Scalar};
+    use rustybuzz;
+    use std;
+    use unicode_bidi::{BidiInfo, ParagraphInfo};
+
+    /// The bidirectional structure of some text, shared between each of the lines shaped from it.
+    #[derive(Debug)]
+    pub struct Bidi<'a> {
+        info: BidiInfo<'a>,
+    }
+
+    /// A font prepared for shaping, borrowing the data retained by the `font::Map`.
+    pub struct Face<'a> {
+        face: rustybuzz::Face<'a>,
+        // The unscaled height of the font, by which `rusttype` divides the scale of each glyph.
+        height: f32,
+    }
+
+    /// A single glyph produced by shaping a line of text.
+    #[derive(Copy, Clone, Debug, PartialEq)]
+    pub struct Glyph {
+        /// The glyph within the font.
+        pub id: super::GlyphId,
+        /// The byte index within the text of the first character of the cluster from which the
+        /// glyph was produced.
+        pub cluster: usize,
+        /// The position of the glyph's origin relative to the start of the line's baseline, with
+        /// the *y* axis pointing downwards as for `rusttype` glyphs.
+        pub position: [Scalar; 2],
+        /// The distance from the glyph's origin to the origin of the next glyph.
+        pub advance: Scalar,
+        /// Whether or not the glyph was produced from a word space, after which `Justify::Full`
+        /// adds space. See `line::is_word_space`.
+        pub is_whitespace: bool,
+    }
+
+    /// A single line of text shaped and reordered for display.
+    #[derive(Clone, Debug, PartialEq)]
+    pub struct Line {
+        /// Every glyph within the line in visual order from left to right.
+        pub glyphs: Vec<Glyph>,
+        /// The range occupied by each `char` of the line along the *x* axis in logical order,
+        /// along with whether or not the `char` is displayed right-to-left.
+        ///
+        /// Where multiple `char`s are shaped into a single glyph, the glyph is divided evenly
+        /// between them.
+        pub chars: Vec<(Range, bool)>,
+        /// The total width of the line.
+        pub width: Scalar,
+    }
+
+    impl<'a> Bidi<'a> {
+        /// Resolve the direction of every paragraph and `char` within the given `text`.
+        pub fn new(text: &'a str) -> Self {
+            Bidi {
+                info: BidiInfo::new(text, None),
+            }
+        }
+
+        /// The text from which the `Bidi` was produced.
+        pub fn text(&self) -> &'a str {
+            self.info.text
+        }
+
+        // The paragraph containing the given byte index.
+        fn paragraph(&self, byte: usize) -> Option<&ParagraphInfo> {
+            self.info
+                .paragraphs
+                .iter()
+                .find(|para| para.range.start <= byte && byte < para.range.end)
+        }
+    }
+
+    impl<'a> Face<'a> {
+        /// Prepare the given `font` for shaping using the given `data` from which it was loaded.
+        ///
+        /// Returns `None` if the data cannot be parsed.
+        pub fn new(font: &super::Font, data: &'a [u8]) -> Option<Self> {
+            let face = rustybuzz::Face::from_slice(data, 0)?;
+            let v_metrics = font.v_metrics_unscaled();
+            let height = v_metrics.ascent - v_metrics.descent;
+            Some(Face {
+                face: face,
+                height: height,
+            })
+        }
+    }
+
+    impl Line {
+        /// The space to add after each whitespace glyph in order to stretch the line to the given
+        /// `width`, as for `line::word_spacing`.
+        pub fn word_spacing(&self, width: Scalar) -> Scalar {
+            let num_spaces = self.glyphs.iter().filter(|g| g.is_whitespace).count();
+            let extra = width - self.width;
+            if num_spaces == 0 || extra < 1e-6 {
+                return 0.0;
+            }
+            extra / num_spaces as Scalar
+        }
+
+        /// The position along the *x* axis of every possible cursor position within the line.
+        ///
+        /// The cursor before each `char` is placed at its leading edge, i.e. the left of
+        /// left-to-right `char`s and the right of right-to-left `char`s, while the cursor at the
+        /// end of the line is placed at the trailing edge of the last `char`.
+        pub fn caret_xs(&self) -> Vec<Scalar> {
+            let mut xs: Vec<_> = self
+                .chars
+                .iter()
+                .map(|&(x, rtl)| if rtl { x.end } else { x.start })
+                .collect();
+            let end = match self.chars.last() {
+                Some(&(x, rtl)) => {
+                    if rtl {
+                        x.start
+                    } else {
+                        x.end
+                    }
+                }
+                None => 0.0,
+            };
+            xs.push(end);
+            xs
+        }
+
+        /// The ranges along the *x* axis occupied by the `char`s within the given range of
+        /// indices, from left to right.
+        ///
+        /// Bidirectional text may produce more than one range for a single logical selection.
+        pub fn selected_ranges(&self, chars: std::ops::Range<usize>) -> Vec<Range> {
+            let end = std::cmp::min(chars.end, self.chars.len());
+            let start = std::cmp::min(chars.start, end);
+            let mut xs: Vec<Range> = self.chars[start..end].iter().map(|&(x, _)| x).collect();
+            xs.sort_by(|a, b| {
+                a.start
+                    .partial_cmp(&b.start)
+                    .unwrap_or(std::cmp::Ordering::Equal)
+            });
+            let mut ranges: Vec<Range> = Vec::with_capacity(xs.len());
+            for x in xs {
+                match ranges.last_mut() {
+                    Some(last) if x.start <= last.end + 1e-3 => last.end = last.end.max(x.end),
+                    _ => ranges.push(x),
+                }
+            }
+            ranges
+        }
+    }
+
+    /// Shape the line occupying the given `byte_range` of the `bidi` text at the given `scale`.
+    ///
+    /// The given `word_spacing` is added after each whitespace glyph, as for `line::layout`.
+    pub fn shape_line(
+        bidi: &Bidi,
+        byte_range: std::ops::Range<usize>,
+        face: &Face,
+        scale: super::Scale,
+        word_spacing: Scalar,
+    ) -> Line {
+        let mut line = Line {
+            glyphs: Vec::new(),
+            chars: Vec::new(),
+            width: 0.0,
+        };
+        if byte_range.start >= byte_range.end {
+            return line;
+        }
+
+        // Shape each run of a single direction in visual order.
+        let text = bidi.text();
+        let para = match bidi.paragraph(byte_range.start) {
+            Some(para) => para,
+            None => return line,
+        };
+        let (levels, runs) = bidi.info.visual_runs(para, byte_range.clone());
+        let scale_x = (scale.x / face.height) as Scalar;
+        let scale_y = (scale.y / face.height) as Scalar;
+        let mut x = 0.0;
+        // The range occupied by each cluster, keyed by its byte index.
+        let mut clusters: Vec<(usize, Range)> = Vec::new();
+        for run in runs {
+            let rtl = levels[run.start].is_rtl();
+            let mut buffer = rustybuzz::UnicodeBuffer::new();
+            buffer.push_str(&text[run.clone()]);
+            buffer.set_direction(if rtl {
+                rustybuzz::Direction::RightToLeft
+            } else {
+                rustybuzz::Direction::LeftToRight
+            });
+            let glyph_buffer = rustybuzz::shape(&face.face, &[], buffer);
+            let infos = glyph_buffer.glyph_infos();
+            let positions = glyph_buffer.glyph_positions();
+            for (info, pos) in infos.iter().zip(positions) {
+                let cluster = run.start + info.cluster as usize;
+                let is_whitespace = text[cluster..]
+                    .chars()
+                    .next()
+                    .map(super::line::is_word_space)
+                    .unwrap_or(false);
+                let mut advance = pos.x_advance as Scalar * scale_x;
+                if is_whitespace {
+                    advance += word_spacing;
+                }
+                let glyph = Glyph {
+                    id: super::GlyphId(info.glyph_id as _),
+                    cluster: cluster,
+                    position: [
+                        x + pos.x_offset as Scalar * scale_x,
+                        -pos.y_offset as Scalar * scale_y,
+                    ],
+                    advance: advance,
+                    is_whitespace: is_whitespace,
+                };
+                // The glyphs of each cluster are always adjacent.
+                let glyph_x = Range::new(x, x + advance);
+                match clusters.last_mut() {
+                    Some(&mut (c, ref mut range)) if c == cluster => {
+                        range.start = range.start.min(glyph_x.start);
+                        range.end = range.end.max(glyph_x.end);
+                    }
+                    _ => clusters.push((cluster, glyph_x)),
+                }
+                line.glyphs.push(glyph);
+                x += advance;
+            }
+        }
+        line.width = x;
+
+        // Divide each cluster between its `char`s in logical order. Any `char`s that produced no
+        // glyph are assigned to the preceding cluster.
+        clusters.sort_by_key(|&(c, _)| c);
+        if let Some(first) = clusters.first_mut() {
+            first.0 = byte_range.start;
+        }
+        for (i, &(cluster, range)) in clusters.iter().enumerate() {
+            let end = clusters
+                .get(i + 1)
+                .map(|&(c, _)| c)
+                .unwrap_or(byte_range.end);
+            let rtl = levels[cluster].is_rtl();
+            let num_chars = text[cluster..end].chars().count();
+            let w = range.len() / num_chars as Scalar;
+            for j in 0..num_chars {
+                let j = j as Scalar;
+                let x = if rtl {
+                    Range::new(range.end - (j + 1.0) * w, range.end - j * w)
+                } else {
+                    Range::new(range.start + j * w, range.start + (j + 1.0) * w)
+                };
+                line.chars.push((x, rtl));
+            }
+        }
+        line
+    }
+
+    /// Shape the line of the `bidi` text described by `info`, to be displayed within `line_rect`
+    /// as yielded by `line::rects`.
+    ///
+    /// Lines aligned via `Justify::Full` that are broken by wrapping are stretched across their
+    /// `Rect`. The given `line_infos` should be measured via `line::Infos::shaped` with the same
+    /// `face`.
+    pub fn shape_line_in_rect(
+        bidi: &Bidi,
+        info: &super::line::Info,
+        justify: super::Justify,
+        line_rect: Rect,
+        face: &Face,
+        scale: super::Scale,
+    ) -> Line {
+        let line = shape_line(bidi, info.byte_range(), face, scale, 0.0);
+        if let (super::Justify::Full, super::line::Break::Wrap { .. }) = (justify, info.end_break) {
+            let word_spacing = line.word_spacing(line_rect.w());
+            if word_spacing > 0.0 {
+                return shape_line(bidi, info.byte_range(), face, scale, word_spacing);
+            }
+        }
+        line
+    }
+
+    /// The `Rect`s highlighting the text between the `start` and `end` cursor indices, as for
+    /// `line::selected_rects`.
+    ///
+    /// Selections spanning both directions of bidirectional text may produce more than one `Rect`
+    /// per line.
+    pub fn selected_rects<I>(
+        text: &str,
+        lines_with_rects: I,
+        face: &Face,
+        font_size: super::FontSize,
+        justify: super::Justify,
+        start: super::cursor::Index,
+        end: super::cursor::Index,
+    ) -> Vec<Rect>
+    where
+        I: Iterator<Item = (super::line::Info, Rect)>,
+    {
+        let scale = super::pt_to_scale(font_size);
+        let bidi = Bidi::new(text);
+        let mut rects = Vec::new();
+        for (i, (info, line_rect)) in lines_with_rects.enumerate() {
+            if i < start.line || i > end.line {
+                continue;
+            }
+            let start_char = if i == start.line { start.char } else { 0 };
+            let end_char = if i == end.line {
+                end.char
+            } else {
+                info.char_range().len()
+            };
+            let line = shape_line_in_rect(&bidi, &info, justify, line_rect, face, scale);
+            for x in line.selected_ranges(start_char..end_char) {
+                let x = x.shift(line_rect.left());
+                rects.push(Rect {
+                    x: x,
+                    y: line_rect.y,
+                });
+            }
+        }
+        rects
+    }
+
+    /// The shaped advance of each `char` of the `bidi` text, indexed by the byte at which the
+    /// `char` begins.
+    ///
+    /// Each paragraph is shaped as a whole, so that lines may be wrapped by the advances of the
+    /// glyphs that are actually displayed. The advance of each other byte is zero.
+    pub fn char_advances(bidi: &Bidi, face: &Face, scale: super::Scale) -> Vec<Scalar> {
+        let text = bidi.text();
+        let mut advances = vec![0.0; text.len()];
+        for para in &bidi.info.paragraphs {
+            let range = para.range.clone();
+            let line = shape_line(bidi, range.clone(), face, scale, 0.0);
+            for ((byte, _), &(x, _)) in text[range.clone()].char_indices().zip(&line.chars) {
+                advances[range.start + byte] = x.len();
+            }
+        }
+        advances
+    }
+
+    /// The index of each `char` of the line occupying the given `byte_range` of the `bidi` text in
+    /// visual order from left to right, along with whether or not it is displayed right-to-left.
+    ///
+    /// Indices are relative to the start of the line. Unlike `shape_line`, this requires no font.
+    pub fn visual_order(bidi: &Bidi, byte_range: std::ops::Range<usize>) -> Vec<(usize, bool)> {
+        if byte_range.start >= byte_range.end {
+            return Vec::new();
+        }
+        let text = bidi.text();
+        let para = match bidi.paragraph(byte_range.start) {
+            Some(para) => para,
+            None => return Vec::new(),
+        };
+        let line_start = byte_range.start;
+        let (levels, runs) = bidi.info.visual_runs(para, byte_range);
+        let mut order = Vec::new();
+        for run in runs {
+            let rtl = levels[run.start].is_rtl();
+            let first_char = text[line_start..run.start].chars().count();
+            let num_chars = text[run].chars().count();
+            let chars = (first_char..first_char + num_chars).map(|i| (i, rtl));
+            if rtl {
+                order.extend(chars.rev());
+            } else {
+                order.extend(chars);
+            }
+        }
+        order
+    }
+}
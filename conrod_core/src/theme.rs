@@ -38,6 +38,12 @@ pub struct Theme {
     pub label_color: Color,
     /// The `Id` of the default font used for text widgets when one is not specified.
     pub font_id: Option<text::font::Id>,
+    /// The family, weight and slant of the default font used for text widgets when neither the
+    /// widget nor the `font_id` specifies one. See `font::Map::query`.
+    pub font_query: Option<text::font::Query>,
+    /// The fonts tried in order for any `char` missing from the font of a text widget, unless
+    /// fallbacks have been specified for that font via `font::Map::set_fallbacks`.
+    pub font_fallbacks: Vec<text::font::Id>,
     /// A default "large" font size.
     pub font_size_large: u32,
     /// A default "medium" font size.
@@ -97,6 +103,8 @@ impl Theme {
             border_width: 1.0,
             label_color: BLACK,
             font_id: None,
+            font_query: None,
+            font_fallbacks: Vec::new(),
             font_size_large: 26,
             font_size_medium: 18,
             font_size_small: 12,
@@ -106,6 +114,33 @@ impl Theme {
         }
     }
 
+    /// The `Id` of the default font used for text widgets that do not specify one.
+    ///
+    /// This is the `font_id` if some, otherwise the font that most closely matches the
+    /// `font_query`, falling back to any font within the given `fonts`.
+    pub fn default_font_id(&self, fonts: &text::font::Map) -> Option<text::font::Id> {
+        self.font_id
+            .or_else(|| self.font_query.and_then(|query| fonts.query(query, None)))
+            .or_else(|| fonts.ids().next())
+    }
+
+    /// The `Id` of the font used for a text widget styled with the given `font_id` and
+    /// `font_query`.
+    ///
+    /// The query is resolved relative to the `font_id`, or to the `default_font_id` if `None`,
+    /// which is used in turn if the query matches no font.
+    pub fn resolve_font_id(
+        &self,
+        fonts: &text::font::Map,
+        font_id: Option<text::font::Id>,
+        font_query: Option<text::font::Query>,
+    ) -> Option<text::font::Id> {
+        let base = font_id.or_else(|| self.default_font_id(fonts));
+        font_query
+            .and_then(|query| fonts.query(query, base))
+            .or(base)
+    }
+
     /// Retrieve the unique default styling for a widget.
     ///
     /// Attempts to cast the `Box<WidgetStyle>` to the **Widget**'s unique associated style **T**.
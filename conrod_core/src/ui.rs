@@ -55,6 +55,8 @@ pub struct Ui {
     global_input: input::Global,
     /// Manages all fonts that have been loaded by the user.
     pub fonts: text::font::Map,
+    /// The layout of recently displayed text, shared by text widgets across frames.
+    text_cache: text::cache::Cache,
     /// The Widget cache, storing state for all widgets.
     widget_graph: Graph,
     /// The widget::Id of the widget that was last updated/set.
@@ -86,6 +88,8 @@ pub struct Ui {
     pending_scroll_events: Vec<event::Ui>,
     /// Mouse cursor
     mouse_cursor: cursor::MouseCursor,
+    /// The text caret of the widget capturing the keyboard, if any.
+    caret_rect: Option<Rect>,
 
     // TODO: Remove the following fields as they should now be handled by `input::Global`.
     /// Window width.
@@ -195,6 +199,7 @@ impl Ui {
             widget_graph: widget_graph,
             theme: maybe_theme.unwrap_or_else(|| Theme::default()),
             fonts: text::font::Map::new(),
+            text_cache: text::cache::Cache::default(),
             window: window,
             win_w: window_dimensions[0],
             win_h: window_dimensions[1],
@@ -209,6 +214,7 @@ impl Ui {
             global_input: input::Global::new(),
             pending_scroll_events: Vec::new(),
             mouse_cursor: cursor::MouseCursor::Arrow,
+            caret_rect: None,
         }
     }
 
@@ -260,6 +266,19 @@ impl Ui {
         self.rect_of(id).map(|rect| rect.xy())
     }
 
+    /// The transform of the widget at the given index, including the transforms of its parents.
+    ///
+    /// This maps points from the untransformed space in which the widget is laid out (e.g. its
+    /// `Rect` and the positions of its input events) to the space in which it is rendered. Use the
+    /// `inverse` to map a position within the window back into the widget's space.
+    ///
+    /// Returns `None` if there is no widget for the given index.
+    pub fn transform_of(&self, id: widget::Id) -> Option<position::Transform> {
+        self.widget_graph
+            .widget(id)
+            .map(|_| graph::algo::transform_of_widget(&self.widget_graph, id))
+    }
+
     /// The `kid_area` of the widget at the given index.
     ///
     /// Returns `None` if there is no widget for the given index.
@@ -279,6 +298,17 @@ impl Ui {
         &self.widget_graph
     }
 
+    /// Borrow the **Ui**'s cache of text layout, shared by text widgets across frames.
+    pub fn text_cache(&self) -> &text::cache::Cache {
+        &self.text_cache
+    }
+
+    /// Mutably borrow the **Ui**'s cache of text layout, e.g. to clear it after replacing fonts or
+    /// to change the number of frames for which unused layouts are retained.
+    pub fn text_cache_mut(&mut self) -> &mut text::cache::Cache {
+        &mut self.text_cache
+    }
+
     /// Borrow the **Ui**'s set of updated widgets.
     ///
     /// This set indicates which widgets have been instantiated since the beginning of the most
@@ -833,6 +863,12 @@ impl Ui {
                 self.global_input.push_event(text_event);
             }
 
+            Input::Ime(ime) => {
+                let widget = self.global_input.current.widget_capturing_keyboard;
+                let ime_event = event::Ui::Ime(widget, ime).into();
+                self.global_input.push_event(ime_event);
+            }
+
             Input::Touch(touch) => match touch.phase {
                 input::touch::Phase::Start => {
                     // Find the widget under the touch.
@@ -1139,6 +1175,9 @@ impl Ui {
         self.maybe_prev_widget_id = None;
         self.maybe_current_parent_id = None;
 
+        // Evict any text layouts that have gone unused for too long.
+        self.text_cache.next_frame();
+
         // Move the previous `updated_widgets` to `prev_updated_widgets` and clear
         // `updated_widgets` so that we're ready to store the newly updated widgets.
         {
@@ -1176,6 +1215,7 @@ impl Ui {
         ui_cell.ui.maybe_current_parent_id = Some(ui_cell.window.into());
 
         ui_cell.set_mouse_cursor(cursor::MouseCursor::Arrow);
+        ui_cell.set_caret_rect(None);
 
         ui_cell
     }
@@ -1276,6 +1316,14 @@ impl Ui {
     pub fn mouse_cursor(&self) -> cursor::MouseCursor {
         self.mouse_cursor
     }
+
+    /// The `Rect` of the text caret displayed by the widget capturing the keyboard, if any.
+    ///
+    /// Backends may use this to position the candidate window of an input method editor next to
+    /// the text being composed.
+    pub fn caret_rect(&self) -> Option<Rect> {
+        self.caret_rect
+    }
 }
 
 impl<'a> UiCell<'a> {
@@ -1347,6 +1395,11 @@ impl<'a> UiCell<'a> {
     pub fn set_mouse_cursor(&mut self, cursor: cursor::MouseCursor) {
         self.ui.mouse_cursor = cursor;
     }
+
+    /// Sets the `Rect` of the text caret. See `Ui::caret_rect`.
+    pub fn set_caret_rect(&mut self, rect: Option<Rect>) {
+        self.ui.caret_rect = rect;
+    }
 }
 
 impl<'a> Drop for UiCell<'a> {
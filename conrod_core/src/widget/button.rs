@@ -38,9 +38,16 @@ pub struct Style {
     /// The font size of the Button's label.
     #[conrod(default = "theme.font_size_medium")]
     pub label_font_size: Option<FontSize>,
+    /// Where to truncate the Button's label should it exceed the available width, if at all.
+    #[conrod(default = "None")]
+    pub label_ellipsis: Option<Option<text::Ellipsis>>,
     /// The ID of the font used to display the label.
     #[conrod(default = "theme.font_id")]
     pub label_font_id: Option<Option<text::font::Id>>,
+    /// The family, weight and slant of the label's font, resolved relative to the
+    /// `label_font_id`.
+    #[conrod(default = "None")]
+    pub label_font_query: Option<Option<text::font::Query>>,
     /// The label's typographic alignment over the *x* axis.
     #[conrod(default = "text::Justify::Center")]
     pub label_justify: Option<text::Justify>,
@@ -241,6 +248,13 @@ impl<'a, S> Button<'a, S> {
         self
     }
 
+    /// Display the label with the font that most closely matches the given query. See
+    /// `widget::Text::font_query`.
+    pub fn label_font_query(mut self, query: text::font::Query) -> Self {
+        self.style.label_font_query = Some(Some(query));
+        self
+    }
+
     /// Align the label to the left of the `Button`'s surface.
     pub fn left_justify_label(mut self) -> Self {
         self.style.label_justify = Some(text::Justify::Left);
@@ -464,9 +478,24 @@ fn label(button_id: widget::Id, label_id: widget::Id, label: &str, style: &Style
     let x = style.label_x(&ui.theme);
     let y = style.label_y(&ui.theme);
     let justify = style.label_justify(&ui.theme);
-    let font_id = style.label_font_id(&ui.theme).or(ui.fonts.ids().next());
+    let font_id = ui.theme.resolve_font_id(
+        &ui.fonts,
+        style.label_font_id(&ui.theme),
+        style.label_font_query(&ui.theme),
+    );
+    // A truncated label must also fit within any padding by which it is placed.
+    let ellipsis = style.label_ellipsis(&ui.theme);
+    let pad = match x {
+        position::Relative::Place(position::Place::Start(Some(pad)))
+        | position::Relative::Place(position::Place::End(Some(pad))) => pad,
+        _ => 0.0,
+    };
+    let border = style.border(&ui.theme);
     widget::Text::new(label)
         .and_then(font_id, widget::Text::font_id)
+        .and_then(ellipsis, |text, ellipsis| {
+            text.ellipsis(ellipsis).padded_w_of(button_id, border + pad)
+        })
         .x_position_relative_to(button_id, x)
         .y_position_relative_to(button_id, y)
         .justify(justify)
@@ -494,4 +523,8 @@ impl<'a, S> Labelable<'a> for Button<'a, S> {
         label_color { style.label_color = Some(Color) }
         label_font_size { style.label_font_size = Some(FontSize) }
     }
+    fn label_ellipsis(mut self, ellipsis: text::Ellipsis) -> Self {
+        self.style.label_ellipsis = Some(Some(ellipsis));
+        self
+    }
 }
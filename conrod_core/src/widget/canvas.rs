@@ -43,6 +43,7 @@ pub struct State {
 
 widget_ids! {
     struct Ids {
+        shadow,
         rectangle,
         title_bar,
     }
@@ -95,6 +96,13 @@ pub struct Style {
     /// The label's typographic alignment over the *x* axis.
     #[conrod(default = "text::Justify::Center")]
     pub title_bar_justify: Option<text::Justify>,
+    /// Where to truncate the title bar's text should it exceed the width of the title bar.
+    #[conrod(default = "None")]
+    pub title_bar_ellipsis: Option<Option<text::Ellipsis>>,
+
+    /// The drop shadow cast by the Canvas, if any.
+    #[conrod(default = "None")]
+    pub shadow: Option<Option<widget::shadow::Style>>,
 }
 
 /// A series of **Canvas** splits along with their unique identifiers.
@@ -210,6 +218,12 @@ impl<'a> Canvas<'a> {
         self.style.title_bar_color = Some(Some(color));
         self
     }
+
+    /// Cast a drop shadow with the given style beneath the `Canvas`.
+    pub fn shadow(mut self, style: widget::shadow::Style) -> Self {
+        self.style.shadow = Some(Some(style));
+        self
+    }
 }
 
 impl<'a> Widget for Canvas<'a> {
@@ -284,8 +298,18 @@ impl<'a> Widget for Canvas<'a> {
             ..
         } = self;
 
-        // BorderedRectangle widget as the rectangle backdrop.
+        // Shadow widget beneath the rectangle backdrop if we were given some shadow style.
         let dim = rect.dim();
+        if let Some(shadow_style) = style.shadow(ui.theme()) {
+            widget::Shadow::styled(dim, shadow_style)
+                .middle_of(id)
+                .graphics_for(id)
+                .place_on_kid_area(false)
+                .depth(f32::MAX)
+                .set(state.ids.shadow, &mut ui);
+        }
+
+        // BorderedRectangle widget as the rectangle backdrop.
         let color = style.color(ui.theme());
         let border = style.border(ui.theme());
         let border_color = style.border_color(ui.theme());
@@ -306,10 +330,12 @@ impl<'a> Widget for Canvas<'a> {
             let justify = style.title_bar_justify(&ui.theme);
             let line_spacing = style.title_bar_line_spacing(&ui.theme);
             let maybe_wrap = style.title_bar_maybe_wrap(&ui.theme);
+            let ellipsis = style.title_bar_ellipsis(&ui.theme);
             widget::TitleBar::new(label, state.ids.rectangle)
                 .and_mut(|title_bar| {
                     title_bar.style.maybe_wrap = Some(maybe_wrap);
                     title_bar.style.justify = Some(justify);
+                    title_bar.style.ellipsis = Some(ellipsis);
                 })
                 .color(color)
                 .border(border)
@@ -458,4 +484,9 @@ impl<'a> ::label::Labelable<'a> for Canvas<'a> {
         label_color { style.title_bar_text_color = Some(Color) }
         label_font_size { style.title_bar_font_size = Some(FontSize) }
     }
+
+    fn label_ellipsis(mut self, ellipsis: text::Ellipsis) -> Self {
+        self.style.title_bar_ellipsis = Some(Some(ellipsis));
+        self
+    }
 }
@@ -55,6 +55,10 @@ pub struct Style {
     /// The ID of the font used to display the label.
     #[conrod(default = "theme.font_id")]
     pub label_font_id: Option<Option<text::font::Id>>,
+    /// The family, weight and slant of the label's font, resolved relative to the
+    /// `label_font_id`.
+    #[conrod(default = "None")]
+    pub label_font_query: Option<Option<text::font::Query>>,
 }
 
 /// The event returned when the text bar or triangle is pressed.
@@ -105,6 +109,13 @@ impl<'a> CollapsibleArea<'a> {
         self.style.label_font_id = Some(Some(font_id));
         self
     }
+
+    /// Display the label with the font that most closely matches the given query. See
+    /// `widget::Text::font_query`.
+    pub fn label_font_query(mut self, query: text::font::Query) -> Self {
+        self.style.label_font_query = Some(Some(query));
+        self
+    }
 }
 
 impl<'a> Widget for CollapsibleArea<'a> {
@@ -140,7 +151,11 @@ impl<'a> Widget for CollapsibleArea<'a> {
         let border = style.border(&ui.theme);
         let border_color = style.border_color(&ui.theme);
         let label_color = style.label_color(&ui.theme);
-        let label_font_id = style.label_font_id(&ui.theme).or(ui.fonts.ids().next());
+        let label_font_id = ui.theme.resolve_font_id(
+            &ui.fonts,
+            style.label_font_id(&ui.theme),
+            style.label_font_query(&ui.theme),
+        );
         let label_font_size = match style.label_font_size(&ui.theme) {
             Some(font_size) => font_size,
             None => std::cmp::max((h / 2.5) as FontSize, 10),
@@ -43,6 +43,9 @@ pub struct Style {
     /// Font size for the item labels.
     #[conrod(default = "theme.font_size_medium")]
     pub label_font_size: Option<FontSize>,
+    /// Where to truncate item labels that exceed the width of the list, if at all.
+    #[conrod(default = "None")]
+    pub label_ellipsis: Option<Option<text::Ellipsis>>,
     /// The label's typographic alignment over the *x* axis.
     #[conrod(default = "text::Justify::Center")]
     pub label_justify: Option<text::Justify>,
@@ -64,12 +67,20 @@ pub struct Style {
     /// The ID of the font used to display the labels.
     #[conrod(default = "theme.font_id")]
     pub label_font_id: Option<Option<text::font::Id>>,
+    /// The family, weight and slant of the label's font, resolved relative to the
+    /// `label_font_id`.
+    #[conrod(default = "None")]
+    pub label_font_query: Option<Option<text::font::Query>>,
+    /// The drop shadow cast by the open menu, if any.
+    #[conrod(default = "None")]
+    pub shadow: Option<Option<widget::shadow::Style>>,
 }
 
 widget_ids! {
     struct Ids {
         closed_menu,
         list,
+        shadow,
     }
 }
 
@@ -158,6 +169,19 @@ impl<'a, T> DropDownList<'a, T> {
         self
     }
 
+    /// Display the label with the font that most closely matches the given query. See
+    /// `widget::Text::font_query`.
+    pub fn label_font_query(mut self, query: text::font::Query) -> Self {
+        self.style.label_font_query = Some(Some(query));
+        self
+    }
+
+    /// Cast a drop shadow with the given style beneath the open menu.
+    pub fn shadow(mut self, style: widget::shadow::Style) -> Self {
+        self.style.shadow = Some(Some(style));
+        self
+    }
+
     /// Align the labels to the left of their `Button`s' surface.
     pub fn left_justify_label(mut self) -> Self {
         self.style.label_justify = Some(text::Justify::Left);
@@ -329,6 +353,17 @@ where
                     scrollbar.set(ui);
                 }
 
+                // Cast the shadow beneath the list's items if we were given some shadow style.
+                if let Some(shadow_style) = style.shadow(&ui.theme) {
+                    widget::Shadow::styled([w, list_h], shadow_style)
+                        .middle_of(state.ids.list)
+                        .parent(state.ids.list)
+                        .graphics_for(state.ids.list)
+                        .place_on_kid_area(false)
+                        .depth(f32::MAX)
+                        .set(state.ids.shadow, ui);
+                }
+
                 // Close the menu if the mouse is pressed and the currently pressed widget is
                 // not any of the drop down list's children.
                 let should_close = clicked_item.is_some()
@@ -378,10 +413,12 @@ impl Style {
             border_color: self.border_color,
             label_color: self.label_color,
             label_font_size: self.label_font_size,
+            label_ellipsis: self.label_ellipsis,
             label_justify: self.label_justify,
             label_x: self.label_x,
             label_y: self.label_y,
             label_font_id: self.label_font_id,
+            label_font_query: self.label_font_query,
         }
     }
 }
@@ -403,4 +440,8 @@ impl<'a, T> Labelable<'a> for DropDownList<'a, T> {
         label_color { style.label_color = Some(Color) }
         label_font_size { style.label_font_size = Some(FontSize) }
     }
+    fn label_ellipsis(mut self, ellipsis: text::Ellipsis) -> Self {
+        self.style.label_ellipsis = Some(Some(ellipsis));
+        self
+    }
 }
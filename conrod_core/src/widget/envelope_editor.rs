@@ -50,6 +50,9 @@ pub struct Style {
     /// The font size of the **EnvelopeEditor**'s label if one was given.
     #[conrod(default = "theme.font_size_medium")]
     pub label_font_size: Option<FontSize>,
+    /// Where to truncate the **EnvelopeEditor**'s label should it exceed the available width, if at all.
+    #[conrod(default = "None")]
+    pub label_ellipsis: Option<Option<text::Ellipsis>>,
     /// The font size of the value label.
     #[conrod(default = "14")]
     pub value_font_size: Option<FontSize>,
@@ -62,6 +65,10 @@ pub struct Style {
     /// The ID of the font used to display the label.
     #[conrod(default = "theme.font_id")]
     pub label_font_id: Option<Option<text::font::Id>>,
+    /// The family, weight and slant of the label's font, resolved relative to the
+    /// `label_font_id`.
+    #[conrod(default = "None")]
+    pub label_font_query: Option<Option<text::font::Query>>,
 }
 
 widget_ids! {
@@ -156,6 +163,13 @@ where
         self
     }
 
+    /// Display the label with the font that most closely matches the given query. See
+    /// `widget::Text::font_query`.
+    pub fn label_font_query(mut self, query: text::font::Query) -> Self {
+        self.style.label_font_query = Some(Some(query));
+        self
+    }
+
     builder_methods! {
         pub point_radius { style.point_radius = Some(Scalar) }
         pub line_thickness { style.line_thickness = Some(Scalar) }
@@ -525,12 +539,22 @@ where
             .border_color(border_color)
             .set(state.ids.rectangle, ui);
 
-        let font_id = style.label_font_id(&ui.theme).or(ui.fonts.ids().next());
+        let font_id = ui.theme.resolve_font_id(
+            &ui.fonts,
+            style.label_font_id(&ui.theme),
+            style.label_font_query(&ui.theme),
+        );
         let label_color = style.label_color(&ui.theme);
         if let Some(label) = maybe_label {
             let font_size = style.label_font_size(&ui.theme);
+            let ellipsis = style.label_ellipsis(&ui.theme);
             widget::Text::new(label)
                 .and_then(font_id, widget::Text::font_id)
+                .and_then(ellipsis, |text, ellipsis| {
+                    text.ellipsis(ellipsis)
+                        .center_justify()
+                        .padded_w_of(id, border)
+                })
                 .middle_of(state.ids.rectangle)
                 .graphics_for(id)
                 .color(label_color)
@@ -674,4 +698,8 @@ where
         label_color { style.label_color = Some(Color) }
         label_font_size { style.label_font_size = Some(FontSize) }
     }
+    fn label_ellipsis(mut self, ellipsis: text::Ellipsis) -> Self {
+        self.style.label_ellipsis = Some(Some(ellipsis));
+        self
+    }
 }
@@ -6,6 +6,7 @@
 use event;
 use std;
 use std::cmp::Ordering;
+use text;
 use widget;
 use {
     color, Borderable, Color, Colorable, FontSize, Labelable, Positionable, Scalar, Sizeable,
@@ -340,6 +341,7 @@ impl<'a> Widget for DirectoryView<'a> {
                         .label_color(text_color)
                         .label_font_size(font_size)
                         .label_x(Relative::Place(Place::Start(Some(font_size as Scalar))))
+                        .label_ellipsis(text::Ellipsis::End)
                         .left_justify_label();
                     item.set(button, ui);
                 }
@@ -5,8 +5,8 @@
 
 use graph::{Container, UniqueWidgetState};
 use position::{
-    Align, Depth, Dimension, Dimensions, Padding, Point, Position, Positionable, Rect, Relative,
-    Sizeable,
+    Align, ClipShape, Depth, Dimension, Dimensions, Padding, Point, Position, Positionable, Rect,
+    Relative, Sizeable, Transform,
 };
 use std;
 use text::font;
@@ -18,6 +18,7 @@ pub use self::id::Id;
 pub use self::primitive::image::{self, Image};
 pub use self::primitive::line::{self, Line};
 pub use self::primitive::point_path::{self, PointPath};
+pub use self::primitive::shadow::{self, Shadow};
 pub use self::primitive::shape::circle::{self, Circle};
 pub use self::primitive::shape::oval::{self, Oval};
 pub use self::primitive::shape::polygon::{self, Polygon};
@@ -214,6 +215,10 @@ pub struct CommonBuilder {
     /// By default, the kid_area is the size of the entire widget, though it may be specified
     /// otherwise via the `Widget::kid_area` method.
     pub crop_kids: bool,
+    /// The shape within the `kid_area` to which children are clipped when `crop_kids` is `true`.
+    ///
+    /// When `None`, children are cropped to the rectangle of the `kid_area`.
+    pub maybe_clip_shape: Option<ClipShape>,
     /// Arguments to the scrolling of the widget's *x* axis.
     pub maybe_x_scroll: Option<scroll::Scroll>,
     /// Arguments to the scrolling of the widget's *y* axis.
@@ -239,6 +244,24 @@ pub struct CommonBuilder {
     /// default.
     /// - Any **Graphic** child of *b* will be considered as a **Graphic** child of *a*.
     pub maybe_graphics_for: Option<Id>,
+    /// The opacity of the widget and all of its children, from `0.0` (transparent) to `1.0`.
+    ///
+    /// The opacity is multiplied with that of the widget's depth-wise parents while producing
+    /// `render::Primitives`.
+    pub opacity: f32,
+    /// Whether or not the widget and its children should be rendered to an isolated layer, which is
+    /// then composited with the widget's opacity.
+    ///
+    /// This is only respected by backends that request isolated layers via
+    /// `render::Primitives::with_isolated_layers`. Otherwise, the opacity is applied to each
+    /// primitive individually.
+    pub is_isolated_layer: bool,
+    /// The transform applied to the widget and all of its children, about the centre of the
+    /// widget's `Rect`.
+    ///
+    /// The transform is composed with that of the widget's depth-wise parents while producing
+    /// `render::Primitives` and while picking the widget under some position.
+    pub transform: Transform,
 }
 
 /// Styling and positioning data that is common between all widget types.
@@ -290,6 +313,12 @@ pub struct CommonState {
     pub maybe_x_scroll_state: Option<scroll::StateX>,
     /// If the widget is scrollable across the *y* axis.
     pub maybe_y_scroll_state: Option<scroll::StateY>,
+    /// The opacity of the widget, not including that of its parents.
+    pub opacity: f32,
+    /// Whether or not the widget is rendered to an isolated layer.
+    pub is_isolated_layer: bool,
+    /// The transform of the widget about its centre, not including that of its parents.
+    pub transform: Transform,
 }
 
 // **Widget** data to be cached prior to the **Widget::update** call in the **widget::set_widget**
@@ -325,6 +354,8 @@ pub struct PreUpdateCache {
     pub maybe_floating: Option<Floating>,
     /// Whether or not the children of the **Widget** should be cropped to its `kid_area`.
     pub crop_kids: bool,
+    /// The shape within the `kid_area` to which the children of the **Widget** are clipped.
+    pub maybe_clip_shape: Option<ClipShape>,
     /// Scrolling data for the **Widget**'s *x* axis if there is some.
     pub maybe_x_scroll_state: Option<scroll::StateX>,
     /// Scrolling data for the **Widget**'s *y* axis if there is some.
@@ -334,6 +365,12 @@ pub struct PreUpdateCache {
     pub maybe_graphics_for: Option<Id>,
     /// A function describing whether or not a given point is over the widget.
     pub is_over: IsOverFn,
+    /// The opacity of the **Widget**, not including that of its parents.
+    pub opacity: f32,
+    /// Whether or not the **Widget** is rendered to an isolated layer.
+    pub is_isolated_layer: bool,
+    /// The transform of the **Widget** about its centre, not including that of its parents.
+    pub transform: Transform,
 }
 
 // **Widget** data to be cached after the **Widget::update** call in the **widget::set_widget**
@@ -781,6 +818,57 @@ pub trait Widget: Common + Sized {
         self
     }
 
+    /// Indicates that all widgets who are children of this widget should be clipped to the given
+    /// `shape` within the `kid_area` of this widget, e.g. to hide children beneath rounded corners.
+    ///
+    /// Children are first cropped to the `kid_area` as with `Widget::crop_kids`. Backends that do
+    /// not support clip shapes will only apply this rectangular crop.
+    ///
+    /// When clip shapes are nested, children are clipped to the innermost shape only.
+    fn crop_kids_to(mut self, shape: ClipShape) -> Self {
+        self.common_mut().maybe_clip_shape = Some(shape);
+        self.crop_kids()
+    }
+
+    /// Set the opacity of the widget and all of its children (the default is `1.0`).
+    ///
+    /// The given opacity is multiplied with that of the widget's parents and applied to the alpha
+    /// channel of every primitive produced by the widget and its children. This is useful for
+    /// fading panels in and out, or for dimming disabled sections of a form.
+    ///
+    /// Note that overlapping children will show through each other unless the widget is also an
+    /// `isolated_layer`.
+    fn opacity(mut self, opacity: f32) -> Self {
+        self.common_mut().opacity = opacity;
+        self
+    }
+
+    /// Indicates that the widget and all of its children should be rendered to an isolated layer,
+    /// which is then composited onto its background using the widget's `opacity`.
+    ///
+    /// Unlike applying the opacity to each primitive, this ensures that overlapping children do not
+    /// show through each other. This requires render-to-texture support, so is only respected by
+    /// backends that request isolated layers via `render::Primitives::with_isolated_layers`.
+    fn isolated_layer(mut self) -> Self {
+        self.common_mut().is_isolated_layer = true;
+        self
+    }
+
+    /// Transform the widget and all of its children, e.g. to rotate or scale them.
+    ///
+    /// The transform is applied about the centre of the widget's `Rect` and is composed with the
+    /// transforms of the widget's parents. Layout is unaffected: the widget is positioned and sized
+    /// as usual before being transformed for rendering and for picking the widget under the mouse.
+    ///
+    /// Note that input events delivered to the widget are not transformed. The transform of a
+    /// widget including that of its parents may be retrieved via `Ui::transform_of`.
+    ///
+    /// Backends that do not support transforms will render the widget untransformed.
+    fn transform(mut self, transform: Transform) -> Self {
+        self.common_mut().transform = transform;
+        self
+    }
+
     /// Makes the widget's `KidArea` scrollable.
     ///
     /// If a widget is scrollable and it has children widgets that fall outside of its `KidArea`,
@@ -936,6 +1024,9 @@ where
                     maybe_floating,
                     maybe_x_scroll_state,
                     maybe_y_scroll_state,
+                    opacity,
+                    is_isolated_layer,
+                    transform,
                     ..
                 } = *container;
 
@@ -957,6 +1048,9 @@ where
                     kid_area: kid_area,
                     maybe_x_scroll_state: maybe_x_scroll_state,
                     maybe_y_scroll_state: maybe_y_scroll_state,
+                    opacity: opacity,
+                    is_isolated_layer: is_isolated_layer,
+                    transform: transform,
                 };
 
                 Some((Some(state), Some(prev_common), Some(style)))
@@ -974,6 +1068,9 @@ where
     let x_pos = widget.get_x_position(ui);
     let y_pos = widget.get_y_position(ui);
     let place_on_kid_area = widget.common().place_on_kid_area;
+    let opacity = widget.common().opacity;
+    let is_isolated_layer = widget.common().is_isolated_layer;
+    let transform = widget.common().transform;
 
     // Determine the id of the canvas that the widget is attached to. If not given explicitly,
     // check the positioning to retrieve the Id from there.
@@ -1148,6 +1245,7 @@ where
 
         // Retrieve whether or not the widget's children should be cropped to it.
         let crop_kids = widget.common().crop_kids;
+        let maybe_clip_shape = widget.common().maybe_clip_shape;
 
         // This will cache the given data into the `ui`'s `widget_graph`.
         let ui: &mut Ui = ui::ref_mut_from_ui_cell(ui);
@@ -1165,10 +1263,14 @@ where
                 maybe_dragged_from: maybe_dragged_from,
                 maybe_floating: maybe_floating,
                 crop_kids: crop_kids,
+                maybe_clip_shape: maybe_clip_shape,
                 maybe_y_scroll_state: maybe_y_scroll_state,
                 maybe_x_scroll_state: maybe_x_scroll_state,
                 maybe_graphics_for: widget.common().maybe_graphics_for,
                 is_over: widget.is_over(),
+                opacity: opacity,
+                is_isolated_layer: is_isolated_layer,
+                transform: transform,
             },
         );
     }
@@ -1183,6 +1285,9 @@ where
         kid_area: kid_area,
         maybe_x_scroll_state: maybe_x_scroll_state,
         maybe_y_scroll_state: maybe_y_scroll_state,
+        opacity: opacity,
+        is_isolated_layer: is_isolated_layer,
+        transform: transform,
     });
 
     // Retrieve the widget's unique state and update it via `Widget::update`.
@@ -1216,8 +1321,13 @@ where
     };
 
     // Determine whether or not the `State` has changed.
-    let state_has_changed =
-        has_state_updated || rect != prev_common.rect || depth != prev_common.depth || is_first_set;
+    let state_has_changed = has_state_updated
+        || rect != prev_common.rect
+        || depth != prev_common.depth
+        || opacity != prev_common.opacity
+        || is_isolated_layer != prev_common.is_isolated_layer
+        || transform != prev_common.transform
+        || is_first_set;
 
     // Determine whether or not the widget's `Style` has changed.
     let style_has_changed = maybe_prev_style
@@ -1295,6 +1405,10 @@ impl Default for CommonBuilder {
             maybe_x_scroll: None,
             maybe_y_scroll: None,
             crop_kids: false,
+            maybe_clip_shape: None,
+            opacity: 1.0,
+            is_isolated_layer: false,
+            transform: Transform::identity(),
         }
     }
 }
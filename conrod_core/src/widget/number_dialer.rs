@@ -44,9 +44,15 @@ pub struct Style {
     /// The font size for the NumberDialer's label.
     #[conrod(default = "theme.font_size_medium")]
     pub label_font_size: Option<FontSize>,
+    /// Where to truncate the NumberDialer's label should it exceed the available width, if at all.
+    #[conrod(default = "None")]
+    pub label_ellipsis: Option<Option<text::Ellipsis>>,
     /// The `Id` associated with the font to use for the `NumberDialer` values.
     #[conrod(default = "theme.font_id")]
     pub font_id: Option<Option<text::font::Id>>,
+    /// The family, weight and slant of the label's font, resolved relative to the `font_id`.
+    #[conrod(default = "None")]
+    pub label_font_query: Option<Option<text::font::Query>>,
 }
 
 widget_ids! {
@@ -145,6 +151,13 @@ where
         self
     }
 
+    /// Display the label with the font that most closely matches the given query. See
+    /// `widget::Text::font_query`.
+    pub fn label_font_query(mut self, query: text::font::Query) -> Self {
+        self.style.label_font_query = Some(Some(query));
+        self
+    }
+
     builder_methods! {
         pub enabled { enabled = bool }
     }
@@ -196,18 +209,13 @@ where
         // Retrieve the `font_id`, as long as a valid `Font` for it still exists.
         //
         // If we've no font to use for text logic, bail out without updating.
-        let font_id = match style.font_id(&ui.theme).or(ui.fonts.ids().next()) {
-            Some(font_id) => font_id,
-            None => return None,
-        };
+        let font_id = ui.theme.resolve_font_id(
+            &ui.fonts,
+            style.font_id(&ui.theme),
+            style.label_font_query(&ui.theme),
+        )?;
 
         let font_size = style.label_font_size(ui.theme());
-        let label_string = maybe_label.map_or_else(|| String::new(), |text| format!("{}: ", text));
-        let label_w = {
-            let font = ui.fonts.get(font_id).unwrap();
-            text::line::width(&label_string, font, font_size)
-        };
-        let label_dim = [label_w, font_size as f64];
         let precision_len = if precision == 0 {
             0
         } else {
@@ -216,6 +224,22 @@ where
         let val_string_len = max.to_string().len() + precision_len;
         let val_string = create_val_string(value, val_string_len, precision);
         let val_string_dim = [val_string_width(font_size, &val_string), font_size as f64];
+        let font = ui.fonts.get(font_id).unwrap();
+        // The label may be truncated to the width left over by the value.
+        let label_string =
+            maybe_label.map_or_else(String::new, |label| match style.label_ellipsis(&ui.theme) {
+                None => format!("{}: ", label),
+                Some(ellipsis) => {
+                    let separator_w = text::line::width(": ", font, font_size);
+                    let max_w = inner_rel_rect.w() - val_string_dim[0] - separator_w;
+                    match text::line::truncate(label, &[font], font_size, max_w, ellipsis) {
+                        Some(truncated) => format!("{}: ", truncated),
+                        None => format!("{}: ", label),
+                    }
+                }
+            });
+        let label_w = text::line::width(&label_string, font, font_size);
+        let label_dim = [label_w, font_size as f64];
         let label_rel_x = -val_string_dim[0] / 2.0;
         let slot_w = value_glyph_slot_width(val_string_dim[1] as u32);
         let slot_h = inner_rel_rect.h();
@@ -442,4 +466,8 @@ impl<'a, T> Labelable<'a> for NumberDialer<'a, T> {
         label_color { style.label_color = Some(Color) }
         label_font_size { style.label_font_size = Some(FontSize) }
     }
+    fn label_ellipsis(mut self, ellipsis: text::Ellipsis) -> Self {
+        self.style.label_ellipsis = Some(Some(ellipsis));
+        self
+    }
 }
@@ -1,7 +1,7 @@
 //! A simple, non-interactive widget for drawing an `Image`.
 
 use image;
-use position::{Dimension, Rect};
+use position::{Dimension, Rect, Scalar};
 use widget;
 use {Color, Ui, Widget};
 
@@ -15,6 +15,8 @@ pub struct Image {
     pub image_id: image::Id,
     /// The rectangle area of the original source image that should be used.
     pub src_rect: Option<Rect>,
+    /// The insets used to draw the image as nine slices, if any.
+    pub nine_slice: Option<NineSlice>,
     /// Unique styling.
     pub style: Style,
 }
@@ -28,6 +30,29 @@ pub struct State {
     pub src_rect: Option<Rect>,
     /// The unique identifier for the image's associated data that will be drawn.
     pub image_id: image::Id,
+    /// The insets used to draw the image as nine slices, if any.
+    pub nine_slice: Option<NineSlice>,
+}
+
+/// The insets that divide the `source_rectangle` of an `Image` into nine slices.
+///
+/// The four corner slices are drawn at their original size, the top and bottom edges are
+/// stretched horizontally, the left and right edges are stretched vertically and the centre is
+/// stretched to fill the remaining area. This allows for skinning widgets of any size from a
+/// single texture without distorting the corners.
+///
+/// Insets are given in the pixels of the source image. If the `Image` is smaller than the sum of
+/// two opposing insets, the corners are scaled down to fit.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct NineSlice {
+    /// The width of the left column of slices.
+    pub left: Scalar,
+    /// The width of the right column of slices.
+    pub right: Scalar,
+    /// The height of the top row of slices.
+    pub top: Scalar,
+    /// The height of the bottom row of slices.
+    pub bottom: Scalar,
 }
 
 /// Unique styling for the `Image` widget.
@@ -68,6 +93,7 @@ impl Image {
             common: widget::CommonBuilder::default(),
             image_id: image_id,
             src_rect: None,
+            nine_slice: None,
             style: Style::default(),
         }
     }
@@ -80,6 +106,19 @@ impl Image {
         self
     }
 
+    /// Draw the image as nine slices divided by the given insets.
+    ///
+    /// As the dimensions of the image are not known until rendering, the insets are applied
+    /// within the `source_rectangle`, which must also be specified. To slice the whole image, use
+    /// a source rectangle with the full dimensions of the image.
+    ///
+    /// Setting a nine-slice `Image` without a `source_rectangle` panics in debug builds. Otherwise,
+    /// the image is drawn whole without slicing.
+    pub fn nine_slice(mut self, nine_slice: NineSlice) -> Self {
+        self.nine_slice = Some(nine_slice);
+        self
+    }
+
     builder_methods! {
         pub color { style.maybe_color = Some(Option<Color>) }
     }
@@ -94,6 +133,7 @@ impl Widget for Image {
         State {
             src_rect: None,
             image_id: self.image_id,
+            nine_slice: None,
         }
     }
 
@@ -118,14 +158,76 @@ impl Widget for Image {
     fn update(self, args: widget::UpdateArgs<Self>) -> Self::Event {
         let widget::UpdateArgs { state, .. } = args;
         let Image {
-            image_id, src_rect, ..
+            image_id,
+            src_rect,
+            nine_slice,
+            ..
         } = self;
 
+        debug_assert!(
+            nine_slice.is_none() || src_rect.is_some(),
+            "a nine-slice `Image` requires a `source_rectangle`"
+        );
+
         if state.image_id != image_id {
             state.update(|state| state.image_id = image_id);
         }
         if state.src_rect != src_rect {
             state.update(|state| state.src_rect = src_rect);
         }
+        if state.nine_slice != nine_slice {
+            state.update(|state| state.nine_slice = nine_slice);
+        }
+    }
+}
+
+impl NineSlice {
+    /// Divide the given `rect` and `source_rect` into nine slices.
+    ///
+    /// Returns a `(rect, source_rect)` pair for each slice, from the bottom left to the top right
+    /// in rows. Slices may be empty where an inset is zero.
+    pub fn slices(&self, rect: Rect, source_rect: Rect) -> [(Rect, Rect); 9] {
+        // Clamp the insets to the source and scale them down to fit within the destination.
+        let fit = |start: Scalar, end: Scalar, len: Scalar| {
+            let (start, end) = (start.max(0.0), end.max(0.0));
+            let scale = if start + end > len {
+                len / (start + end)
+            } else {
+                1.0
+            };
+            (start * scale, end * scale)
+        };
+        let (src_l, src_r) = fit(self.left, self.right, source_rect.w());
+        let (src_b, src_t) = fit(self.bottom, self.top, source_rect.h());
+        let (dst_l, dst_r) = fit(src_l, src_r, rect.w());
+        let (dst_b, dst_t) = fit(src_b, src_t, rect.h());
+
+        // The edges of each column and row, from the left and from the bottom.
+        let edges = |r: Rect, l: Scalar, rr: Scalar, b: Scalar, t: Scalar| {
+            let xs = [r.left(), r.left() + l, r.right() - rr, r.right()];
+            let ys = [r.bottom(), r.bottom() + b, r.top() - t, r.top()];
+            (xs, ys)
+        };
+        let (dst_xs, dst_ys) = edges(rect, dst_l, dst_r, dst_b, dst_t);
+        let (src_xs, src_ys) = edges(source_rect, src_l, src_r, src_b, src_t);
+
+        let slice = |i: usize| {
+            let (col, row) = (i % 3, i / 3);
+            let corners = |xs: [Scalar; 4], ys: [Scalar; 4]| {
+                Rect::from_corners([xs[col], ys[row]], [xs[col + 1], ys[row + 1]])
+            };
+            (corners(dst_xs, dst_ys), corners(src_xs, src_ys))
+        };
+        [
+            slice(0),
+            slice(1),
+            slice(2),
+            slice(3),
+            slice(4),
+            slice(5),
+            slice(6),
+            slice(7),
+            slice(8),
+        ]
     }
 }
@@ -42,6 +42,12 @@ pub struct Style {
     pub maybe_thickness: Option<Scalar>,
     /// The style with which the ends of the line are drawn.
     pub maybe_cap: Option<Cap>,
+    /// The length of each dash when drawn with the `Dashed` pattern.
+    pub maybe_dash_length: Option<Scalar>,
+    /// The distance between each dash or dot when drawn with a `Dashed` or `Dotted` pattern.
+    pub maybe_gap_length: Option<Scalar>,
+    /// The distance into the pattern at which the line begins.
+    pub maybe_phase: Option<Scalar>,
 }
 
 /// The pattern used to draw the line.
@@ -55,6 +61,21 @@ pub enum Pattern {
     Dotted,
 }
 
+/// The lengths used to break a line into a `Dashed` or `Dotted` pattern.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Dashes {
+    /// The length of each dash.
+    ///
+    /// This is ignored by the `Dotted` pattern, where each dot's diameter is the line thickness.
+    pub dash: Scalar,
+    /// The distance between the end of one dash or dot and the start of the next.
+    pub gap: Scalar,
+    /// The distance into the pattern at which the line begins.
+    ///
+    /// Animating this value produces "marching ants".
+    pub phase: Scalar,
+}
+
 /// Whether the end of the **Line** should be flat or rounded.
 #[allow(dead_code)]
 #[derive(Copy, Clone, Debug, PartialEq)]
@@ -67,6 +88,11 @@ pub enum Cap {
 
 const DEFAULT_THICKNESS: Scalar = 1.0;
 
+/// The default length of a dash as a multiple of the line thickness.
+const DEFAULT_DASH_LENGTH_PER_THICKNESS: Scalar = 4.0;
+/// The default gap between dashes or dots as a multiple of the line thickness.
+const DEFAULT_GAP_LENGTH_PER_THICKNESS: Scalar = 2.0;
+
 impl Line {
     /// Build a new **Line** widget with the given style.
     pub fn styled(start: Point, end: Point, style: Style) -> Self {
@@ -148,6 +174,24 @@ impl Line {
         self
     }
 
+    /// The length of each dash when drawn with a `Dashed` pattern.
+    pub fn dash_length(mut self, length: Scalar) -> Self {
+        self.style.set_dash_length(length);
+        self
+    }
+
+    /// The distance between each dash or dot when drawn with a `Dashed` or `Dotted` pattern.
+    pub fn gap_length(mut self, length: Scalar) -> Self {
+        self.style.set_gap_length(length);
+        self
+    }
+
+    /// The distance into the `Dashed` or `Dotted` pattern at which the line begins.
+    pub fn phase(mut self, phase: Scalar) -> Self {
+        self.style.set_phase(phase);
+        self
+    }
+
     fn calc_rect(&self) -> Rect {
         let thickness = self.style.maybe_thickness.unwrap_or(DEFAULT_THICKNESS);
         let corners = rect_corners(self.start, self.end, thickness * 0.5);
@@ -163,6 +207,9 @@ impl Style {
             maybe_color: None,
             maybe_thickness: None,
             maybe_cap: None,
+            maybe_dash_length: None,
+            maybe_gap_length: None,
+            maybe_phase: None,
         }
     }
 
@@ -205,6 +252,24 @@ impl Style {
         self
     }
 
+    /// The style with some given dash length.
+    pub fn dash_length(mut self, length: Scalar) -> Self {
+        self.set_dash_length(length);
+        self
+    }
+
+    /// The style with some given gap length.
+    pub fn gap_length(mut self, length: Scalar) -> Self {
+        self.set_gap_length(length);
+        self
+    }
+
+    /// The style with some given pattern phase.
+    pub fn phase(mut self, phase: Scalar) -> Self {
+        self.set_phase(phase);
+        self
+    }
+
     /// Set the pattern for the line.
     pub fn set_pattern(&mut self, pattern: Pattern) {
         self.maybe_pattern = Some(pattern);
@@ -225,6 +290,21 @@ impl Style {
         self.maybe_cap = Some(cap);
     }
 
+    /// Set the length of each dash for the line.
+    pub fn set_dash_length(&mut self, length: Scalar) {
+        self.maybe_dash_length = Some(length);
+    }
+
+    /// Set the length of the gap between each dash or dot for the line.
+    pub fn set_gap_length(&mut self, length: Scalar) {
+        self.maybe_gap_length = Some(length);
+    }
+
+    /// Set the distance into the pattern at which the line begins.
+    pub fn set_phase(&mut self, phase: Scalar) {
+        self.maybe_phase = Some(phase);
+    }
+
     /// The Pattern for the Line.
     pub fn get_pattern(&self, theme: &Theme) -> Pattern {
         const DEFAULT_PATTERN: Pattern = Pattern::Solid;
@@ -270,6 +350,27 @@ impl Style {
             })
             .unwrap_or(DEFAULT_CAP)
     }
+
+    /// The dash, gap and phase lengths used to draw `Dashed` and `Dotted` patterns.
+    ///
+    /// Unless specified, the dash and gap lengths are proportional to the line's thickness.
+    pub fn get_dashes(&self, theme: &Theme) -> Dashes {
+        let thickness = self.get_thickness(theme);
+        let default = theme.widget_style::<Style>().map(|default| default.style);
+        let dash = self
+            .maybe_dash_length
+            .or_else(|| default.and_then(|style| style.maybe_dash_length))
+            .unwrap_or(thickness * DEFAULT_DASH_LENGTH_PER_THICKNESS);
+        let gap = self
+            .maybe_gap_length
+            .or_else(|| default.and_then(|style| style.maybe_gap_length))
+            .unwrap_or(thickness * DEFAULT_GAP_LENGTH_PER_THICKNESS);
+        let phase = self
+            .maybe_phase
+            .or_else(|| default.and_then(|style| style.maybe_phase))
+            .unwrap_or(0.0);
+        Dashes { dash, gap, phase }
+    }
 }
 
 impl Widget for Line {
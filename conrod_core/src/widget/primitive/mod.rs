@@ -7,6 +7,7 @@
 pub mod image;
 pub mod line;
 pub mod point_path;
+pub mod shadow;
 pub mod shape;
 pub mod text;
 
@@ -4,8 +4,9 @@ use graph;
 use utils::{vec2_add, vec2_sub};
 use widget;
 use widget::triangles::Triangle;
-use {Color, Colorable, Point, Positionable, Scalar, Sizeable, Theme, Widget};
+use {Color, Colorable, Point, Positionable, Rect, Scalar, Sizeable, Theme, Widget};
 
+pub use super::line::Dashes;
 pub use super::line::Pattern;
 pub use super::line::Style;
 
@@ -31,6 +32,9 @@ pub struct State {
 }
 
 /// An iterator that triangulates a point path.
+///
+/// By default the path is triangulated as a solid stroke. Use the `pattern` builder method to
+/// break the stroke up into dashes or dots.
 #[derive(Clone)]
 pub struct Triangles<I> {
     next: Option<Triangle<Point>>,
@@ -38,8 +42,29 @@ pub struct Triangles<I> {
     points: I,
     half_thickness: Scalar,
     cap: widget::line::Cap,
+    pattern: Pattern,
+    dashes: Dashes,
+    // The segment currently being walked along, its length and the distance walked so far.
+    segment: Option<Segment>,
+    // The distance remaining until the current dash ends (or begins) or until the next dot.
+    until_toggle: Scalar,
+    // Whether or not the walk is currently within a dash.
+    is_on: bool,
+    // The triangles of the dot currently being yielded.
+    dot: Option<widget::oval::Triangles>,
+}
+
+#[derive(Copy, Clone)]
+struct Segment {
+    start: Point,
+    end: Point,
+    len: Scalar,
+    walked: Scalar,
 }
 
+/// The number of lines used to draw the edge of each dot in a `Dotted` pattern.
+const DOT_RESOLUTION: usize = 12;
+
 impl<I> PointPath<I> {
     /// The same as [**PointPath::new**](./struct.PointPath#method.new) but with th given style.
     pub fn styled(points: I, style: Style) -> Self {
@@ -141,6 +166,24 @@ impl<I> PointPath<I> {
         self.style.set_pattern(Pattern::Dotted);
         self
     }
+
+    /// The length of each dash when drawn with a `Dashed` pattern.
+    pub fn dash_length(mut self, length: Scalar) -> Self {
+        self.style.set_dash_length(length);
+        self
+    }
+
+    /// The distance between each dash or dot when drawn with a `Dashed` or `Dotted` pattern.
+    pub fn gap_length(mut self, length: Scalar) -> Self {
+        self.style.set_gap_length(length);
+        self
+    }
+
+    /// The distance into the `Dashed` or `Dotted` pattern at which the path begins.
+    pub fn phase(mut self, phase: Scalar) -> Self {
+        self.style.set_phase(phase);
+        self
+    }
 }
 
 impl<I> Widget for PointPath<I>
@@ -237,9 +280,181 @@ where
         points: points,
         half_thickness: thickness / 2.0,
         cap: cap,
+        pattern: Pattern::Solid,
+        dashes: Dashes {
+            dash: 0.0,
+            gap: 0.0,
+            phase: 0.0,
+        },
+        segment: None,
+        until_toggle: 0.0,
+        is_on: true,
+        dot: None,
     })
 }
 
+/// Triangulate a point path using the given style.
+///
+/// This is the same as `triangles`, but also breaks up the path using the style's `Pattern`.
+///
+/// Returns `None` if the given iterator yields less than one point.
+pub fn styled_triangles<I>(
+    points: I,
+    style: &Style,
+    theme: &Theme,
+) -> Option<Triangles<I::IntoIter>>
+where
+    I: IntoIterator<Item = Point>,
+{
+    let cap = style.get_cap(theme);
+    let thickness = style.get_thickness(theme);
+    let pattern = style.get_pattern(theme);
+    let dashes = style.get_dashes(theme);
+    triangles(points, cap, thickness).map(|tris| tris.pattern(pattern, dashes))
+}
+
+impl<I> Triangles<I> {
+    /// Break the stroke up using the given `Pattern` and `Dashes` lengths.
+    ///
+    /// Dashes and dots are measured along the whole path, so a pattern continues uninterrupted
+    /// around the corners between consecutive points.
+    pub fn pattern(mut self, pattern: Pattern, dashes: Dashes) -> Self {
+        self.pattern = pattern;
+        self.dashes = dashes;
+        self.is_on = true;
+        self.until_toggle = 0.0;
+        let period = self.period();
+        if period > 0.0 {
+            let offset = dashes.phase.rem_euclid(period);
+            match pattern {
+                Pattern::Solid => (),
+                Pattern::Dashed => {
+                    if offset < dashes.dash {
+                        self.is_on = true;
+                        self.until_toggle = dashes.dash - offset;
+                    } else {
+                        self.is_on = false;
+                        self.until_toggle = period - offset;
+                    }
+                }
+                // Dots are centred within the first `thickness` of each period.
+                Pattern::Dotted => {
+                    let radius = self.half_thickness;
+                    self.until_toggle = (radius - offset).rem_euclid(period);
+                }
+            }
+        }
+        self
+    }
+
+    // The length of a single repetition of the pattern.
+    fn period(&self) -> Scalar {
+        let Dashes { dash, gap, .. } = self.dashes;
+        match self.pattern {
+            Pattern::Solid => 0.0,
+            Pattern::Dashed => dash.max(0.0) + gap.max(0.0),
+            Pattern::Dotted => self.half_thickness * 2.0 + gap.max(0.0),
+        }
+    }
+}
+
+impl<I> Triangles<I>
+where
+    I: Iterator<Item = Point>,
+{
+    // Produce the next segment of the path that has some length.
+    fn next_segment(&mut self) -> Option<Segment> {
+        for point in self.points.by_ref() {
+            let start = self.prev;
+            self.prev = point;
+            let len = (point[0] - start[0]).hypot(point[1] - start[1]);
+            if len > 0.0 {
+                return Some(Segment {
+                    start,
+                    end: point,
+                    len,
+                    walked: 0.0,
+                });
+            }
+        }
+        None
+    }
+
+    // Yield the two triangles describing the line from `a` to `b`.
+    fn line(&mut self, a: Point, b: Point) -> Triangle<Point> {
+        let tris = widget::line::triangles(a, b, self.half_thickness);
+        self.next = Some(tris[1]);
+        tris[0]
+    }
+
+    fn next_solid(&mut self) -> Option<Triangle<Point>> {
+        self.points.next().map(|point| {
+            let (a, b) = (self.prev, point);
+            self.prev = point;
+            self.line(a, b)
+        })
+    }
+
+    fn next_patterned(&mut self) -> Option<Triangle<Point>> {
+        loop {
+            if let Some(triangle) = self.dot.as_mut().and_then(|dot| dot.next()) {
+                return Some(triangle);
+            }
+            self.dot = None;
+
+            let mut segment = match self.segment.take() {
+                Some(segment) if segment.walked < segment.len => segment,
+                _ => self.next_segment()?,
+            };
+            let remaining = segment.len - segment.walked;
+            let point_at = move |dist: Scalar| {
+                let t = dist / segment.len;
+                let x = segment.start[0] + (segment.end[0] - segment.start[0]) * t;
+                let y = segment.start[1] + (segment.end[1] - segment.start[1]) * t;
+                [x, y]
+            };
+
+            match self.pattern {
+                Pattern::Solid => unreachable!(),
+
+                Pattern::Dashed => {
+                    let step = self.until_toggle.min(remaining);
+                    let (a, b) = (point_at(segment.walked), point_at(segment.walked + step));
+                    segment.walked += step;
+                    self.until_toggle -= step;
+                    self.segment = Some(segment);
+                    let was_on = self.is_on;
+                    if self.until_toggle <= 0.0 {
+                        self.is_on = !self.is_on;
+                        self.until_toggle = if self.is_on {
+                            self.dashes.dash
+                        } else {
+                            self.dashes.gap
+                        };
+                    }
+                    if was_on && step > 0.0 {
+                        return Some(self.line(a, b));
+                    }
+                }
+
+                Pattern::Dotted => {
+                    if self.until_toggle > remaining {
+                        self.until_toggle -= remaining;
+                        continue;
+                    }
+                    segment.walked += self.until_toggle;
+                    let centre = point_at(segment.walked);
+                    self.segment = Some(segment);
+                    self.until_toggle = self.period();
+                    let diameter = self.half_thickness * 2.0;
+                    let rect = Rect::from_xy_dim(centre, [diameter, diameter]);
+                    self.dot = Some(widget::oval::triangles(rect, DOT_RESOLUTION));
+                }
+            }
+        }
+    }
+}
+
 impl<I> Iterator for Triangles<I>
 where
     I: Iterator<Item = Point>,
@@ -249,13 +464,18 @@ where
         if let Some(triangle) = self.next.take() {
             return Some(triangle);
         }
-        self.points.next().map(|point| {
-            let (a, b) = (self.prev, point);
-            self.prev = point;
-            let tris = widget::line::triangles(a, b, self.half_thickness);
-            self.next = Some(tris[1]);
-            tris[0]
-        })
+        let is_solid = match self.pattern {
+            Pattern::Solid => true,
+            Pattern::Dashed => self.dashes.gap <= 0.0,
+            Pattern::Dotted => false,
+        };
+        if is_solid {
+            return self.next_solid();
+        }
+        if self.period() <= 0.0 || (self.pattern == Pattern::Dashed && self.dashes.dash <= 0.0) {
+            return None;
+        }
+        self.next_patterned()
     }
 }
 
@@ -0,0 +1,197 @@
+//! A simple, non-interactive widget for drawing a soft drop shadow beneath a rectangular area.
+
+use graph;
+use std::f64::consts::PI;
+use widget;
+use widget::triangles::{ColoredPoint, Triangle};
+use {color, Color, Colorable, Dimensions, Point, Rect, Scalar, Sizeable, Theme, Widget};
+
+/// A soft drop shadow cast by a rectangle with the `Shadow`'s dimensions.
+///
+/// The shadow is offset from the widget's `Rect`, grown by its `spread` and then blurred over the
+/// `blur_radius`, so it is usually positioned and sized to match the widget that casts it, e.g.
+/// via `.wh_of(id).middle_of(id)`.
+///
+/// Shadows are never considered to be under the mouse and are not cropped by the widget that
+/// casts them, i.e. the depth-wise parent for which the shadow is a `graphics_for` child.
+#[derive(Copy, Clone, Debug, WidgetCommon_)]
+pub struct Shadow {
+    /// Data necessary and common for all widget builder types.
+    #[conrod(common_builder)]
+    pub common: widget::CommonBuilder,
+    /// Unique styling for the **Shadow**.
+    pub style: Style,
+}
+
+/// Unique styling for the **Shadow** widget.
+#[derive(Copy, Clone, Debug, Default, PartialEq, WidgetStyle_)]
+pub struct Style {
+    /// The color of the shadow beneath the casting rectangle.
+    #[conrod(default = "color::BLACK.alpha(0.5)")]
+    pub color: Option<Color>,
+    /// The distance by which the shadow is offset from the casting rectangle.
+    #[conrod(default = "[0.0, -4.0]")]
+    pub offset: Option<Point>,
+    /// The distance over which the edge of the shadow fades to transparent.
+    #[conrod(default = "12.0")]
+    pub blur_radius: Option<Scalar>,
+    /// The distance by which the shadow is grown beyond the casting rectangle before blurring.
+    #[conrod(default = "0.0")]
+    pub spread: Option<Scalar>,
+}
+
+/// Unique state for the **Shadow**, which has none.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct State;
+
+/// The number of triangles used to round each corner of the blurred edge.
+const CORNER_RESOLUTION: usize = 8;
+
+impl Shadow {
+    /// Build a shadow cast by a rectangle with the given dimensions and style.
+    pub fn styled(dim: Dimensions, style: Style) -> Self {
+        Shadow {
+            common: widget::CommonBuilder::default(),
+            style: style,
+        }
+        .wh(dim)
+    }
+
+    /// Build a shadow cast by a rectangle with the given dimensions.
+    pub fn new(dim: Dimensions) -> Self {
+        Shadow::styled(dim, Style::default())
+    }
+
+    builder_methods! {
+        pub offset { style.offset = Some(Point) }
+        pub blur_radius { style.blur_radius = Some(Scalar) }
+        pub spread { style.spread = Some(Scalar) }
+    }
+}
+
+impl Widget for Shadow {
+    type State = State;
+    type Style = Style;
+    type Event = ();
+
+    fn init_state(&self, _: widget::id::Generator) -> Self::State {
+        State
+    }
+
+    fn style(&self) -> Self::Style {
+        self.style
+    }
+
+    fn is_over(&self) -> widget::IsOverFn {
+        is_over_widget
+    }
+
+    fn update(self, _args: widget::UpdateArgs<Self>) -> Self::Event {}
+}
+
+impl Colorable for Shadow {
+    fn color(mut self, color: Color) -> Self {
+        self.style.color = Some(color);
+        self
+    }
+}
+
+/// Shadows never capture input.
+pub fn is_over_widget(_: &graph::Container, _: Point, _: &Theme) -> widget::IsOver {
+    false.into()
+}
+
+/// Tessellate the shadow cast by the given `rect`, extending the `triangles` buffer.
+///
+/// The shadow is produced as a solid core surrounded by a ring that fades from the given `color`
+/// to transparent, centred on the edge of the offset and spread rectangle.
+pub fn triangles(
+    triangles: &mut Vec<Triangle<ColoredPoint>>,
+    rect: Rect,
+    offset: Point,
+    blur_radius: Scalar,
+    spread: Scalar,
+    color: color::Rgba,
+) {
+    let (l, r, b, t) = rect.l_r_b_t();
+    let spread_x = spread.max(-(r - l) / 2.0);
+    let spread_y = spread.max(-(t - b) / 2.0);
+    let (l, r) = (l + offset[0] - spread_x, r + offset[0] + spread_x);
+    let (b, t) = (b + offset[1] - spread_y, t + offset[1] + spread_y);
+
+    // The core is inset by half of the blur, and the ring reaches the same distance outside.
+    let half_blur = blur_radius.max(0.0) / 2.0;
+    let inset_x = half_blur.min((r - l) / 2.0);
+    let inset_y = half_blur.min((t - b) / 2.0);
+    let (cl, cr, cb, ct) = (l + inset_x, r - inset_x, b + inset_y, t - inset_y);
+    let (ring_x, ring_y) = (inset_x + half_blur, inset_y + half_blur);
+
+    let color::Rgba(red, green, blue, _) = color;
+    let clear = color::Rgba(red, green, blue, 0.0);
+    let mut quad = |a: ColoredPoint, b: ColoredPoint, c: ColoredPoint, d: ColoredPoint| {
+        triangles.push(Triangle([a, b, c]));
+        triangles.push(Triangle([a, c, d]));
+    };
+
+    if cr > cl && ct > cb {
+        quad(
+            ([cl, ct], color),
+            ([cr, ct], color),
+            ([cr, cb], color),
+            ([cl, cb], color),
+        );
+    }
+    if half_blur <= 0.0 {
+        return;
+    }
+
+    // The blurred edges along each side of the core.
+    if cr > cl {
+        let (top, bottom) = (ct + ring_y, cb - ring_y);
+        quad(
+            ([cl, ct], color),
+            ([cl, top], clear),
+            ([cr, top], clear),
+            ([cr, ct], color),
+        );
+        quad(
+            ([cl, cb], color),
+            ([cr, cb], color),
+            ([cr, bottom], clear),
+            ([cl, bottom], clear),
+        );
+    }
+    if ct > cb {
+        let (left, right) = (cl - ring_x, cr + ring_x);
+        quad(
+            ([cl, cb], color),
+            ([left, cb], clear),
+            ([left, ct], clear),
+            ([cl, ct], color),
+        );
+        quad(
+            ([cr, cb], color),
+            ([cr, ct], color),
+            ([right, ct], clear),
+            ([right, cb], clear),
+        );
+    }
+
+    // The rounded, blurred corners, starting with the top right and moving anti-clockwise.
+    let corners = [[cr, ct], [cl, ct], [cl, cb], [cr, cb]];
+    for (i, &[x, y]) in corners.iter().enumerate() {
+        let start = i as Scalar * PI / 2.0;
+        let point = |step: usize| {
+            let radians = start + step as Scalar * PI / 2.0 / CORNER_RESOLUTION as Scalar;
+            [x + ring_x * radians.cos(), y + ring_y * radians.sin()]
+        };
+        for step in 0..CORNER_RESOLUTION {
+            let tri = [
+                ([x, y], color),
+                (point(step), clear),
+                (point(step + 1), clear),
+            ];
+            triangles.push(Triangle(tri));
+        }
+    }
+}
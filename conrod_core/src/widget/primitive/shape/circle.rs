@@ -3,7 +3,7 @@
 use super::oval::{Full, Oval};
 use super::Style;
 use widget;
-use {Color, Dimensions, Scalar};
+use {color, Color, Dimensions, Scalar};
 
 /// A tiny wrapper around the **Oval** widget type.
 #[derive(Copy, Clone, Debug)]
@@ -30,6 +30,11 @@ impl Circle {
         Oval::fill_with(rad_to_dim(radius), color)
     }
 
+    /// Build a new circular **Oval** filled with the given gradient.
+    pub fn fill_gradient(radius: Scalar, gradient: color::Gradient) -> Oval<Full> {
+        Oval::fill_gradient(rad_to_dim(radius), gradient)
+    }
+
     /// Build a new circular **Outline**d **Oval** widget.
     pub fn outline(radius: Scalar) -> Oval<Full> {
         Oval::outline(rad_to_dim(radius))
@@ -1,6 +1,6 @@
 //! A module encompassing the primitive 2D shape widgets.
 
-use color::Color;
+use color::{Color, Gradient};
 use theme::Theme;
 use widget;
 
@@ -11,12 +11,16 @@ pub mod rectangle;
 pub mod triangles;
 
 /// The style for some 2D shape.
-#[derive(Copy, Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq)]
 pub enum Style {
     /// The outline of the shape with this style.
     Outline(widget::line::Style),
     /// A rectangle filled with this color.
     Fill(Option<Color>),
+    /// The shape filled with this gradient.
+    ///
+    /// The gradient's points are relative to the centre of the shape's bounding rectangle.
+    Gradient(Gradient),
 }
 
 impl Style {
@@ -30,6 +34,11 @@ impl Style {
         Style::Fill(Some(color))
     }
 
+    /// A `Gradient` style with the given `Gradient`.
+    pub fn gradient(gradient: Gradient) -> Self {
+        Style::Gradient(gradient)
+    }
+
     /// A default `Outline` style.
     pub fn outline() -> Self {
         Style::Outline(widget::line::Style::new())
@@ -47,18 +56,28 @@ impl Style {
     }
 
     /// Set the color for the style.
+    ///
+    /// A `Gradient` style is left untouched, as its colors are given by its stops.
     pub fn set_color(&mut self, color: Color) {
         match *self {
             Style::Fill(ref mut maybe_color) => *maybe_color = Some(color),
             Style::Outline(ref mut line_style) => line_style.set_color(color),
+            Style::Gradient(_) => (),
         }
     }
 
     /// Get the color of the Rectangle.
+    ///
+    /// For a `Gradient` style, this is the color of the first stop.
     pub fn get_color(&self, theme: &Theme) -> Color {
         match *self {
             Style::Fill(maybe_color) => maybe_color.unwrap_or(theme.shape_color),
             Style::Outline(style) => style.get_color(theme),
+            Style::Gradient(ref gradient) => gradient
+                .stops()
+                .first()
+                .map(|&(_, color)| color)
+                .unwrap_or(theme.shape_color),
         }
     }
 }
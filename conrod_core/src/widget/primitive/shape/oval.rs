@@ -5,10 +5,10 @@ use graph;
 use std;
 use widget;
 use widget::triangles::Triangle;
-use {Color, Colorable, Dimensions, Point, Rect, Scalar, Sizeable, Theme, Widget};
+use {color, Color, Colorable, Dimensions, Point, Rect, Scalar, Sizeable, Theme, Widget};
 
 /// A simple, non-interactive widget for drawing a single **Oval**.
-#[derive(Copy, Clone, Debug, WidgetCommon_)]
+#[derive(Clone, Debug, WidgetCommon_)]
 pub struct Oval<S> {
     /// Data necessary and common for all widget builder types.
     #[conrod(common_builder)]
@@ -88,6 +88,11 @@ impl Oval<Full> {
         Oval::styled(dim, Style::fill_with(color))
     }
 
+    /// Build a new **Oval** filled with the given gradient.
+    pub fn fill_gradient(dim: Dimensions, gradient: color::Gradient) -> Self {
+        Oval::styled(dim, Style::gradient(gradient))
+    }
+
     /// Build a new **Outline**d **Oval** widget.
     pub fn outline(dim: Dimensions) -> Self {
         Oval::styled(dim, Style::outline())
@@ -5,7 +5,7 @@ use graph;
 use utils::{bounding_box_for_points, vec2_add, vec2_sub};
 use widget;
 use widget::triangles::Triangle;
-use {Color, Colorable, Point, Positionable, Sizeable, Theme, Widget};
+use {color, Color, Colorable, Point, Positionable, Sizeable, Theme, Widget};
 
 /// A basic, non-interactive, arbitrary **Polygon** widget.
 ///
@@ -13,7 +13,7 @@ use {Color, Colorable, Point, Positionable, Sizeable, Theme, Widget};
 ///
 /// **Polygon** will automatically close all shapes, so the given list of points does not need to
 /// start and end with the same position.
-#[derive(Copy, Clone, Debug, WidgetCommon_)]
+#[derive(Clone, Debug, WidgetCommon_)]
 pub struct Polygon<I> {
     /// Data necessary and common for all widget builder types.
     #[conrod(common_builder)]
@@ -74,6 +74,11 @@ impl<I> Polygon<I> {
         Polygon::styled(points, Style::fill_with(color))
     }
 
+    /// Build a **Polygon** filled with the given **Gradient**.
+    pub fn fill_gradient(points: I, gradient: color::Gradient) -> Self {
+        Polygon::styled(points, Style::gradient(gradient))
+    }
+
     /// Build a **Polygon** with the default **Outline** style.
     pub fn outline(points: I) -> Self {
         Polygon::styled(points, Style::outline())
@@ -263,7 +268,7 @@ where
         }
 
         let kind = match *style {
-            Style::Fill(_) => Kind::Fill,
+            Style::Fill(_) | Style::Gradient(_) => Kind::Fill,
             Style::Outline(_) => Kind::Outline,
         };
 
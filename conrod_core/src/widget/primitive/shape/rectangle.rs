@@ -6,10 +6,10 @@
 use super::Style;
 use widget;
 use widget::triangles::Triangle;
-use {Color, Colorable, Dimensions, Point, Rect, Sizeable, Widget};
+use {color, Color, Colorable, Dimensions, Point, Rect, Sizeable, Widget};
 
 /// A basic, non-interactive rectangle shape widget.
-#[derive(Copy, Clone, Debug, WidgetCommon_)]
+#[derive(Clone, Debug, WidgetCommon_)]
 pub struct Rectangle {
     /// Data necessary and common for all widget builder types.
     #[conrod(common_builder)]
@@ -53,6 +53,11 @@ impl Rectangle {
         Rectangle::styled(dim, Style::fill_with(color))
     }
 
+    /// Build a new rectangle widget filled with the given gradient.
+    pub fn fill_gradient(dim: Dimensions, gradient: color::Gradient) -> Self {
+        Rectangle::styled(dim, Style::gradient(gradient))
+    }
+
     /// Build a new outlined rectangle widget.
     pub fn outline(dim: Dimensions) -> Self {
         Rectangle::styled(dim, Style::outline())
@@ -82,7 +87,7 @@ impl Widget for Rectangle {
         let widget::UpdateArgs { state, style, .. } = args;
 
         let kind = match *style {
-            Style::Fill(_) => Kind::Fill,
+            Style::Fill(_) | Style::Gradient(_) => Kind::Fill,
             Style::Outline(_) => Kind::Outline,
         };
 
@@ -45,6 +45,7 @@ pub struct MultiColor;
 
 /// A single triangle described by three vertices.
 #[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Triangle<V>(pub [V; 3])
 where
     V: Vertex;
@@ -385,6 +386,143 @@ where
     }
 }
 
+/// The number of steps across a radial gradient's largest radius used to determine how finely
+/// triangles should be subdivided when colored by `gradient`.
+const RADIAL_GRADIENT_STEPS: Scalar = 32.0;
+
+/// The maximum depth to which a single triangle may be subdivided when colored by `gradient`.
+const MAX_RADIAL_GRADIENT_DEPTH: usize = 6;
+
+/// Colors the given triangles with the given `Gradient`, pushing the resulting multicolored
+/// triangles onto `colored`.
+///
+/// The gradient's points are described relative to the given `origin`.
+///
+/// As colors are interpolated linearly between each vertex, triangles are subdivided so that the
+/// result matches the gradient. Triangles are split along each color stop of a linear gradient,
+/// while triangles covered by a radial gradient are subdivided until they are small enough to
+/// appear smooth.
+pub fn gradient<I>(
+    colored: &mut Vec<Triangle<ColoredPoint>>,
+    triangles: I,
+    gradient: &color::Gradient,
+    origin: Point,
+) where
+    I: IntoIterator<Item = Triangle<Point>>,
+{
+    let colored_point = |p: Point, offset: Scalar| {
+        let offset = offset.clamp(0.0, 1.0);
+        (p, gradient.color_at_offset(offset).to_rgb())
+    };
+
+    match *gradient {
+        color::Gradient::Linear((sx, sy), (ex, ey), ref stops) => {
+            let (dx, dy) = (ex - sx, ey - sy);
+            let len_sq = dx * dx + dy * dy;
+            let offset = |p: Point| {
+                if len_sq == 0.0 {
+                    return 0.0;
+                }
+                let (x, y) = (p[0] - origin[0] - sx, p[1] - origin[1] - sy);
+                (x * dx + y * dy) / len_sq
+            };
+
+            // The offsets at which the color of the gradient changes direction.
+            let cuts = [0.0, 1.0];
+            let cuts = cuts
+                .iter()
+                .cloned()
+                .chain(stops.iter().map(|&(offset, _)| offset));
+
+            let mut polygons: Vec<OffsetPolygon> = Vec::new();
+            let mut split = Vec::new();
+            for triangle in triangles {
+                polygons.clear();
+                polygons.push(triangle.iter().map(|&p| (p, offset(p))).collect());
+                for cut in cuts.clone() {
+                    split.clear();
+                    for polygon in polygons.drain(..) {
+                        let (below, above) = split_polygon(&polygon, cut);
+                        split.extend(Some(below).into_iter().chain(Some(above)));
+                    }
+                    polygons.extend(split.drain(..).filter(|polygon| polygon.len() >= 3));
+                }
+                for polygon in &polygons {
+                    let (first, first_offset) = polygon[0];
+                    for pair in polygon[1..].windows(2) {
+                        let a = colored_point(first, first_offset);
+                        let b = colored_point(pair[0].0, pair[0].1);
+                        let c = colored_point(pair[1].0, pair[1].1);
+                        colored.push(Triangle([a, b, c]));
+                    }
+                }
+            }
+        }
+
+        color::Gradient::Radial((sx, sy), start_r, (ex, ey), end_r, _) => {
+            let extent = start_r.max(end_r).max((ex - sx).hypot(ey - sy));
+            let max_edge = extent / RADIAL_GRADIENT_STEPS;
+            let offset = |p: Point| gradient.offset_at((p[0] - origin[0], p[1] - origin[1]));
+            let mut stack = Vec::new();
+            for triangle in triangles {
+                stack.push((triangle, 0));
+                while let Some((tri, depth)) = stack.pop() {
+                    let [a, b, c] = tri.0;
+                    let longest = dist(a, b).max(dist(b, c)).max(dist(c, a));
+                    if depth >= MAX_RADIAL_GRADIENT_DEPTH || longest <= max_edge || max_edge <= 0.0
+                    {
+                        let a = colored_point(a, offset(a));
+                        let b = colored_point(b, offset(b));
+                        let c = colored_point(c, offset(c));
+                        colored.push(Triangle([a, b, c]));
+                        continue;
+                    }
+                    let (ab, bc, ca) = (mid(a, b), mid(b, c), mid(c, a));
+                    let depth = depth + 1;
+                    stack.push((Triangle([a, ab, ca]), depth));
+                    stack.push((Triangle([ab, b, bc]), depth));
+                    stack.push((Triangle([ca, bc, c]), depth));
+                    stack.push((Triangle([ab, bc, ca]), depth));
+                }
+            }
+        }
+    }
+}
+
+// A convex polygon whose points are paired with their offset along a linear gradient.
+type OffsetPolygon = Vec<(Point, Scalar)>;
+
+// Split the given polygon into the parts that lie below and above the given offset.
+fn split_polygon(polygon: &[(Point, Scalar)], cut: Scalar) -> (OffsetPolygon, OffsetPolygon) {
+    let mut below = Vec::new();
+    let mut above = Vec::new();
+    for (i, &a) in polygon.iter().enumerate() {
+        let b = polygon[(i + 1) % polygon.len()];
+        if a.1 <= cut {
+            below.push(a);
+        }
+        if a.1 >= cut {
+            above.push(a);
+        }
+        if (a.1 < cut && b.1 > cut) || (a.1 > cut && b.1 < cut) {
+            let t = (cut - a.1) / (b.1 - a.1);
+            let x = a.0[0] + (b.0[0] - a.0[0]) * t;
+            let y = a.0[1] + (b.0[1] - a.0[1]) * t;
+            below.push(([x, y], cut));
+            above.push(([x, y], cut));
+        }
+    }
+    (below, above)
+}
+
+fn dist(a: Point, b: Point) -> Scalar {
+    (b[0] - a[0]).hypot(b[1] - a[1])
+}
+
+fn mid(a: Point, b: Point) -> Point {
+    [(a[0] + b[0]) * 0.5, (a[1] + b[1]) * 0.5]
+}
+
 /// Triangulates the given quad, represented by four points that describe its edges in either
 /// clockwise or anti-clockwise order.
 ///
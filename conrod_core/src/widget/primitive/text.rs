@@ -3,6 +3,7 @@
 use position::{Dimension, Scalar};
 use std;
 use text;
+use theme::Theme;
 use utils;
 use widget;
 use {Color, Colorable, FontSize, Ui, Widget};
@@ -13,6 +14,22 @@ use {Color, Colorable, FontSize, Ui, Widget};
 ///
 /// If some horizontal dimension is given, the text will automatically wrap to the width and align
 /// in accordance with the produced **Alignment**.
+///
+/// The **Text** may instead be built from a list of styled spans via `Text::rich`, in which case
+/// each span may have its own color, font, size, underline and highlight.
+///
+/// A line that is too wide may instead be truncated to the width with an ellipsis via
+/// `Text::ellipsis`.
+///
+/// Each line may be decorated with an underline, strikethrough, overline and background highlight
+/// via `Text::decorations`, or the `underline`, `strikethrough`, `overline` and `highlight`
+/// builder methods.
+///
+/// The font may be selected by its family, weight and slant rather than by its `Id` via
+/// `Text::font_query`, `font_family`, `bold` and `italic`.
+///
+/// Any `char` missing from the font of the **Text** is displayed with the first font of its
+/// fallback chain that contains it. See `text::font::Map::chain`.
 #[derive(Clone, Debug, WidgetCommon_)]
 pub struct Text<'a> {
     /// Data necessary and common for all widget builder types.
@@ -20,6 +37,8 @@ pub struct Text<'a> {
     pub common: widget::CommonBuilder,
     /// The text to be drawn by the **Text**.
     pub text: &'a str,
+    /// The styled spans to be drawn in place of the `text`, if any.
+    pub spans: Option<&'a [text::rich::Span<'a>]>,
     /// Unique styling for the **Text**.
     pub style: Style,
 }
@@ -45,29 +64,29 @@ pub struct Style {
     /// The id of the font to use for rendering and layout.
     #[conrod(default = "theme.font_id")]
     pub font_id: Option<Option<text::font::Id>>,
-    // /// The line styling for the text.
-    // #[conrod(default = "None")]
-    // pub line: Option<Option<Line>>,
+    /// The family, weight and slant of the font, resolved relative to the `font_id`.
+    #[conrod(default = "None")]
+    pub font_query: Option<Option<text::font::Query>>,
+    /// Where to truncate lines that exceed the width, if at all.
+    #[conrod(default = "None")]
+    pub ellipsis: Option<Option<text::Ellipsis>>,
+    /// The lines and background with which each line of the text is decorated.
+    #[conrod(default = "text::Decorations::default()")]
+    pub decorations: Option<text::Decorations>,
 }
 
-/// The way in which text should wrap around the width.
-#[derive(Copy, Clone, Debug, PartialEq)]
-pub enum Wrap {
-    /// Wrap at the first character that exceeds the width.
-    Character,
-    /// Wrap at the first word that exceeds the width.
-    Whitespace,
-}
+pub use text::line::Wrap;
 
-// /// Line styling for the **Text**.
-// pub enum Line {
-//     /// Underline the text.
-//     Under,
-//     /// Overline the text.
-//     Over,
-//     /// Strikethrough the text.
-//     Through,
-// }
+impl Style {
+    /// The `Id` of the font with which the text is displayed. See `Theme::resolve_font_id`.
+    pub fn resolve_font_id(
+        &self,
+        theme: &Theme,
+        fonts: &text::font::Map,
+    ) -> Option<text::font::Id> {
+        theme.resolve_font_id(fonts, self.font_id(theme), self.font_query(theme))
+    }
+}
 
 /// The state to be stored between updates for the **Text**.
 #[derive(Clone, Debug, PartialEq)]
@@ -76,6 +95,11 @@ pub struct State {
     pub string: String,
     /// The indices and width for each line of text within the `string`.
     pub line_infos: Vec<text::line::Info>,
+    /// Each styled run of text when built via `Text::rich`, otherwise empty.
+    pub runs: Vec<text::rich::Run>,
+    /// The complete text when the `string` has been truncated with an ellipsis, e.g. for display
+    /// within a tooltip.
+    pub full_string: Option<String>,
 }
 
 impl<'a> Text<'a> {
@@ -84,6 +108,20 @@ impl<'a> Text<'a> {
         Text {
             common: widget::CommonBuilder::default(),
             text: text,
+            spans: None,
+            style: Style::default(),
+        }
+    }
+
+    /// Build a new **Text** widget from the given styled spans.
+    ///
+    /// Lines are broken across the spans as though they were a single string. Styling that is not
+    /// specified by a span falls back to the style of the **Text**.
+    pub fn rich(spans: &'a [text::rich::Span<'a>]) -> Self {
+        Text {
+            common: widget::CommonBuilder::default(),
+            text: "",
+            spans: Some(spans),
             style: Style::default(),
         }
     }
@@ -106,12 +144,100 @@ impl<'a> Text<'a> {
         self
     }
 
+    /// Truncate each line that exceeds the width of the **Text**, replacing the removed text with
+    /// an ellipsis at the given position.
+    ///
+    /// Truncated lines are never wrapped. Text built via `Text::rich` is not truncated.
+    pub fn ellipsis(mut self, ellipsis: text::Ellipsis) -> Self {
+        self.style.ellipsis = Some(Some(ellipsis));
+        self
+    }
+
+    /// Draw a line beneath each line of the **Text**.
+    pub fn underline(mut self) -> Self {
+        self.style.decorations = Some(text::Decorations {
+            underline: true,
+            ..self.style.decorations.unwrap_or_default()
+        });
+        self
+    }
+
+    /// Draw a line through the middle of each line of the **Text**.
+    pub fn strikethrough(mut self) -> Self {
+        self.style.decorations = Some(text::Decorations {
+            strikethrough: true,
+            ..self.style.decorations.unwrap_or_default()
+        });
+        self
+    }
+
+    /// Draw a line above each line of the **Text**.
+    pub fn overline(mut self) -> Self {
+        self.style.decorations = Some(text::Decorations {
+            overline: true,
+            ..self.style.decorations.unwrap_or_default()
+        });
+        self
+    }
+
+    /// Fill the area behind each line of the **Text** with the given color.
+    pub fn highlight(mut self, color: Color) -> Self {
+        self.style.decorations = Some(text::Decorations {
+            highlight: Some(color),
+            ..self.style.decorations.unwrap_or_default()
+        });
+        self
+    }
+
     /// A method for specifying the `Font` used for displaying the `Text`.
     pub fn font_id(mut self, font_id: text::font::Id) -> Self {
         self.style.font_id = Some(Some(font_id));
         self
     }
 
+    /// Display the **Text** with the font that most closely matches the given query.
+    ///
+    /// A query without a family selects from the family of the `font_id`, e.g. to display a bold
+    /// variant of the default font. See `text::font::Map::query`.
+    pub fn font_query(mut self, query: text::font::Query) -> Self {
+        self.style.font_query = Some(Some(query));
+        self
+    }
+
+    /// Display the **Text** with a font of the given family.
+    pub fn font_family(self, family: &str) -> Self {
+        let query = self.query();
+        self.font_query(text::font::Query {
+            family: Some(text::font::Family::new(family)),
+            ..query
+        })
+    }
+
+    /// Display the **Text** with a font of the given weight.
+    pub fn font_weight(self, weight: text::font::Weight) -> Self {
+        let query = self.query();
+        self.font_query(query.weight(weight))
+    }
+
+    /// Display the **Text** with a bold font.
+    pub fn bold(self) -> Self {
+        self.font_weight(text::font::Weight::BOLD)
+    }
+
+    /// Display the **Text** with an italic font.
+    pub fn italic(self) -> Self {
+        let query = self.query();
+        self.font_query(query.italic())
+    }
+
+    // The font query specified so far, if any.
+    fn query(&self) -> text::font::Query {
+        self.style
+            .font_query
+            .and_then(|query| query)
+            .unwrap_or_default()
+    }
+
     /// Build the **Text** with the given **Style**.
     pub fn with_style(mut self, style: Style) -> Self {
         self.style = style;
@@ -133,10 +259,161 @@ impl<'a> Text<'a> {
         self.justify(text::Justify::Right)
     }
 
+    /// Align wrapped lines of text to both ends of the bounding **Rect**'s *x* axis range by
+    /// adding space between words.
+    pub fn full_justify(self) -> Self {
+        self.justify(text::Justify::Full)
+    }
+
     builder_methods! {
         pub font_size { style.font_size = Some(FontSize) }
         pub justify { style.justify = Some(text::Justify) }
         pub line_spacing { style.line_spacing = Some(Scalar) }
+        pub decorations { style.decorations = Some(text::Decorations) }
+    }
+
+    // The style of the **Text** with which each of its spans is resolved.
+    //
+    // Returns `None` if there are no fonts.
+    fn rich_style(&self, ui: &Ui) -> Option<text::rich::Style> {
+        let font_id = self.style.resolve_font_id(&ui.theme, &ui.fonts)?;
+        let decorations = self.style.decorations(&ui.theme);
+        Some(text::rich::Style {
+            color: self.style.color(&ui.theme),
+            font_id: font_id,
+            font_size: self.style.font_size(&ui.theme),
+            underline: decorations.underline,
+            highlight: decorations.highlight,
+        })
+    }
+
+    // The `line::Info` of each line of the given plain `text` displayed with the font of the given
+    // `font_id`, wrapped to the `max_width` if some `maybe_wrap` is given.
+    //
+    // Layouts are shared across frames via the `Ui`'s `text::cache::Cache`.
+    fn line_infos(
+        &self,
+        ui: &Ui,
+        text: &str,
+        font_id: text::font::Id,
+        maybe_wrap: Option<Wrap>,
+        max_width: Scalar,
+    ) -> std::sync::Arc<[text::line::Info]> {
+        let font_size = self.style.font_size(&ui.theme);
+        let justify = self.style.justify(&ui.theme);
+        let wrap = maybe_wrap.map(|wrap| (wrap, max_width));
+        let key = text::cache::Key::new(text, &[font_id], font_size, wrap, justify);
+        ui.text_cache().line_infos(key, text, || {
+            let font = match ui.fonts.get(font_id) {
+                Some(font) => font,
+                None => return vec![],
+            };
+            let infos = match maybe_wrap {
+                None => text::line::infos(text, font, font_size),
+                Some(Wrap::Character) => {
+                    text::line::infos(text, font, font_size).wrap_by_character(max_width)
+                }
+                Some(Wrap::Whitespace) => {
+                    text::line::infos(text, font, font_size).wrap_by_whitespace(max_width)
+                }
+            };
+            // Lines are measured by their shaped width if the data of the font is available.
+            #[cfg(feature = "shaping")]
+            let face = ui
+                .fonts
+                .data(font_id)
+                .and_then(|data| text::shaping::Face::new(font, data));
+            #[cfg(feature = "shaping")]
+            let infos = infos.shaped(face.as_ref());
+            infos.collect::<Vec<_>>()
+        })
+    }
+
+    // The layout of the given rich text `spans` within the given `width`.
+    //
+    // Layouts are shared across frames via the `Ui`'s `text::cache::Cache`, so sizing and updating
+    // the **Text** lay out its spans once between them. Returns `None` if there are no fonts.
+    fn rich_layout(
+        &self,
+        ui: &Ui,
+        spans: &[text::rich::Span],
+        width: Scalar,
+        maybe_wrap: Option<Wrap>,
+        justify: text::Justify,
+        line_spacing: Scalar,
+    ) -> Option<std::sync::Arc<text::rich::Layout>> {
+        let base = self.rich_style(ui)?;
+        let fallbacks = &ui.theme.font_fallbacks;
+        let key = text::cache::Key::rich(
+            spans,
+            base,
+            fallbacks,
+            width,
+            maybe_wrap,
+            justify,
+            line_spacing,
+        );
+        let layout = ui.text_cache().rich_layout(key, spans, || {
+            text::rich::layout(
+                spans,
+                base,
+                &ui.fonts,
+                fallbacks,
+                width,
+                maybe_wrap,
+                justify,
+                line_spacing,
+            )
+        });
+        Some(layout)
+    }
+
+    // A single span covering the given plain `text`, if any of its `char`s must be displayed with
+    // a fallback of its font.
+    fn fallback_span<'b>(&self, ui: &Ui, text: &'b str) -> Option<text::rich::Span<'b>> {
+        if self.spans.is_some() {
+            return None;
+        }
+        let font_id = self.style.resolve_font_id(&ui.theme, &ui.fonts)?;
+        let fallbacks = &ui.theme.font_fallbacks;
+        let runs = ui.fonts.fallback_runs(text, font_id, fallbacks);
+        if runs.iter().all(|&(_, id)| id == font_id) {
+            return None;
+        }
+        Some(text::rich::Span::new(text))
+    }
+
+    // The ellipsis with which lines of the plain `text` are truncated, if any.
+    fn maybe_ellipsis(&self, ui: &Ui) -> Option<text::Ellipsis> {
+        match self.spans {
+            Some(_) => None,
+            None => self.style.ellipsis(&ui.theme),
+        }
+    }
+
+    // The wrapping of the **Text**, taking into account that truncated lines are never wrapped.
+    fn maybe_wrap(&self, ui: &Ui) -> Option<Wrap> {
+        match self.maybe_ellipsis(ui) {
+            Some(_) => None,
+            None => self.style.maybe_wrap(&ui.theme),
+        }
+    }
+
+    // The plain `text` with each line that exceeds the given width truncated.
+    //
+    // Truncations are shared across frames via the `Ui`'s `text::cache::Cache`, so the text is
+    // only measured again once it, its font or the width changes. Returns `None` if no line was
+    // truncated.
+    fn truncate(&self, ui: &Ui, max_width: Scalar) -> Option<std::sync::Arc<str>> {
+        let ellipsis = self.maybe_ellipsis(ui)?;
+        let font_id = self.style.resolve_font_id(&ui.theme, &ui.fonts)?;
+        let chain = ui.fonts.chain(font_id, &ui.theme.font_fallbacks);
+        let font_size = self.style.font_size(&ui.theme);
+        let key = text::cache::Key::truncated(self.text, &chain, font_size, max_width, ellipsis);
+        ui.text_cache().truncated(key, self.text, || {
+            let fonts: Vec<_> = chain.iter().filter_map(|&id| ui.fonts.get(id)).collect();
+            text::line::truncate(self.text, &fonts, font_size, max_width, ellipsis)
+        })
     }
 }
 
@@ -149,6 +426,8 @@ impl<'a> Widget for Text<'a> {
         State {
             string: String::new(),
             line_infos: Vec::new(),
+            runs: Vec::new(),
+            full_string: None,
         }
     }
 
@@ -161,22 +440,31 @@ impl<'a> Widget for Text<'a> {
     /// The `Font` used by the `Text` is retrieved in order to determine the width of each line. If
     /// the font used by the `Text` cannot be found, a dimension of `Absolute(0.0)` is returned.
     fn default_x_dimension(&self, ui: &Ui) -> Dimension {
-        let font = match self
+        let font_id = match self
             .style
-            .font_id(&ui.theme)
-            .or(ui.fonts.ids().next())
-            .and_then(|id| ui.fonts.get(id))
+            .resolve_font_id(&ui.theme, &ui.fonts)
+            .and_then(|id| ui.fonts.get(id).map(|_| id))
         {
-            Some(font) => font,
+            Some(font_id) => font_id,
             None => return Dimension::Absolute(0.0),
         };
 
-        let font_size = self.style.font_size(&ui.theme);
-        let mut max_width = 0.0;
-        for line in self.text.lines() {
-            let width = text::line::width(line, font, font_size);
-            max_width = utils::partial_max(max_width, width);
+        let fallback_span = self.fallback_span(ui, self.text);
+        let spans = self
+            .spans
+            .or_else(|| fallback_span.as_ref().map(std::slice::from_ref));
+        if let Some(spans) = spans {
+            let left = text::Justify::Left;
+            let width = self
+                .rich_layout(ui, spans, f64::MAX, None, left, 0.0)
+                .map_or(0.0, |layout| layout.width);
+            return Dimension::Absolute(width);
         }
+
+        let line_infos = self.line_infos(ui, self.text, font_id, None, f64::MAX);
+        let max_width = line_infos
+            .iter()
+            .fold(0.0, |max, info| utils::partial_max(max, info.width));
         Dimension::Absolute(max_width)
     }
 
@@ -187,30 +475,39 @@ impl<'a> Widget for Text<'a> {
     fn default_y_dimension(&self, ui: &Ui) -> Dimension {
         use position::Sizeable;
 
-        let font = match self
+        let font_id = match self
             .style
-            .font_id(&ui.theme)
-            .or(ui.fonts.ids().next())
-            .and_then(|id| ui.fonts.get(id))
+            .resolve_font_id(&ui.theme, &ui.fonts)
+            .and_then(|id| ui.fonts.get(id).map(|_| id))
         {
-            Some(font) => font,
+            Some(font_id) => font_id,
             None => return Dimension::Absolute(0.0),
         };
 
+        let fallback_span = self.fallback_span(ui, self.text);
+        let spans = self
+            .spans
+            .or_else(|| fallback_span.as_ref().map(std::slice::from_ref));
+        if let Some(spans) = spans {
+            let (max_w, maybe_wrap) = match self.get_w(ui) {
+                Some(max_w) => (max_w, self.maybe_wrap(ui)),
+                None => (f64::MAX, None),
+            };
+            let justify = self.style.justify(&ui.theme);
+            let line_spacing = self.style.line_spacing(&ui.theme);
+            let height = self
+                .rich_layout(ui, spans, max_w, maybe_wrap, justify, line_spacing)
+                .map_or(0.0, |layout| layout.height);
+            return Dimension::Absolute(height);
+        }
+
         let text = &self.text;
         let font_size = self.style.font_size(&ui.theme);
-        let num_lines = match self.style.maybe_wrap(&ui.theme) {
+        let num_lines = match self.maybe_wrap(ui) {
             None => text.lines().count(),
             Some(wrap) => match self.get_w(ui) {
                 None => text.lines().count(),
-                Some(max_w) => match wrap {
-                    Wrap::Character => text::line::infos(text, font, font_size)
-                        .wrap_by_character(max_w)
-                        .count(),
-                    Wrap::Whitespace => text::line::infos(text, font, font_size)
-                        .wrap_by_whitespace(max_w)
-                        .count(),
-                },
+                Some(max_w) => self.line_infos(ui, text, font_id, Some(wrap), max_w).len(),
             },
         };
         let line_spacing = self.style.line_spacing(&ui.theme);
@@ -227,55 +524,67 @@ impl<'a> Widget for Text<'a> {
             ui,
             ..
         } = args;
-        let Text { text, .. } = self;
+        let maybe_wrap = self.maybe_wrap(ui);
+
+        // Lines that exceed the width are truncated if an ellipsis was given, in which case the
+        // complete text is kept alongside.
+        let truncated = self.truncate(ui, rect.w());
+        let text = truncated.as_ref().map(|s| &s[..]).unwrap_or(self.text);
+        let full_string = truncated.as_ref().map(|_| self.text);
+        if state.full_string.as_ref().map(|s| &s[..]) != full_string {
+            state.update(|state| state.full_string = full_string.map(str::to_owned));
+        }
 
-        let maybe_wrap = style.maybe_wrap(ui.theme());
-        let font_size = style.font_size(ui.theme());
+        // Rich text is laid out as a whole, and only updated if the layout has changed. Plain text
+        // that requires fallback fonts is laid out in the same manner, with a run for each font.
+        let fallback_span = self.fallback_span(ui, text);
+        let spans = self
+            .spans
+            .or_else(|| fallback_span.as_ref().map(std::slice::from_ref));
+        if let Some(spans) = spans {
+            let justify = style.justify(ui.theme());
+            let line_spacing = style.line_spacing(ui.theme());
+            let layout =
+                match self.rich_layout(ui, spans, rect.w(), maybe_wrap, justify, line_spacing) {
+                    Some(layout) => layout,
+                    None => return,
+                };
+            if state.string != layout.text
+                || state.line_infos != layout.line_infos
+                || state.runs != layout.runs
+            {
+                state.update(|state| {
+                    state.string = layout.text.clone();
+                    state.line_infos = layout.line_infos.clone();
+                    state.runs = layout.runs.clone();
+                });
+            }
+            return;
+        }
 
-        let font = match style
-            .font_id(&ui.theme)
-            .or(ui.fonts.ids().next())
-            .and_then(|id| ui.fonts.get(id))
+        let font_id = match style
+            .resolve_font_id(&ui.theme, &ui.fonts)
+            .and_then(|id| ui.fonts.get(id).map(|_| id))
         {
-            Some(font) => font,
+            Some(font_id) => font_id,
             None => return,
         };
 
-        // Produces an iterator yielding info for each line within the `text`.
-        let new_line_infos = || match maybe_wrap {
-            None => text::line::infos(text, font, font_size),
-            Some(Wrap::Character) => {
-                text::line::infos(text, font, font_size).wrap_by_character(rect.w())
-            }
-            Some(Wrap::Whitespace) => {
-                text::line::infos(text, font, font_size).wrap_by_whitespace(rect.w())
-            }
-        };
+        if !state.runs.is_empty() {
+            state.update(|state| state.runs.clear());
+        }
+
+        let line_infos = self.line_infos(ui, text, font_id, maybe_wrap, rect.w());
 
         // If the string is different, we must update both the string and the line breaks.
+        // Otherwise, we'll check to see if we have to update the line breaks.
         if &state.string[..] != text {
             state.update(|state| {
                 state.string = text.to_owned();
-                state.line_infos = new_line_infos().collect();
+                state.line_infos = line_infos.to_vec();
             });
-
-        // Otherwise, we'll check to see if we have to update the line breaks.
-        } else {
-            use std::borrow::Cow;
-            use utils::write_if_different;
-
-            // Compare the line_infos and only collect the new ones if they are different.
-            let maybe_new_line_infos = {
-                let line_infos = &state.line_infos[..];
-                match write_if_different(line_infos, new_line_infos()) {
-                    Cow::Owned(new) => Some(new),
-                    _ => None,
-                }
-            };
-
-            if let Some(new_line_infos) = maybe_new_line_infos {
-                state.update(|state| state.line_infos = new_line_infos);
-            }
+        } else if state.line_infos[..] != line_infos[..] {
+            state.update(|state| state.line_infos = line_infos.to_vec());
         }
     }
 }
@@ -4,16 +4,18 @@
 //! circle used to draw the corners.
 
 use graph;
+use position::ClipShape;
 use std::f64::consts::PI;
 use widget;
 use widget::primitive::shape::oval::Circumference;
 use widget::primitive::shape::Style;
 use {
-    Color, Colorable, Dimensions, Point, Positionable, Range, Rect, Scalar, Sizeable, Theme, Widget,
+    color, Color, Colorable, Dimensions, Point, Positionable, Range, Rect, Scalar, Sizeable, Theme,
+    Widget,
 };
 
 /// Draws a rectangle with corners rounded via the given radius.
-#[derive(Copy, Clone, Debug, WidgetCommon_)]
+#[derive(Clone, Debug, WidgetCommon_)]
 pub struct RoundedRectangle {
     /// Data necessary and common for all widget builder types.
     #[conrod(common_builder)]
@@ -40,9 +42,16 @@ pub struct State {
 
 impl RoundedRectangle {
     /// Build a rounded rectangle with the given dimensions and style.
+    ///
+    /// If the rounded rectangle crops its children via `Widget::crop_kids`, they are also clipped
+    /// to its rounded corners.
     pub fn styled(dim: Dimensions, radius: Scalar, style: Style) -> Self {
+        let common = widget::CommonBuilder {
+            maybe_clip_shape: Some(ClipShape::RoundedRectangle { radius: radius }),
+            ..widget::CommonBuilder::default()
+        };
         RoundedRectangle {
-            common: widget::CommonBuilder::default(),
+            common: common,
             style: style,
             radius: radius,
             corner_resolution: DEFAULT_CORNER_RESOLUTION,
@@ -60,6 +69,11 @@ impl RoundedRectangle {
         RoundedRectangle::styled(dim, radius, Style::fill_with(color))
     }
 
+    /// Build a new rounded rectangle widget filled with the given gradient.
+    pub fn fill_gradient(dim: Dimensions, radius: Scalar, gradient: color::Gradient) -> Self {
+        RoundedRectangle::styled(dim, radius, Style::gradient(gradient))
+    }
+
     /// Build a new outlined rounded rectangle widget.
     pub fn outline(dim: Dimensions, radius: Scalar) -> Self {
         RoundedRectangle::styled(dim, radius, Style::outline())
@@ -117,7 +131,7 @@ impl Widget for RoundedRectangle {
         } = self;
         let points = points(rect, radius, corner_resolution);
         let (x, y, w, h) = rect.x_y_w_h();
-        widget::Polygon::styled(points, *style)
+        widget::Polygon::styled(points, style.clone())
             .x_y(x, y)
             .w_h(w, h)
             .parent(id)
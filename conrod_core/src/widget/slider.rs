@@ -5,7 +5,7 @@ use position::{Padding, Range, Rect, Scalar};
 use text;
 use widget;
 use widget::triangles::Triangle;
-use {Borderable, Color, Colorable, FontSize, Labelable, Positionable, Widget};
+use {Borderable, Color, Colorable, FontSize, Labelable, Positionable, Sizeable, Widget};
 
 /// Linear value selection.
 ///
@@ -53,9 +53,16 @@ pub struct Style {
     /// The font-size for the Slider's label.
     #[conrod(default = "theme.font_size_medium")]
     pub label_font_size: Option<FontSize>,
+    /// Where to truncate the Slider's label should it exceed the available width, if at all.
+    #[conrod(default = "None")]
+    pub label_ellipsis: Option<Option<text::Ellipsis>>,
     /// The ID of the font used to display the label.
     #[conrod(default = "theme.font_id")]
     pub label_font_id: Option<Option<text::font::Id>>,
+    /// The family, weight and slant of the label's font, resolved relative to the
+    /// `label_font_id`.
+    #[conrod(default = "None")]
+    pub label_font_query: Option<Option<text::font::Query>>,
 }
 
 widget_ids! {
@@ -91,6 +98,13 @@ impl<'a, T> Slider<'a, T> {
         self
     }
 
+    /// Display the label with the font that most closely matches the given query. See
+    /// `widget::Text::font_query`.
+    pub fn label_font_query(mut self, query: text::font::Query) -> Self {
+        self.style.label_font_query = Some(Some(query));
+        self
+    }
+
     builder_methods! {
         pub skew { skew = f32 }
         pub enabled { enabled = bool }
@@ -267,10 +281,18 @@ where
         if let Some(label) = maybe_label {
             let label_color = style.label_color(ui.theme());
             let font_size = style.label_font_size(ui.theme());
-            let font_id = style.label_font_id(&ui.theme).or(ui.fonts.ids().next());
+            let font_id = ui.theme.resolve_font_id(
+                &ui.fonts,
+                style.label_font_id(&ui.theme),
+                style.label_font_query(&ui.theme),
+            );
+            let ellipsis = style.label_ellipsis(&ui.theme);
             //const TEXT_PADDING: f64 = 10.0;
             widget::Text::new(label)
                 .and_then(font_id, widget::Text::font_id)
+                .and_then(ellipsis, |text, ellipsis| {
+                    text.ellipsis(ellipsis).padded_w_of(id, border)
+                })
                 .and(|text| {
                     if is_horizontal {
                         text.mid_left_of(id)
@@ -310,4 +332,8 @@ impl<'a, T> Labelable<'a> for Slider<'a, T> {
         label_color { style.label_color = Some(Color) }
         label_font_size { style.label_font_size = Some(FontSize) }
     }
+    fn label_ellipsis(mut self, ellipsis: text::Ellipsis) -> Self {
+        self.style.label_ellipsis = Some(Some(ellipsis));
+        self
+    }
 }
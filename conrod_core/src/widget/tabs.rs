@@ -51,9 +51,15 @@ pub struct Style {
     /// Font size of the number dialer's label.
     #[conrod(default = "theme.font_size_medium")]
     pub label_font_size: Option<FontSize>,
+    /// Where to truncate tab labels that exceed the size of their tab, if at all.
+    #[conrod(default = "None")]
+    pub label_ellipsis: Option<Option<text::Ellipsis>>,
     /// The `font::Id` of the number dialer's font.
     #[conrod(default = "None")]
     pub font_id: Option<Option<text::font::Id>>,
+    /// The family, weight and slant of the labels' font, resolved relative to the `font_id`.
+    #[conrod(default = "None")]
+    pub label_font_query: Option<Option<text::font::Query>>,
     /// The styling for each `Canvas`.
     #[conrod(default = "widget::canvas::Style::default()")]
     pub canvas: Option<widget::canvas::Style>,
@@ -169,6 +175,22 @@ impl<'a> Tabs<'a> {
         self
     }
 
+    /// Where to truncate tab labels that exceed the size of their tab, or `None` to let them
+    /// overflow.
+    ///
+    /// By default, labels are truncated at their end.
+    pub fn label_ellipsis(mut self, maybe_ellipsis: Option<text::Ellipsis>) -> Self {
+        self.style.label_ellipsis = Some(maybe_ellipsis);
+        self
+    }
+
+    /// Display the tab labels with the font that most closely matches the given query. See
+    /// `widget::Text::font_query`.
+    pub fn label_font_query(mut self, query: text::font::Query) -> Self {
+        self.style.label_font_query = Some(Some(query));
+        self
+    }
+
     builder_methods! {
         pub starting_tab_idx { maybe_starting_tab_idx = Some(usize) }
         pub label_color { style.label_color = Some(Color) }
@@ -212,9 +234,9 @@ impl<'a> Widget for Tabs<'a> {
                 }
             }
             Layout::Vertical => {
-                let max_text_width = style
-                    .font_id(theme)
-                    .or(fonts.ids().next())
+                let font_id = style.font_id(theme);
+                let max_text_width = theme
+                    .resolve_font_id(fonts, font_id, style.label_font_query(theme))
                     .and_then(|id| fonts.get(id))
                     .map(|font| max_text_width(self.tabs.iter(), font_size, font))
                     .unwrap_or(0.0);
@@ -245,9 +267,12 @@ impl<'a> Widget for Tabs<'a> {
         let layout = style.layout(&ui.theme);
         let font_size = style.label_font_size(&ui.theme);
         let canvas_style = style.canvas(&ui.theme);
-        let max_text_width = style
-            .font_id(&ui.theme)
-            .or(ui.fonts.ids().next())
+        let font_id = ui.theme.resolve_font_id(
+            &ui.fonts,
+            style.font_id(&ui.theme),
+            style.label_font_query(&ui.theme),
+        );
+        let max_text_width = font_id
             .and_then(|id| ui.fonts.get(id))
             .map(|font| max_text_width(self.tabs.iter(), font_size, font))
             .unwrap_or(0.0);
@@ -294,6 +319,7 @@ impl<'a> Widget for Tabs<'a> {
             let border = canvas_style.border(&ui.theme);
             let border_color = canvas_style.border_color(ui.theme());
             let label_color = style.label_color(ui.theme());
+            let label_ellipsis = style.label_ellipsis(ui.theme());
             let mut maybe_selected_tab_idx = state
                 .maybe_selected_tab_idx
                 .or(maybe_starting_tab_idx)
@@ -314,6 +340,8 @@ impl<'a> Widget for Tabs<'a> {
                     .border_color(border_color)
                     .label(label)
                     .label_color(label_color)
+                    .and_then(font_id, |button, id| button.label_font_id(id))
+                    .and_then(label_ellipsis, |button, e| button.label_ellipsis(e))
                     .parent(id)
                     .set(tab.button_id, &mut ui)
                     .was_clicked()
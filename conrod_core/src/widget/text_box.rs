@@ -17,6 +17,7 @@ pub struct TextBox<'a> {
     common: widget::CommonBuilder,
     text: &'a str,
     style: Style,
+    clear_history: bool,
 }
 
 /// Unique graphical styling for the TextBox.
@@ -49,6 +50,12 @@ pub struct Style {
     /// The font used for the `Text`.
     #[conrod(default = "theme.font_id")]
     pub font_id: Option<Option<text::font::Id>>,
+    /// The family, weight and slant of the font, resolved relative to the `font_id`.
+    #[conrod(default = "None")]
+    pub font_query: Option<Option<text::font::Query>>,
+    /// The maximum number of edits that may be undone.
+    #[conrod(default = "widget::text_edit::DEFAULT_HISTORY_CAPACITY")]
+    pub history_capacity: Option<usize>,
 }
 
 widget_ids! {
@@ -70,6 +77,7 @@ impl<'a> TextBox<'a> {
             common: widget::CommonBuilder::default(),
             style: Style::default(),
             text: text,
+            clear_history: false,
         }
     }
 
@@ -94,11 +102,26 @@ impl<'a> TextBox<'a> {
         self
     }
 
+    /// Display the text with the font that most closely matches the given query. See
+    /// `widget::Text::font_query`.
+    pub fn font_query(mut self, query: text::font::Query) -> Self {
+        self.style.font_query = Some(Some(query));
+        self
+    }
+
+    /// Forget all edits that could otherwise be undone or redone. See
+    /// `widget::TextEdit::clear_history`.
+    pub fn clear_history(mut self) -> Self {
+        self.clear_history = true;
+        self
+    }
+
     builder_methods! {
         pub text_color { style.text_color = Some(Color) }
         pub font_size { style.font_size = Some(FontSize) }
         pub justify { style.justify = Some(text::Justify) }
         pub pad_text { style.text_padding = Some(Scalar) }
+        pub history_capacity { style.history_capacity = Some(usize) }
     }
 }
 
@@ -136,7 +159,11 @@ impl<'a> Widget for TextBox<'a> {
             ui,
             ..
         } = args;
-        let TextBox { text, .. } = self;
+        let TextBox {
+            text,
+            clear_history,
+            ..
+        } = self;
 
         let font_size = style.font_size(ui.theme());
         let border = style.border(ui.theme());
@@ -165,14 +192,22 @@ impl<'a> Widget for TextBox<'a> {
         let mut events = Vec::new();
 
         let text_color = style.text_color(ui.theme());
-        let font_id = style.font_id(&ui.theme).or(ui.fonts.ids().next());
-        if let Some(new_string) = widget::TextEdit::new(text)
+        let font_id = style.font_id(&ui.theme);
+        let font_query = style.font_query(&ui.theme);
+        let history_capacity = style.history_capacity(&ui.theme);
+        let mut text_edit = widget::TextEdit::new(text);
+        if clear_history {
+            text_edit = text_edit.clear_history();
+        }
+        if let Some(new_string) = text_edit
             .and_then(font_id, widget::TextEdit::font_id)
+            .and_then(font_query, widget::TextEdit::font_query)
             .wh(text_rect.dim())
             .xy(text_rect.xy())
             .font_size(font_size)
             .color(text_color)
             .justify(justify)
+            .history_capacity(history_capacity)
             .parent(id)
             .set(state.ids.text_edit, ui)
         {
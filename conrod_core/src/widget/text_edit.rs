@@ -7,6 +7,7 @@ use input;
 use position::{Align, Dimension, Point, Range, Rect, Scalar};
 use std;
 use text;
+use theme::Theme;
 use utils;
 use widget;
 use widget::primitive::text::Wrap;
@@ -22,6 +23,7 @@ pub struct TextEdit<'a> {
     common: widget::CommonBuilder,
     text: &'a str,
     style: Style,
+    clear_history: bool,
 }
 
 /// Unique graphical styling for the TextEdit.
@@ -51,11 +53,32 @@ pub struct Style {
     /// The font used for the `Text`.
     #[conrod(default = "theme.font_id")]
     pub font_id: Option<Option<text::font::Id>>,
+    /// The family, weight and slant of the font, resolved relative to the `font_id`.
+    #[conrod(default = "None")]
+    pub font_query: Option<Option<text::font::Query>>,
+    /// The maximum number of edits that may be undone.
+    #[conrod(default = "DEFAULT_HISTORY_CAPACITY")]
+    pub history_capacity: Option<usize>,
+}
+
+/// The number of edits that may be undone unless otherwise specified via `history_capacity`.
+pub const DEFAULT_HISTORY_CAPACITY: usize = 100;
+
+impl Style {
+    /// The `Id` of the font with which the text is displayed. See `Theme::resolve_font_id`.
+    pub fn resolve_font_id(
+        &self,
+        theme: &Theme,
+        fonts: &text::font::Map,
+    ) -> Option<text::font::Id> {
+        theme.resolve_font_id(fonts, self.font_id(theme), self.font_query(theme))
+    }
 }
 
 widget_ids! {
     struct Ids {
         selected_rectangles[],
+        preedit_underlines[],
         text,
         cursor,
     }
@@ -68,9 +91,61 @@ pub struct State {
     drag: Option<Drag>,
     /// Information about each line of text.
     line_infos: Vec<text::line::Info>,
+    /// The edits that may be undone and redone.
+    history: History,
+    /// Text being composed via an input method editor, if any.
+    preedit: Option<Preedit>,
     ids: Ids,
 }
 
+/// Text being composed via an input method editor, displayed at the cursor until committed.
+#[derive(Clone, Debug, PartialEq)]
+struct Preedit {
+    string: String,
+    /// The byte range of the IME's cursor within the `string`, if visible.
+    cursor: Option<(usize, usize)>,
+}
+
+/// The text as displayed with a `Preedit` inserted at the cursor.
+struct PreeditLayout {
+    text: String,
+    line_infos: Vec<text::line::Info>,
+    /// The range occupied by the `Preedit`.
+    start: text::cursor::Index,
+    end: text::cursor::Index,
+    /// The position of the IME's cursor, or the end of the `Preedit` if it is not visible.
+    caret: text::cursor::Index,
+}
+
+impl State {
+    /// The edits that may be undone and redone.
+    pub fn history(&self) -> &History {
+        &self.history
+    }
+}
+
+/// The history of edits to a `TextEdit`'s text, allowing them to be undone and redone.
+///
+/// Consecutively typed characters are grouped so that each undo removes roughly one word, while
+/// every other edit (deleting, pasting, inserting a new line) may be undone on its own.
+#[derive(Clone, Debug, PartialEq)]
+pub struct History {
+    /// The text and cursor before each edit, most recent last.
+    undo: std::collections::VecDeque<Snapshot>,
+    /// The text and cursor before each undo, most recent last.
+    redo: Vec<Snapshot>,
+    /// The position of the cursor after the last typed character, while its group is open.
+    typing: Option<Cursor>,
+    capacity: usize,
+}
+
+/// The text and cursor of a `TextEdit` at some point in its `History`.
+#[derive(Clone, Debug, PartialEq)]
+struct Snapshot {
+    text: String,
+    cursor: Cursor,
+}
+
 /// Track whether some sort of dragging is currently occurring.
 #[derive(Copy, Clone, Debug, PartialEq)]
 pub enum Drag {
@@ -98,6 +173,132 @@ pub enum Cursor {
     },
 }
 
+impl History {
+    /// An empty history that remembers at most `capacity` edits.
+    pub fn new(capacity: usize) -> Self {
+        History {
+            undo: std::collections::VecDeque::new(),
+            redo: Vec::new(),
+            typing: None,
+            capacity,
+        }
+    }
+
+    /// The maximum number of edits that may be undone.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Bound the number of edits that may be undone, forgetting the oldest edits beyond it.
+    pub fn set_capacity(&mut self, capacity: usize) {
+        self.capacity = capacity;
+        while self.undo.len() > capacity {
+            self.undo.pop_front();
+        }
+        self.redo.truncate(capacity);
+    }
+
+    /// Forget all edits.
+    pub fn clear(&mut self) {
+        self.undo.clear();
+        self.redo.clear();
+        self.typing = None;
+    }
+
+    /// The number of edits that may be undone.
+    pub fn undo_len(&self) -> usize {
+        self.undo.len()
+    }
+
+    /// The number of undone edits that may be redone.
+    pub fn redo_len(&self) -> usize {
+        self.redo.len()
+    }
+
+    /// Whether or not there is an edit that may be undone.
+    pub fn can_undo(&self) -> bool {
+        !self.undo.is_empty()
+    }
+
+    /// Whether or not there is an undone edit that may be redone.
+    pub fn can_redo(&self) -> bool {
+        !self.redo.is_empty()
+    }
+
+    // Record the `text` and `cursor` from before some edit other than typing.
+    fn record(&mut self, text: &str, cursor: Cursor) {
+        self.typing = None;
+        self.redo.clear();
+        if self.capacity == 0 {
+            return;
+        }
+        if self.undo.len() == self.capacity {
+            self.undo.pop_front();
+        }
+        self.undo.push_back(Snapshot {
+            text: text.to_owned(),
+            cursor,
+        });
+    }
+
+    // Record the `text` and `cursor` from before typing the given `string`, which left the cursor
+    // at `new_cursor`.
+    //
+    // The string joins the group of the previously typed characters if the cursor has not moved
+    // since. Typing whitespace ends the group, so that each group spans roughly one word.
+    fn record_typing(&mut self, text: &str, cursor: Cursor, string: &str, new_cursor: Cursor) {
+        if self.typing != Some(cursor) || self.undo.is_empty() {
+            self.record(text, cursor);
+        }
+        let ends_word = match string.chars().last() {
+            Some(c) => c.is_whitespace(),
+            None => false,
+        };
+        self.typing = if ends_word { None } else { Some(new_cursor) };
+    }
+
+    // Swap the current `text` and `cursor` for those before the most recent edit.
+    fn undo(&mut self, text: &str, cursor: Cursor) -> Option<(String, Cursor)> {
+        let snapshot = self.undo.pop_back()?;
+        self.typing = None;
+        self.redo.push(Snapshot {
+            text: text.to_owned(),
+            cursor,
+        });
+        Some((snapshot.text, snapshot.cursor))
+    }
+
+    // Swap the current `text` and `cursor` for those before the most recent undo.
+    fn redo(&mut self, text: &str, cursor: Cursor) -> Option<(String, Cursor)> {
+        let snapshot = self.redo.pop()?;
+        self.typing = None;
+        self.undo.push_back(Snapshot {
+            text: text.to_owned(),
+            cursor,
+        });
+        Some((snapshot.text, snapshot.cursor))
+    }
+}
+
+impl Default for History {
+    fn default() -> Self {
+        History::new(DEFAULT_HISTORY_CAPACITY)
+    }
+}
+
+impl Cursor {
+    // Ensure the indices of the cursor lie within the given lines.
+    fn clamp_to_lines(self, line_infos: &[text::line::Info]) -> Self {
+        match self {
+            Cursor::Idx(index) => Cursor::Idx(index.clamp_to_lines(line_infos.iter().cloned())),
+            Cursor::Selection { start, end } => Cursor::Selection {
+                start: start.clamp_to_lines(line_infos.iter().cloned()),
+                end: end.clamp_to_lines(line_infos.iter().cloned()),
+            },
+        }
+    }
+}
+
 impl<'a> TextEdit<'a> {
     /// Construct a TextEdit widget.
     pub fn new(text: &'a str) -> Self {
@@ -105,9 +306,16 @@ impl<'a> TextEdit<'a> {
             common: widget::CommonBuilder::default(),
             style: Style::default(),
             text: text,
+            clear_history: false,
         }
     }
 
+    /// Forget all edits that could otherwise be undone or redone, e.g. after loading a new text.
+    pub fn clear_history(mut self) -> Self {
+        self.clear_history = true;
+        self
+    }
+
     /// The `TextEdit` will wrap text via the whitespace that precedes the first width-exceeding
     /// character.
     ///
@@ -139,6 +347,12 @@ impl<'a> TextEdit<'a> {
         self.justify(text::Justify::Right)
     }
 
+    /// Align wrapped lines of text to both ends of the bounding **Rect**'s *x* axis range by
+    /// adding space between words.
+    pub fn full_justify(self) -> Self {
+        self.justify(text::Justify::Full)
+    }
+
     /// Align the text to the left of its bounding **Rect**'s *y* axis range.
     pub fn align_text_bottom(self) -> Self {
         self.y_align_text(Align::Start)
@@ -165,6 +379,13 @@ impl<'a> TextEdit<'a> {
         self
     }
 
+    /// Display the text with the font that most closely matches the given query. See
+    /// `widget::Text::font_query`.
+    pub fn font_query(mut self, query: text::font::Query) -> Self {
+        self.style.font_query = Some(Some(query));
+        self
+    }
+
     builder_methods! {
         pub font_size { style.font_size = Some(FontSize) }
         pub justify { style.justify = Some(text::Justify) }
@@ -172,6 +393,7 @@ impl<'a> TextEdit<'a> {
         pub line_wrap { style.line_wrap = Some(Wrap) }
         pub line_spacing { style.line_spacing = Some(Scalar) }
         pub restrict_to_height { style.restrict_to_height = Some(bool) }
+        pub history_capacity { style.history_capacity = Some(usize) }
     }
 }
 
@@ -189,6 +411,8 @@ impl<'a> Widget for TextEdit<'a> {
             cursor: Cursor::Idx(text::cursor::Index { line: 0, char: 0 }),
             drag: None,
             line_infos: Vec::new(),
+            history: History::default(),
+            preedit: None,
             ids: Ids::new(id_gen),
         }
     }
@@ -206,27 +430,34 @@ impl<'a> Widget for TextEdit<'a> {
 
         // Otherwise the height is unrestricted, and we should infer the height as the total height
         // of the fully styled, wrapped text.
-        let font = match self
+        let font_id = match self
             .style
-            .font_id(&ui.theme)
-            .or(ui.fonts.ids().next())
-            .and_then(|id| ui.fonts.get(id))
+            .resolve_font_id(&ui.theme, &ui.fonts)
+            .and_then(|id| ui.fonts.get(id).map(|_| id))
         {
-            Some(font) => font,
+            Some(font_id) => font_id,
             None => return Dimension::Absolute(0.0),
         };
+        let fonts = fallback_fonts(ui, font_id);
+        let font = &fonts[0];
 
         let text = &self.text;
         let font_size = self.style.font_size(&ui.theme);
+        let line_wrap = self.style.line_wrap(&ui.theme);
         let num_lines = match self.get_w(ui) {
             None => text.lines().count(),
-            Some(max_w) => match self.style.line_wrap(&ui.theme) {
-                Wrap::Character => text::line::infos(text, font, font_size)
-                    .wrap_by_character(max_w)
-                    .count(),
-                Wrap::Whitespace => text::line::infos(text, font, font_size)
-                    .wrap_by_whitespace(max_w)
-                    .count(),
+            Some(max_w) => match fallback_metrics(text, &fonts, font_size) {
+                Some(metrics) => {
+                    text::line::span_infos(text, &metrics, max_w, Some(line_wrap)).count()
+                }
+                None => match line_wrap {
+                    Wrap::Character => text::line::infos(text, font, font_size)
+                        .wrap_by_character(max_w)
+                        .count(),
+                    Wrap::Whitespace => text::line::infos(text, font, font_size)
+                        .wrap_by_whitespace(max_w)
+                        .count(),
+                },
             },
         };
         let line_spacing = self.style.line_spacing(&ui.theme);
@@ -244,15 +475,18 @@ impl<'a> Widget for TextEdit<'a> {
             ui,
             ..
         } = args;
-        let TextEdit { text, .. } = self;
+        let TextEdit {
+            text,
+            clear_history,
+            ..
+        } = self;
         let mut text = std::borrow::Cow::Borrowed(text);
 
         // Retrieve the `font_id`, as long as a valid `Font` for it still exists.
         //
         // If we've no font to use for text logic, bail out without updating.
         let font_id = match style
-            .font_id(&ui.theme)
-            .or(ui.fonts.ids().next())
+            .resolve_font_id(&ui.theme, &ui.fonts)
             .and_then(|id| ui.fonts.get(id).map(|_| id))
         {
             Some(font_id) => font_id,
@@ -266,6 +500,16 @@ impl<'a> Widget for TextEdit<'a> {
         let line_spacing = style.line_spacing(ui.theme());
         let restrict_to_height = style.restrict_to_height(ui.theme());
 
+        // Text is shaped if the data of its font is available. The data is shared so that the
+        // `face` does not borrow the `ui`.
+        #[cfg(feature = "shaping")]
+        let font_data = ui.fonts.data(font_id).cloned();
+        #[cfg(feature = "shaping")]
+        let face = font_data.as_ref().and_then(|data| {
+            let font = ui.fonts.get(font_id).unwrap();
+            text::shaping::Face::new(font, data)
+        });
+
         /// Returns an iterator yielding the `text::line::Info` for each line in the given text
         /// with the given styling.
         type LineInfos<'a> = text::line::Infos<'a, text::line::NextBreakFnPtr>;
@@ -283,43 +527,51 @@ impl<'a> Widget for TextEdit<'a> {
             }
         }
 
+        // The fonts tried in order for each `char`, cloned so that they do not borrow the `ui`.
+        let fonts = fallback_fonts(ui, font_id);
+
+        // The `text::line::Info` for each line in the given text, measuring any `char`s that are
+        // missing from the `font` with its fallbacks.
+        let measure_lines = |text: &str, font: &text::Font, max_width: Scalar| {
+            if let Some(metrics) = fallback_metrics(text, &fonts, font_size) {
+                let wrap = Some(line_wrap);
+                return text::line::span_infos(text, &metrics, max_width, wrap).collect();
+            }
+            let infos = line_infos(text, font, font_size, line_wrap, max_width);
+            #[cfg(feature = "shaping")]
+            let infos = infos.shaped(face.as_ref());
+            infos.collect::<Vec<_>>()
+        };
+
         // Check to see if the given text has changed since the last time the widget was updated.
+        //
+        // The layout of the given text is shared across frames via the `Ui`'s text cache.
         {
-            let maybe_new_line_infos = {
-                let line_info_slice = &state.line_infos[..];
+            let font_chain = ui.fonts.chain(font_id, &ui.theme.font_fallbacks);
+            let wrap = Some((line_wrap, rect.w()));
+            let key = text::cache::Key::new(&text, &font_chain, font_size, wrap, justify);
+            let new_line_infos = ui.text_cache().line_infos(key, &text, || {
                 let font = ui.fonts.get(font_id).unwrap();
-                let new_line_infos = line_infos(&text, font, font_size, line_wrap, rect.w());
-                match utils::write_if_different(line_info_slice, new_line_infos) {
-                    std::borrow::Cow::Owned(new) => Some(new),
-                    _ => None,
-                }
-            };
-
-            if let Some(new_line_infos) = maybe_new_line_infos {
-                state.update(|state| state.line_infos = new_line_infos);
+                measure_lines(&text, font, rect.w())
+            });
+            if state.line_infos[..] != new_line_infos[..] {
+                state.update(|state| state.line_infos = new_line_infos.to_vec());
             }
         }
 
         // Validate the position of the cursor. Ensure the indices lie within the text.
-        match state.cursor {
-            Cursor::Idx(index) => {
-                let new_index = index.clamp_to_lines(state.line_infos.iter().cloned());
-                if index != new_index {
-                    let new_cursor = Cursor::Idx(new_index);
-                    state.update(|state| state.cursor = new_cursor);
-                }
-            }
-            Cursor::Selection { start, end } => {
-                let new_start = start.clamp_to_lines(state.line_infos.iter().cloned());
-                let new_end = end.clamp_to_lines(state.line_infos.iter().cloned());
-                if start != new_start || end != new_end {
-                    let new_cursor = Cursor::Selection {
-                        start: new_start,
-                        end: new_end,
-                    };
-                    state.update(|state| state.cursor = new_cursor);
-                }
-            }
+        let new_cursor = state.cursor.clamp_to_lines(&state.line_infos);
+        if state.cursor != new_cursor {
+            state.update(|state| state.cursor = new_cursor);
+        }
+
+        // Apply the history's bounds and clear it if requested.
+        let history_capacity = style.history_capacity(ui.theme());
+        if state.history.capacity() != history_capacity {
+            state.update(|state| state.history.set_capacity(history_capacity));
+        }
+        if clear_history && (state.history.can_undo() || state.history.can_redo()) {
+            state.update(|state| state.history.clear());
         }
 
         // Find the position of the cursor at the given index over the given text.
@@ -338,6 +590,10 @@ impl<'a> Widget for TextEdit<'a> {
                 line_spacing,
                 rect,
             );
+            #[cfg(feature = "shaping")]
+            let xys_per_line = xys_per_line.shaped(face.as_ref());
+            let metrics = fallback_metrics(text, &fonts, font_size);
+            let xys_per_line = xys_per_line.spans(metrics.as_ref().map(|m| &m[..]));
             text::cursor::xy_at(xys_per_line, cursor_idx)
         };
 
@@ -359,6 +615,10 @@ impl<'a> Widget for TextEdit<'a> {
                 line_spacing,
                 rect,
             );
+            #[cfg(feature = "shaping")]
+            let xys_per_line = xys_per_line.shaped(face.as_ref());
+            let metrics = fallback_metrics(text, &fonts, font_size);
+            let xys_per_line = xys_per_line.spans(metrics.as_ref().map(|m| &m[..]));
             text::cursor::closest_cursor_index_and_xy(xy, xys_per_line)
         };
 
@@ -369,7 +629,7 @@ impl<'a> Widget for TextEdit<'a> {
                                             line_infos: &[text::line::Info],
                                             font: &text::Font|
          -> Option<text::cursor::Index> {
-            let mut xys_per_line = text::cursor::xys_per_line_from_text(
+            let xys_per_line = text::cursor::xys_per_line_from_text(
                 text,
                 line_infos,
                 font,
@@ -379,6 +639,10 @@ impl<'a> Widget for TextEdit<'a> {
                 line_spacing,
                 rect,
             );
+            #[cfg(feature = "shaping")]
+            let xys_per_line = xys_per_line.shaped(face.as_ref());
+            let metrics = fallback_metrics(text, &fonts, font_size);
+            let mut xys_per_line = xys_per_line.spans(metrics.as_ref().map(|m| &m[..]));
             xys_per_line.nth(line_idx).and_then(|(line_xs, _)| {
                 let (char_idx, _) = text::cursor::closest_cursor_index_on_line(x_pos, line_xs);
                 Some(text::cursor::Index {
@@ -388,8 +652,33 @@ impl<'a> Widget for TextEdit<'a> {
             })
         };
 
+        // The `Rect` covering the text between the given cursor indices on each line.
+        let selected_rects_at = |start: text::cursor::Index,
+                                 end: text::cursor::Index,
+                                 text: &str,
+                                 line_infos: &[text::line::Info],
+                                 font: &text::Font|
+         -> Vec<Rect> {
+            let xys_per_line = text::cursor::xys_per_line_from_text(
+                text,
+                line_infos,
+                font,
+                font_size,
+                justify,
+                y_align,
+                line_spacing,
+                rect,
+            );
+            #[cfg(feature = "shaping")]
+            let xys_per_line = xys_per_line.shaped(face.as_ref());
+            let metrics = fallback_metrics(text, &fonts, font_size);
+            let xys_per_line = xys_per_line.spans(metrics.as_ref().map(|m| &m[..]));
+            text::cursor::selected_rects(xys_per_line, start, end)
+        };
+
         let mut cursor = state.cursor;
         let mut drag = state.drag;
+        let mut preedit = state.preedit.clone();
 
         // Insert the given `string` at the given `cursor` position within the given `text`.
         //
@@ -432,8 +721,7 @@ impl<'a> Widget for TextEdit<'a> {
             };
 
             // Calculate the new `line_infos` for the `new_text`.
-            let new_line_infos: Vec<_> =
-                { line_infos(&new_text, font, font_size, line_wrap, rect.w()).collect() };
+            let new_line_infos = measure_lines(&new_text, font, rect.w());
 
             // Check that the new text would not exceed the `inner_rect` bounds.
             let num_lines = new_line_infos.len();
@@ -538,12 +826,14 @@ impl<'a> Widget for TextEdit<'a> {
 
                                     let end = match (key, delete_word) {
                                         (input::Key::Backspace, false) => {
-                                            cursor_idx.previous(line_infos)
+                                            cursor_idx.previous(&text, line_infos)
                                         }
                                         (input::Key::Backspace, true) => {
                                             cursor_idx.previous_word_start(&text, line_infos)
                                         }
-                                        (input::Key::Delete, false) => cursor_idx.next(line_infos),
+                                        (input::Key::Delete, false) => {
+                                            cursor_idx.next(&text, line_infos)
+                                        }
                                         (input::Key::Delete, true) => {
                                             cursor_idx.next_word_end(&text, line_infos)
                                         }
@@ -576,6 +866,9 @@ impl<'a> Widget for TextEdit<'a> {
                                     text::cursor::index_before_char(line_infos, new_cursor_char_idx)
                                         .expect("char index was out of range")
                                 };
+                                if start_idx != end_idx {
+                                    state.update(|state| state.history.record(&text, cursor));
+                                }
                                 cursor = Cursor::Idx(new_cursor_idx);
                                 *text.to_mut() = text
                                     .chars()
@@ -585,8 +878,7 @@ impl<'a> Widget for TextEdit<'a> {
                                 state.update(|state| {
                                     let font = ui.fonts.get(font_id).unwrap();
                                     let w = rect.w();
-                                    state.line_infos =
-                                        line_infos(&text, font, font_size, line_wrap, w).collect();
+                                    state.line_infos = measure_lines(&text, font, w);
                                 });
                             }
                         }
@@ -616,8 +908,10 @@ impl<'a> Widget for TextEdit<'a> {
                                     (input::Key::Right, true) => {
                                         cursor_idx.next_word_end(&text, line_infos)
                                     }
-                                    (input::Key::Left, false) => cursor_idx.previous(line_infos),
-                                    (input::Key::Right, false) => cursor_idx.next(line_infos),
+                                    (input::Key::Left, false) => cursor_idx.left(&text, line_infos),
+                                    (input::Key::Right, false) => {
+                                        cursor_idx.right(&text, line_infos)
+                                    }
 
                                     // Up/Down movement
                                     _ => cursor_xy_at(cursor_idx, &text, &state.line_infos, font)
@@ -782,9 +1076,12 @@ impl<'a> Widget for TextEdit<'a> {
                                         font,
                                     ) {
                                         Some((new_text, new_cursor, new_line_infos)) => {
+                                            state.update(|state| {
+                                                state.history.record(&text, cursor);
+                                                state.line_infos = new_line_infos;
+                                            });
                                             *text.to_mut() = new_text;
                                             cursor = new_cursor;
-                                            state.update(|state| state.line_infos = new_line_infos);
                                         }
                                         _ => (),
                                     }
@@ -792,6 +1089,40 @@ impl<'a> Widget for TextEdit<'a> {
                             }
                         }
 
+                        input::Key::Z | input::Key::Y => {
+                            // Undo on Ctrl+z, redo on Ctrl+Shift+z or Ctrl+y.
+                            let modifiers = press.modifiers;
+                            if !modifiers.contains(input::keyboard::ModifierKey::CTRL) {
+                                continue 'events;
+                            }
+                            let shift = modifiers.contains(input::keyboard::ModifierKey::SHIFT);
+                            let is_redo = key == input::Key::Y || shift;
+                            let history = &state.history;
+                            let can_restore = if is_redo {
+                                history.can_redo()
+                            } else {
+                                history.can_undo()
+                            };
+                            if !can_restore {
+                                continue 'events;
+                            }
+                            let mut restored = None;
+                            state.update(|state| {
+                                restored = if is_redo {
+                                    state.history.redo(&text, cursor)
+                                } else {
+                                    state.history.undo(&text, cursor)
+                                };
+                            });
+                            if let Some((restored_text, restored_cursor)) = restored {
+                                let font = ui.fonts.get(font_id).unwrap();
+                                let new_line_infos = measure_lines(&restored_text, font, rect.w());
+                                cursor = restored_cursor.clamp_to_lines(&new_line_infos);
+                                *text.to_mut() = restored_text;
+                                state.update(|state| state.line_infos = new_line_infos);
+                            }
+                        }
+
                         input::Key::End => {
                             // move cursor to end.
                             let mut line_infos = state.line_infos.iter().cloned();
@@ -830,9 +1161,12 @@ impl<'a> Widget for TextEdit<'a> {
                             let font = ui.fonts.get(font_id).unwrap();
                             match insert_text("\n", cursor, &text, &state.line_infos, font) {
                                 Some((new_text, new_cursor, new_line_infos)) => {
+                                    state.update(|state| {
+                                        state.history.record(&text, cursor);
+                                        state.line_infos = new_line_infos;
+                                    });
                                     *text.to_mut() = new_text;
                                     cursor = new_cursor;
-                                    state.update(|state| state.line_infos = new_line_infos);
                                 }
                                 _ => (),
                             }
@@ -873,14 +1207,55 @@ impl<'a> Widget for TextEdit<'a> {
                     let font = ui.fonts.get(font_id).unwrap();
                     match insert_text(&string, cursor, &text, &state.line_infos, font) {
                         Some((new_text, new_cursor, new_line_infos)) => {
+                            state.update(|state| {
+                                let history = &mut state.history;
+                                history.record_typing(&text, cursor, &string, new_cursor);
+                                state.line_infos = new_line_infos;
+                            });
                             *text.to_mut() = new_text;
                             cursor = new_cursor;
-                            state.update(|state| state.line_infos = new_line_infos);
                         }
                         _ => (),
                     }
                 }
 
+                event::Widget::Ime(event::Ime::Preedit {
+                    string,
+                    cursor: ime_cursor,
+                }) => {
+                    preedit = if string.is_empty() {
+                        None
+                    } else {
+                        Some(Preedit {
+                            string,
+                            cursor: ime_cursor,
+                        })
+                    };
+                }
+
+                event::Widget::Ime(event::Ime::Commit(string)) => {
+                    preedit = None;
+                    if string.is_empty() {
+                        continue 'events;
+                    }
+                    let font = ui.fonts.get(font_id).unwrap();
+                    if let Some((new_text, new_cursor, new_line_infos)) =
+                        insert_text(&string, cursor, &text, &state.line_infos, font)
+                    {
+                        state.update(|state| {
+                            state.history.record(&text, cursor);
+                            state.line_infos = new_line_infos;
+                        });
+                        *text.to_mut() = new_text;
+                        cursor = new_cursor;
+                    }
+                }
+
+                // Any composition is abandoned once the keyboard is no longer captured.
+                event::Widget::UncapturesInputSource(input::Source::Keyboard) => {
+                    preedit = None;
+                }
+
                 // Check whether or not we need to extend a text selection or drag some text.
                 event::Widget::Drag(drag_event)
                     if drag_event.button == input::MouseButton::Left =>
@@ -931,6 +1306,49 @@ impl<'a> Widget for TextEdit<'a> {
             state.update(|state| state.drag = drag);
         }
 
+        if state.preedit != preedit {
+            state.update(|state| state.preedit = preedit);
+        }
+
+        // Text being composed via an IME is displayed in place of the selection.
+        let preedit = match state.preedit {
+            None => None,
+            Some(ref preedit) => {
+                let font = ui.fonts.get(font_id).unwrap();
+                insert_text(&preedit.string, cursor, &text, &state.line_infos, font).and_then(
+                    |(display_text, end, display_line_infos)| {
+                        let end = match end {
+                            Cursor::Idx(idx) => idx,
+                            Cursor::Selection { end, .. } => end,
+                        };
+                        let infos = display_line_infos.iter().cloned();
+                        let end_char = text::glyph::index_after_cursor(infos.clone(), end)?;
+                        let start_char = end_char - preedit.string.chars().count();
+                        let start = text::cursor::index_before_char(infos.clone(), start_char)?;
+                        let caret = preedit
+                            .cursor
+                            .and_then(|(byte, _)| preedit.string.get(..byte))
+                            .and_then(|s| {
+                                let char = start_char + s.chars().count();
+                                text::cursor::index_before_char(infos, char)
+                            })
+                            .unwrap_or(end);
+                        Some(PreeditLayout {
+                            text: display_text,
+                            line_infos: display_line_infos,
+                            start,
+                            end,
+                            caret,
+                        })
+                    },
+                )
+            }
+        };
+        let (display_text, display_line_infos): (&str, &[text::line::Info]) = match preedit {
+            Some(ref preedit) => (&preedit.text, &preedit.line_infos),
+            None => (&text, &state.line_infos),
+        };
+
         // Takes the `String` from the `Cow` if the `Cow` is `Owned`.
         fn take_if_owned(text: std::borrow::Cow<str>) -> Option<String> {
             match text {
@@ -941,7 +1359,7 @@ impl<'a> Widget for TextEdit<'a> {
 
         let color = style.color(ui.theme());
         let font_size = style.font_size(ui.theme());
-        let num_lines = state.line_infos.iter().count();
+        let num_lines = display_line_infos.len();
         let text_height = text::height(num_lines, font_size, line_spacing);
         let text_y_range = Range::new(0.0, text_height).align_to(y_align, rect.y);
         let text_rect = Rect {
@@ -950,8 +1368,8 @@ impl<'a> Widget for TextEdit<'a> {
         };
 
         match line_wrap {
-            Wrap::Whitespace => widget::Text::new(&text).wrap_by_word(),
-            Wrap::Character => widget::Text::new(&text).wrap_by_character(),
+            Wrap::Whitespace => widget::Text::new(display_text).wrap_by_word(),
+            Wrap::Character => widget::Text::new(display_text).wrap_by_character(),
         }
         .font_id(font_id)
         .wh(text_rect.dim())
@@ -965,9 +1383,10 @@ impl<'a> Widget for TextEdit<'a> {
         .set(state.ids.text, ui);
 
         // Draw the line for the cursor.
-        let cursor_idx = match cursor {
-            Cursor::Idx(idx) => idx,
-            Cursor::Selection { end, .. } => end,
+        let cursor_idx = match (&preedit, cursor) {
+            (Some(preedit), _) => preedit.caret,
+            (None, Cursor::Idx(idx)) => idx,
+            (None, Cursor::Selection { end, .. }) => end,
         };
 
         // If this widget is not capturing the keyboard, no need to draw cursor or selection.
@@ -977,7 +1396,8 @@ impl<'a> Widget for TextEdit<'a> {
 
         let (cursor_x, cursor_y_range) = {
             let font = ui.fonts.get(font_id).unwrap();
-            cursor_xy_at(cursor_idx, &text, &state.line_infos, font).unwrap_or_else(|| {
+            let line_infos = display_line_infos;
+            cursor_xy_at(cursor_idx, display_text, line_infos, font).unwrap_or_else(|| {
                 let x = rect.left();
                 let y = Range::new(0.0, font_size as Scalar).align_to(y_align, rect.y);
                 (x, y)
@@ -993,6 +1413,11 @@ impl<'a> Widget for TextEdit<'a> {
             .parent(id)
             .color(color)
             .set(state.ids.cursor, ui);
+        let caret_rect = Rect {
+            x: Range::new(cursor_x, cursor_x),
+            y: cursor_y_range,
+        };
+        ui.set_caret_rect(Some(caret_rect));
 
         // If the cursor position has changed due to input AND one of our parent widgets are
         // scrollable AND the change in cursor position would cause the cursor to fall outside the
@@ -1019,23 +1444,96 @@ impl<'a> Widget for TextEdit<'a> {
             }
         }
 
-        if let Cursor::Selection { start, end } = cursor {
+        // Underline the text being composed via an IME.
+        if let Some(ref preedit) = preedit {
+            let underline_rects = {
+                let font = ui.fonts.get(font_id).unwrap();
+                let (start, end) = (preedit.start, preedit.end);
+                selected_rects_at(start, end, &preedit.text, &preedit.line_infos, font)
+            };
+
+            if state.ids.preedit_underlines.len() < underline_rects.len() {
+                let num_rects = underline_rects.len();
+                let id_gen = &mut ui.widget_id_generator();
+                state.update(|state| state.ids.preedit_underlines.resize(num_rects, id_gen));
+            }
+
+            let iter = state.ids.preedit_underlines.iter().zip(&underline_rects);
+            for (&underline_id, underline_rect) in iter {
+                widget::Rectangle::fill([underline_rect.w(), 1.0])
+                    .x_y(underline_rect.x(), underline_rect.bottom() + 0.5)
+                    .color(color)
+                    .graphics_for(id)
+                    .parent(id)
+                    .set(underline_id, ui);
+            }
+        }
+
+        if let (Cursor::Selection { start, end }, None) = (cursor, &preedit) {
             let (start, end) = (std::cmp::min(start, end), std::cmp::max(start, end));
 
-            let selected_rects: Vec<Rect> = {
-                let line_infos = state.line_infos.iter().cloned();
-                let lines = line_infos.clone().map(|info| &text[info.byte_range()]);
-                let line_rects = text::line::rects(
-                    line_infos.clone(),
-                    font_size,
-                    rect,
-                    justify,
-                    y_align,
-                    line_spacing,
-                );
-                let lines_with_rects = lines.zip(line_rects.clone());
-                let font = ui.fonts.get(font_id).unwrap();
-                text::line::selected_rects(lines_with_rects, font, font_size, start, end).collect()
+            // Text using fallback fonts is measured in spans, as for its cursor positions.
+            let metrics = fallback_metrics(&text, &fonts, font_size);
+            let selected_rects: Vec<Rect> = match metrics {
+                Some(ref metrics) => {
+                    let xys_per_line = text::cursor::xys_per_line_from_text(
+                        &text,
+                        &state.line_infos,
+                        &fonts[0],
+                        font_size,
+                        justify,
+                        y_align,
+                        line_spacing,
+                        rect,
+                    );
+                    let xys_per_line = xys_per_line.spans(Some(&metrics[..]));
+                    text::cursor::selected_rects(xys_per_line, start, end)
+                }
+                None => {
+                    let line_infos = state.line_infos.iter().cloned();
+                    let line_rects = text::line::rects(
+                        line_infos.clone(),
+                        font_size,
+                        rect,
+                        justify,
+                        y_align,
+                        line_spacing,
+                    );
+                    let font = ui.fonts.get(font_id).unwrap();
+                    let unshaped_rects = || {
+                        let lines_with_rects = line_infos.clone().zip(line_rects.clone());
+                        text::line::selected_rects(
+                            lines_with_rects,
+                            font,
+                            &text,
+                            font_size,
+                            justify,
+                            start,
+                            end,
+                        )
+                        .collect()
+                    };
+                    // Bidirectional text may require more than one `Rect` per line once shaped.
+                    #[cfg(feature = "shaping")]
+                    let selected_rects = match face {
+                        Some(ref face) => {
+                            let infos_with_rects = line_infos.clone().zip(line_rects.clone());
+                            text::shaping::selected_rects(
+                                &text,
+                                infos_with_rects,
+                                face,
+                                font_size,
+                                justify,
+                                start,
+                                end,
+                            )
+                        }
+                        None => unshaped_rects(),
+                    };
+                    #[cfg(not(feature = "shaping"))]
+                    let selected_rects = unshaped_rects();
+                    selected_rects
+                }
             };
 
             // Ensure we have at least as many widgets as selected_rectangles.
@@ -1062,6 +1560,44 @@ impl<'a> Widget for TextEdit<'a> {
     }
 }
 
+// The fonts tried in order for each `char` of text displayed with the font of the given `font_id`.
+//
+// The fonts are cloned so that they do not borrow the `ui`.
+fn fallback_fonts(ui: &Ui, font_id: text::font::Id) -> Vec<text::Font> {
+    ui.fonts
+        .chain(font_id, &ui.theme.font_fallbacks)
+        .into_iter()
+        .filter_map(|id| ui.fonts.get(id).cloned())
+        .collect()
+}
+
+// The font and size of each run of the given `text`, where each `char` is measured with the first
+// of the `fonts` that has a glyph for it.
+//
+// Returns `None` if every `char` is displayed with the first font.
+fn fallback_metrics<'a>(
+    text: &str,
+    fonts: &'a [text::Font],
+    font_size: FontSize,
+) -> Option<Vec<text::line::SpanMetrics<'a>>> {
+    if fonts.len() < 2 {
+        return None;
+    }
+    let runs = text::font::fallback_runs(text, fonts);
+    if runs.iter().all(|&(_, ix)| ix == 0) {
+        return None;
+    }
+    let metrics = runs
+        .into_iter()
+        .map(|(range, ix)| text::line::SpanMetrics {
+            end_byte: range.end,
+            font: &fonts[ix],
+            font_size: font_size,
+        })
+        .collect();
+    Some(metrics)
+}
+
 impl<'a> Colorable for TextEdit<'a> {
     builder_method!(color { style.color = Some(Color) });
 }
@@ -65,6 +65,12 @@ pub struct Style {
     /// The font used for the `Text`.
     #[conrod(default = "theme.font_id")]
     pub font_id: Option<Option<text::font::Id>>,
+    /// The family, weight and slant of the label's font, resolved relative to the `font_id`.
+    #[conrod(default = "None")]
+    pub label_font_query: Option<Option<text::font::Query>>,
+    /// Where to truncate the `Text` should it exceed the width of the title bar, if at all.
+    #[conrod(default = "None")]
+    pub ellipsis: Option<Option<text::Ellipsis>>,
 }
 
 /// The padding between the edge of the title bar and the title bar's label.
@@ -120,6 +126,13 @@ impl<'a> TitleBar<'a> {
         self
     }
 
+    /// Display the label with the font that most closely matches the given query. See
+    /// `widget::Text::font_query`.
+    pub fn label_font_query(mut self, query: text::font::Query) -> Self {
+        self.style.label_font_query = Some(Some(query));
+        self
+    }
+
     builder_methods! {
         pub line_spacing { style.line_spacing = Some(Scalar) }
     }
@@ -181,13 +194,19 @@ impl<'a> Widget for TitleBar<'a> {
         let font_size = style.font_size(ui.theme());
         let line_spacing = style.line_spacing(ui.theme());
         let maybe_wrap = style.maybe_wrap(ui.theme());
-        let font_id = style.font_id(&ui.theme).or(ui.fonts.ids().next());
+        let font_id = ui.theme.resolve_font_id(
+            &ui.fonts,
+            style.font_id(&ui.theme),
+            style.label_font_query(&ui.theme),
+        );
         let label_x = style.label_x(&ui.theme);
         let label_y = style.label_y(&ui.theme);
+        let ellipsis = style.ellipsis(&ui.theme);
         widget::Text::new(label)
             .and_mut(|text| {
                 text.style.maybe_wrap = Some(maybe_wrap);
                 text.style.justify = Some(justify);
+                text.style.ellipsis = Some(ellipsis);
             })
             .and_then(font_id, widget::Text::font_id)
             .padded_w_of(state.ids.rectangle, border)
@@ -218,4 +237,9 @@ impl<'a> Labelable<'a> for TitleBar<'a> {
         label_color { style.text_color = Some(Color) }
         label_font_size { style.font_size = Some(FontSize) }
     }
+
+    fn label_ellipsis(mut self, ellipsis: text::Ellipsis) -> Self {
+        self.style.ellipsis = Some(Some(ellipsis));
+        self
+    }
 }
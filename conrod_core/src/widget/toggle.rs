@@ -3,7 +3,7 @@
 use position::{self, Align};
 use text;
 use widget;
-use {Borderable, Color, Colorable, FontSize, Labelable, Positionable, Scalar, Widget};
+use {Borderable, Color, Colorable, FontSize, Labelable, Positionable, Scalar, Sizeable, Widget};
 
 /// A pressable widget for toggling the state of a bool.
 ///
@@ -41,9 +41,16 @@ pub struct Style {
     /// The font size for the Toggle's Text label.
     #[conrod(default = "theme.font_size_medium")]
     pub label_font_size: Option<FontSize>,
+    /// Where to truncate the Toggle's label should it exceed the available width, if at all.
+    #[conrod(default = "None")]
+    pub label_ellipsis: Option<Option<text::Ellipsis>>,
     /// The ID of the font used to display the label.
     #[conrod(default = "theme.font_id")]
     pub label_font_id: Option<Option<text::font::Id>>,
+    /// The family, weight and slant of the label's font, resolved relative to the
+    /// `label_font_id`.
+    #[conrod(default = "None")]
+    pub label_font_query: Option<Option<text::font::Query>>,
     /// The position of the title bar's `Label` widget over the *x* axis.
     #[conrod(default = "position::Relative::Align(Align::Middle)")]
     pub label_x: Option<position::Relative>,
@@ -106,6 +113,13 @@ impl<'a> Toggle<'a> {
         self
     }
 
+    /// Display the label with the font that most closely matches the given query. See
+    /// `widget::Text::font_query`.
+    pub fn label_font_query(mut self, query: text::font::Query) -> Self {
+        self.style.label_font_query = Some(Some(query));
+        self
+    }
+
     /// Specify the label's position relatively to `Toggle` along the *x* axis.
     pub fn label_x(mut self, x: position::Relative) -> Self {
         self.style.label_x = Some(x);
@@ -200,11 +214,21 @@ impl<'a> Widget for Toggle<'a> {
         if let Some(label) = maybe_label {
             let color = style.label_color(ui.theme());
             let font_size = style.label_font_size(ui.theme());
-            let font_id = style.label_font_id(&ui.theme).or(ui.fonts.ids().next());
+            let font_id = ui.theme.resolve_font_id(
+                &ui.fonts,
+                style.label_font_id(&ui.theme),
+                style.label_font_query(&ui.theme),
+            );
             let x = style.label_x(&ui.theme);
             let y = style.label_y(&ui.theme);
+            let ellipsis = style.label_ellipsis(&ui.theme);
             widget::Text::new(label)
                 .and_then(font_id, widget::Text::font_id)
+                .and_then(ellipsis, |text, ellipsis| {
+                    text.ellipsis(ellipsis)
+                        .center_justify()
+                        .padded_w_of(id, border)
+                })
                 .x_position_relative_to(id, x)
                 .y_position_relative_to(id, y)
                 .graphics_for(id)
@@ -234,4 +258,8 @@ impl<'a> Labelable<'a> for Toggle<'a> {
         label_color { style.label_color = Some(Color) }
         label_font_size { style.label_font_size = Some(FontSize) }
     }
+    fn label_ellipsis(mut self, ellipsis: text::Ellipsis) -> Self {
+        self.style.label_ellipsis = Some(Some(ellipsis));
+        self
+    }
 }
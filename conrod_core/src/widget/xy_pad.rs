@@ -4,7 +4,7 @@ use num::Float;
 use text;
 use utils::{map_range, val_to_string};
 use widget;
-use {Borderable, Color, Colorable, FontSize, Labelable, Positionable, Scalar, Widget};
+use {Borderable, Color, Colorable, FontSize, Labelable, Positionable, Scalar, Sizeable, Widget};
 
 /// Used for displaying and controlling a 2D point on a cartesian plane within a given range.
 ///
@@ -44,9 +44,16 @@ pub struct Style {
     /// The font size for the XYPad's label.
     #[conrod(default = "theme.font_size_medium")]
     pub label_font_size: Option<FontSize>,
+    /// Where to truncate the XYPad's label should it exceed the available width, if at all.
+    #[conrod(default = "None")]
+    pub label_ellipsis: Option<Option<text::Ellipsis>>,
     /// The ID of the font used to display the label.
     #[conrod(default = "theme.font_id")]
     pub label_font_id: Option<Option<text::font::Id>>,
+    /// The family, weight and slant of the label's font, resolved relative to the
+    /// `label_font_id`.
+    #[conrod(default = "None")]
+    pub label_font_query: Option<Option<text::font::Query>>,
     /// The font size for the XYPad's *value* label.
     #[conrod(default = "14")]
     pub value_font_size: Option<FontSize>,
@@ -93,6 +100,13 @@ impl<'a, X, Y> XYPad<'a, X, Y> {
         self
     }
 
+    /// Display the label with the font that most closely matches the given query. See
+    /// `widget::Text::font_query`.
+    pub fn label_font_query(mut self, query: text::font::Query) -> Self {
+        self.style.label_font_query = Some(Some(query));
+        self
+    }
+
     builder_methods! {
         pub line_thickness { style.line_thickness = Some(Scalar) }
         pub value_font_size { style.value_font_size = Some(FontSize) }
@@ -193,11 +207,21 @@ where
 
         // Label **Text** widget.
         let label_color = style.label_color(ui.theme());
-        let font_id = style.label_font_id(&ui.theme).or(ui.fonts.ids().next());
+        let font_id = ui.theme.resolve_font_id(
+            &ui.fonts,
+            style.label_font_id(&ui.theme),
+            style.label_font_query(&ui.theme),
+        );
         if let Some(label) = maybe_label {
             let label_font_size = style.label_font_size(ui.theme());
+            let ellipsis = style.label_ellipsis(&ui.theme);
             widget::Text::new(label)
                 .and_then(font_id, widget::Text::font_id)
+                .and_then(ellipsis, |text, ellipsis| {
+                    text.ellipsis(ellipsis)
+                        .center_justify()
+                        .padded_w_of(id, border)
+                })
                 .middle_of(state.ids.rectangle)
                 .graphics_for(id)
                 .color(label_color)
@@ -280,4 +304,8 @@ impl<'a, X, Y> Labelable<'a> for XYPad<'a, X, Y> {
         label_color { style.label_color = Some(Color) }
         label_font_size { style.label_font_size = Some(FontSize) }
     }
+    fn label_ellipsis(mut self, ellipsis: text::Ellipsis) -> Self {
+        self.style.label_ellipsis = Some(Some(ellipsis));
+        self
+    }
 }